@@ -0,0 +1,11 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use naom::script::lang::{NetworkVersion, Script};
+
+// Malformed scripts must be rejected by `interpret`, never panic. V0 also runs the
+// opcodes in `DISABLED_OPCODES`, which the default context rejects up front
+fuzz_target!(|script: Script| {
+    let _ = script.interpret();
+    let _ = script.interpret_for_version(NetworkVersion::V0);
+});
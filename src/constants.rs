@@ -1,8 +1,18 @@
+use crate::script::OpCodes;
+
 /*------- TRANSACTION CONSTANTS -------*/
 pub const TX_PREPEND: u8 = b'g';
 pub const RECEIPT_DEFAULT_DRS_TX_HASH: &str = "default_drs_tx_hash";
 pub const MAX_METADATA_BYTES: usize = 800;
+// Metadata limit for receipts created by legacy `NETWORK_VERSION_V0` scripts
+pub const MAX_METADATA_BYTES_V0: usize = 256;
+pub const RECEIPT_METADATA_REQUIRED_KEYS: &[&str] = &["name", "description"];
+pub const MAX_DATA_ASSET_BYTES: usize = 800;
 pub const TX_HASH_LENGTH: usize = 32;
+// Bytes used to serialize an enum variant tag
+pub const SERIALIZED_TAG_LEN: usize = 4;
+// Bytes used to serialize the length prefix of a sequence
+pub const SERIALIZED_LEN_PREFIX: usize = 8;
 
 /*------- ADDRESS CONSTANTS -------*/
 pub const V0_ADDRESS_LENGTH: usize = 16;
@@ -19,6 +29,8 @@ pub const NETWORK_VERSION_V0: u64 = 0;
 // Network version to support temporary address structure on wallet
 // TODO: Deprecate after addresses retire
 pub const NETWORK_VERSION_TEMP: u64 = 99999;
+// Network id mixed into signable hashes for replay protection; the default id mixes nothing in
+pub const DEFAULT_NETWORK_ID: u64 = 0;
 
 /*------- VALUE HANDLING CONSTANTS --------*/
 // Number of decimal places to divide to in display
@@ -34,6 +46,8 @@ pub const RECEIPT_ACCEPT_VAL: &str = "PAYMENT_ACCEPT";
 /*------- BLOCK CONSTANTS --------*/
 // Maximum number of bytes that a block can contain
 pub const MAX_BLOCK_SIZE: usize = 1000;
+// Number of blocks after its creation before a coinbase output may be spent
+pub const COINBASE_MATURITY: u64 = 100;
 
 /*------- SCRIPT CONSTANTS -------*/
 // Maximum number of bytes pushable to the stack
@@ -42,10 +56,48 @@ pub const MAX_SCRIPT_ITEM_SIZE: u16 = 520;
 pub const MAX_OPS_PER_SCRIPT: u8 = 201;
 // Maximum number of public keys per multisig
 pub const MAX_PUB_KEYS_PER_MULTISIG: u8 = 20;
+// Minimum number of signatures required by a standard multisig, as 0-of-n is anyone-can-spend
+pub const MIN_MULTISIG_THRESHOLD: usize = 1;
 // Maximum script length in bytes
 pub const MAX_SCRIPT_SIZE: u16 = 10000;
 // Maximum number of values on script interpreter stack
 pub const MAX_STACK_SIZE: u16 = 1000;
+// Maximum number of bytes held by the values on script interpreter stack
+pub const MAX_STACK_MEMORY_BYTES: usize = 100_000;
+// Maximum number of data bytes carried by an OP_BURN (OP_RETURN) data-carrier output
+pub const MAX_OP_RETURN_BYTES: usize = 80;
+// Compact script encoding tags for non-opcode stack entries, kept clear of the opcode byte values
+pub const COMPACT_TAG_SIGNATURE: u8 = 0xf0;
+pub const COMPACT_TAG_PUBKEY: u8 = 0xf1;
+pub const COMPACT_TAG_PUBKEY_HASH_HEX: u8 = 0xf2;
+pub const COMPACT_TAG_PUBKEY_HASH_TEXT: u8 = 0xf3;
+pub const COMPACT_TAG_BYTES_HEX: u8 = 0xf4;
+pub const COMPACT_TAG_BYTES_TEXT: u8 = 0xf5;
+pub const COMPACT_TAG_NUM: u8 = 0xf6;
+pub const COMPACT_TAG_SIGNED_NUM: u8 = 0xf7;
+// Number of characters kept at each end of long values rendered in script assembly
+pub const ASM_TRUNCATE_LEN: usize = 8;
+// Prefix of values rendered in script assembly as the hex of their UTF-8 bytes
+pub const ASM_HEX_PREFIX: &str = "0x";
+// Opcodes rejected by the interpreter, except for legacy (V0 and TEMP) scripts
+pub const DISABLED_OPCODES: &[OpCodes] = &[
+    OpCodes::OP_CAT,
+    OpCodes::OP_SUBSTR,
+    OpCodes::OP_LEFT,
+    OpCodes::OP_RIGHT,
+    OpCodes::OP_INVERT,
+    OpCodes::OP_AND,
+    OpCodes::OP_OR,
+    OpCodes::OP_XOR,
+    OpCodes::OP_2MUL,
+    OpCodes::OP_2DIV,
+    OpCodes::OP_MUL,
+    OpCodes::OP_DIV,
+    OpCodes::OP_MOD,
+    OpCodes::OP_LSHIFT,
+    OpCodes::OP_RSHIFT,
+    OpCodes::OP_CHECKAGGSIG,
+];
 // Threshold for lock_time: below this value it is interpreted as block number,
 // otherwise as UNIX timestamp.
 pub const LOCKTIME_THRESHOLD: u32 = 500000000; // Tue Nov 5 00:53:20 1985 UTC
@@ -72,6 +124,7 @@ pub const SIXTEEN: usize = 16;
 /*------- TRACE MESSAGES -------*/
 // constants
 pub const OP0: &str = "OP_0";
+pub const OPPUSHDATA: &str = "OP_PUSHDATA";
 pub const OP1: &str = "OP_1";
 pub const OP2: &str = "OP_2";
 pub const OP3: &str = "OP_3";
@@ -90,6 +143,7 @@ pub const OP15: &str = "OP_15";
 pub const OP16: &str = "OP_16";
 
 pub const OP0_DESC: &str = "Pushes number ZERO onto the stack";
+pub const OPPUSHDATA_DESC: &str = "Pushes the following script entry onto the stack as data";
 pub const OP1_DESC: &str = "Pushes number ONE onto the stack";
 pub const OP2_DESC: &str = "Pushes number TWO onto the stack";
 pub const OP3_DESC: &str = "Pushes number THREE onto the stack";
@@ -179,6 +233,10 @@ pub const OPSUBSTR: &str = "OP_SUBSTR";
 pub const OPLEFT: &str = "OP_LEFT";
 pub const OPRIGHT: &str = "OP_RIGHT";
 pub const OPSIZE: &str = "OP_SIZE";
+pub const OPREVERSE: &str = "OP_REVERSE";
+pub const OPSPLIT: &str = "OP_SPLIT";
+pub const OPNUM2BIN: &str = "OP_NUM2BIN";
+pub const OPBIN2NUM: &str = "OP_BIN2NUM";
 
 pub const OPCAT_DESC: &str = "Concatenates the two strings on top of the stack";
 pub const OPSUBSTR_DESC: &str = "Extracts a substring from the third-to-top item on the stack";
@@ -186,6 +244,13 @@ pub const OPLEFT_DESC: &str = "Extracts a left substring from the second-to-top
 pub const OPRIGHT_DESC: &str =
     "Extracts a right substring from the second-to-top item on the stack";
 pub const OPSIZE_DESC: &str = "Computes the size in bytes of the string on top of the stack";
+pub const OPREVERSE_DESC: &str = "Reverses the bytes of the hex string on top of the stack";
+pub const OPSPLIT_DESC: &str =
+    "Splits the second-to-top string on the stack at the index on top of the stack";
+pub const OPNUM2BIN_DESC: &str =
+    "Converts the second-to-top number on the stack to a byte string of the width on top of the stack";
+pub const OPBIN2NUM_DESC: &str =
+    "Converts the minimally encoded byte string on top of the stack to a number";
 
 // bitwise logic
 pub const OPINVERT: &str = "OP_INVERT";
@@ -226,9 +291,15 @@ pub const OPLESSTHAN: &str = "OP_LESSTHAN";
 pub const OPGREATERTHAN: &str = "OP_GREATERTHAN";
 pub const OPLESSTHANOREQUAL: &str = "OP_LESSTHANOREQUAL";
 pub const OPGREATERTHANOREQUAL: &str = "OP_GREATERTHANOREQUAL";
+pub const OPLESSTHANVERIFY: &str = "OP_LESSTHANVERIFY";
+pub const OPGREATERTHANVERIFY: &str = "OP_GREATERTHANVERIFY";
 pub const OPMIN: &str = "OP_MIN";
 pub const OPMAX: &str = "OP_MAX";
 pub const OPWITHIN: &str = "OP_WITHIN";
+pub const OPLESSTHANSIGNED: &str = "OP_LESSTHAN_SIGNED";
+pub const OPGREATERTHANSIGNED: &str = "OP_GREATERTHAN_SIGNED";
+pub const OPLESSTHANOREQUALSIGNED: &str = "OP_LESSTHANOREQUAL_SIGNED";
+pub const OPGREATERTHANOREQUALSIGNED: &str = "OP_GREATERTHANOREQUAL_SIGNED";
 
 pub const OP1ADD_DESC: &str = "Adds ONE to the number on top of the stack";
 pub const OP1SUB_DESC: &str = "Subtracts ONE from the number on top of the stack";
@@ -259,13 +330,21 @@ pub const OPLESSTHAN_DESC: &str = "Substitutes the two numbers on top of the sta
 pub const OPGREATERTHAN_DESC: &str = "Substitutes the two numbers on top of the stack with ONE if the second-to-top is greater than the top item, with ZERO otherwise";
 pub const OPLESSTHANOREQUAL_DESC: &str = "Substitutes the two numbers on top of the stack with ONE if the second-to-top is less than or equal to the top item, with ZERO otherwise";
 pub const OPGREATERTHANOREQUAL_DESC: &str = "Substitutes the two numbers on top of the stack with ONE if the second-to-top is greater than or equal to the top item, with ZERO otherwise";
+pub const OPLESSTHANVERIFY_DESC: &str = "Computes OP_LESSTHAN and OP_VERIFY in sequence";
+pub const OPGREATERTHANVERIFY_DESC: &str = "Computes OP_GREATERTHAN and OP_VERIFY in sequence";
 pub const OPMIN_DESC: &str =
     "Substitutes the two numbers on top of the stack with the minimum between the two";
 pub const OPMAX_DESC: &str =
     "Substitutes the two numbers on top of the stack with the maximum between the two";
 pub const OPWITHIN_DESC: &str = "Substitutes the three numbers on top of the the stack with ONE if the third-to-top is greater or equal to the second-to-top and less than the top item, with ZERO otherwise";
+pub const OPLESSTHANSIGNED_DESC: &str = "Substitutes the two numbers on top of the stack with ONE if the second-to-top is less than the top item as signed numbers, with ZERO otherwise";
+pub const OPGREATERTHANSIGNED_DESC: &str = "Substitutes the two numbers on top of the stack with ONE if the second-to-top is greater than the top item as signed numbers, with ZERO otherwise";
+pub const OPLESSTHANOREQUALSIGNED_DESC: &str = "Substitutes the two numbers on top of the stack with ONE if the second-to-top is less than or equal to the top item as signed numbers, with ZERO otherwise";
+pub const OPGREATERTHANOREQUALSIGNED_DESC: &str = "Substitutes the two numbers on top of the stack with ONE if the second-to-top is greater than or equal to the top item as signed numbers, with ZERO otherwise";
 
 // crypto
+pub const OPRIPEMD160: &str = "OP_RIPEMD160";
+pub const OPHASH160: &str = "OP_HASH160";
 pub const OPSHA3: &str = "OP_SHA3";
 pub const OPHASH256: &str = "OP_HASH256";
 pub const OPHASH256V0: &str = "OP_HASH256_V0";
@@ -274,7 +353,14 @@ pub const OPCHECKSIG: &str = "OP_CHECKSIG";
 pub const OPCHECKSIGVERIFY: &str = "OP_CHECKSIGVERIFY";
 pub const OPCHECKMULTISIG: &str = "OP_CHECKMULTISIG";
 pub const OPCHECKMULTISIGVERIFY: &str = "OP_CHECKMULTISIGVERIFY";
+pub const OPCHECKMULTISIGORDERED: &str = "OP_CHECKMULTISIG_ORDERED";
+pub const OPCHECKDATASIG: &str = "OP_CHECKDATASIG";
+pub const OPCODESEPARATOR: &str = "OP_CODESEPARATOR";
+pub const OPCHECKAGGSIG: &str = "OP_CHECKAGGSIG";
 
+pub const OPRIPEMD160_DESC: &str = "Hashes the top item on the stack using RIPEMD-160";
+pub const OPHASH160_DESC: &str =
+    "Hashes the top item on the stack using SHA-256 and then RIPEMD-160";
 pub const OPSHA3_DESC: &str = "Hashes the top item on the stack using SHA3-256";
 pub const OPHASH256_DESC: &str =
     "Creates standard address from public key and pushes it onto the stack";
@@ -288,6 +374,24 @@ pub const OPCHECKSIGVERIFY_DESC: &str = "Runs OP_CHECKSIG and OP_VERIFY in seque
 pub const OPCHECKMULTISIG_DESC: &str =
     "Pushes ONE onto the stack if the m-of-n multi-signature is valid, ZERO otherwise";
 pub const OPCHECKMULTISIGVERIFY_DESC: &str = "Runs OP_CHECKMULTISIG and OP_VERIFY in sequence";
+pub const OPCHECKMULTISIGORDERED_DESC: &str = "Pushes ONE onto the stack if the m-of-n multi-signature is valid with signatures in the same order as their public keys, ZERO otherwise";
+pub const OPCHECKDATASIG_DESC: &str =
+    "Pushes ONE onto the stack if the signature over the decoded message is valid, ZERO otherwise";
+pub const OPCODESEPARATOR_DESC: &str = "Does nothing, as signatures do not commit to the script";
+pub const OPCHECKAGGSIG_DESC: &str =
+    "Pushes ONE onto the stack if the signature is valid for the aggregate of the public keys, ZERO otherwise";
+
+// locktime
+pub const OPCHECKLOCKTIMEVERIFY: &str = "OP_CHECKLOCKTIMEVERIFY";
+
+pub const OPCHECKLOCKTIMEVERIFY_DESC: &str =
+    "Ends execution with an error if the number on top of the stack is greater than the current block height";
+
+// covenants
+pub const OPCHECKTEMPLATEVERIFY: &str = "OP_CHECKTEMPLATEVERIFY";
+
+pub const OPCHECKTEMPLATEVERIFY_DESC: &str =
+    "Ends execution with an error if the spending transaction's outputs do not hash to the commitment on top of the stack";
 
 /*------- ERROR MESSAGES -------*/
 // opcodes
@@ -305,8 +409,19 @@ pub const ERROR_INVALID_SIGNATURE: &str = "Signature is not valid";
 pub const ERROR_INVALID_MULTISIGNATURE: &str = "Multi-signature is not valid";
 pub const ERROR_NUM_PUBKEYS: &str = "Number of public keys provided is not correct";
 pub const ERROR_NUM_SIGNATURES: &str = "Number of signatures provided is not correct";
+pub const ERROR_LOCKTIME: &str = "Lock time has not elapsed";
+pub const ERROR_TEMPLATE_MISMATCH: &str =
+    "Spending transaction outputs do not match the committed template";
 // script
 pub const ERROR_MAX_SCRIPT_SIZE: &str = "Script size exceeds MAX_SCRIPT_SIZE-byte limit";
 pub const ERROR_MAX_STACK_SIZE: &str = "Stack size exceeds MAX_STACK_SIZE limit";
+pub const ERROR_MAX_STACK_MEMORY: &str = "Stack memory exceeds MAX_STACK_MEMORY_BYTES-byte limit";
 pub const ERROR_MAX_OPS_SCRIPT: &str =
     "Number of opcodes in script exceeds MAX_OPS_PER_SCRIPT limit";
+pub const ERROR_UNBALANCED_CONDITION: &str = "Condition stack is not empty";
+pub const ERROR_INVALID_ASM: &str = "Script assembly is not valid";
+pub const ERROR_INVALID_ENCODING: &str = "Script bytes could not be deserialized";
+pub const ERROR_RESOURCE_EXHAUSTED: &str =
+    "Number of executed opcodes exceeds the interpreter budget";
+pub const ERROR_DISABLED_OPCODE: &str = "Script contains a disabled opcode";
+pub const ERROR_NON_PUSH_UNLOCK: &str = "Unlocking script contains opcodes rather than only data";
@@ -2,7 +2,12 @@ pub use ring;
 use std::convert::TryInto;
 
 pub mod sign_ed25519 {
-    use super::deserialize_slice;
+    use super::{deserialize_hex_slice, serialize_hex_slice};
+    use curve25519_dalek::constants::ED25519_BASEPOINT_TABLE;
+    use curve25519_dalek::edwards::{CompressedEdwardsY, EdwardsPoint};
+    use curve25519_dalek::scalar::Scalar;
+    use curve25519_dalek::traits::Identity;
+    use ring::digest::{self, SHA512};
     pub use ring::signature::Ed25519KeyPair as SecretKeyBase;
     use ring::signature::KeyPair;
     pub use ring::signature::Signature as SignatureBase;
@@ -19,12 +24,17 @@ pub mod sign_ed25519 {
     const SIGNATURE_LEN: usize = ELEM_LEN + SCALAR_LEN;
     pub const ED25519_SIGNATURE_LEN: usize = SIGNATURE_LEN;
 
+    /// Position of the seed in the PKCS8 documents generated by ring
+    const PKCS8_SEED_OFFSET: usize = 16;
+    const SEED_LEN: usize = 32;
+
     /// Signature data
     /// We used sodiumoxide serialization before (treated it as slice with 64 bit length prefix).
     #[derive(Clone, Copy, Debug, PartialOrd, Ord, PartialEq, Eq, Serialize, Deserialize)]
+    #[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
     pub struct Signature(
-        #[serde(serialize_with = "<[_]>::serialize")]
-        #[serde(deserialize_with = "deserialize_slice")]
+        #[serde(serialize_with = "serialize_hex_slice")]
+        #[serde(deserialize_with = "deserialize_hex_slice")]
         [u8; ED25519_SIGNATURE_LEN],
     );
 
@@ -43,9 +53,10 @@ pub mod sign_ed25519 {
     /// Public key data
     /// We used sodiumoxide serialization before (treated it as slice with 64 bit length prefix).
     #[derive(Clone, Copy, Debug, PartialOrd, Ord, PartialEq, Eq, Serialize, Deserialize)]
+    #[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
     pub struct PublicKey(
-        #[serde(serialize_with = "<[_]>::serialize")]
-        #[serde(deserialize_with = "deserialize_slice")]
+        #[serde(serialize_with = "serialize_hex_slice")]
+        #[serde(deserialize_with = "deserialize_hex_slice")]
         [u8; ED25519_PUBLIC_KEY_LEN],
     );
 
@@ -84,11 +95,109 @@ pub mod sign_ed25519 {
         upk.verify(msg, sig.as_ref()).is_ok()
     }
 
+    /// Verifies a standalone signature over a message, such as one signed by a wallet,
+    /// with the semantics of `OP_CHECKSIG`. ring only accepts signatures with a reduced
+    /// scalar `S`, so a signature cannot be malleated by adding the group order to `S`
+    ///
+    /// ### Arguments
+    ///
+    /// * `msg` - Message that was signed
+    /// * `sig` - Signature to verify
+    /// * `pk`  - Public key to verify against
+    pub fn verify_message(msg: &[u8], sig: &Signature, pk: &PublicKey) -> bool {
+        verify_detached(sig, msg, pk)
+    }
+
     pub fn sign_detached(msg: &[u8], sk: &SecretKey) -> Signature {
         let secret = SecretKeyBase::from_pkcs8(sk.as_ref()).unwrap();
         Signature(secret.sign(msg).as_ref().try_into().unwrap())
     }
 
+    /// Aggregates public keys under the sum-of-keys convention: the aggregate key is the
+    /// sum of the members' curve points, signed for by `sign_aggregate` with the sum of
+    /// their secret scalars. Returns None if there are no keys or one is not a curve point
+    ///
+    /// NOTE: This stands in until a real aggregate scheme is wired in. Plain key sums are
+    /// open to rogue-key attacks, where a member picks its key to cancel out the others',
+    /// so member keys must be known to be honestly generated
+    ///
+    /// ### Arguments
+    ///
+    /// * `pks` - Public keys of the members
+    pub fn aggregate_public_keys(pks: &[PublicKey]) -> Option<PublicKey> {
+        if pks.is_empty() {
+            return None;
+        }
+        let mut sum = EdwardsPoint::identity();
+        for pk in pks {
+            sum += CompressedEdwardsY(pk.0).decompress()?;
+        }
+        Some(PublicKey(sum.compress().to_bytes()))
+    }
+
+    /// Signs a message for the aggregate of the members' public keys, as defined by
+    /// `aggregate_public_keys`. Returns None if there are no keys or one cannot be decoded
+    ///
+    /// ### Arguments
+    ///
+    /// * `msg` - Message to sign
+    /// * `sks` - Secret keys of the members
+    pub fn sign_aggregate(msg: &[u8], sks: &[SecretKey]) -> Option<Signature> {
+        if sks.is_empty() {
+            return None;
+        }
+        let mut secret = Scalar::zero();
+        let mut nonce = digest::Context::new(&SHA512);
+        for sk in sks {
+            let (scalar, prefix) = expand_secret_key(sk)?;
+            secret += scalar;
+            nonce.update(&prefix);
+        }
+        nonce.update(msg);
+        let r = scalar_from_digest(nonce.finish());
+        let big_r = (&r * &ED25519_BASEPOINT_TABLE).compress();
+        let big_a = (&secret * &ED25519_BASEPOINT_TABLE).compress();
+
+        let mut challenge = digest::Context::new(&SHA512);
+        challenge.update(big_r.as_bytes());
+        challenge.update(big_a.as_bytes());
+        challenge.update(msg);
+        let s = r + scalar_from_digest(challenge.finish()) * secret;
+
+        let mut sig = [0; SIGNATURE_LEN];
+        sig[..ELEM_LEN].copy_from_slice(big_r.as_bytes());
+        sig[ELEM_LEN..].copy_from_slice(s.as_bytes());
+        Some(Signature(sig))
+    }
+
+    /// Expands a secret key into its signing scalar and nonce prefix, as RFC 8032 does
+    fn expand_secret_key(sk: &SecretKey) -> Option<(Scalar, [u8; SCALAR_LEN])> {
+        let pair = SecretKeyBase::from_pkcs8(sk.as_ref()).ok()?;
+        let seed = sk.0.get(PKCS8_SEED_OFFSET..PKCS8_SEED_OFFSET + SEED_LEN)?;
+        let hash = digest::digest(&SHA512, seed);
+        let (scalar, prefix) = hash.as_ref().split_at(SCALAR_LEN);
+
+        let mut bytes: [u8; SCALAR_LEN] = scalar.try_into().ok()?;
+        bytes[0] &= 248;
+        bytes[SCALAR_LEN - 1] &= 127;
+        bytes[SCALAR_LEN - 1] |= 64;
+        let scalar = Scalar::from_bytes_mod_order(bytes);
+
+        // Guard against a key document laid out differently to ring's
+        let pk = (&scalar * &ED25519_BASEPOINT_TABLE).compress();
+        if pk.as_bytes() != pair.public_key().as_ref() {
+            return None;
+        }
+        Some((scalar, prefix.try_into().ok()?))
+    }
+
+    /// Reduces a SHA-512 digest to a scalar
+    fn scalar_from_digest(digest: digest::Digest) -> Scalar {
+        let mut wide = [0; 2 * SCALAR_LEN];
+        wide.copy_from_slice(digest.as_ref());
+        Scalar::from_bytes_mod_order_wide(&wide)
+    }
+
     pub fn verify_append(sm: &[u8], pk: &PublicKey) -> bool {
         if sm.len() > ED25519_SIGNATURE_LEN {
             let start = sm.len() - ED25519_SIGNATURE_LEN;
@@ -117,6 +226,70 @@ pub mod sign_ed25519 {
     }
 }
 
+pub mod signature_scheme {
+    use super::sign_ed25519::{self, PublicKey, SecretKey, Signature, ED25519_SIGNATURE_LEN};
+    use ring::signature::ED25519_PUBLIC_KEY_LEN;
+    use serde::{Deserialize, Serialize};
+
+    /// A digital signature scheme, operating on encoded keys and signatures
+    pub trait SignatureScheme {
+        /// Signs a message, returning `None` if the secret key cannot be decoded
+        fn sign(&self, msg: &[u8], sk: &[u8]) -> Option<Vec<u8>>;
+
+        /// Verifies a detached signature of a message against a public key
+        fn verify(&self, sig: &[u8], msg: &[u8], pk: &[u8]) -> bool;
+
+        /// Length in bytes of an encoded public key
+        fn public_key_len(&self) -> usize;
+
+        /// Length in bytes of an encoded signature
+        fn signature_len(&self) -> usize;
+    }
+
+    /// The ed25519 scheme backing `sign_ed25519`
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+    pub struct Ed25519;
+
+    impl SignatureScheme for Ed25519 {
+        fn sign(&self, msg: &[u8], sk: &[u8]) -> Option<Vec<u8>> {
+            ring::signature::Ed25519KeyPair::from_pkcs8(sk).ok()?;
+            let sk = SecretKey::from_slice(sk)?;
+            Some(sign_ed25519::sign_detached(msg, &sk).as_ref().to_vec())
+        }
+
+        fn verify(&self, sig: &[u8], msg: &[u8], pk: &[u8]) -> bool {
+            match (Signature::from_slice(sig), PublicKey::from_slice(pk)) {
+                (Some(sig), Some(pk)) => sign_ed25519::verify_detached(&sig, msg, &pk),
+                _ => false,
+            }
+        }
+
+        fn public_key_len(&self) -> usize {
+            ED25519_PUBLIC_KEY_LEN
+        }
+
+        fn signature_len(&self) -> usize {
+            ED25519_SIGNATURE_LEN
+        }
+    }
+
+    /// Tag selecting the signature scheme signature opcodes verify with
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+    pub enum SignatureSchemeId {
+        #[default]
+        Ed25519,
+    }
+
+    impl SignatureSchemeId {
+        /// Returns the implementation of the tagged scheme
+        pub fn scheme(&self) -> &'static dyn SignatureScheme {
+            match self {
+                Self::Ed25519 => &Ed25519,
+            }
+        }
+    }
+}
+
 pub mod secretbox_chacha20_poly1305 {
     // Use key and nonce separately like rust-tls does
     use super::{deserialize_slice, generate_random};
@@ -266,6 +439,54 @@ pub mod sha3_256 {
     }
 }
 
+pub mod sha256 {
+    pub use ring::digest::Digest;
+    use ring::digest::SHA256;
+
+    pub fn digest(data: &[u8]) -> Digest {
+        ring::digest::digest(&SHA256, data)
+    }
+}
+
+pub mod ripemd160 {
+    pub use ::ripemd160::digest::Output;
+    pub use ::ripemd160::Digest;
+    pub use ::ripemd160::Ripemd160;
+
+    pub fn digest(data: &[u8]) -> Output<Ripemd160> {
+        Ripemd160::digest(data)
+    }
+}
+
+/// Serializes a byte array as a hex string for human-readable formats such as JSON,
+/// and as a length-prefixed slice otherwise. Used for the public keys and signatures
+/// found in transactions, so that their JSON is human-inspectable
+fn serialize_hex_slice<S: serde::Serializer>(
+    value: &[u8],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    if serializer.is_human_readable() {
+        serializer.serialize_str(&hex::encode(value))
+    } else {
+        serde::Serialize::serialize(value, serializer)
+    }
+}
+
+/// Deserializes a byte array serialized by `serialize_hex_slice`
+fn deserialize_hex_slice<'de, D: serde::Deserializer<'de>, const N: usize>(
+    deserializer: D,
+) -> Result<[u8; N], D::Error> {
+    if !deserializer.is_human_readable() {
+        return deserialize_slice(deserializer);
+    }
+    let value: String = serde::Deserialize::deserialize(deserializer)?;
+    let value = hex::decode(value).map_err(serde::de::Error::custom)?;
+    value
+        .as_slice()
+        .try_into()
+        .map_err(|e| serde::de::Error::custom(format!("Invalid array: {e:?}")))
+}
+
 fn deserialize_slice<'de, D: serde::Deserializer<'de>, const N: usize>(
     deserializer: D,
 ) -> Result<[u8; N], D::Error> {
@@ -275,6 +496,17 @@ fn deserialize_slice<'de, D: serde::Deserializer<'de>, const N: usize>(
         .map_err(|e| serde::de::Error::custom(format!("Invalid array: {e:?}")))
 }
 
+/// Compares two strings in time independent of their contents, for comparisons that
+/// touch secret-derived data. Only the lengths may leak through timing
+///
+/// ### Arguments
+///
+/// * `a`   - First string to compare
+/// * `b`   - Second string to compare
+pub fn constant_time_eq(a: &str, b: &str) -> bool {
+    ring::constant_time::verify_slices_are_equal(a.as_bytes(), b.as_bytes()).is_ok()
+}
+
 pub fn generate_random<const N: usize>() -> [u8; N] {
     let mut value: [u8; N] = [0; N];
 
@@ -284,3 +516,78 @@ pub fn generate_random<const N: usize>() -> [u8; N] {
 
     value
 }
+
+/*---- TESTS ----*/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// Checks that constant-time comparison agrees with `==`
+    fn should_match_eq_in_constant_time_eq() {
+        for _ in 0..1000 {
+            let [len_a, len_b, flip]: [u8; 3] = generate_random();
+            let a = hex::encode(&generate_random::<8>()[..(len_a % 9) as usize]);
+            let mut b = a.clone();
+            if flip % 3 == 1 {
+                b = hex::encode(&generate_random::<8>()[..(len_b % 9) as usize]);
+            } else if flip % 3 == 2 && !b.is_empty() {
+                b.replace_range(..1, if b.starts_with('0') { "1" } else { "0" });
+            }
+
+            assert_eq!(constant_time_eq(&a, &b), a == b);
+        }
+    }
+
+    #[test]
+    /// Checks that standalone messages verify only with an unmalleated signature from the signing key
+    fn should_verify_message() {
+        use sign_ed25519::*;
+        let (pk, sk) = gen_keypair();
+        let (other_pk, _) = gen_keypair();
+        let msg = b"signed by a wallet";
+        let sig = sign_detached(msg, &sk);
+
+        // Adding the group order L to S gives a non-canonical encoding of the same signature,
+        // which ring already rejects as S is not reduced
+        let l = [
+            0xed, 0xd3, 0xf5, 0x5c, 0x1a, 0x63, 0x12, 0x58, 0xd6, 0x9c, 0xf7, 0xa2, 0xde, 0xf9,
+            0xde, 0x14, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x10,
+        ];
+        let mut malleated = sig.as_ref().to_vec();
+        let mut carry = 0;
+        for (b, l) in malleated[32..].iter_mut().zip(l.iter()) {
+            let sum = *b as u16 + *l as u16 + carry;
+            *b = sum as u8;
+            carry = sum >> 8;
+        }
+        let malleated = Signature::from_slice(&malleated).unwrap();
+
+        assert!(verify_message(msg, &sig, &pk));
+        assert!(!verify_message(b"tampered", &sig, &pk));
+        assert!(!verify_message(msg, &sig, &other_pk));
+        assert!(!verify_detached(&malleated, msg, &pk));
+        assert!(!verify_message(msg, &malleated, &pk));
+    }
+
+    #[test]
+    /// Checks that an aggregate signature verifies against the aggregate of the signers' keys only
+    fn should_verify_aggregate_signature() {
+        use sign_ed25519::*;
+        let keys: Vec<_> = (0..3).map(|_| gen_keypair()).collect();
+        let (pks, sks): (Vec<_>, Vec<_>) = keys.into_iter().unzip();
+        let msg = b"aggregate";
+
+        let agg_pk = aggregate_public_keys(&pks).unwrap();
+        let agg_sig = sign_aggregate(msg, &sks).unwrap();
+        let partial_pk = aggregate_public_keys(&pks[..2]).unwrap();
+
+        assert!(verify_detached(&agg_sig, msg, &agg_pk));
+        assert!(!verify_detached(&agg_sig, b"other", &agg_pk));
+        assert!(!verify_detached(&agg_sig, msg, &partial_pk));
+        assert_eq!(aggregate_public_keys(&pks[..1]), Some(pks[0]));
+        assert_eq!(aggregate_public_keys(&[]), None);
+        assert_eq!(sign_aggregate(msg, &[]), None);
+    }
+}
@@ -1,12 +1,72 @@
+use crate::constants::{
+    ASM_TRUNCATE_LEN, MAX_METADATA_BYTES, MAX_METADATA_BYTES_V0, NETWORK_VERSION_V0,
+    RECEIPT_METADATA_REQUIRED_KEYS,
+};
 use crate::primitives::transaction::OutPoint;
 use crate::utils::{add_btreemap, format_for_display};
 use serde::{Deserialize, Serialize};
-use std::{collections::BTreeMap, fmt, iter, mem::size_of, ops};
+use std::collections::{BTreeMap, BTreeSet};
+use std::{error, fmt, iter, mem::size_of, ops};
 
 /// A structure representing the amount of tokens in an instance
 #[derive(Deserialize, Serialize, Default, Debug, Copy, Clone, Eq, PartialEq, PartialOrd, Ord)]
 pub struct TokenAmount(pub u64);
 
+impl TokenAmount {
+    /// Adds two amounts, returning `None` on overflow
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        self.0.checked_add(other.0).map(Self)
+    }
+
+    /// Subtracts `other` from this amount, returning `None` on underflow
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        self.0.checked_sub(other.0).map(Self)
+    }
+
+    /// Subtracts `other` from this amount, stopping at zero
+    pub fn saturating_sub(self, other: Self) -> Self {
+        Self(self.0.saturating_sub(other.0))
+    }
+
+    /// Splits this amount into a payment and the change left over
+    ///
+    /// ### Arguments
+    ///
+    /// * `payment` - Amount to pay out of this amount
+    pub fn split_payment(self, payment: Self) -> Result<(Self, Self), ChangeError> {
+        match self.checked_sub(payment) {
+            Some(change) => Ok((payment, change)),
+            None => Err(ChangeError::InsufficientFunds {
+                available: self,
+                payment,
+            }),
+        }
+    }
+}
+
+/// Errors raised when splitting a `TokenAmount` into payment and change
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeError {
+    InsufficientFunds {
+        available: TokenAmount,
+        payment: TokenAmount,
+    },
+}
+
+impl fmt::Display for ChangeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::InsufficientFunds { available, payment } => write!(
+                f,
+                "Payment of {} exceeds available {}",
+                payment.0, available.0
+            ),
+        }
+    }
+}
+
+impl error::Error for ChangeError {}
+
 impl fmt::Display for TokenAmount {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let format_result = format_for_display(&self.0);
@@ -92,8 +152,75 @@ impl ReceiptAsset {
             metadata,
         }
     }
+
+    /// Validates the receipt's metadata, if any. Metadata must fit in
+    /// `MAX_METADATA_BYTES`, and metadata that looks like a JSON object must parse
+    /// and contain every key in `RECEIPT_METADATA_REQUIRED_KEYS`
+    pub fn validate_metadata(&self) -> Result<(), MetadataError> {
+        self.validate_metadata_for_version(None)
+    }
+
+    /// Validates the receipt's metadata as `validate_metadata`, with the size limit
+    /// of the given version
+    ///
+    /// ### Arguments
+    ///
+    /// * `version` - Address version of the creating script, None being the current version
+    pub fn validate_metadata_for_version(&self, version: Option<u64>) -> Result<(), MetadataError> {
+        let metadata = match &self.metadata {
+            Some(metadata) => metadata,
+            None => return Ok(()),
+        };
+        if metadata.len() > metadata_limit_for_version(version) {
+            return Err(MetadataError::TooLarge);
+        }
+        if !metadata.trim_start().starts_with('{') {
+            return Ok(());
+        }
+        let value: serde_json::Value =
+            serde_json::from_str(metadata).map_err(|_| MetadataError::InvalidJson)?;
+        match RECEIPT_METADATA_REQUIRED_KEYS
+            .iter()
+            .find(|key| value.get(**key).is_none())
+        {
+            Some(key) => Err(MetadataError::MissingKey(key.to_string())),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Gets the maximum receipt metadata size in bytes for a version
+///
+/// ### Arguments
+///
+/// * `version` - Address version of the creating script, None being the current version
+pub fn metadata_limit_for_version(version: Option<u64>) -> usize {
+    match version {
+        Some(NETWORK_VERSION_V0) => MAX_METADATA_BYTES_V0,
+        _ => MAX_METADATA_BYTES,
+    }
+}
+
+/// Errors raised when validating receipt metadata
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MetadataError {
+    TooLarge,
+    InvalidJson,
+    MissingKey(String),
 }
 
+impl fmt::Display for MetadataError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::TooLarge => write!(f, "Metadata exceeds the metadata byte limit"),
+            Self::InvalidJson => write!(f, "Metadata is not valid JSON"),
+            Self::MissingKey(key) => write!(f, "Metadata is missing required key: {key}"),
+        }
+    }
+}
+
+impl error::Error for MetadataError {}
+
 /// Data asset struct
 #[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
 pub struct DataAsset {
@@ -106,11 +233,47 @@ pub struct DataAsset {
 /// * `Token`   - An asset struct representation of the ZNT token
 /// * `Data`    - A data asset
 /// * `Receipt` - A receipt for a payment. The value indicates the number of receipt assets
+/// * `Bundle`  - Several assets paid to the same address by a single output
 #[derive(Deserialize, Serialize, Debug, Clone, Eq, Ord, PartialEq, PartialOrd)]
 pub enum Asset {
     Token(TokenAmount),
     Data(DataAsset),
     Receipt(ReceiptAsset),
+    Bundle(Vec<BundleMember>),
+}
+
+/// Asset held by a `Bundle`. Bundles cannot be nested, so this mirrors `Asset`
+/// without its `Bundle` variant
+#[derive(Deserialize, Serialize, Debug, Clone, Eq, Ord, PartialEq, PartialOrd)]
+pub enum BundleMember {
+    Token(TokenAmount),
+    Data(DataAsset),
+    Receipt(ReceiptAsset),
+}
+
+impl From<BundleMember> for Asset {
+    fn from(member: BundleMember) -> Self {
+        match member {
+            BundleMember::Token(tokens) => Asset::Token(tokens),
+            BundleMember::Data(data) => Asset::Data(data),
+            BundleMember::Receipt(receipt) => Asset::Receipt(receipt),
+        }
+    }
+}
+
+impl BundleMember {
+    pub fn token_u64(amount: u64) -> Self {
+        BundleMember::Token(TokenAmount(amount))
+    }
+
+    pub fn receipt(amount: u64, drs_tx_hash: Option<String>, metadata: Option<String>) -> Self {
+        BundleMember::Receipt(ReceiptAsset::new(amount, drs_tx_hash, metadata))
+    }
+
+    /// Get the member as a standalone `Asset`
+    pub fn to_asset(&self) -> Asset {
+        self.clone().into()
+    }
 }
 
 impl Default for Asset {
@@ -119,24 +282,72 @@ impl Default for Asset {
     }
 }
 
+impl fmt::Display for Asset {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.render(true))
+    }
+}
+
 impl Asset {
     /// Modify `self` of `Asset` struct to obtain `drs_tx_hash`
     /// from either the asset itself or its corresponding `OutPoint`
     pub fn with_fixed_hash(mut self, out_point: &OutPoint) -> Self {
-        if let Asset::Receipt(ref mut receipt_asset) = self {
-            if receipt_asset.drs_tx_hash.is_none() {
+        match self {
+            Asset::Receipt(ref mut receipt_asset) if receipt_asset.drs_tx_hash.is_none() => {
                 receipt_asset.drs_tx_hash = Some(&out_point.t_hash).cloned();
             }
+            Asset::Bundle(ref mut members) => {
+                for member in members {
+                    if let BundleMember::Receipt(receipt_asset) = member {
+                        if receipt_asset.drs_tx_hash.is_none() {
+                            receipt_asset.drs_tx_hash = Some(out_point.t_hash.clone());
+                        }
+                    }
+                }
+            }
+            _ => {}
         }
         self
     }
 
+    /// Obtain the `Asset` to on-spend `self` with: a `Receipt` of the same amount that
+    /// keeps its `drs_tx_hash`, or takes `drs_tx_hash` if it has none, with its metadata
+    /// cleared. Receipts in a `Bundle` are treated alike, and other variants are returned unchanged
+    ///
+    /// ### Arguments
+    ///
+    /// * `drs_tx_hash`  - DRS transaction hash to inherit if `self` has none
+    pub fn on_spend_receipt(&self, drs_tx_hash: String) -> Asset {
+        match self {
+            Asset::Receipt(receipt) => Asset::receipt(
+                receipt.amount,
+                Some(receipt.drs_tx_hash.clone().unwrap_or(drs_tx_hash)),
+                None,
+            ),
+            Asset::Bundle(members) => Asset::Bundle(
+                members
+                    .iter()
+                    .map(|member| match member {
+                        BundleMember::Receipt(receipt) => BundleMember::receipt(
+                            receipt.amount,
+                            Some(receipt.drs_tx_hash.clone().unwrap_or(drs_tx_hash.clone())),
+                            None,
+                        ),
+                        _ => member.clone(),
+                    })
+                    .collect(),
+            ),
+            _ => self.clone(),
+        }
+    }
+
     /// Get optional `drs_tx_hash` value for `Asset`
     pub fn get_drs_tx_hash(&self) -> Option<&String> {
         match self {
             Asset::Token(_) => None,
             Asset::Data(_) => None, /* TODO: This will have to change */
             Asset::Receipt(receipt) => receipt.drs_tx_hash.as_ref(),
+            Asset::Bundle(_) => None, /* Members may each have their own */
         }
     }
 
@@ -145,6 +356,10 @@ impl Asset {
             Asset::Token(_) => None,
             Asset::Data(_) => None,
             Asset::Receipt(receipt) => receipt.metadata.as_ref(),
+            Asset::Bundle(members) => members.iter().find_map(|member| match member {
+                BundleMember::Receipt(receipt) => receipt.metadata.as_ref(),
+                _ => None,
+            }),
         }
     }
 
@@ -153,9 +368,37 @@ impl Asset {
             Asset::Token(_) => size_of::<TokenAmount>(),
             Asset::Data(d) => d.data.len(),
             Asset::Receipt(_) => size_of::<u64>(),
+            Asset::Bundle(members) => members.iter().map(|m| m.to_asset().len()).sum(),
+        }
+    }
+
+    /// Get the assets carried by `self`: the members of a `Bundle`, or `self` otherwise
+    pub fn members(&self) -> Vec<Asset> {
+        match self {
+            Asset::Bundle(members) => members.iter().map(BundleMember::to_asset).collect(),
+            _ => vec![self.clone()],
         }
     }
 
+    /// Checks that a `Bundle` holds at least two assets, and at most one `Token`,
+    /// one `Receipt` per `drs_tx_hash` and one `Data` per payload.
+    /// Other variants are always well formed
+    pub fn bundle_is_well_formed(&self) -> bool {
+        let members = match self {
+            Asset::Bundle(members) => members,
+            _ => return true,
+        };
+        let mut kinds = BTreeSet::new();
+        members.len() > 1
+            && members.iter().all(|member| match member {
+                BundleMember::Token(_) => kinds.insert((0, Vec::new())),
+                BundleMember::Receipt(r) => {
+                    kinds.insert((1, r.drs_tx_hash.clone().unwrap_or_default().into_bytes()))
+                }
+                BundleMember::Data(d) => kinds.insert((2, d.data.clone())),
+            })
+    }
+
     pub fn token_u64(amount: u64) -> Self {
         Asset::Token(TokenAmount(amount))
     }
@@ -170,7 +413,8 @@ impl Asset {
     /// ### Note
     ///
     /// This function will return false for `Receipt` assets
-    /// getting added together that do not have the same `drs_tx_hash`
+    /// getting added together that do not have the same `drs_tx_hash`,
+    /// or if the sum overflows
     ///
     /// ### Arguments
     ///
@@ -178,14 +422,20 @@ impl Asset {
     pub fn add_assign(&mut self, rhs: &Self) -> bool {
         match (self, rhs) {
             (Asset::Token(lhs_tokens), Asset::Token(rhs_tokens)) => {
-                *lhs_tokens += *rhs_tokens;
+                match lhs_tokens.checked_add(*rhs_tokens) {
+                    Some(sum) => *lhs_tokens = sum,
+                    None => return false,
+                }
                 true
             }
             (Asset::Receipt(lhs_receipts), Asset::Receipt(rhs_receipts)) => {
                 if lhs_receipts.drs_tx_hash != rhs_receipts.drs_tx_hash {
                     return false;
                 }
-                lhs_receipts.amount += rhs_receipts.amount;
+                match lhs_receipts.amount.checked_add(rhs_receipts.amount) {
+                    Some(sum) => lhs_receipts.amount = sum,
+                    None => return false,
+                }
                 true
             }
             _ => false,
@@ -291,6 +541,9 @@ impl Asset {
     pub fn token_amount(&self) -> TokenAmount {
         match self {
             Asset::Token(v) => *v,
+            Asset::Bundle(members) => members.iter().fold(TokenAmount(0), |sum, member| {
+                TokenAmount(sum.0.saturating_add(member.to_asset().token_amount().0))
+            }),
             _ => TokenAmount(0),
         }
     }
@@ -298,29 +551,83 @@ impl Asset {
     pub fn receipt_amount(&self) -> u64 {
         match self {
             Asset::Receipt(v) => v.amount,
+            Asset::Bundle(members) => members.iter().fold(0, |sum, m| {
+                sum.saturating_add(m.to_asset().receipt_amount())
+            }),
             _ => 0,
         }
     }
+
+    /// Renders the asset as a short human-readable summary, truncating long DRS hashes
+    ///
+    /// Example: 3 receipts of DRS 0123abcd..4567cdef
+    pub fn summary(&self) -> String {
+        self.render(false)
+    }
+
+    /// Renders the asset for display: tokens as a ZNT amount, receipts with their count
+    /// and DRS hash, data assets with their count and size, and bundles as a list
+    ///
+    /// ### Arguments
+    ///
+    /// * `full`    - Whether long DRS hashes are kept in full
+    fn render(&self, full: bool) -> String {
+        let plural = |amount: u64, noun: &str| match amount {
+            1 => format!("{amount} {noun}"),
+            _ => format!("{amount} {noun}s"),
+        };
+        match self {
+            Asset::Token(tokens) => format!("{tokens} ZNT"),
+            Asset::Receipt(r) => {
+                let drs = match &r.drs_tx_hash {
+                    Some(h) if !full && h.is_ascii() && h.len() > 2 * ASM_TRUNCATE_LEN => format!(
+                        "{}..{}",
+                        &h[..ASM_TRUNCATE_LEN],
+                        &h[h.len() - ASM_TRUNCATE_LEN..]
+                    ),
+                    Some(h) => h.clone(),
+                    None => "pending".to_owned(),
+                };
+                format!("{} of DRS {drs}", plural(r.amount, "receipt"))
+            }
+            Asset::Data(d) => format!(
+                "{} of {} bytes",
+                plural(d.amount, "data asset"),
+                d.data.len()
+            ),
+            Asset::Bundle(members) => {
+                let assets: Vec<String> =
+                    members.iter().map(|m| m.to_asset().render(full)).collect();
+                format!("[{}]", assets.join(", "))
+            }
+        }
+    }
 }
 
-/// `AssetValue` struct used to represent the a running total of `Token` and `Receipt` assets
+/// `AssetValue` struct used to represent the a running total of `Token`, `Receipt` and `Data` assets
 #[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct AssetValues {
     pub tokens: TokenAmount,
     // Note: Receipts from create transactions will have `drs_tx_hash` = `t_hash`
     pub receipts: BTreeMap<String, u64>, /* `drs_tx_hash` - amount */
+    pub data: BTreeMap<Vec<u8>, u64>,    /* `data` - amount */
 }
 
 impl ops::AddAssign for AssetValues {
     fn add_assign(&mut self, rhs: Self) {
         self.tokens += rhs.tokens;
         add_btreemap(&mut self.receipts, rhs.receipts);
+        add_btreemap(&mut self.data, rhs.data);
     }
 }
 
 impl AssetValues {
     pub fn new(tokens: TokenAmount, receipts: BTreeMap<String, u64>) -> Self {
-        Self { tokens, receipts }
+        Self {
+            tokens,
+            receipts,
+            data: Default::default(),
+        }
     }
 
     pub fn token_u64(tokens: u64) -> Self {
@@ -336,7 +643,26 @@ impl AssetValues {
     }
 
     pub fn is_equal(&self, rhs: &AssetValues) -> bool {
-        self.tokens == rhs.tokens && self.receipts == rhs.receipts
+        self.tokens == rhs.tokens && self.receipts == rhs.receipts && self.data == rhs.data
+    }
+
+    /// Describes, per asset type, how far `self` is over or under `other`
+    ///
+    /// ### Arguments
+    ///
+    /// * `other`   - Running total to compare against
+    pub fn diff(&self, other: &AssetValues) -> AssetValuesDiff {
+        let mut diff = AssetValuesDiff::default();
+        if self.tokens > other.tokens {
+            diff.over.tokens = self.tokens - other.tokens;
+        } else {
+            diff.under.tokens = other.tokens - self.tokens;
+        }
+        diff_btreemap(&mut diff.over.receipts, &self.receipts, &other.receipts);
+        diff_btreemap(&mut diff.under.receipts, &other.receipts, &self.receipts);
+        diff_btreemap(&mut diff.over.data, &self.data, &other.data);
+        diff_btreemap(&mut diff.under.data, &other.data, &self.data);
+        diff
     }
 
     // See if the running total is enough for a required `Asset` amount
@@ -352,38 +678,212 @@ impl AssetValues {
                     false
                 }
             }
-            _ => false,
+            Asset::Data(data) => {
+                matches!(self.data.get(&data.data), Some(amount) if *amount >= data.amount)
+            }
+            Asset::Bundle(members) => members.iter().all(|m| self.has_enough(&m.to_asset())),
         }
     }
 
-    /// Add the `rhs` parameter to `self`
+    /// Add the `rhs` parameter to `self`, saturating at the maximum amount
     pub fn update_add(&mut self, rhs: &Asset) {
+        if !self.checked_update_add(rhs) {
+            match rhs {
+                Asset::Token(_) => self.tokens = TokenAmount(u64::MAX),
+                Asset::Receipt(receipts) => {
+                    if let Some(drs_tx_hash) = &receipts.drs_tx_hash {
+                        self.receipts.insert(drs_tx_hash.clone(), u64::MAX);
+                    }
+                }
+                Asset::Data(data) => {
+                    self.data.insert(data.data.clone(), u64::MAX);
+                }
+                Asset::Bundle(members) => {
+                    members.iter().for_each(|m| self.update_add(&m.to_asset()))
+                }
+            }
+        }
+    }
+
+    /// Add the `rhs` parameter to `self`, returning false and leaving `self`
+    /// unchanged if the sum overflows
+    pub fn checked_update_add(&mut self, rhs: &Asset) -> bool {
         match rhs {
-            Asset::Token(tokens) => self.tokens += *tokens,
+            Asset::Token(tokens) => match self.tokens.checked_add(*tokens) {
+                Some(sum) => self.tokens = sum,
+                None => return false,
+            },
             Asset::Receipt(receipts) => {
                 if let Some(drs_tx_hash) = &receipts.drs_tx_hash {
-                    self.receipts
-                        .entry(drs_tx_hash.clone())
-                        .and_modify(|amount| *amount += receipts.amount)
-                        .or_insert(receipts.amount);
+                    let amount = self.receipts.entry(drs_tx_hash.clone()).or_default();
+                    match amount.checked_add(receipts.amount) {
+                        Some(sum) => *amount = sum,
+                        None => return false,
+                    }
                 }
             }
-            _ => {}
+            Asset::Data(data) => {
+                let amount = self.data.entry(data.data.clone()).or_default();
+                match amount.checked_add(data.amount) {
+                    Some(sum) => *amount = sum,
+                    None => return false,
+                }
+            }
+            Asset::Bundle(members) => {
+                let mut sum = self.clone();
+                if !members
+                    .iter()
+                    .all(|m| sum.checked_update_add(&m.to_asset()))
+                {
+                    return false;
+                }
+                *self = sum;
+            }
         }
+        true
     }
 
-    // Subtract the `rhs` parameter from `self`
+    // Subtract the `rhs` parameter from `self`, stopping at zero
     pub fn update_sub(&mut self, rhs: &Asset) {
         match rhs {
-            Asset::Token(tokens) => self.tokens -= *tokens,
+            Asset::Token(tokens) => self.tokens = self.tokens.saturating_sub(*tokens),
             Asset::Receipt(receipts) => {
                 receipts.drs_tx_hash.as_ref().and_then(|drs_tx_hash| {
                     self.receipts
                         .get_mut(drs_tx_hash)
-                        .map(|amount| *amount -= receipts.amount)
+                        .map(|amount| *amount = amount.saturating_sub(receipts.amount))
                 });
             }
-            _ => {}
+            Asset::Data(data) => {
+                if let Some(amount) = self.data.get_mut(&data.data) {
+                    *amount = amount.saturating_sub(data.amount);
+                }
+            }
+            Asset::Bundle(members) => members.iter().for_each(|m| self.update_sub(&m.to_asset())),
         }
     }
 }
+
+/// Per asset type difference between two `AssetValues`: the amounts by which one
+/// running total is over and under the other
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct AssetValuesDiff {
+    pub over: AssetValues,
+    pub under: AssetValues,
+}
+
+impl AssetValuesDiff {
+    /// Whether the compared running totals are equal
+    pub fn is_empty(&self) -> bool {
+        self.over.is_empty() && self.under.is_empty()
+    }
+}
+
+impl fmt::Display for AssetValuesDiff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut parts = Vec::new();
+        for (direction, values) in [("over", &self.over), ("under", &self.under)] {
+            if values.tokens > TokenAmount(0) {
+                parts.push(format!("tokens {} by {}", direction, values.tokens.0));
+            }
+            for (drs_tx_hash, amount) in &values.receipts {
+                parts.push(format!(
+                    "receipts {} {} by {}",
+                    drs_tx_hash, direction, amount
+                ));
+            }
+            for (data, amount) in &values.data {
+                parts.push(format!(
+                    "data {} {} by {}",
+                    hex::encode(data),
+                    direction,
+                    amount
+                ));
+            }
+        }
+
+        if parts.is_empty() {
+            write!(f, "equal")
+        } else {
+            write!(f, "{}", parts.join(", "))
+        }
+    }
+}
+
+/// Inserts into `diff` the amount by which each entry of `lhs` exceeds `rhs`
+///
+/// ### Arguments
+///
+/// * `diff`    - Map receiving the excess amounts
+/// * `lhs`     - Running totals to compare
+/// * `rhs`     - Running totals to compare against
+fn diff_btreemap<K: Ord + Clone>(
+    diff: &mut BTreeMap<K, u64>,
+    lhs: &BTreeMap<K, u64>,
+    rhs: &BTreeMap<K, u64>,
+) {
+    for (key, amount) in lhs {
+        let excess = amount.saturating_sub(rhs.get(key).copied().unwrap_or_default());
+        if excess > 0 {
+            diff.insert(key.clone(), excess);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// Checks that each asset type is rendered for display
+    fn should_display_assets() {
+        let drs_tx_hash = "0123456789abcdef0123456789abcdef".to_owned();
+        let receipt = Asset::receipt(3, Some(drs_tx_hash.clone()), None);
+        let data = Asset::Data(DataAsset {
+            data: vec![0; 15],
+            amount: 1,
+        });
+
+        assert_eq!(Asset::token_u64(25200).to_string(), "1 ZNT");
+        assert_eq!(
+            receipt.to_string(),
+            format!("3 receipts of DRS {drs_tx_hash}")
+        );
+        assert_eq!(receipt.summary(), "3 receipts of DRS 01234567..89abcdef");
+        assert_eq!(
+            Asset::receipt(1, None, None).to_string(),
+            "1 receipt of DRS pending"
+        );
+        assert_eq!(data.to_string(), "1 data asset of 15 bytes");
+        assert_eq!(
+            Asset::Bundle(vec![
+                BundleMember::token_u64(12600),
+                BundleMember::receipt(3, Some(drs_tx_hash.to_owned()), None)
+            ])
+            .summary(),
+            "[0.5 ZNT, 3 receipts of DRS 01234567..89abcdef]"
+        );
+    }
+
+    #[test]
+    /// Checks that an amount is split into payment and change, or fails when insufficient
+    fn should_split_payment() {
+        let amount = TokenAmount(33);
+
+        assert_eq!(
+            amount.split_payment(TokenAmount(33)),
+            Ok((TokenAmount(33), TokenAmount(0)))
+        );
+        assert_eq!(
+            amount.split_payment(TokenAmount(11)),
+            Ok((TokenAmount(11), TokenAmount(22)))
+        );
+        assert_eq!(
+            amount.split_payment(TokenAmount(34)),
+            Err(ChangeError::InsufficientFunds {
+                available: amount,
+                payment: TokenAmount(34),
+            })
+        );
+    }
+}
@@ -1,5 +1,8 @@
+use crate::crypto::sha3_256;
 use crate::primitives::asset::Asset;
+use bincode::serialize;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
 
 /// The expectation to be met in a specific DRUID transaction
 #[derive(Default, Clone, Debug, Ord, Eq, PartialEq, Serialize, Deserialize, PartialOrd)]
@@ -28,4 +31,26 @@ impl DdeValues {
     pub fn new() -> Self {
         Default::default()
     }
+
+    /// Derives the DRUID the participants should share from the participant count and
+    /// expectations, matching `compute_druid` over transactions that each declare them
+    pub fn expected_druid(&self) -> String {
+        construct_druid(self.participants, self.expectations.iter())
+    }
+}
+
+/// Constructs a DRUID as the hex encoded SHA3-256 of the participant count and the
+/// sorted, deduplicated expectations
+///
+/// ### Arguments
+///
+/// * `participants`    - Number of participating transactions
+/// * `expectations`    - Expectations to be met by the participants
+pub fn construct_druid<'a>(
+    participants: usize,
+    expectations: impl Iterator<Item = &'a DruidExpectation>,
+) -> String {
+    let expectations: BTreeSet<&DruidExpectation> = expectations.collect();
+    let bytes = serialize(&(participants as u64, expectations)).unwrap_or_default();
+    hex::encode(sha3_256::digest(&bytes))
 }
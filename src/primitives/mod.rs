@@ -2,3 +2,4 @@ pub mod asset;
 pub mod block;
 pub mod druid;
 pub mod transaction;
+pub mod utxo;
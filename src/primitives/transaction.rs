@@ -1,16 +1,23 @@
 #![allow(unused)]
 use crate::constants::*;
+use crate::crypto::sha3_256;
 use crate::crypto::sign_ed25519::{PublicKey, Signature};
 use crate::primitives::{
-    asset::{Asset, ReceiptAsset, TokenAmount},
+    asset::{Asset, AssetValues, DataAsset, ReceiptAsset, TokenAmount},
     druid::{DdeValues, DruidExpectation},
+    utxo::UtxoSet,
 };
 use crate::script::lang::Script;
 use crate::script::{OpCodes, StackEntry};
 use crate::utils::is_valid_amount;
-use bincode::serialize;
+use crate::utils::transaction_utils::{
+    construct_address_for, construct_p2sh_address, construct_tx_hash,
+};
+use bincode::{deserialize, serialize};
 use bytes::Bytes;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::mem::size_of;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum DrsTxHashSpec {
@@ -140,15 +147,69 @@ impl TxOut {
         }
     }
 
-    //TODO: Add handling for `Data' asset variant
+    /// Creates a new TxOut instance for a `Data` asset
+    pub fn new_data_amount(to_address: String, data: DataAsset) -> TxOut {
+        TxOut {
+            value: Asset::Data(data),
+            script_public_key: Some(to_address),
+            ..Default::default()
+        }
+    }
+
     pub fn new_asset(to_address: String, asset: Asset) -> TxOut {
         match asset {
             Asset::Token(amount) => TxOut::new_token_amount(to_address, amount),
             Asset::Receipt(receipt) => TxOut::new_receipt_amount(to_address, receipt),
-            _ => panic!("Cannot create TxOut for asset of type {:?}", asset),
+            Asset::Data(data) => TxOut::new_data_amount(to_address, data),
+            Asset::Bundle(_) => TxOut {
+                value: asset,
+                script_public_key: Some(to_address),
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Creates a new zero value TxOut embedding data in a `Script::data_carrier` script,
+    /// which is held hex encoded in place of an address
+    ///
+    /// ### Arguments
+    ///
+    /// * `data`    - Data to embed
+    pub fn new_data_carrier(data: Vec<u8>) -> TxOut {
+        let script = Script::data_carrier(data);
+        TxOut {
+            script_public_key: Some(hex::encode(script.to_bytes())),
+            ..Default::default()
         }
     }
 
+    /// Gets the data embedded by a data-carrier TxOut, if it is one
+    pub fn data_carrier_payload(&self) -> Option<Vec<u8>> {
+        let bytes = hex::decode(self.script_public_key.as_ref()?).ok()?;
+        deserialize::<Script>(&bytes).ok()?.data_carrier_payload()
+    }
+
+    /// Creates a new TxOut destroying receipts of a DRS group, locked by a
+    /// `Script::receipt_burn_lock` script held hex encoded in place of an address
+    ///
+    /// ### Arguments
+    ///
+    /// * `amount`      - Number of receipts to burn
+    /// * `drs_tx_hash` - DRS transaction hash of the receipts
+    pub fn new_receipt_burn(amount: u64, drs_tx_hash: String) -> TxOut {
+        TxOut {
+            value: Asset::receipt(amount, Some(drs_tx_hash), None),
+            script_public_key: Some(hex::encode(Script::receipt_burn_lock().to_bytes())),
+            ..Default::default()
+        }
+    }
+
+    /// Whether the TxOut burns its value, being locked by a `Script::receipt_burn_lock` script
+    pub fn is_receipt_burn(&self) -> bool {
+        let burn_lock = hex::encode(Script::receipt_burn_lock().to_bytes());
+        self.script_public_key.as_deref() == Some(burn_lock.as_str())
+    }
+
     /// Returns whether current tx_out is a P2SH
     pub fn is_p2sh_tx_out(&self) -> bool {
         if let Some(pk) = &self.script_public_key {
@@ -158,6 +219,52 @@ impl TxOut {
 
         false
     }
+
+    /// Whether the TxOut pays to the P2PKH address of one of the given public key hashes
+    ///
+    /// ### Arguments
+    ///
+    /// * `pubkey_hashes`   - Addresses of the public keys owned
+    pub fn is_spendable_by(&self, pubkey_hashes: &BTreeSet<String>) -> bool {
+        match &self.script_public_key {
+            Some(address) => !self.is_p2sh_tx_out() && pubkey_hashes.contains(address),
+            None => false,
+        }
+    }
+
+    /// Whether the TxOut pays to the P2SH address of a multisig redeem script for which
+    /// the owned public keys can provide at least the required number of signatures
+    ///
+    /// ### Arguments
+    ///
+    /// * `redeem`          - Multisig redeem script the TxOut is expected to lock to
+    /// * `pubkey_hashes`   - Addresses of the public keys owned, under any network version
+    pub fn is_spendable_by_multisig(
+        &self,
+        redeem: &Script,
+        pubkey_hashes: &BTreeSet<String>,
+    ) -> bool {
+        let m = match redeem.is_multisig() {
+            Some((m, _)) => m,
+            None => return false,
+        };
+        if self.script_public_key.as_ref() != Some(&construct_p2sh_address(redeem)) {
+            return false;
+        }
+
+        let address_versions = [None, Some(NETWORK_VERSION_V0), Some(NETWORK_VERSION_TEMP)];
+        let owned = redeem
+            .stack
+            .iter()
+            .filter(|entry| match entry {
+                StackEntry::PubKey(pk) => address_versions
+                    .iter()
+                    .any(|v| pubkey_hashes.contains(&construct_address_for(pk, *v))),
+                _ => false,
+            })
+            .count();
+        owned >= m
+    }
 }
 
 /// The basic transaction that is broadcasted on the network and contained in
@@ -187,6 +294,20 @@ impl Transaction {
         }
     }
 
+    /// Serializes the transaction to JSON, with opcodes as mnemonics and keys and signatures as hex
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+
+    /// Deserializes a transaction from JSON produced by `to_json`
+    ///
+    /// ### Arguments
+    ///
+    /// * `json`    - JSON representation of the transaction
+    pub fn from_json(json: &str) -> serde_json::Result<Transaction> {
+        serde_json::from_str(json)
+    }
+
     /// Get the total transaction size in bytes
     pub fn get_total_size(&self) -> usize {
         let bytes = match serialize(self) {
@@ -196,6 +317,160 @@ impl Transaction {
         bytes.len()
     }
 
+    /// Computes a stable SHA3-256 hash identifying the whole transaction, hex encoded.
+    /// The serialization order is the inputs, the outputs, the version, then the DRUID
+    /// info as its DRUID, participant count and expectations. Expectations are sorted
+    /// first, as their order does not define the transaction. This is not the id referenced
+    /// by `OutPoint`s, which is `construct_tx_hash`
+    pub fn tx_hash(&self) -> String {
+        let druid_info = self.druid_info.as_ref().map(|info| {
+            let mut expectations: Vec<&DruidExpectation> = info.expectations.iter().collect();
+            expectations.sort();
+            (&info.druid, info.participants as u64, expectations)
+        });
+        let bytes = serialize(&(&self.inputs, &self.outputs, self.version as u64, druid_info))
+            .unwrap_or_default();
+        hex::encode(sha3_256::digest(&bytes))
+    }
+
+    /// Computes the hash committed to by OP_CHECKTEMPLATEVERIFY: a SHA3-256 over the
+    /// number of inputs and the serialized outputs, so a covenant fixes who the spending
+    /// transaction pays and how much. Committing to the input count stops two outputs
+    /// under the same covenant from being spent together to pay the template only once
+    pub fn template_hash(&self) -> [u8; 32] {
+        let input_count = (self.inputs.len() as u64).to_le_bytes();
+        let outputs = serialize(&self.outputs).unwrap_or_default();
+        let parts = [&input_count[..], &outputs];
+        sha3_256::digest_all(parts.iter().copied()).into()
+    }
+
+    /// Sorts the outputs into canonical order: by address, then asset, then locktime and
+    /// DRS block hash, so that transactions paying the same outputs hash equally.
+    /// Reordering changes the `OutPoint`s of the outputs and the transaction hash, so this
+    /// must be called before signing. DRUID expectations match outputs by address and
+    /// asset rather than position, but participants must agree on the canonical form
+    /// before exchanging transaction hashes
+    pub fn canonicalize(&mut self) {
+        self.outputs
+            .sort_by(|a, b| canonical_key(a).cmp(&canonical_key(b)));
+    }
+
+    /// Whether the outputs are in the canonical order produced by `canonicalize`
+    pub fn is_canonical(&self) -> bool {
+        self.outputs
+            .windows(2)
+            .all(|pair| canonical_key(&pair[0]) <= canonical_key(&pair[1]))
+    }
+
+    /// Computes the size in bytes of the serialized transaction, for fee calculation.
+    /// Unlike `get_total_size`, the transaction is not serialized to measure it
+    pub fn estimated_size(&self) -> usize {
+        bincode::serialized_size(self).unwrap_or_default() as usize
+    }
+
+    /// Sums the transaction's outputs by asset type: tokens, receipts keyed by DRS
+    /// transaction hash and data assets keyed by their data. Receipts created by this
+    /// transaction are keyed by its own hash
+    pub fn output_totals(&self) -> AssetValues {
+        let mut totals = AssetValues::default();
+        let mut t_hash = None;
+        for (n, tx_out) in self.outputs.iter().enumerate() {
+            let asset = if tx_out.value.is_receipt() && tx_out.value.get_drs_tx_hash().is_none() {
+                let t_hash = t_hash.get_or_insert_with(|| construct_tx_hash(self));
+                let out_point = OutPoint::new(t_hash.clone(), n as i32);
+                tx_out.value.clone().with_fixed_hash(&out_point)
+            } else {
+                tx_out.value.clone()
+            };
+            totals.update_add(&asset);
+        }
+        totals
+    }
+
+    /// Sums the values of the outputs spent by the transaction's inputs by asset type,
+    /// in the same way as `output_totals`. Inputs not found in the UTXO set are skipped
+    ///
+    /// ### Arguments
+    ///
+    /// * `utxo`    - UTXO set holding the outputs spent
+    pub fn input_totals(&self, utxo: &UtxoSet) -> AssetValues {
+        self.input_totals_with(utxo.lookup_fn())
+    }
+
+    /// Sums the values of the outputs spent by the transaction's inputs as `input_totals`
+    /// does, resolving each spent output through a lookup function
+    ///
+    /// ### Arguments
+    ///
+    /// * `lookup`  - Function returning the output an outpoint refers to, if unspent
+    pub fn input_totals_with<'a>(
+        &self,
+        lookup: impl Fn(&OutPoint) -> Option<&'a TxOut>,
+    ) -> AssetValues {
+        let mut totals = AssetValues::default();
+        for out_point in self.spent_outpoints() {
+            if let Some(tx_out) = lookup(out_point) {
+                totals.update_add(&tx_out.value.clone().with_fixed_hash(out_point));
+            }
+        }
+        totals
+    }
+
+    /// Gets the outputs paying to an address, paired with their index in the transaction
+    ///
+    /// ### Arguments
+    ///
+    /// * `address` - Address the outputs pay to
+    pub fn outputs_to(&self, address: &str) -> Vec<(usize, &TxOut)> {
+        self.outputs
+            .iter()
+            .enumerate()
+            .filter(|(_, tx_out)| tx_out.script_public_key.as_deref() == Some(address))
+            .collect()
+    }
+
+    /// Iterates over the `OutPoint`s spent by the transaction's inputs, skipping
+    /// inputs that do not spend a previous output
+    pub fn spent_outpoints(&self) -> impl Iterator<Item = &OutPoint> {
+        self.inputs.iter().filter_map(|i| i.previous_out.as_ref())
+    }
+
+    /// Whether this transaction spends any of the same `OutPoint`s as another
+    ///
+    /// ### Arguments
+    ///
+    /// * `other`   - Transaction to check against
+    pub fn conflicts_with(&self, other: &Transaction) -> bool {
+        let spent: BTreeSet<&OutPoint> = self.spent_outpoints().collect();
+        other
+            .spent_outpoints()
+            .any(|out_point| spent.contains(out_point))
+    }
+
+    /// Whether every input spends an `OutPoint` present in the UTXO set. Checked before
+    /// any signature verification, so transactions spending missing outputs fail early
+    ///
+    /// ### Arguments
+    ///
+    /// * `utxo`    - UTXO set holding the outputs spent
+    pub fn all_inputs_available(&self, utxo: &UtxoSet) -> bool {
+        self.inputs.iter().all(|tx_in| {
+            tx_in
+                .previous_out
+                .as_ref()
+                .is_some_and(|out_point| utxo.contains(out_point))
+        })
+    }
+
+    /// Whether every input's unlocking script is in its canonical form, as checked by
+    /// `Script::is_canonical_unlock`. Otherwise the unlocking scripts could be altered
+    /// without invalidating the transaction, changing its hash
+    pub fn is_non_malleable(&self) -> bool {
+        self.inputs
+            .iter()
+            .all(|tx_in| tx_in.script_signature.is_canonical_unlock())
+    }
+
     /// Gets the create asset assigned to this transaction, if it exists
     fn get_create_asset(&self) -> Option<&Asset> {
         let is_create = self.inputs.len() == 1
@@ -233,3 +508,17 @@ impl Transaction {
         false
     }
 }
+
+/// Key ordering outputs in canonical transactions
+///
+/// ### Arguments
+///
+/// * `tx_out`  - Output to order
+fn canonical_key(tx_out: &TxOut) -> (&Option<String>, &Asset, u64, &Option<String>) {
+    (
+        &tx_out.script_public_key,
+        &tx_out.value,
+        tx_out.locktime,
+        &tx_out.drs_block_hash,
+    )
+}
@@ -0,0 +1,230 @@
+use crate::primitives::transaction::{OutPoint, Transaction, TxOut};
+use crate::utils::transaction_utils::{construct_tx_hash, get_inputs_previous_out_point};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// A set of unspent transaction outputs, indexed by their `OutPoint`
+#[derive(Default, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UtxoSet(BTreeMap<OutPoint, TxOut>);
+
+impl UtxoSet {
+    /// Creates a new, empty UTXO set
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Inserts an unspent `TxOut`, returning the previous value for the `OutPoint` if any
+    ///
+    /// ### Arguments
+    ///
+    /// * `out_point`   - `OutPoint` of the `TxOut`
+    /// * `tx_out`      - Unspent `TxOut`
+    pub fn insert(&mut self, out_point: OutPoint, tx_out: TxOut) -> Option<TxOut> {
+        self.0.insert(out_point, tx_out)
+    }
+
+    /// Removes an unspent `TxOut`, returning it if it was present
+    ///
+    /// ### Arguments
+    ///
+    /// * `out_point`   - `OutPoint` of the `TxOut` to remove
+    pub fn remove(&mut self, out_point: &OutPoint) -> Option<TxOut> {
+        self.0.remove(out_point)
+    }
+
+    /// Gets the unspent `TxOut` for an `OutPoint`
+    ///
+    /// ### Arguments
+    ///
+    /// * `out_point`   - `OutPoint` of the `TxOut`
+    pub fn get(&self, out_point: &OutPoint) -> Option<&TxOut> {
+        self.0.get(out_point)
+    }
+
+    /// Checks whether the set contains an unspent `TxOut` for an `OutPoint`
+    ///
+    /// ### Arguments
+    ///
+    /// * `out_point`   - `OutPoint` of the `TxOut`
+    pub fn contains(&self, out_point: &OutPoint) -> bool {
+        self.0.contains_key(out_point)
+    }
+
+    /// Iterates over the unspent `TxOut`s in `OutPoint` order
+    pub fn iter(&self) -> impl Iterator<Item = (&OutPoint, &TxOut)> {
+        self.0.iter()
+    }
+
+    /// Gets the unspent `TxOut`s paying to an address, in `OutPoint` order
+    ///
+    /// ### Arguments
+    ///
+    /// * `address` - Address the `TxOut`s pay to
+    pub fn outputs_for_address(&self, address: &str) -> Vec<(&OutPoint, &TxOut)> {
+        self.iter()
+            .filter(|(_, tx_out)| tx_out.script_public_key.as_deref() == Some(address))
+            .collect()
+    }
+
+    /// Produces a lookup closure over this set, as expected by `tx_is_valid`
+    pub fn lookup_fn<'a>(&'a self) -> impl Fn(&OutPoint) -> Option<&'a TxOut> + 'a {
+        move |out_point| self.get(out_point)
+    }
+
+    /// Applies a transaction to the set, removing the `TxOut`s it spends
+    /// and inserting the `TxOut`s it creates. Receipt burn `TxOut`s can never be
+    /// spent, so are left out of the set and their receipts out of circulation
+    ///
+    /// ### Arguments
+    ///
+    /// * `tx`  - Transaction to apply
+    pub fn apply_tx(&mut self, tx: &Transaction) {
+        for out_point in get_inputs_previous_out_point(std::iter::once(tx)) {
+            self.remove(out_point);
+        }
+
+        let tx_hash = construct_tx_hash(tx);
+        for (n, tx_out) in tx.outputs.iter().enumerate() {
+            if tx_out.is_receipt_burn() {
+                continue;
+            }
+            self.insert(OutPoint::new(tx_hash.clone(), n as i32), tx_out.clone());
+        }
+    }
+}
+
+impl From<BTreeMap<OutPoint, TxOut>> for UtxoSet {
+    /// Creates a new UTXO set from a map of `OutPoint`s to unspent `TxOut`s
+    fn from(utxo: BTreeMap<OutPoint, TxOut>) -> Self {
+        UtxoSet(utxo)
+    }
+}
+
+/*---- TESTS ----*/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primitives::asset::{Asset, AssetValues, TokenAmount};
+    use crate::utils::script_utils::tx_is_valid;
+    use crate::utils::test_utils::generate_tx_with_ins_and_outs_assets;
+
+    #[test]
+    /// Checks that a transaction validated against a UTXO set is applied to it
+    fn should_validate_and_apply_tx() {
+        let (utxo, tx) = generate_tx_with_ins_and_outs_assets(
+            &[(3, None, None), (2, None, None)],
+            &[(4, None), (1, None)],
+        );
+        let mut utxo_set = UtxoSet::from(utxo);
+        let spent: Vec<OutPoint> = tx
+            .inputs
+            .iter()
+            .map(|tx_in| tx_in.previous_out.clone().unwrap())
+            .collect();
+
+        assert!(spent.iter().all(|out_point| utxo_set.contains(out_point)));
+        assert!(tx_is_valid(&tx, utxo_set.lookup_fn()));
+
+        utxo_set.apply_tx(&tx);
+
+        let tx_hash = construct_tx_hash(&tx);
+        assert!(!spent.iter().any(|out_point| utxo_set.contains(out_point)));
+        assert_eq!(
+            utxo_set.get(&OutPoint::new(tx_hash.clone(), 0)),
+            Some(&tx.outputs[0])
+        );
+        assert_eq!(
+            utxo_set.get(&OutPoint::new(tx_hash, 1)),
+            Some(&tx.outputs[1])
+        );
+
+        // Spent outputs can no longer be used
+        assert!(!tx_is_valid(&tx, utxo_set.lookup_fn()));
+    }
+
+    #[test]
+    /// Checks that a mixed token/receipt transaction's totals are aggregated by asset type
+    fn should_aggregate_input_and_output_totals() {
+        let (utxo, mut tx) = generate_tx_with_ins_and_outs_assets(
+            &[
+                (3, Some("drs_tx_hash"), None),
+                (5, None, None),
+                (2, Some("drs_tx_hash"), None),
+            ],
+            &[(4, None), (5, Some("drs_tx_hash")), (1, None)],
+        );
+        let utxo_set = UtxoSet::from(utxo);
+        tx.outputs.push(TxOut::new_asset(
+            "address".to_owned(),
+            Asset::receipt(2, None, None),
+        ));
+        let tx_hash = construct_tx_hash(&tx);
+
+        let mut expected_ins = AssetValues::token_u64(5);
+        expected_ins.receipts.insert("drs_tx_hash".to_owned(), 5);
+        let mut expected_outs = AssetValues::token_u64(5);
+        expected_outs.receipts.insert("drs_tx_hash".to_owned(), 5);
+        expected_outs.receipts.insert(tx_hash, 2);
+
+        assert_eq!(tx.input_totals(&utxo_set), expected_ins);
+        assert_eq!(tx.output_totals(), expected_outs);
+        assert_eq!(tx.input_totals(&UtxoSet::new()), AssetValues::default());
+    }
+
+    #[test]
+    /// Checks insertion and removal of unspent outputs
+    fn should_insert_and_remove_tx_out() {
+        let mut utxo_set = UtxoSet::new();
+        let out_point = OutPoint::new("tx_hash".to_owned(), 0);
+        let tx_out = TxOut::new_token_amount("address".to_owned(), Default::default());
+
+        assert_eq!(utxo_set.insert(out_point.clone(), tx_out.clone()), None);
+        assert!(utxo_set.contains(&out_point));
+        assert_eq!(utxo_set.get(&out_point), Some(&tx_out));
+        assert_eq!(utxo_set.remove(&out_point), Some(tx_out));
+        assert!(!utxo_set.contains(&out_point));
+    }
+
+    #[test]
+    /// Checks that outputs are found by address, including repeated addresses
+    fn should_find_outputs_by_address() {
+        let (a, b, c) = ("a".repeat(64), "b".repeat(64), "c".repeat(64));
+        let mut tx = Transaction::new();
+        tx.outputs = vec![
+            TxOut::new_token_amount(a.clone(), TokenAmount(1)),
+            TxOut::new_token_amount(b.clone(), TokenAmount(2)),
+            TxOut::new_token_amount(a.clone(), TokenAmount(3)),
+        ];
+
+        let to_a = tx.outputs_to(&a);
+        let to_b = tx.outputs_to(&b);
+        assert_eq!(to_a, vec![(0, &tx.outputs[0]), (2, &tx.outputs[2])]);
+        assert_eq!(to_b, vec![(1, &tx.outputs[1])]);
+        assert!(tx.outputs_to(&c).is_empty());
+
+        let mut utxo_set = UtxoSet::new();
+        utxo_set.apply_tx(&tx);
+        let tx_hash = construct_tx_hash(&tx);
+        let out_point = |n| OutPoint::new(tx_hash.clone(), n);
+        assert_eq!(
+            utxo_set.outputs_for_address(&a),
+            vec![
+                (&out_point(0), &tx.outputs[0]),
+                (&out_point(2), &tx.outputs[2])
+            ]
+        );
+        assert_eq!(
+            utxo_set.outputs_for_address(&b),
+            vec![(&out_point(1), &tx.outputs[1])]
+        );
+        assert!(utxo_set.outputs_for_address(&c).is_empty());
+
+        // Spent outputs are no longer found
+        utxo_set.remove(&out_point(0));
+        assert_eq!(
+            utxo_set.outputs_for_address(&a),
+            vec![(&out_point(2), &tx.outputs[2])]
+        );
+    }
+}
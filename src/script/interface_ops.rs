@@ -3,9 +3,10 @@ use crate::constants::*;
 use crate::crypto::sha3_256;
 use crate::crypto::sign_ed25519 as sign;
 use crate::crypto::sign_ed25519::{PublicKey, Signature};
+use crate::crypto::{ripemd160, sha256};
 use crate::primitives::asset::{Asset, TokenAmount};
 use crate::primitives::transaction::*;
-use crate::script::lang::{ConditionStack, Script, Stack};
+use crate::script::lang::{entry_size, ConditionStack, Script, ScriptContext, ScriptError, Stack};
 use crate::script::{OpCodes, StackEntry};
 use crate::utils::error_utils::*;
 use crate::utils::transaction_utils::{
@@ -15,6 +16,7 @@ use bincode::serialize;
 use bytes::Bytes;
 use hex::encode;
 use std::collections::BTreeMap;
+use std::convert::TryFrom;
 use tracing::{debug, error, info, trace};
 
 /*---- CONSTANTS OPS ----*/
@@ -26,10 +28,49 @@ use tracing::{debug, error, info, trace};
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_0(stack: &mut Stack) -> bool {
+pub fn op_0(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OP0, OP0_DESC);
     trace(op, desc);
-    stack.push(StackEntry::Num(ZERO))
+    stack.try_push(StackEntry::Num(ZERO))
+}
+
+/// OP_PUSHDATA: Pushes the entry that follows it in the script onto the stack as data
+///
+/// Example: OP_PUSHDATA([], data) -> [data] if size(data) <= max_item_size
+///          OP_PUSHDATA([], data) -> fail   if size(data) > max_item_size
+///
+/// Info: Unlike an inline `Bytes` entry, whose size is only bounded by `Stack::try_push`,
+///       the operand is declared as a data blob and its size is checked explicitly
+///       against the interpreter's item size limit before it is pushed
+///
+/// ### Arguments
+///
+/// * `stack`          - mutable reference to the stack
+/// * `data`           - entry following the opcode in the script
+/// * `max_item_size`  - maximum size in bytes of the pushed entry
+pub fn op_pushdata(
+    stack: &mut Stack,
+    data: Option<&StackEntry>,
+    max_item_size: usize,
+) -> Result<(), ScriptError> {
+    let (op, desc) = (OPPUSHDATA, OPPUSHDATA_DESC);
+    trace(op, desc);
+    let data = match data {
+        Some(StackEntry::Op(_)) => {
+            error_item_type(op);
+            return Err(ScriptError::ItemType);
+        }
+        Some(data) => data,
+        None => {
+            error_num_items(op);
+            return Err(ScriptError::NumItems);
+        }
+    };
+    if entry_size(data) > max_item_size {
+        error_item_size(op);
+        return Err(ScriptError::ItemSize);
+    }
+    stack.try_push(data.clone())
 }
 
 /// OP_1: Pushes number ONE onto the stack
@@ -39,10 +80,10 @@ pub fn op_0(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_1(stack: &mut Stack) -> bool {
+pub fn op_1(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OP1, OP1_DESC);
     trace(op, desc);
-    stack.push(StackEntry::Num(ONE))
+    stack.try_push(StackEntry::Num(ONE))
 }
 
 /// OP_2: Pushes number TWO onto the stack
@@ -52,10 +93,10 @@ pub fn op_1(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_2(stack: &mut Stack) -> bool {
+pub fn op_2(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OP2, OP2_DESC);
     trace(op, desc);
-    stack.push(StackEntry::Num(TWO))
+    stack.try_push(StackEntry::Num(TWO))
 }
 
 /// OP_3: Pushes number THREE onto the stack
@@ -65,10 +106,10 @@ pub fn op_2(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_3(stack: &mut Stack) -> bool {
+pub fn op_3(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OP3, OP3_DESC);
     trace(op, desc);
-    stack.push(StackEntry::Num(THREE))
+    stack.try_push(StackEntry::Num(THREE))
 }
 
 /// OP_4: Pushes number FOUR onto the stack
@@ -78,10 +119,10 @@ pub fn op_3(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_4(stack: &mut Stack) -> bool {
+pub fn op_4(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OP4, OP4_DESC);
     trace(op, desc);
-    stack.push(StackEntry::Num(FOUR))
+    stack.try_push(StackEntry::Num(FOUR))
 }
 
 /// OP_5: Pushes number FIVE onto the stack
@@ -91,10 +132,10 @@ pub fn op_4(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_5(stack: &mut Stack) -> bool {
+pub fn op_5(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OP5, OP5_DESC);
     trace(op, desc);
-    stack.push(StackEntry::Num(FIVE))
+    stack.try_push(StackEntry::Num(FIVE))
 }
 
 /// OP_6: Pushes number SIX onto the stack
@@ -104,10 +145,10 @@ pub fn op_5(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_6(stack: &mut Stack) -> bool {
+pub fn op_6(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OP6, OP6_DESC);
     trace(op, desc);
-    stack.push(StackEntry::Num(SIX))
+    stack.try_push(StackEntry::Num(SIX))
 }
 
 /// OP_7: Pushes number SEVEN onto the stack
@@ -117,10 +158,10 @@ pub fn op_6(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_7(stack: &mut Stack) -> bool {
+pub fn op_7(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OP7, OP7_DESC);
     trace(op, desc);
-    stack.push(StackEntry::Num(SEVEN))
+    stack.try_push(StackEntry::Num(SEVEN))
 }
 
 /// OP_8: Pushes number EIGHT onto the stack
@@ -130,10 +171,10 @@ pub fn op_7(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_8(stack: &mut Stack) -> bool {
+pub fn op_8(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OP8, OP8_DESC);
     trace(op, desc);
-    stack.push(StackEntry::Num(EIGHT))
+    stack.try_push(StackEntry::Num(EIGHT))
 }
 
 /// OP_9: Pushes number NINE onto the stack
@@ -143,10 +184,10 @@ pub fn op_8(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_9(stack: &mut Stack) -> bool {
+pub fn op_9(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OP9, OP9_DESC);
     trace(op, desc);
-    stack.push(StackEntry::Num(NINE))
+    stack.try_push(StackEntry::Num(NINE))
 }
 
 /// OP_10: Pushes number TEN onto the stack
@@ -156,10 +197,10 @@ pub fn op_9(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_10(stack: &mut Stack) -> bool {
+pub fn op_10(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OP10, OP10_DESC);
     trace(op, desc);
-    stack.push(StackEntry::Num(TEN))
+    stack.try_push(StackEntry::Num(TEN))
 }
 
 /// OP_11: Pushes number ELEVEN onto the stack
@@ -169,10 +210,10 @@ pub fn op_10(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_11(stack: &mut Stack) -> bool {
+pub fn op_11(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OP11, OP11_DESC);
     trace(op, desc);
-    stack.push(StackEntry::Num(ELEVEN))
+    stack.try_push(StackEntry::Num(ELEVEN))
 }
 
 /// OP_12: Pushes number TWELVE onto the stack
@@ -182,10 +223,10 @@ pub fn op_11(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_12(stack: &mut Stack) -> bool {
+pub fn op_12(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OP12, OP12_DESC);
     trace(op, desc);
-    stack.push(StackEntry::Num(TWELVE))
+    stack.try_push(StackEntry::Num(TWELVE))
 }
 
 /// OP_13: Pushes number THIRTEEN onto the stack
@@ -195,10 +236,10 @@ pub fn op_12(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_13(stack: &mut Stack) -> bool {
+pub fn op_13(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OP13, OP13_DESC);
     trace(op, desc);
-    stack.push(StackEntry::Num(THIRTEEN))
+    stack.try_push(StackEntry::Num(THIRTEEN))
 }
 
 /// OP_14: Pushes number FOURTEEN onto the stack
@@ -208,10 +249,10 @@ pub fn op_13(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_14(stack: &mut Stack) -> bool {
+pub fn op_14(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OP14, OP14_DESC);
     trace(op, desc);
-    stack.push(StackEntry::Num(FOURTEEN))
+    stack.try_push(StackEntry::Num(FOURTEEN))
 }
 
 /// OP_15: Pushes number FIFTEEN onto the stack
@@ -221,10 +262,10 @@ pub fn op_14(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_15(stack: &mut Stack) -> bool {
+pub fn op_15(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OP15, OP15_DESC);
     trace(op, desc);
-    stack.push(StackEntry::Num(FIFTEEN))
+    stack.try_push(StackEntry::Num(FIFTEEN))
 }
 
 /// OP_16: Pushes number SIXTEEN onto the stack
@@ -234,10 +275,10 @@ pub fn op_15(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_16(stack: &mut Stack) -> bool {
+pub fn op_16(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OP16, OP16_DESC);
     trace(op, desc);
-    stack.push(StackEntry::Num(SIXTEEN))
+    stack.try_push(StackEntry::Num(SIXTEEN))
 }
 
 /*---- FLOW CONTROL OPS ----*/
@@ -249,10 +290,10 @@ pub fn op_16(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_nop(stack: &mut Stack) -> bool {
+pub fn op_nop(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OPNOP, OPNOP_DESC);
     trace(op, desc);
-    true
+    Ok(())
 }
 
 /// OP_IF: Checks if the top item on the stack is not ZERO and executes the next block of instructions
@@ -261,7 +302,7 @@ pub fn op_nop(stack: &mut Stack) -> bool {
 ///
 /// * `stack`  - mutable reference to the stack
 /// * `cond_stack`  - mutable reference to the condition stack
-pub fn op_if(stack: &mut Stack, cond_stack: &mut ConditionStack) -> bool {
+pub fn op_if(stack: &mut Stack, cond_stack: &mut ConditionStack) -> Result<(), ScriptError> {
     let (op, desc) = (OPIF, OPIF_DESC);
     trace(op, desc);
     let cond = if cond_stack.all_true() {
@@ -269,11 +310,11 @@ pub fn op_if(stack: &mut Stack, cond_stack: &mut ConditionStack) -> bool {
             Some(StackEntry::Num(n)) => n,
             Some(_) => {
                 error_item_type(op);
-                return false;
+                return Err(ScriptError::ItemType);
             }
             _ => {
                 error_num_items(op);
-                return false;
+                return Err(ScriptError::NumItems);
             }
         };
         n != ZERO
@@ -281,7 +322,7 @@ pub fn op_if(stack: &mut Stack, cond_stack: &mut ConditionStack) -> bool {
         false
     };
     cond_stack.push(cond);
-    true
+    Ok(())
 }
 
 /// OP_NOTIF: Checks if the top item on the stack is ZERO and executes the next block of instructions
@@ -290,7 +331,7 @@ pub fn op_if(stack: &mut Stack, cond_stack: &mut ConditionStack) -> bool {
 ///
 /// * `stack`  - mutable reference to the stack
 /// * `cond_stack`  - mutable reference to the condition stack
-pub fn op_notif(stack: &mut Stack, cond_stack: &mut ConditionStack) -> bool {
+pub fn op_notif(stack: &mut Stack, cond_stack: &mut ConditionStack) -> Result<(), ScriptError> {
     let (op, desc) = (OPNOTIF, OPNOTIF_DESC);
     trace(op, desc);
     let cond = if cond_stack.all_true() {
@@ -298,11 +339,11 @@ pub fn op_notif(stack: &mut Stack, cond_stack: &mut ConditionStack) -> bool {
             Some(StackEntry::Num(n)) => n,
             Some(_) => {
                 error_item_type(op);
-                return false;
+                return Err(ScriptError::ItemType);
             }
             _ => {
                 error_num_items(op);
-                return false;
+                return Err(ScriptError::NumItems);
             }
         };
         n == ZERO
@@ -310,7 +351,7 @@ pub fn op_notif(stack: &mut Stack, cond_stack: &mut ConditionStack) -> bool {
         false
     };
     cond_stack.push(cond);
-    true
+    Ok(())
 }
 
 /// OP_ELSE: Executes the next block of instructions if the previous OP_IF or OP_NOTIF was not executed
@@ -318,15 +359,14 @@ pub fn op_notif(stack: &mut Stack, cond_stack: &mut ConditionStack) -> bool {
 /// ### Arguments
 ///
 /// * `cond_stack`  - mutable reference to the condition stack
-pub fn op_else(cond_stack: &mut ConditionStack) -> bool {
+pub fn op_else(cond_stack: &mut ConditionStack) -> Result<(), ScriptError> {
     let (op, desc) = (OPELSE, OPELSE_DESC);
     trace(op, desc);
-    if cond_stack.is_empty() {
+    if !cond_stack.toggle() {
         error_empty_condition(op);
-        return false;
+        return Err(ScriptError::EmptyCondition);
     }
-    cond_stack.toggle();
-    true
+    Ok(())
 }
 
 /// OP_ENDIF: Ends an OP_IF or OP_NOTIF block
@@ -334,15 +374,14 @@ pub fn op_else(cond_stack: &mut ConditionStack) -> bool {
 /// ### Arguments
 ///
 /// * `cond_stack`  - mutable reference to the condition stack
-pub fn op_endif(cond_stack: &mut ConditionStack) -> bool {
+pub fn op_endif(cond_stack: &mut ConditionStack) -> Result<(), ScriptError> {
     let (op, desc) = (OPENDIF, OPENDIF_DESC);
     trace(op, desc);
-    if cond_stack.is_empty() {
+    if !cond_stack.pop() {
         error_empty_condition(op);
-        return false;
+        return Err(ScriptError::EmptyCondition);
     }
-    cond_stack.pop();
-    true
+    Ok(())
 }
 
 /// OP_VERIFY: Removes the top item from the stack and ends execution with an error if it is ZERO
@@ -353,22 +392,21 @@ pub fn op_endif(cond_stack: &mut ConditionStack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_verify(stack: &mut Stack) -> bool {
+pub fn op_verify(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OPVERIFY, OPVERIFY_DESC);
     trace(op, desc);
     match stack.pop() {
-        Some(x) => {
-            if x == StackEntry::Num(ZERO) {
-                error_verify(op);
-                return false;
-            }
+        Some(StackEntry::Num(ZERO)) | Some(StackEntry::SignedNum(0)) => {
+            error_verify(op);
+            return Err(ScriptError::Verify);
         }
+        Some(_) => (),
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
-    true
+    Ok(())
 }
 
 /// OP_BURN: Ends execution with an error
@@ -378,11 +416,11 @@ pub fn op_verify(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_burn(stack: &mut Stack) -> bool {
+pub fn op_burn(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OPBURN, OPBURN_DESC);
     trace(op, desc);
     error_burn(op);
-    false
+    Err(ScriptError::Burn)
 }
 
 /*---- STACK OPS ----*/
@@ -395,17 +433,17 @@ pub fn op_burn(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_toaltstack(stack: &mut Stack) -> bool {
+pub fn op_toaltstack(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OPTOALTSTACK, OPTOALTSTACK_DESC);
     trace(op, desc);
     match stack.pop() {
         Some(x) => stack.alt_stack.push(x),
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
-    true
+    Ok(())
 }
 
 /// OP_FROMALTSTACK: Moves the top item from the alt stack to the top of the main stack
@@ -415,17 +453,17 @@ pub fn op_toaltstack(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_fromaltstack(stack: &mut Stack) -> bool {
+pub fn op_fromaltstack(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OPFROMALTSTACK, OPFROMALTSTACK_DESC);
     trace(op, desc);
     match stack.alt_stack.pop() {
-        Some(x) => stack.push(x),
+        Some(x) => stack.try_push(x)?,
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
-    true
+    Ok(())
 }
 
 /// OP_2DROP: Removes the top two items from the stack
@@ -435,16 +473,16 @@ pub fn op_fromaltstack(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_2drop(stack: &mut Stack) -> bool {
+pub fn op_2drop(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OP2DROP, OP2DROP_DESC);
     trace(op, desc);
     let len = stack.main_stack.len();
     if len < TWO {
         error_num_items(op);
-        return false;
+        return Err(ScriptError::NumItems);
     }
     stack.main_stack.drain(len - TWO..);
-    true
+    Ok(())
 }
 
 /// OP_2DUP: Duplicates the top two items on the stack
@@ -454,17 +492,17 @@ pub fn op_2drop(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_2dup(stack: &mut Stack) -> bool {
+pub fn op_2dup(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OP2DUP, OP2DUP_DESC);
     trace(op, desc);
     let len = stack.main_stack.len();
     if len < TWO {
         error_num_items(op);
-        return false;
+        return Err(ScriptError::NumItems);
     }
     let last_two = stack.main_stack[len - TWO..].to_vec();
     stack.main_stack.extend_from_slice(&last_two);
-    true
+    Ok(())
 }
 
 /// OP_3DUP: Duplicates the top three items on the stack
@@ -474,17 +512,17 @@ pub fn op_2dup(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_3dup(stack: &mut Stack) -> bool {
+pub fn op_3dup(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OP3DUP, OP3DUP_DESC);
     trace(op, desc);
     let len = stack.main_stack.len();
     if len < THREE {
         error_num_items(op);
-        return false;
+        return Err(ScriptError::NumItems);
     }
     let last_three = stack.main_stack[len - THREE..].to_vec();
     stack.main_stack.extend_from_slice(&last_three);
-    true
+    Ok(())
 }
 
 /// OP_2OVER: Copies the second-to-top pair of items to the top of the stack
@@ -494,17 +532,17 @@ pub fn op_3dup(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_2over(stack: &mut Stack) -> bool {
+pub fn op_2over(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OP2OVER, OP2OVER_DESC);
     trace(op, desc);
     let len = stack.main_stack.len();
     if len < FOUR {
         error_num_items(op);
-        return false;
+        return Err(ScriptError::NumItems);
     }
     let items = stack.main_stack[len - FOUR..len - TWO].to_vec();
     stack.main_stack.extend_from_slice(&items);
-    true
+    Ok(())
 }
 
 /// OP_2ROT: Moves the third-to-top pair of items to the top of the stack
@@ -514,18 +552,18 @@ pub fn op_2over(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_2rot(stack: &mut Stack) -> bool {
+pub fn op_2rot(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OP2ROT, OP2ROT_DESC);
     trace(op, desc);
     let len = stack.main_stack.len();
     if len < SIX {
         error_num_items(op);
-        return false;
+        return Err(ScriptError::NumItems);
     }
     let items = stack.main_stack[len - SIX..len - FOUR].to_vec();
     stack.main_stack.drain(len - SIX..len - FOUR);
     stack.main_stack.extend_from_slice(&items);
-    true
+    Ok(())
 }
 
 /// OP_2SWAP: Swaps the top two pairs of items on the stack
@@ -535,17 +573,17 @@ pub fn op_2rot(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_2swap(stack: &mut Stack) -> bool {
+pub fn op_2swap(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OP2SWAP, OP2SWAP_DESC);
     trace(op, desc);
     let len = stack.main_stack.len();
     if len < FOUR {
         error_num_items(op);
-        return false;
+        return Err(ScriptError::NumItems);
     }
     stack.main_stack.swap(len - FOUR, len - TWO);
     stack.main_stack.swap(len - THREE, len - ONE);
-    true
+    Ok(())
 }
 
 /// OP_IFDUP: Duplicates the top item on the stack if it is not ZERO
@@ -556,21 +594,18 @@ pub fn op_2swap(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_ifdup(stack: &mut Stack) -> bool {
+pub fn op_ifdup(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OPIFDUP, OPIFDUP_DESC);
     trace(op, desc);
     match stack.last() {
-        Some(x) => {
-            if x != StackEntry::Num(ZERO) {
-                stack.push(x);
-            }
-        }
+        Some(StackEntry::Num(ZERO)) | Some(StackEntry::SignedNum(0)) => (),
+        Some(x) => stack.try_push(x)?,
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
-    true
+    Ok(())
 }
 
 /// OP_DEPTH: Pushes the stack size onto the stack
@@ -580,10 +615,10 @@ pub fn op_ifdup(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_depth(stack: &mut Stack) -> bool {
+pub fn op_depth(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OPDEPTH, OPDEPTH_DESC);
     trace(op, desc);
-    stack.push(StackEntry::Num(stack.main_stack.len()))
+    stack.try_push(StackEntry::Num(stack.main_stack.len()))
 }
 
 /// OP_DROP: Removes the top item from the stack
@@ -593,17 +628,17 @@ pub fn op_depth(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_drop(stack: &mut Stack) -> bool {
+pub fn op_drop(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OPDROP, OPDROP_DESC);
     trace(op, desc);
     match stack.pop() {
         Some(x) => (),
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
-    true
+    Ok(())
 }
 
 /// OP_DUP: Duplicates the top item on the stack
@@ -613,17 +648,17 @@ pub fn op_drop(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_dup(stack: &mut Stack) -> bool {
+pub fn op_dup(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OPDUP, OPDUP_DESC);
     trace(op, desc);
     match stack.last() {
-        Some(x) => stack.push(x),
+        Some(x) => stack.try_push(x)?,
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
-    true
+    Ok(())
 }
 
 /// OP_NIP: Removes the second-to-top item from the stack
@@ -633,16 +668,16 @@ pub fn op_dup(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_nip(stack: &mut Stack) -> bool {
+pub fn op_nip(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OPNIP, OPNIP_DESC);
     trace(op, desc);
     let len = stack.main_stack.len();
     if len < TWO {
         error_num_items(op);
-        return false;
+        return Err(ScriptError::NumItems);
     }
     stack.main_stack.remove(len - TWO);
-    true
+    Ok(())
 }
 
 /// OP_OVER: Copies the second-to-top item to the top of the stack
@@ -652,77 +687,81 @@ pub fn op_nip(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_over(stack: &mut Stack) -> bool {
+pub fn op_over(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OPOVER, OPOVER_DESC);
     trace(op, desc);
     let len = stack.main_stack.len();
     if len < TWO {
         error_num_items(op);
-        return false;
+        return Err(ScriptError::NumItems);
     }
     let x1 = stack.main_stack[len - TWO].clone();
-    stack.push(x1)
+    stack.try_push(x1)
 }
 
 /// OP_PICK: Copies the (n+1)th-to-top item to the top of the stack, where n is the top item on the stack
 ///
 /// Example: OP_PICK([x, x2, x1, x0, 3]) -> [x, x2, x1, x0, x]
 ///
+/// Info: Fails with ItemIndex if n is not below the stack depth
+///
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_pick(stack: &mut Stack) -> bool {
+pub fn op_pick(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OPPICK, OPPICK_DESC);
     trace(op, desc);
     let n = match stack.pop() {
         Some(StackEntry::Num(n)) => n,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
     let len = stack.main_stack.len();
     if n >= len {
         error_item_index(op);
-        return false;
+        return Err(ScriptError::ItemIndex);
     }
     let x = stack.main_stack[len - ONE - n].clone();
-    stack.push(x)
+    stack.try_push(x)
 }
 
 /// OP_ROLL: Moves the (n+1)th-to-top item to the top of the stack, where n is the top item on the stack
 ///
 /// Example: OP_ROLL([x, x2, x1, x0, 3]) -> [x2, x1, x0, x]
 ///
+/// Info: Fails with ItemIndex if there is no item n below the top to move
+///
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_roll(stack: &mut Stack) -> bool {
+pub fn op_roll(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OPROLL, OPROLL_DESC);
     trace(op, desc);
     let n = match stack.pop() {
         Some(StackEntry::Num(n)) => n,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
     let len = stack.main_stack.len();
     if n >= len {
         error_item_index(op);
-        return false;
+        return Err(ScriptError::ItemIndex);
     }
     let x = stack.main_stack[len - ONE - n].clone();
     stack.main_stack.remove(len - ONE - n);
-    stack.push(x)
+    stack.try_push(x)
 }
 
 /// OP_ROT: Moves the third-to-top item to the top of the stack
@@ -732,17 +771,17 @@ pub fn op_roll(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_rot(stack: &mut Stack) -> bool {
+pub fn op_rot(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OPROT, OPROT_DESC);
     trace(op, desc);
     let len = stack.main_stack.len();
     if len < THREE {
         error_num_items(op);
-        return false;
+        return Err(ScriptError::NumItems);
     }
     stack.main_stack.swap(len - THREE, len - TWO);
     stack.main_stack.swap(len - TWO, len - ONE);
-    true
+    Ok(())
 }
 
 /// OP_SWAP: Swaps the top two items on the stack
@@ -752,16 +791,16 @@ pub fn op_rot(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_swap(stack: &mut Stack) -> bool {
+pub fn op_swap(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OPSWAP, OPSWAP_DESC);
     trace(op, desc);
     let len = stack.main_stack.len();
     if len < TWO {
         error_num_items(op);
-        return false;
+        return Err(ScriptError::NumItems);
     }
     stack.main_stack.swap(len - TWO, len - ONE);
-    true
+    Ok(())
 }
 
 /// OP_TUCK: Copies the top item behind the second-to-top item on the stack
@@ -771,17 +810,17 @@ pub fn op_swap(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_tuck(stack: &mut Stack) -> bool {
+pub fn op_tuck(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OPTUCK, OPTUCK_DESC);
     trace(op, desc);
     let len = stack.main_stack.len();
     if len < TWO {
         error_num_items(op);
-        return false;
+        return Err(ScriptError::NumItems);
     }
     let x2 = stack.main_stack[len - ONE].clone();
     stack.main_stack.insert(len - TWO, x2);
-    true
+    Ok(())
 }
 
 /*---- SPLICE OPS ----*/
@@ -793,37 +832,37 @@ pub fn op_tuck(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_cat(stack: &mut Stack) -> bool {
+pub fn op_cat(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OPCAT, OPCAT_DESC);
     trace(op, desc);
     let s2 = match stack.pop() {
         Some(StackEntry::Bytes(s)) => s,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
     let s1 = match stack.pop() {
         Some(StackEntry::Bytes(s)) => s,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
     if s1.len() + s2.len() > MAX_SCRIPT_ITEM_SIZE as usize {
         error_item_size(op);
-        return false;
+        return Err(ScriptError::ItemSize);
     }
     let cat = [s1, s2].join("");
-    stack.push(StackEntry::Bytes(cat))
+    stack.try_push(StackEntry::Bytes(cat))
 }
 
 /// OP_SUBSTR: Extracts a substring from the third-to-top item on the stack
@@ -833,56 +872,56 @@ pub fn op_cat(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_substr(stack: &mut Stack) -> bool {
+pub fn op_substr(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OPSUBSTR, OPSUBSTR_DESC);
     trace(op, desc);
     let n2 = match stack.pop() {
         Some(StackEntry::Num(n)) => n,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
     let n1 = match stack.pop() {
         Some(StackEntry::Num(n)) => n,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
     let s = match stack.pop() {
         Some(StackEntry::Bytes(s)) => s,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
     if n1 >= s.len() {
         error_item_index(op);
-        return false;
+        return Err(ScriptError::ItemIndex);
     }
     if n2 > s.len() {
         error_item_index(op);
-        return false;
+        return Err(ScriptError::ItemIndex);
     }
-    if n1 + n2 > s.len() {
+    if n1 + n2 > s.len() || !s.is_char_boundary(n1) || !s.is_char_boundary(n1 + n2) {
         error_item_index(op);
-        return false;
+        return Err(ScriptError::ItemIndex);
     }
     let substr = s[n1..n1 + n2].to_string();
-    stack.push(StackEntry::Bytes(substr))
+    stack.try_push(StackEntry::Bytes(substr))
 }
 
 /// OP_LEFT: Extracts a left substring from the second-to-top item on the stack
@@ -893,36 +932,39 @@ pub fn op_substr(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_left(stack: &mut Stack) -> bool {
+pub fn op_left(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OPLEFT, OPLEFT_DESC);
     trace(op, desc);
     let n = match stack.pop() {
         Some(StackEntry::Num(n)) => n,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
     let s = match stack.pop() {
         Some(StackEntry::Bytes(s)) => s,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
     if n >= s.len() {
-        stack.push(StackEntry::Bytes(s))
+        stack.try_push(StackEntry::Bytes(s))
+    } else if !s.is_char_boundary(n) {
+        error_item_index(op);
+        Err(ScriptError::ItemIndex)
     } else {
         let left = s[..n].to_string();
-        stack.push(StackEntry::Bytes(left))
+        stack.try_push(StackEntry::Bytes(left))
     }
 }
 
@@ -934,36 +976,39 @@ pub fn op_left(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_right(stack: &mut Stack) -> bool {
+pub fn op_right(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OPRIGHT, OPRIGHT_DESC);
     trace(op, desc);
     let n = match stack.pop() {
         Some(StackEntry::Num(n)) => n,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
     let s = match stack.pop() {
         Some(StackEntry::Bytes(s)) => s,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
     if n >= s.len() {
-        stack.push(StackEntry::Bytes("".to_string()))
+        stack.try_push(StackEntry::Bytes("".to_string()))
+    } else if !s.is_char_boundary(n) {
+        error_item_index(op);
+        Err(ScriptError::ItemIndex)
     } else {
         let right = s[n..].to_string();
-        stack.push(StackEntry::Bytes(right))
+        stack.try_push(StackEntry::Bytes(right))
     }
 }
 
@@ -974,21 +1019,188 @@ pub fn op_right(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_size(stack: &mut Stack) -> bool {
+pub fn op_size(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OPSIZE, OPSIZE_DESC);
     trace(op, desc);
     let s = match stack.last() {
         Some(StackEntry::Bytes(s)) => s,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
+        }
+    };
+    stack.try_push(StackEntry::Num(s.len()))
+}
+
+/// OP_REVERSE: Reverses the bytes of the hex string on top of the stack, for converting
+/// between little- and big-endian encodings
+///
+/// Example: OP_REVERSE([hex(b)]) -> [hex(reverse(b))]
+///          OP_REVERSE([s])      -> fail if s is not hex
+///
+/// ### Arguments
+///
+/// * `stack`  - mutable reference to the stack
+pub fn op_reverse(stack: &mut Stack) -> Result<(), ScriptError> {
+    let (op, desc) = (OPREVERSE, OPREVERSE_DESC);
+    trace(op, desc);
+    let mut bin = match stack.pop() {
+        Some(StackEntry::Bytes(s)) => match hex::decode(s) {
+            Ok(bin) => bin,
+            Err(_) => {
+                error_item_type(op);
+                return Err(ScriptError::ItemType);
+            }
+        },
+        Some(_) => {
+            error_item_type(op);
+            return Err(ScriptError::ItemType);
+        }
+        _ => {
+            error_num_items(op);
+            return Err(ScriptError::NumItems);
+        }
+    };
+    bin.reverse();
+    stack.try_push(StackEntry::Bytes(hex::encode(bin)))
+}
+
+/// OP_SPLIT: Splits the second-to-top string on the stack at the index on top of the stack
+///
+/// Example: OP_SPLIT([s, n]) -> [s[..n], s[n..]] if n <= len(s)
+///          OP_SPLIT([s, n]) -> fail             if n > len(s)
+///
+/// ### Arguments
+///
+/// * `stack`  - mutable reference to the stack
+pub fn op_split(stack: &mut Stack) -> Result<(), ScriptError> {
+    let (op, desc) = (OPSPLIT, OPSPLIT_DESC);
+    trace(op, desc);
+    let n = match stack.pop() {
+        Some(StackEntry::Num(n)) => n,
+        Some(_) => {
+            error_item_type(op);
+            return Err(ScriptError::ItemType);
+        }
+        _ => {
+            error_num_items(op);
+            return Err(ScriptError::NumItems);
+        }
+    };
+    let s = match stack.pop() {
+        Some(StackEntry::Bytes(s)) => s,
+        Some(_) => {
+            error_item_type(op);
+            return Err(ScriptError::ItemType);
+        }
+        _ => {
+            error_num_items(op);
+            return Err(ScriptError::NumItems);
+        }
+    };
+    if n > s.len() || !s.is_char_boundary(n) {
+        error_item_index(op);
+        return Err(ScriptError::ItemIndex);
+    }
+    let (left, right) = s.split_at(n);
+    stack.try_push(StackEntry::Bytes(left.to_string()))?;
+    stack.try_push(StackEntry::Bytes(right.to_string()))
+}
+
+/// OP_NUM2BIN: Converts the second-to-top number on the stack to a hex encoded,
+/// little-endian byte string of the width on top of the stack
+///
+/// Example: OP_NUM2BIN([n, w]) -> [hex(le(n, w))] if n fits in w bytes
+///          OP_NUM2BIN([n, w]) -> fail            if n does not fit in w bytes
+///
+/// ### Arguments
+///
+/// * `stack`  - mutable reference to the stack
+pub fn op_num2bin(stack: &mut Stack) -> Result<(), ScriptError> {
+    let (op, desc) = (OPNUM2BIN, OPNUM2BIN_DESC);
+    trace(op, desc);
+    let w = match stack.pop() {
+        Some(StackEntry::Num(w)) => w,
+        Some(_) => {
+            error_item_type(op);
+            return Err(ScriptError::ItemType);
+        }
+        _ => {
+            error_num_items(op);
+            return Err(ScriptError::NumItems);
+        }
+    };
+    let n = match stack.pop() {
+        Some(StackEntry::Num(n)) => n,
+        Some(_) => {
+            error_item_type(op);
+            return Err(ScriptError::ItemType);
+        }
+        _ => {
+            error_num_items(op);
+            return Err(ScriptError::NumItems);
+        }
+    };
+    if w > MAX_SCRIPT_ITEM_SIZE as usize / TWO {
+        error_item_size(op);
+        return Err(ScriptError::ItemSize);
+    }
+    let bytes = n.to_le_bytes();
+    let len = bytes.iter().rposition(|b| *b != 0).map_or(0, |i| i + 1);
+    if len > w {
+        error_overflow(op);
+        return Err(ScriptError::ArithmeticOverflow);
+    }
+    let mut bin = bytes[..len].to_vec();
+    bin.resize(w, 0);
+    stack.try_push(StackEntry::Bytes(hex::encode(bin)))
+}
+
+/// OP_BIN2NUM: Converts the hex encoded, little-endian byte string on top of the stack
+/// to a number. The byte string must be minimally encoded, without trailing zero bytes
+///
+/// Example: OP_BIN2NUM([hex(le(n))]) -> [n]
+///          OP_BIN2NUM([s])          -> fail if s is not minimally encoded or n overflows
+///
+/// ### Arguments
+///
+/// * `stack`  - mutable reference to the stack
+pub fn op_bin2num(stack: &mut Stack) -> Result<(), ScriptError> {
+    let (op, desc) = (OPBIN2NUM, OPBIN2NUM_DESC);
+    trace(op, desc);
+    let bin = match stack.pop() {
+        Some(StackEntry::Bytes(s)) => match hex::decode(s) {
+            Ok(bin) => bin,
+            Err(_) => {
+                error_item_type(op);
+                return Err(ScriptError::ItemType);
+            }
+        },
+        Some(_) => {
+            error_item_type(op);
+            return Err(ScriptError::ItemType);
+        }
+        _ => {
+            error_num_items(op);
+            return Err(ScriptError::NumItems);
         }
     };
-    stack.push(StackEntry::Num(s.len()))
+    if bin.last() == Some(&0) {
+        error_item_type(op);
+        return Err(ScriptError::ItemType);
+    }
+    let num_len = usize::BITS as usize / EIGHT;
+    if bin.len() > num_len {
+        error_overflow(op);
+        return Err(ScriptError::ArithmeticOverflow);
+    }
+    let mut bytes = [0; usize::BITS as usize / EIGHT];
+    bytes[..bin.len()].copy_from_slice(&bin);
+    stack.try_push(StackEntry::Num(usize::from_le_bytes(bytes)))
 }
 
 /*---- BITWISE LOGIC OPS ----*/
@@ -1000,21 +1212,21 @@ pub fn op_size(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_invert(stack: &mut Stack) -> bool {
+pub fn op_invert(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OPINVERT, OPINVERT_DESC);
     trace(op, desc);
     let n = match stack.pop() {
         Some(StackEntry::Num(n)) => n,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
-    stack.push(StackEntry::Num(!n))
+    stack.try_push(StackEntry::Num(!n))
 }
 
 /// OP_AND: Computes bitwise AND between the two numbers on top of the stack
@@ -1024,32 +1236,32 @@ pub fn op_invert(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_and(stack: &mut Stack) -> bool {
+pub fn op_and(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OPAND, OPAND_DESC);
     trace(op, desc);
     let n2 = match stack.pop() {
         Some(StackEntry::Num(n)) => n,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
     let n1 = match stack.pop() {
         Some(StackEntry::Num(n)) => n,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
-    stack.push(StackEntry::Num(n1 & n2))
+    stack.try_push(StackEntry::Num(n1 & n2))
 }
 
 /// OP_OR: Computes bitwise OR between the two numbers on top of the stack
@@ -1059,32 +1271,32 @@ pub fn op_and(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_or(stack: &mut Stack) -> bool {
+pub fn op_or(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OPOR, OPOR_DESC);
     trace(op, desc);
     let n2 = match stack.pop() {
         Some(StackEntry::Num(n)) => n,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
     let n1 = match stack.pop() {
         Some(StackEntry::Num(n)) => n,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
-    stack.push(StackEntry::Num(n1 | n2))
+    stack.try_push(StackEntry::Num(n1 | n2))
 }
 
 /// OP_XOR: Computes bitwise XOR between the two numbers on top of the stack
@@ -1094,32 +1306,32 @@ pub fn op_or(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_xor(stack: &mut Stack) -> bool {
+pub fn op_xor(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OPXOR, OPXOR_DESC);
     trace(op, desc);
     let n2 = match stack.pop() {
         Some(StackEntry::Num(n)) => n,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
     let n1 = match stack.pop() {
         Some(StackEntry::Num(n)) => n,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
-    stack.push(StackEntry::Num(n1 ^ n2))
+    stack.try_push(StackEntry::Num(n1 ^ n2))
 }
 
 /// OP_EQUAL: Substitutes the top two items on the stack with ONE if they are equal, with ZERO otherwise.
@@ -1130,27 +1342,27 @@ pub fn op_xor(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_equal(stack: &mut Stack) -> bool {
+pub fn op_equal(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OPEQUAL, OPEQUAL_DESC);
     trace(op, desc);
     let x2 = match stack.pop() {
         Some(x) => x,
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
     let x1 = match stack.pop() {
         Some(x) => x,
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
     if x1 == x2 {
-        stack.push(StackEntry::Num(ONE))
+        stack.try_push(StackEntry::Num(ONE))
     } else {
-        stack.push(StackEntry::Num(ZERO))
+        stack.try_push(StackEntry::Num(ZERO))
     }
 }
 
@@ -1162,28 +1374,28 @@ pub fn op_equal(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_equalverify(stack: &mut Stack) -> bool {
+pub fn op_equalverify(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OPEQUALVERIFY, OPEQUALVERIFY_DESC);
     trace(op, desc);
     let x2 = match stack.pop() {
         Some(x) => x,
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
     let x1 = match stack.pop() {
         Some(x) => x,
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
     if x1 != x2 {
         error_not_equal_items(op);
-        return false;
+        return Err(ScriptError::NotEqualItems);
     }
-    true
+    Ok(())
 }
 
 /*---- ARITHMETIC OPS ----*/
@@ -1195,25 +1407,25 @@ pub fn op_equalverify(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_1add(stack: &mut Stack) -> bool {
+pub fn op_1add(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OP1ADD, OP1ADD_DESC);
     trace(op, desc);
     let n = match stack.pop() {
         Some(StackEntry::Num(n)) => n,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
     match n.checked_add(ONE) {
-        Some(n) => stack.push(StackEntry::Num(n)),
+        Some(n) => stack.try_push(StackEntry::Num(n)),
         _ => {
             error_overflow(op);
-            false
+            Err(ScriptError::ArithmeticOverflow)
         }
     }
 }
@@ -1225,25 +1437,25 @@ pub fn op_1add(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_1sub(stack: &mut Stack) -> bool {
+pub fn op_1sub(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OP1SUB, OP1SUB_DESC);
     trace(op, desc);
     let n = match stack.pop() {
         Some(StackEntry::Num(n)) => n,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
     match n.checked_sub(ONE) {
-        Some(n) => stack.push(StackEntry::Num(n)),
+        Some(n) => stack.try_push(StackEntry::Num(n)),
         _ => {
             error_overflow(op);
-            false
+            Err(ScriptError::ArithmeticOverflow)
         }
     }
 }
@@ -1255,25 +1467,25 @@ pub fn op_1sub(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_2mul(stack: &mut Stack) -> bool {
+pub fn op_2mul(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OP2MUL, OP2MUL_DESC);
     trace(op, desc);
     let n = match stack.pop() {
         Some(StackEntry::Num(n)) => n,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
     match n.checked_mul(TWO) {
-        Some(n) => stack.push(StackEntry::Num(n)),
+        Some(n) => stack.try_push(StackEntry::Num(n)),
         _ => {
             error_overflow(op);
-            false
+            Err(ScriptError::ArithmeticOverflow)
         }
     }
 }
@@ -1285,21 +1497,21 @@ pub fn op_2mul(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_2div(stack: &mut Stack) -> bool {
+pub fn op_2div(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OP2DIV, OP2DIV_DESC);
     trace(op, desc);
     let n = match stack.pop() {
         Some(StackEntry::Num(n)) => n,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
-    stack.push(StackEntry::Num(n / TWO))
+    stack.try_push(StackEntry::Num(n / TWO))
 }
 
 /// OP_NOT: Substitutes the number on top of the stack with ONE if it is equal to ZERO, with ZERO otherwise
@@ -1310,24 +1522,24 @@ pub fn op_2div(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_not(stack: &mut Stack) -> bool {
+pub fn op_not(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OPNOT, OPNOT_DESC);
     trace(op, desc);
     let n = match stack.pop() {
         Some(StackEntry::Num(n)) => n,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
     if n == ZERO {
-        stack.push(StackEntry::Num(ONE))
+        stack.try_push(StackEntry::Num(ONE))
     } else {
-        stack.push(StackEntry::Num(ZERO))
+        stack.try_push(StackEntry::Num(ZERO))
     }
 }
 
@@ -1339,24 +1551,24 @@ pub fn op_not(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_0notequal(stack: &mut Stack) -> bool {
+pub fn op_0notequal(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OP0NOTEQUAL, OP0NOTEQUAL_DESC);
     trace(op, desc);
     let n = match stack.pop() {
         Some(StackEntry::Num(n)) => n,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
     if n != ZERO {
-        stack.push(StackEntry::Num(ONE))
+        stack.try_push(StackEntry::Num(ONE))
     } else {
-        stack.push(StackEntry::Num(ZERO))
+        stack.try_push(StackEntry::Num(ZERO))
     }
 }
 
@@ -1364,39 +1576,31 @@ pub fn op_0notequal(stack: &mut Stack) -> bool {
 ///
 /// Example: OP_ADD([n1, n2]) -> [n1+n2]
 ///
+/// Info: Both numbers must be of the same kind, either `Num` or `SignedNum`
+///
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_add(stack: &mut Stack) -> bool {
+pub fn op_add(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OPADD, OPADD_DESC);
     trace(op, desc);
-    let n2 = match stack.pop() {
-        Some(StackEntry::Num(n)) => n,
-        Some(_) => {
-            error_item_type(op);
-            return false;
+    let n2 = pop_number(stack, op)?;
+    let n1 = pop_number(stack, op)?;
+    let result = match (n1, n2) {
+        (StackEntry::Num(n1), StackEntry::Num(n2)) => n1.checked_add(n2).map(StackEntry::Num),
+        (StackEntry::SignedNum(n1), StackEntry::SignedNum(n2)) => {
+            n1.checked_add(n2).map(StackEntry::SignedNum)
         }
         _ => {
-            error_num_items(op);
-            return false;
-        }
-    };
-    let n1 = match stack.pop() {
-        Some(StackEntry::Num(n)) => n,
-        Some(_) => {
             error_item_type(op);
-            return false;
-        }
-        _ => {
-            error_num_items(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
     };
-    match n1.checked_add(n2) {
-        Some(n) => stack.push(StackEntry::Num(n)),
+    match result {
+        Some(n) => stack.try_push(n),
         _ => {
             error_overflow(op);
-            false
+            Err(ScriptError::ArithmeticOverflow)
         }
     }
 }
@@ -1405,39 +1609,32 @@ pub fn op_add(stack: &mut Stack) -> bool {
 ///
 /// Example: OP_SUB([n1, n2]) -> [n1-n2]
 ///
+/// Info: Both numbers must be of the same kind. `Num` results below ZERO fail as an
+///       overflow, while `SignedNum` results may be negative
+///
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_sub(stack: &mut Stack) -> bool {
+pub fn op_sub(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OPSUB, OPSUB_DESC);
     trace(op, desc);
-    let n2 = match stack.pop() {
-        Some(StackEntry::Num(n)) => n,
-        Some(_) => {
-            error_item_type(op);
-            return false;
+    let n2 = pop_number(stack, op)?;
+    let n1 = pop_number(stack, op)?;
+    let result = match (n1, n2) {
+        (StackEntry::Num(n1), StackEntry::Num(n2)) => n1.checked_sub(n2).map(StackEntry::Num),
+        (StackEntry::SignedNum(n1), StackEntry::SignedNum(n2)) => {
+            n1.checked_sub(n2).map(StackEntry::SignedNum)
         }
         _ => {
-            error_num_items(op);
-            return false;
-        }
-    };
-    let n1 = match stack.pop() {
-        Some(StackEntry::Num(n)) => n,
-        Some(_) => {
             error_item_type(op);
-            return false;
-        }
-        _ => {
-            error_num_items(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
     };
-    match n1.checked_sub(n2) {
-        Some(n) => stack.push(StackEntry::Num(n)),
+    match result {
+        Some(n) => stack.try_push(n),
         _ => {
             error_overflow(op);
-            false
+            Err(ScriptError::ArithmeticOverflow)
         }
     }
 }
@@ -1449,36 +1646,36 @@ pub fn op_sub(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_mul(stack: &mut Stack) -> bool {
+pub fn op_mul(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OPMUL, OPMUL_DESC);
     trace(op, desc);
     let n2 = match stack.pop() {
         Some(StackEntry::Num(n)) => n,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
     let n1 = match stack.pop() {
         Some(StackEntry::Num(n)) => n,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
     match n1.checked_mul(n2) {
-        Some(n) => stack.push(StackEntry::Num(n)),
+        Some(n) => stack.try_push(StackEntry::Num(n)),
         _ => {
             error_overflow(op);
-            false
+            Err(ScriptError::ArithmeticOverflow)
         }
     }
 }
@@ -1490,36 +1687,36 @@ pub fn op_mul(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_div(stack: &mut Stack) -> bool {
+pub fn op_div(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OPDIV, OPDIV_DESC);
     trace(op, desc);
     let n2 = match stack.pop() {
         Some(StackEntry::Num(n)) => n,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
     let n1 = match stack.pop() {
         Some(StackEntry::Num(n)) => n,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
     match n1.checked_div(n2) {
-        Some(n) => stack.push(StackEntry::Num(n)),
+        Some(n) => stack.try_push(StackEntry::Num(n)),
         _ => {
             error_div_zero(op);
-            false
+            Err(ScriptError::DivZero)
         }
     }
 }
@@ -1531,36 +1728,36 @@ pub fn op_div(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_mod(stack: &mut Stack) -> bool {
+pub fn op_mod(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OPMOD, OPMOD_DESC);
     trace(op, desc);
     let n2 = match stack.pop() {
         Some(StackEntry::Num(n)) => n,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
     let n1 = match stack.pop() {
         Some(StackEntry::Num(n)) => n,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
     match n1.checked_rem(n2) {
-        Some(n) => stack.push(StackEntry::Num(n)),
+        Some(n) => stack.try_push(StackEntry::Num(n)),
         _ => {
             error_div_zero(op);
-            false
+            Err(ScriptError::DivZero)
         }
     }
 }
@@ -1572,36 +1769,36 @@ pub fn op_mod(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_lshift(stack: &mut Stack) -> bool {
+pub fn op_lshift(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OPLSHIFT, OPLSHIFT_DESC);
     trace(op, desc);
     let n2 = match stack.pop() {
         Some(StackEntry::Num(n)) => n,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
     let n1 = match stack.pop() {
         Some(StackEntry::Num(n)) => n,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
     match n1.checked_shl(n2 as u32) {
-        Some(n) => stack.push(StackEntry::Num(n)),
+        Some(n) => stack.try_push(StackEntry::Num(n)),
         _ => {
-            error_div_zero(op);
-            false
+            error_overflow(op);
+            Err(ScriptError::ArithmeticOverflow)
         }
     }
 }
@@ -1613,36 +1810,36 @@ pub fn op_lshift(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_rshift(stack: &mut Stack) -> bool {
+pub fn op_rshift(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OPRIGHT, OPRIGHT_DESC);
     trace(op, desc);
     let n2 = match stack.pop() {
         Some(StackEntry::Num(n)) => n,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
     let n1 = match stack.pop() {
         Some(StackEntry::Num(n)) => n,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
     match n1.checked_shr(n2 as u32) {
-        Some(n) => stack.push(StackEntry::Num(n)),
+        Some(n) => stack.try_push(StackEntry::Num(n)),
         _ => {
-            error_div_zero(op);
-            false
+            error_overflow(op);
+            Err(ScriptError::ArithmeticOverflow)
         }
     }
 }
@@ -1655,35 +1852,35 @@ pub fn op_rshift(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_booland(stack: &mut Stack) -> bool {
+pub fn op_booland(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OPBOOLAND, OPBOOLAND_DESC);
     trace(op, desc);
     let n2 = match stack.pop() {
         Some(StackEntry::Num(n)) => n,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
     let n1 = match stack.pop() {
         Some(StackEntry::Num(n)) => n,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
     if n1 != ZERO && n2 != ZERO {
-        stack.push(StackEntry::Num(ONE))
+        stack.try_push(StackEntry::Num(ONE))
     } else {
-        stack.push(StackEntry::Num(ZERO))
+        stack.try_push(StackEntry::Num(ZERO))
     }
 }
 
@@ -1695,35 +1892,35 @@ pub fn op_booland(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_boolor(stack: &mut Stack) -> bool {
+pub fn op_boolor(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OPBOOLOR, OPBOOLOR_DESC);
     trace(op, desc);
     let n2 = match stack.pop() {
         Some(StackEntry::Num(n)) => n,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
     let n1 = match stack.pop() {
         Some(StackEntry::Num(n)) => n,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
     if n1 != ZERO || n2 != ZERO {
-        stack.push(StackEntry::Num(ONE))
+        stack.try_push(StackEntry::Num(ONE))
     } else {
-        stack.push(StackEntry::Num(ZERO))
+        stack.try_push(StackEntry::Num(ZERO))
     }
 }
 
@@ -1735,35 +1932,35 @@ pub fn op_boolor(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_numequal(stack: &mut Stack) -> bool {
+pub fn op_numequal(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OPNUMEQUAL, OPNUMEQUAL_DESC);
     trace(op, desc);
     let n2 = match stack.pop() {
         Some(StackEntry::Num(n)) => n,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
     let n1 = match stack.pop() {
         Some(StackEntry::Num(n)) => n,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
     if n1 == n2 {
-        stack.push(StackEntry::Num(ONE))
+        stack.try_push(StackEntry::Num(ONE))
     } else {
-        stack.push(StackEntry::Num(ZERO))
+        stack.try_push(StackEntry::Num(ZERO))
     }
 }
 
@@ -1775,36 +1972,36 @@ pub fn op_numequal(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_numequalverify(stack: &mut Stack) -> bool {
+pub fn op_numequalverify(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OPNUMEQUALVERIFY, OPNUMEQUALVERIFY_DESC);
     trace(op, desc);
     let n2 = match stack.pop() {
         Some(StackEntry::Num(n)) => n,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
     let n1 = match stack.pop() {
         Some(StackEntry::Num(n)) => n,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
     if n1 != n2 {
         error_not_equal_items(op);
-        return false;
+        return Err(ScriptError::NotEqualItems);
     }
-    true
+    Ok(())
 }
 
 /// OP_NUMNOTEQUAL: Substitutes the two numbers on top of the stack with ONE if they are not equal, with ZERO otherwise
@@ -1815,35 +2012,35 @@ pub fn op_numequalverify(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_numnotequal(stack: &mut Stack) -> bool {
+pub fn op_numnotequal(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OPNUMNOTEQUAL, OPNUMNOTEQUAL_DESC);
     trace(op, desc);
     let n2 = match stack.pop() {
         Some(StackEntry::Num(n)) => n,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
     let n1 = match stack.pop() {
         Some(StackEntry::Num(n)) => n,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
     if n1 != n2 {
-        stack.push(StackEntry::Num(ONE))
+        stack.try_push(StackEntry::Num(ONE))
     } else {
-        stack.push(StackEntry::Num(ZERO))
+        stack.try_push(StackEntry::Num(ZERO))
     }
 }
 
@@ -1855,35 +2052,35 @@ pub fn op_numnotequal(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_lessthan(stack: &mut Stack) -> bool {
+pub fn op_lessthan(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OPLESSTHAN, OPLESSTHAN_DESC);
     trace(op, desc);
     let n2 = match stack.pop() {
         Some(StackEntry::Num(n)) => n,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
     let n1 = match stack.pop() {
         Some(StackEntry::Num(n)) => n,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
     if n1 < n2 {
-        stack.push(StackEntry::Num(ONE))
+        stack.try_push(StackEntry::Num(ONE))
     } else {
-        stack.push(StackEntry::Num(ZERO))
+        stack.try_push(StackEntry::Num(ZERO))
     }
 }
 
@@ -1895,36 +2092,116 @@ pub fn op_lessthan(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_greaterthan(stack: &mut Stack) -> bool {
+pub fn op_greaterthan(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OP0NOTEQUAL, OP0NOTEQUAL_DESC);
     trace(op, desc);
     let n2 = match stack.pop() {
         Some(StackEntry::Num(n)) => n,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
     let n1 = match stack.pop() {
         Some(StackEntry::Num(n)) => n,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
     if n1 > n2 {
-        stack.push(StackEntry::Num(ONE))
+        stack.try_push(StackEntry::Num(ONE))
     } else {
-        stack.push(StackEntry::Num(ZERO))
+        stack.try_push(StackEntry::Num(ZERO))
+    }
+}
+
+/// OP_LESSTHANVERIFY: Computes OP_LESSTHAN and OP_VERIFY in sequence
+///
+/// Example: OP_LESSTHANVERIFY([n1, n2]) -> []   if n1 < n2
+///          OP_LESSTHANVERIFY([n1, n2]) -> fail if n1 >= n2
+///
+/// ### Arguments
+///
+/// * `stack`  - mutable reference to the stack
+pub fn op_lessthanverify(stack: &mut Stack) -> Result<(), ScriptError> {
+    let (op, desc) = (OPLESSTHANVERIFY, OPLESSTHANVERIFY_DESC);
+    trace(op, desc);
+    let n2 = match stack.pop() {
+        Some(StackEntry::Num(n)) => n,
+        Some(_) => {
+            error_item_type(op);
+            return Err(ScriptError::ItemType);
+        }
+        _ => {
+            error_num_items(op);
+            return Err(ScriptError::NumItems);
+        }
+    };
+    let n1 = match stack.pop() {
+        Some(StackEntry::Num(n)) => n,
+        Some(_) => {
+            error_item_type(op);
+            return Err(ScriptError::ItemType);
+        }
+        _ => {
+            error_num_items(op);
+            return Err(ScriptError::NumItems);
+        }
+    };
+    if n1 >= n2 {
+        error_verify(op);
+        return Err(ScriptError::Verify);
+    }
+    Ok(())
+}
+
+/// OP_GREATERTHANVERIFY: Computes OP_GREATERTHAN and OP_VERIFY in sequence
+///
+/// Example: OP_GREATERTHANVERIFY([n1, n2]) -> []   if n1 > n2
+///          OP_GREATERTHANVERIFY([n1, n2]) -> fail if n1 <= n2
+///
+/// ### Arguments
+///
+/// * `stack`  - mutable reference to the stack
+pub fn op_greaterthanverify(stack: &mut Stack) -> Result<(), ScriptError> {
+    let (op, desc) = (OPGREATERTHANVERIFY, OPGREATERTHANVERIFY_DESC);
+    trace(op, desc);
+    let n2 = match stack.pop() {
+        Some(StackEntry::Num(n)) => n,
+        Some(_) => {
+            error_item_type(op);
+            return Err(ScriptError::ItemType);
+        }
+        _ => {
+            error_num_items(op);
+            return Err(ScriptError::NumItems);
+        }
+    };
+    let n1 = match stack.pop() {
+        Some(StackEntry::Num(n)) => n,
+        Some(_) => {
+            error_item_type(op);
+            return Err(ScriptError::ItemType);
+        }
+        _ => {
+            error_num_items(op);
+            return Err(ScriptError::NumItems);
+        }
+    };
+    if n1 <= n2 {
+        error_verify(op);
+        return Err(ScriptError::Verify);
     }
+    Ok(())
 }
 
 /// OP_LESSTHANOREQUAL: Substitutes the two numbers on top of the stack with ONE if the second-to-top is less than or equal to the top item, with ZERO otherwise
@@ -1935,35 +2212,35 @@ pub fn op_greaterthan(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_lessthanorequal(stack: &mut Stack) -> bool {
+pub fn op_lessthanorequal(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OPLESSTHANOREQUAL, OPLESSTHANOREQUAL_DESC);
     trace(op, desc);
     let n2 = match stack.pop() {
         Some(StackEntry::Num(n)) => n,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
     let n1 = match stack.pop() {
         Some(StackEntry::Num(n)) => n,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
     if n1 <= n2 {
-        stack.push(StackEntry::Num(ONE))
+        stack.try_push(StackEntry::Num(ONE))
     } else {
-        stack.push(StackEntry::Num(ZERO))
+        stack.try_push(StackEntry::Num(ZERO))
     }
 }
 
@@ -1975,35 +2252,35 @@ pub fn op_lessthanorequal(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_greaterthanorequal(stack: &mut Stack) -> bool {
+pub fn op_greaterthanorequal(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OPGREATERTHANOREQUAL, OPGREATERTHANOREQUAL_DESC);
     trace(op, desc);
     let n2 = match stack.pop() {
         Some(StackEntry::Num(n)) => n,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
     let n1 = match stack.pop() {
         Some(StackEntry::Num(n)) => n,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
     if n1 >= n2 {
-        stack.push(StackEntry::Num(ONE))
+        stack.try_push(StackEntry::Num(ONE))
     } else {
-        stack.push(StackEntry::Num(ZERO))
+        stack.try_push(StackEntry::Num(ZERO))
     }
 }
 
@@ -2015,32 +2292,32 @@ pub fn op_greaterthanorequal(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_min(stack: &mut Stack) -> bool {
+pub fn op_min(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OPMIN, OPMIN_DESC);
     trace(op, desc);
     let n2 = match stack.pop() {
         Some(StackEntry::Num(n)) => n,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
     let n1 = match stack.pop() {
         Some(StackEntry::Num(n)) => n,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
-    stack.push(StackEntry::Num(n1.min(n2)))
+    stack.try_push(StackEntry::Num(n1.min(n2)))
 }
 
 /// OP_MAX: Substitutes the two numbers on top of the stack with the maximum between the two
@@ -2051,32 +2328,32 @@ pub fn op_min(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_max(stack: &mut Stack) -> bool {
+pub fn op_max(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OPMAX, OPMAX_DESC);
     trace(op, desc);
     let n2 = match stack.pop() {
         Some(StackEntry::Num(n)) => n,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
     let n1 = match stack.pop() {
         Some(StackEntry::Num(n)) => n,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
-    stack.push(StackEntry::Num(n1.max(n2)))
+    stack.try_push(StackEntry::Num(n1.max(n2)))
 }
 
 /// OP_WITHIN: Substitutes the three numbers on top of the the stack with ONE if the third-to-top is greater or equal to the second-to-top and less than the top item, with ZERO otherwise
@@ -2087,51 +2364,193 @@ pub fn op_max(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_within(stack: &mut Stack) -> bool {
+pub fn op_within(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OPWITHIN, OPWITHIN_DESC);
     trace(op, desc);
     let n3 = match stack.pop() {
         Some(StackEntry::Num(n)) => n,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
     let n2 = match stack.pop() {
         Some(StackEntry::Num(n)) => n,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
     let n1 = match stack.pop() {
         Some(StackEntry::Num(n)) => n,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
     if n1 >= n2 && n1 < n3 {
-        stack.push(StackEntry::Num(ONE))
+        stack.try_push(StackEntry::Num(ONE))
     } else {
-        stack.push(StackEntry::Num(ZERO))
+        stack.try_push(StackEntry::Num(ZERO))
+    }
+}
+
+/// OP_LESSTHAN_SIGNED: Substitutes the two numbers on top of the stack with ONE if the second-to-top is less than the top item as signed numbers, with ZERO otherwise
+///
+/// Example: OP_LESSTHAN_SIGNED([n1, n2]) -> [1] if n1 < n2
+///          OP_LESSTHAN_SIGNED([n1, n2]) -> [0] if n1 >= n2
+///
+/// Info: `Num` and `SignedNum` may be mixed, see `pop_signed`
+///
+/// ### Arguments
+///
+/// * `stack`  - mutable reference to the stack
+pub fn op_lessthan_signed(stack: &mut Stack) -> Result<(), ScriptError> {
+    let (op, desc) = (OPLESSTHANSIGNED, OPLESSTHANSIGNED_DESC);
+    trace(op, desc);
+    let n2 = pop_signed(stack, op)?;
+    let n1 = pop_signed(stack, op)?;
+    if n1 < n2 {
+        stack.try_push(StackEntry::Num(ONE))
+    } else {
+        stack.try_push(StackEntry::Num(ZERO))
+    }
+}
+
+/// OP_GREATERTHAN_SIGNED: Substitutes the two numbers on top of the stack with ONE if the second-to-top is greater than the top item as signed numbers, with ZERO otherwise
+///
+/// Example: OP_GREATERTHAN_SIGNED([n1, n2]) -> [1] if n1 > n2
+///          OP_GREATERTHAN_SIGNED([n1, n2]) -> [0] if n1 <= n2
+///
+/// Info: `Num` and `SignedNum` may be mixed, see `pop_signed`
+///
+/// ### Arguments
+///
+/// * `stack`  - mutable reference to the stack
+pub fn op_greaterthan_signed(stack: &mut Stack) -> Result<(), ScriptError> {
+    let (op, desc) = (OPGREATERTHANSIGNED, OPGREATERTHANSIGNED_DESC);
+    trace(op, desc);
+    let n2 = pop_signed(stack, op)?;
+    let n1 = pop_signed(stack, op)?;
+    if n1 > n2 {
+        stack.try_push(StackEntry::Num(ONE))
+    } else {
+        stack.try_push(StackEntry::Num(ZERO))
+    }
+}
+
+/// OP_LESSTHANOREQUAL_SIGNED: Substitutes the two numbers on top of the stack with ONE if the second-to-top is less than or equal to the top item as signed numbers, with ZERO otherwise
+///
+/// Example: OP_LESSTHANOREQUAL_SIGNED([n1, n2]) -> [1] if n1 <= n2
+///          OP_LESSTHANOREQUAL_SIGNED([n1, n2]) -> [0] if n1 > n2
+///
+/// Info: `Num` and `SignedNum` may be mixed, see `pop_signed`
+///
+/// ### Arguments
+///
+/// * `stack`  - mutable reference to the stack
+pub fn op_lessthanorequal_signed(stack: &mut Stack) -> Result<(), ScriptError> {
+    let (op, desc) = (OPLESSTHANOREQUALSIGNED, OPLESSTHANOREQUALSIGNED_DESC);
+    trace(op, desc);
+    let n2 = pop_signed(stack, op)?;
+    let n1 = pop_signed(stack, op)?;
+    if n1 <= n2 {
+        stack.try_push(StackEntry::Num(ONE))
+    } else {
+        stack.try_push(StackEntry::Num(ZERO))
+    }
+}
+
+/// OP_GREATERTHANOREQUAL_SIGNED: Substitutes the two numbers on top of the stack with ONE if the second-to-top is greater than or equal to the top item as signed numbers, with ZERO otherwise
+///
+/// Example: OP_GREATERTHANOREQUAL_SIGNED([n1, n2]) -> [1] if n1 >= n2
+///          OP_GREATERTHANOREQUAL_SIGNED([n1, n2]) -> [0] if n1 < n2
+///
+/// Info: `Num` and `SignedNum` may be mixed, see `pop_signed`
+///
+/// ### Arguments
+///
+/// * `stack`  - mutable reference to the stack
+pub fn op_greaterthanorequal_signed(stack: &mut Stack) -> Result<(), ScriptError> {
+    let (op, desc) = (OPGREATERTHANOREQUALSIGNED, OPGREATERTHANOREQUALSIGNED_DESC);
+    trace(op, desc);
+    let n2 = pop_signed(stack, op)?;
+    let n1 = pop_signed(stack, op)?;
+    if n1 >= n2 {
+        stack.try_push(StackEntry::Num(ONE))
+    } else {
+        stack.try_push(StackEntry::Num(ZERO))
     }
 }
 
 /*---- CRYPTO OPS ----*/
 
+/// OP_RIPEMD160: Hashes the top item on the stack using RIPEMD-160
+///
+/// Example: OP_RIPEMD160([x]) -> [RIPEMD-160(x)]
+///
+/// ### Arguments
+///
+/// * `stack`  - mutable reference to the stack
+pub fn op_ripemd160(stack: &mut Stack) -> Result<(), ScriptError> {
+    let (op, desc) = (OPRIPEMD160, OPRIPEMD160_DESC);
+    trace(op, desc);
+    let data = match stack.pop() {
+        Some(StackEntry::Signature(sig)) => sig.as_ref().to_owned(),
+        Some(StackEntry::PubKey(pk)) => pk.as_ref().to_owned(),
+        Some(StackEntry::PubKeyHash(s)) | Some(StackEntry::Bytes(s)) => s.as_bytes().to_owned(),
+        Some(_) => {
+            error_item_type(op);
+            return Err(ScriptError::ItemType);
+        }
+        _ => {
+            error_num_items(op);
+            return Err(ScriptError::NumItems);
+        }
+    };
+    let hash = hex::encode(ripemd160::digest(&data));
+    stack.try_push(StackEntry::Bytes(hash))
+}
+
+/// OP_HASH160: Hashes the top item on the stack using SHA-256 and then RIPEMD-160
+///
+/// Example: OP_HASH160([x]) -> [RIPEMD-160(SHA-256(x))]
+///
+/// ### Arguments
+///
+/// * `stack`  - mutable reference to the stack
+pub fn op_hash160(stack: &mut Stack) -> Result<(), ScriptError> {
+    let (op, desc) = (OPHASH160, OPHASH160_DESC);
+    trace(op, desc);
+    let data = match stack.pop() {
+        Some(StackEntry::Signature(sig)) => sig.as_ref().to_owned(),
+        Some(StackEntry::PubKey(pk)) => pk.as_ref().to_owned(),
+        Some(StackEntry::PubKeyHash(s)) | Some(StackEntry::Bytes(s)) => s.as_bytes().to_owned(),
+        Some(_) => {
+            error_item_type(op);
+            return Err(ScriptError::ItemType);
+        }
+        _ => {
+            error_num_items(op);
+            return Err(ScriptError::NumItems);
+        }
+    };
+    let hash = hex::encode(ripemd160::digest(sha256::digest(&data).as_ref()));
+    stack.try_push(StackEntry::Bytes(hash))
+}
+
 /// OP_SHA3: Hashes the top item on the stack using SHA3-256
 ///
 /// Example: OP_SHA3([x]) -> [SHA3-256(x)]
@@ -2139,7 +2558,7 @@ pub fn op_within(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_sha3(stack: &mut Stack) -> bool {
+pub fn op_sha3(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OPSHA3, OPSHA3_DESC);
     trace(op, desc);
     let data = match stack.pop() {
@@ -2148,15 +2567,15 @@ pub fn op_sha3(stack: &mut Stack) -> bool {
         Some(StackEntry::PubKeyHash(s)) | Some(StackEntry::Bytes(s)) => s.as_bytes().to_owned(),
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
     let hash = hex::encode(sha3_256::digest(&data));
-    stack.push(StackEntry::Bytes(hash))
+    stack.try_push(StackEntry::Bytes(hash))
 }
 
 /// OP_HASH256: Creates standard address from public key and pushes it onto the stack
@@ -2166,22 +2585,22 @@ pub fn op_sha3(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_hash256(stack: &mut Stack) -> bool {
+pub fn op_hash256(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OPHASH256, OPHASH256_DESC);
     trace(op, desc);
     let pk = match stack.pop() {
         Some(StackEntry::PubKey(pk)) => pk,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
     let addr = construct_address(&pk);
-    stack.push(StackEntry::PubKeyHash(addr))
+    stack.try_push(StackEntry::PubKeyHash(addr))
 }
 
 /// OP_HASH256_V0: Creates v0 address from public key and pushes it onto the stack
@@ -2195,22 +2614,22 @@ pub fn op_hash256(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_hash256_v0(stack: &mut Stack) -> bool {
+pub fn op_hash256_v0(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OPHASH256V0, OPHASH256V0_DESC);
     trace(op, desc);
     let pk = match stack.pop() {
         Some(StackEntry::PubKey(pk)) => pk,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
     let addr_v0 = construct_address_v0(&pk);
-    stack.push(StackEntry::PubKeyHash(addr_v0))
+    stack.try_push(StackEntry::PubKeyHash(addr_v0))
 }
 
 /// OP_HASH256_TEMP: Creates temporary address from public key and pushes it onto the stack
@@ -2224,22 +2643,22 @@ pub fn op_hash256_v0(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_hash256_temp(stack: &mut Stack) -> bool {
+pub fn op_hash256_temp(stack: &mut Stack) -> Result<(), ScriptError> {
     let (op, desc) = (OPHASH256TEMP, OPHASH256TEMP_DESC);
     trace(op, desc);
     let pk = match stack.pop() {
         Some(StackEntry::PubKey(pk)) => pk,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
     let addr_temp = construct_address_temp(&pk);
-    stack.push(StackEntry::PubKeyHash(addr_temp))
+    stack.try_push(StackEntry::PubKeyHash(addr_temp))
 }
 
 /// OP_CHECKSIG: Pushes ONE onto the stack if the signature is valid, ZERO otherwise
@@ -2252,46 +2671,47 @@ pub fn op_hash256_temp(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_checksig(stack: &mut Stack) -> bool {
+/// * `ctx`    - reference to the script execution context
+pub fn op_checksig(stack: &mut Stack, ctx: &ScriptContext) -> Result<(), ScriptError> {
     let (op, desc) = (OPCHECKSIG, OPCHECKSIG_DESC);
     trace(op, desc);
     let pk = match stack.pop() {
         Some(StackEntry::PubKey(pk)) => pk,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
     let sig = match stack.pop() {
         Some(StackEntry::Signature(sig)) => sig,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
     let msg = match stack.pop() {
         Some(StackEntry::Bytes(s)) => s,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
-    if (!sign::verify_detached(&sig, msg.as_bytes(), &pk)) {
-        stack.push(StackEntry::Num(ZERO))
+    if !verify_sig(&sig, msg.as_bytes(), &pk, ctx) {
+        stack.try_push(StackEntry::Num(ZERO))
     } else {
-        stack.push(StackEntry::Num(ONE))
+        stack.try_push(StackEntry::Num(ONE))
     }
 }
 
@@ -2303,47 +2723,109 @@ pub fn op_checksig(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_checksigverify(stack: &mut Stack) -> bool {
+/// * `ctx`    - reference to the script execution context
+pub fn op_checksigverify(stack: &mut Stack, ctx: &ScriptContext) -> Result<(), ScriptError> {
     let (op, desc) = (OPCHECKSIGVERIFY, OPCHECKSIGVERIFY_DESC);
     trace(op, desc);
     let pk = match stack.pop() {
         Some(StackEntry::PubKey(pk)) => pk,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
     let sig = match stack.pop() {
         Some(StackEntry::Signature(sig)) => sig,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
     let msg = match stack.pop() {
         Some(StackEntry::Bytes(s)) => s,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
-    if (!sign::verify_detached(&sig, msg.as_bytes(), &pk)) {
+    if !verify_sig(&sig, msg.as_bytes(), &pk, ctx) {
         error_invalid_signature(op);
-        return false;
+        return Err(ScriptError::InvalidSignature);
+    }
+    Ok(())
+}
+
+/// OP_CHECKDATASIG: Pushes ONE onto the stack if the signature over the decoded message is valid, ZERO otherwise
+///
+/// Example: OP_CHECKDATASIG([msg, sig, pk]) -> [1] if Verify(sig, hex_decode(msg), pk) == 1
+///          OP_CHECKDATASIG([msg, sig, pk]) -> [0] if Verify(sig, hex_decode(msg), pk) == 0
+///
+/// Info: Unlike OP_CHECKSIG, the signature is over the raw message bytes rather than their
+///       hex encoding, so oracles can sign arbitrary data blobs
+///
+/// ### Arguments
+///
+/// * `stack`  - mutable reference to the stack
+/// * `ctx`    - reference to the script execution context
+pub fn op_checkdatasig(stack: &mut Stack, ctx: &ScriptContext) -> Result<(), ScriptError> {
+    let (op, desc) = (OPCHECKDATASIG, OPCHECKDATASIG_DESC);
+    trace(op, desc);
+    let pk = match stack.pop() {
+        Some(StackEntry::PubKey(pk)) => pk,
+        Some(_) => {
+            error_item_type(op);
+            return Err(ScriptError::ItemType);
+        }
+        _ => {
+            error_num_items(op);
+            return Err(ScriptError::NumItems);
+        }
+    };
+    let sig = match stack.pop() {
+        Some(StackEntry::Signature(sig)) => sig,
+        Some(_) => {
+            error_item_type(op);
+            return Err(ScriptError::ItemType);
+        }
+        _ => {
+            error_num_items(op);
+            return Err(ScriptError::NumItems);
+        }
+    };
+    let msg = match stack.pop() {
+        Some(StackEntry::Bytes(s)) => match hex::decode(s) {
+            Ok(msg) => msg,
+            Err(_) => {
+                error_item_type(op);
+                return Err(ScriptError::ItemType);
+            }
+        },
+        Some(_) => {
+            error_item_type(op);
+            return Err(ScriptError::ItemType);
+        }
+        _ => {
+            error_num_items(op);
+            return Err(ScriptError::NumItems);
+        }
+    };
+    if !verify_sig(&sig, &msg, &pk, ctx) {
+        stack.try_push(StackEntry::Num(ZERO))
+    } else {
+        stack.try_push(StackEntry::Num(ONE))
     }
-    true
 }
 
 /// OP_CHECKMULTISIG: Pushes ONE onto the stack if the m-of-n multi-signature is valid, ZERO otherwise
@@ -2357,23 +2839,24 @@ pub fn op_checksigverify(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_checkmultisig(stack: &mut Stack) -> bool {
+/// * `ctx`    - reference to the script execution context
+pub fn op_checkmultisig(stack: &mut Stack, ctx: &ScriptContext) -> Result<(), ScriptError> {
     let (op, desc) = (OPCHECKMULTISIG, OPCHECKMULTISIG_DESC);
     trace(op, desc);
     let n = match stack.pop() {
         Some(StackEntry::Num(n)) => n,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
     if n > MAX_PUB_KEYS_PER_MULTISIG as usize {
         error_num_pubkeys(op);
-        return false;
+        return Err(ScriptError::NumPubkeys);
     }
     let mut pks = Vec::new();
     while let Some(StackEntry::PubKey(_)) = stack.last() {
@@ -2383,22 +2866,22 @@ pub fn op_checkmultisig(stack: &mut Stack) -> bool {
     }
     if pks.len() != n {
         error_num_pubkeys(op);
-        return false;
+        return Err(ScriptError::NumPubkeys);
     }
     let m = match stack.pop() {
         Some(StackEntry::Num(n)) => n,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
     if m > n {
         error_num_signatures(op);
-        return false;
+        return Err(ScriptError::NumSignatures);
     }
     let mut sigs = Vec::new();
     while let Some(StackEntry::Signature(_)) = stack.last() {
@@ -2408,23 +2891,23 @@ pub fn op_checkmultisig(stack: &mut Stack) -> bool {
     }
     if sigs.len() != m {
         error_num_signatures(op);
-        return false;
+        return Err(ScriptError::NumSignatures);
     }
     let msg = match stack.pop() {
         Some(StackEntry::Bytes(s)) => s,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
-    if !verify_multisig(&sigs, &msg, &mut pks) {
-        stack.push(StackEntry::Num(ZERO))
+    if !verify_multisig(&sigs, &msg, &mut pks, ctx) {
+        stack.try_push(StackEntry::Num(ZERO))
     } else {
-        stack.push(StackEntry::Num(ONE))
+        stack.try_push(StackEntry::Num(ONE))
     }
 }
 
@@ -2436,23 +2919,24 @@ pub fn op_checkmultisig(stack: &mut Stack) -> bool {
 /// ### Arguments
 ///
 /// * `stack`  - mutable reference to the stack
-pub fn op_checkmultisigverify(stack: &mut Stack) -> bool {
+/// * `ctx`    - reference to the script execution context
+pub fn op_checkmultisigverify(stack: &mut Stack, ctx: &ScriptContext) -> Result<(), ScriptError> {
     let (op, desc) = (OPCHECKMULTISIG, OPCHECKMULTISIG_DESC);
     trace(op, desc);
     let n = match stack.pop() {
         Some(StackEntry::Num(n)) => n,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
     if n > MAX_PUB_KEYS_PER_MULTISIG as usize {
         error_num_pubkeys(op);
-        return false;
+        return Err(ScriptError::NumPubkeys);
     }
     let mut pks = Vec::new();
     while let Some(StackEntry::PubKey(_)) = stack.last() {
@@ -2462,22 +2946,22 @@ pub fn op_checkmultisigverify(stack: &mut Stack) -> bool {
     }
     if pks.len() != n {
         error_num_pubkeys(op);
-        return false;
+        return Err(ScriptError::NumPubkeys);
     }
     let m = match stack.pop() {
         Some(StackEntry::Num(n)) => n,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
     if m > n {
         error_num_signatures(op);
-        return false;
+        return Err(ScriptError::NumSignatures);
     }
     let mut sigs = Vec::new();
     while let Some(StackEntry::Signature(_)) = stack.last() {
@@ -2489,18 +2973,277 @@ pub fn op_checkmultisigverify(stack: &mut Stack) -> bool {
         Some(StackEntry::Bytes(s)) => s,
         Some(_) => {
             error_item_type(op);
-            return false;
+            return Err(ScriptError::ItemType);
         }
         _ => {
             error_num_items(op);
-            return false;
+            return Err(ScriptError::NumItems);
         }
     };
-    if !verify_multisig(&sigs, &msg, &mut pks) {
+    if !verify_multisig(&sigs, &msg, &mut pks, ctx) {
         error_invalid_multisignature(op);
-        return false;
+        return Err(ScriptError::InvalidMultisignature);
+    }
+    Ok(())
+}
+
+/// OP_CHECKMULTISIG_ORDERED: Pushes ONE onto the stack if the m-of-n multi-signature is valid
+///                           with signatures in the same order as their public keys, ZERO otherwise
+///
+/// Example: OP_CHECKMULTISIG_ORDERED([msg, sig1, sig3, m, pk1, pk2, pk3, n]) -> [1] if VerifyOrdered(sig1, sig3, msg, pk1, pk2, pk3) == 1
+///          OP_CHECKMULTISIG_ORDERED([msg, sig3, sig1, m, pk1, pk2, pk3, n]) -> [0]
+///
+/// Info: Each public key is tried at most once, so verification runs in O(n)
+///
+/// ### Arguments
+///
+/// * `stack`  - mutable reference to the stack
+/// * `ctx`    - reference to the script execution context
+pub fn op_checkmultisig_ordered(stack: &mut Stack, ctx: &ScriptContext) -> Result<(), ScriptError> {
+    let (op, desc) = (OPCHECKMULTISIGORDERED, OPCHECKMULTISIGORDERED_DESC);
+    trace(op, desc);
+    let n = match stack.pop() {
+        Some(StackEntry::Num(n)) => n,
+        Some(_) => {
+            error_item_type(op);
+            return Err(ScriptError::ItemType);
+        }
+        _ => {
+            error_num_items(op);
+            return Err(ScriptError::NumItems);
+        }
+    };
+    if n > MAX_PUB_KEYS_PER_MULTISIG as usize {
+        error_num_pubkeys(op);
+        return Err(ScriptError::NumPubkeys);
+    }
+    let mut pks = Vec::new();
+    while let Some(StackEntry::PubKey(_)) = stack.last() {
+        if let Some(StackEntry::PubKey(pk)) = stack.pop() {
+            pks.push(pk);
+        }
+    }
+    if pks.len() != n {
+        error_num_pubkeys(op);
+        return Err(ScriptError::NumPubkeys);
+    }
+    let m = match stack.pop() {
+        Some(StackEntry::Num(n)) => n,
+        Some(_) => {
+            error_item_type(op);
+            return Err(ScriptError::ItemType);
+        }
+        _ => {
+            error_num_items(op);
+            return Err(ScriptError::NumItems);
+        }
+    };
+    if m > n {
+        error_num_signatures(op);
+        return Err(ScriptError::NumSignatures);
+    }
+    let mut sigs = Vec::new();
+    while let Some(StackEntry::Signature(_)) = stack.last() {
+        if let Some(StackEntry::Signature(sig)) = stack.pop() {
+            sigs.push(sig);
+        }
+    }
+    if sigs.len() != m {
+        error_num_signatures(op);
+        return Err(ScriptError::NumSignatures);
+    }
+    let msg = match stack.pop() {
+        Some(StackEntry::Bytes(s)) => s,
+        Some(_) => {
+            error_item_type(op);
+            return Err(ScriptError::ItemType);
+        }
+        _ => {
+            error_num_items(op);
+            return Err(ScriptError::NumItems);
+        }
+    };
+    if !verify_multisig_ordered(&sigs, &msg, &pks, ctx) {
+        stack.try_push(StackEntry::Num(ZERO))
+    } else {
+        stack.try_push(StackEntry::Num(ONE))
     }
-    true
+}
+
+/// OP_CODESEPARATOR: Does nothing, as signatures do not commit to the script
+///
+/// Example: OP_CODESEPARATOR([x]) -> [x]
+///
+/// Info: In Bitcoin this marks where the script subset covered by later signatures begins.
+///       Signatures here are over the outpoint hash rather than the script, so there is
+///       nothing to mark. It is accepted so that scripts from Bitcoin tooling still run
+///
+/// ### Arguments
+///
+/// * `stack`  - mutable reference to the stack
+pub fn op_codeseparator(stack: &mut Stack) -> Result<(), ScriptError> {
+    let (op, desc) = (OPCODESEPARATOR, OPCODESEPARATOR_DESC);
+    trace(op, desc);
+    Ok(())
+}
+
+/// OP_CHECKAGGSIG: Pushes ONE onto the stack if the signature is valid for the aggregate of the public keys, ZERO otherwise
+///
+/// Example: OP_CHECKAGGSIG([msg, sig, pk1, pk2, pk3, n]) -> [1] if Verify(sig, msg, Aggregate(pk1, pk2, pk3)) == 1
+///          OP_CHECKAGGSIG([msg, sig, pk1, pk2, pk3, n]) -> [0] if Verify(sig, msg, Aggregate(pk1, pk2, pk3)) == 0
+///
+/// Info: Verifies a single signature in place of the O(m*n) checks of OP_CHECKMULTISIG.
+///       Keys are aggregated under the sum-of-keys convention of `aggregate_public_keys`,
+///       which is n-of-n only and open to rogue-key attacks, so the opcode is disabled
+///       until a MuSig-style scheme is wired in
+///
+/// ### Arguments
+///
+/// * `stack`  - mutable reference to the stack
+/// * `ctx`    - reference to the script execution context
+pub fn op_checkaggsig(stack: &mut Stack, ctx: &ScriptContext) -> Result<(), ScriptError> {
+    let (op, desc) = (OPCHECKAGGSIG, OPCHECKAGGSIG_DESC);
+    trace(op, desc);
+    let n = match stack.pop() {
+        Some(StackEntry::Num(n)) => n,
+        Some(_) => {
+            error_item_type(op);
+            return Err(ScriptError::ItemType);
+        }
+        _ => {
+            error_num_items(op);
+            return Err(ScriptError::NumItems);
+        }
+    };
+    if n > MAX_PUB_KEYS_PER_MULTISIG as usize {
+        error_num_pubkeys(op);
+        return Err(ScriptError::NumPubkeys);
+    }
+    let mut pks = Vec::new();
+    while let Some(StackEntry::PubKey(_)) = stack.last() {
+        if let Some(StackEntry::PubKey(pk)) = stack.pop() {
+            pks.push(pk);
+        }
+    }
+    if pks.is_empty() || pks.len() != n {
+        error_num_pubkeys(op);
+        return Err(ScriptError::NumPubkeys);
+    }
+    let sig = match stack.pop() {
+        Some(StackEntry::Signature(sig)) => sig,
+        Some(_) => {
+            error_item_type(op);
+            return Err(ScriptError::ItemType);
+        }
+        _ => {
+            error_num_items(op);
+            return Err(ScriptError::NumItems);
+        }
+    };
+    let msg = match stack.pop() {
+        Some(StackEntry::Bytes(s)) => s,
+        Some(_) => {
+            error_item_type(op);
+            return Err(ScriptError::ItemType);
+        }
+        _ => {
+            error_num_items(op);
+            return Err(ScriptError::NumItems);
+        }
+    };
+    match sign::aggregate_public_keys(&pks) {
+        Some(pk) if verify_sig(&sig, msg.as_bytes(), &pk, ctx) => {
+            stack.try_push(StackEntry::Num(ONE))
+        }
+        _ => stack.try_push(StackEntry::Num(ZERO)),
+    }
+}
+
+/*---- LOCKTIME OPS ----*/
+
+/// OP_CHECKLOCKTIMEVERIFY: Ends execution with an error if the number on top of the stack is greater than the current block height
+///
+/// Example: OP_CHECKLOCKTIMEVERIFY([n]) -> [n]  if n <= block_height
+///          OP_CHECKLOCKTIMEVERIFY([n]) -> fail if n > block_height
+///
+/// ### Arguments
+///
+/// * `stack`  - mutable reference to the stack
+/// * `ctx`    - reference to the script execution context
+pub fn op_checklocktimeverify(stack: &mut Stack, ctx: &ScriptContext) -> Result<(), ScriptError> {
+    let (op, desc) = (OPCHECKLOCKTIMEVERIFY, OPCHECKLOCKTIMEVERIFY_DESC);
+    trace(op, desc);
+    let n = match stack.last() {
+        Some(StackEntry::Num(n)) => n,
+        Some(_) => {
+            error_item_type(op);
+            return Err(ScriptError::ItemType);
+        }
+        _ => {
+            error_num_items(op);
+            return Err(ScriptError::NumItems);
+        }
+    };
+    if n as u64 > ctx.block_height {
+        error_locktime(op);
+        return Err(ScriptError::Locktime);
+    }
+    Ok(())
+}
+
+/*---- COVENANT OPS ----*/
+
+/// OP_CHECKTEMPLATEVERIFY: Ends execution with an error if the spending transaction's input
+/// count and outputs do not hash to the 32-byte commitment on top of the stack
+///
+/// Example: OP_CHECKTEMPLATEVERIFY([h]) -> [h]  if h == template_hash
+///          OP_CHECKTEMPLATEVERIFY([h]) -> fail if h != template_hash
+///
+/// ### Arguments
+///
+/// * `stack`  - mutable reference to the stack
+/// * `ctx`    - reference to the script execution context
+pub fn op_checktemplateverify(stack: &mut Stack, ctx: &ScriptContext) -> Result<(), ScriptError> {
+    let (op, desc) = (OPCHECKTEMPLATEVERIFY, OPCHECKTEMPLATEVERIFY_DESC);
+    trace(op, desc);
+    let commitment = match stack.last() {
+        Some(StackEntry::Bytes(s)) => match hex::decode(s) {
+            Ok(h) if h.len() == 32 => h,
+            _ => {
+                error_item_type(op);
+                return Err(ScriptError::ItemType);
+            }
+        },
+        Some(_) => {
+            error_item_type(op);
+            return Err(ScriptError::ItemType);
+        }
+        _ => {
+            error_num_items(op);
+            return Err(ScriptError::NumItems);
+        }
+    };
+    match ctx.template_hash {
+        Some(h) if h[..] == commitment[..] => Ok(()),
+        _ => {
+            error_template_mismatch(op);
+            Err(ScriptError::TemplateMismatch)
+        }
+    }
+}
+
+/// Verifies a signature with the context's signature scheme. ring already rejects
+/// ed25519 signatures with an unreduced scalar `S`, so no separate canonical check is needed
+///
+/// ### Arguments
+///
+/// * `sig` - signature to verify
+/// * `msg` - data to verify against
+/// * `pk`  - public key to verify against
+/// * `ctx` - context the script is executed in
+fn verify_sig(sig: &Signature, msg: &[u8], pk: &PublicKey, ctx: &ScriptContext) -> bool {
+    let scheme = ctx.signature_scheme.scheme();
+    scheme.verify(sig.as_ref(), msg, pk.as_ref())
 }
 
 /// Verifies an m-of-n multi-signature
@@ -2510,11 +3253,17 @@ pub fn op_checkmultisigverify(stack: &mut Stack) -> bool {
 /// * `sigs` - signatures to verify
 /// * `msg`  - data to verify against
 /// * `pks`  - public keys to match against
-fn verify_multisig(sigs: &[Signature], msg: &String, pks: &mut Vec<PublicKey>) -> bool {
+/// * `ctx`  - context the script is executed in
+fn verify_multisig(
+    sigs: &[Signature],
+    msg: &String,
+    pks: &mut Vec<PublicKey>,
+    ctx: &ScriptContext,
+) -> bool {
     let mut num_valid_sigs = ZERO;
     for (index_sig, sig) in sigs.iter().enumerate() {
         for (index_pk, pk) in pks.iter().enumerate() {
-            if sign::verify_detached(sig, msg.as_bytes(), pk) {
+            if verify_sig(sig, msg.as_bytes(), pk, ctx) {
                 num_valid_sigs += ONE;
                 pks.remove(index_pk);
                 break;
@@ -2526,3 +3275,64 @@ fn verify_multisig(sigs: &[Signature], msg: &String, pks: &mut Vec<PublicKey>) -
     }
     true
 }
+
+/// Verifies an m-of-n multi-signature where signatures appear in the same order as their public keys
+///
+/// ### Arguments
+///
+/// * `sigs` - signatures to verify
+/// * `msg`  - data to verify against
+/// * `pks`  - public keys to match against, in the same order as the signatures
+/// * `ctx`  - context the script is executed in
+fn verify_multisig_ordered(
+    sigs: &[Signature],
+    msg: &str,
+    pks: &[PublicKey],
+    ctx: &ScriptContext,
+) -> bool {
+    let mut pks = pks.iter();
+    sigs.iter()
+        .all(|sig| pks.any(|pk| verify_sig(sig, msg.as_bytes(), pk, ctx)))
+}
+
+/// Pops a number from the top of the stack as a signed number, for the signed comparison
+/// opcodes. A `Num` above `i64::MAX` has no signed value, so fails with an overflow.
+/// All other opcodes keep unsigned semantics for `Num`
+///
+/// ### Arguments
+///
+/// * `stack`  - mutable reference to the stack
+/// * `op`     - name of the calling opcode, for logging
+fn pop_signed(stack: &mut Stack, op: &str) -> Result<i64, ScriptError> {
+    match pop_number(stack, op)? {
+        StackEntry::SignedNum(n) => Ok(n),
+        StackEntry::Num(n) => i64::try_from(n).map_err(|_| {
+            error_overflow(op);
+            ScriptError::ArithmeticOverflow
+        }),
+        _ => {
+            error_item_type(op);
+            Err(ScriptError::ItemType)
+        }
+    }
+}
+
+/// Pops a number, signed or unsigned, from the top of the stack
+///
+/// ### Arguments
+///
+/// * `stack`  - mutable reference to the stack
+/// * `op`     - name of the calling opcode, for logging
+fn pop_number(stack: &mut Stack, op: &str) -> Result<StackEntry, ScriptError> {
+    match stack.pop() {
+        Some(n @ (StackEntry::Num(_) | StackEntry::SignedNum(_))) => Ok(n),
+        Some(_) => {
+            error_item_type(op);
+            Err(ScriptError::ItemType)
+        }
+        _ => {
+            error_num_items(op);
+            Err(ScriptError::NumItems)
+        }
+    }
+}
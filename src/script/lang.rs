@@ -4,18 +4,26 @@ use crate::crypto::sha3_256;
 use crate::crypto::sign_ed25519::{
     PublicKey, Signature, ED25519_PUBLIC_KEY_LEN, ED25519_SIGNATURE_LEN,
 };
+use crate::crypto::signature_scheme::SignatureSchemeId;
+use crate::primitives::transaction::Transaction;
 use crate::script::interface_ops::*;
 use crate::script::{OpCodes, StackEntry};
 use crate::utils::error_utils::*;
-use crate::utils::transaction_utils::{construct_address, construct_address_for};
-use bincode::serialize;
+use crate::utils::transaction_utils::{
+    construct_address, construct_address_for, construct_p2sh_address,
+};
+use bincode::{deserialize, serialize};
 use bytes::Bytes;
 use hex::encode;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::convert::TryFrom;
+use std::{error, fmt};
 use tracing::{error, warn};
 
 /// Stack for script execution
 #[derive(Clone, Debug, PartialOrd, Eq, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
 pub struct Stack {
     pub main_stack: Vec<StackEntry>,
     pub alt_stack: Vec<StackEntry>,
@@ -38,13 +46,45 @@ impl Stack {
 
     /// Checks if the stack is valid
     pub fn is_valid(&self) -> bool {
-        if self.main_stack.len() + self.alt_stack.len() > MAX_STACK_SIZE as usize {
+        self.is_within_size(MAX_STACK_SIZE as usize)
+            && self.is_within_memory(MAX_STACK_MEMORY_BYTES)
+    }
+
+    /// Checks if the number of items on the main and alt stacks is within the given limit
+    ///
+    /// ### Arguments
+    ///
+    /// * `max_stack_size`  - Maximum number of items allowed on the stacks
+    pub fn is_within_size(&self, max_stack_size: usize) -> bool {
+        if self.main_stack.len() + self.alt_stack.len() > max_stack_size {
             error_max_stack_size();
             return false;
         }
         true
     }
 
+    /// Number of bytes held by the items on the main and alt stacks, as measured by `entry_size`
+    pub fn memory_bytes(&self) -> usize {
+        self.main_stack
+            .iter()
+            .chain(self.alt_stack.iter())
+            .map(entry_size)
+            .sum()
+    }
+
+    /// Checks if the bytes held by the items on the main and alt stacks are within the given limit
+    ///
+    /// ### Arguments
+    ///
+    /// * `max_stack_memory`    - Maximum number of bytes allowed on the stacks
+    pub fn is_within_memory(&self, max_stack_memory: usize) -> bool {
+        if self.memory_bytes() > max_stack_memory {
+            error_max_stack_memory();
+            return false;
+        }
+        true
+    }
+
     /// Pops the top item from the stack
     pub fn pop(&mut self) -> Option<StackEntry> {
         self.main_stack.pop()
@@ -57,27 +97,62 @@ impl Stack {
 
     /// Checks if the last item on the stack is not zero
     pub fn is_last_non_zero(&self) -> bool {
-        self.last() != Some(StackEntry::Num(ZERO))
+        !matches!(
+            self.last(),
+            Some(StackEntry::Num(ZERO)) | Some(StackEntry::SignedNum(0))
+        )
     }
 
     /// Pushes a new entry onto the stack
     pub fn push(&mut self, stack_entry: StackEntry) -> bool {
-        match stack_entry.clone() {
-            StackEntry::Op(_) => {
-                return false;
-            }
-            StackEntry::PubKeyHash(s) | StackEntry::Bytes(s) => {
-                if s.len() > MAX_SCRIPT_ITEM_SIZE as usize {
-                    return false;
-                }
-            }
-            _ => (),
+        self.try_push(stack_entry).is_ok()
+    }
+
+    /// Pushes a new entry onto the stack, returning the reason it was rejected if any
+    pub fn try_push(&mut self, stack_entry: StackEntry) -> Result<(), ScriptError> {
+        if let StackEntry::Op(_) = stack_entry {
+            return Err(ScriptError::ItemType);
+        }
+        if entry_size(&stack_entry) > MAX_SCRIPT_ITEM_SIZE as usize {
+            return Err(ScriptError::ItemSize);
         }
         self.main_stack.push(stack_entry);
-        true
+        Ok(())
+    }
+}
+
+/// Computes the size in bytes of a stack entry, as accounted for in scripts and on the stack
+///
+/// ### Arguments
+///
+/// * `entry`   - Stack entry to compute the size of
+pub fn entry_size(entry: &StackEntry) -> usize {
+    match entry {
+        StackEntry::Op(_) => ONE,
+        StackEntry::Signature(_) => ED25519_SIGNATURE_LEN,
+        StackEntry::PubKey(_) => ED25519_PUBLIC_KEY_LEN,
+        StackEntry::PubKeyHash(s) | StackEntry::Bytes(s) => s.len(),
+        StackEntry::Num(_) => usize::BITS as usize / EIGHT,
+        StackEntry::SignedNum(_) => i64::BITS as usize / EIGHT,
     }
 }
 
+/// Adds a stack entry to a running script length in bytes and opcode count,
+/// returning None if either overflows
+///
+/// ### Arguments
+///
+/// * `counts`  - Script length in bytes and number of opcodes so far
+/// * `entry`   - Stack entry to add
+fn checked_add_entry(counts: (usize, usize), entry: &StackEntry) -> Option<(usize, usize)> {
+    let (len, ops_count) = counts;
+    let ops_count = match entry {
+        StackEntry::Op(_) => ops_count.checked_add(ONE)?,
+        _ => ops_count,
+    };
+    Some((len.checked_add(entry_size(entry))?, ops_count))
+}
+
 impl From<Vec<StackEntry>> for Stack {
     /// Creates a new stack with a pre-filled main stack
     fn from(stack: Vec<StackEntry>) -> Self {
@@ -128,18 +203,25 @@ impl ConditionStack {
         self.size += ONE;
     }
 
-    /// Pops the top value from the condition stack
-    pub fn pop(&mut self) {
+    /// Pops the top value from the condition stack. Returns false if the stack is empty
+    pub fn pop(&mut self) -> bool {
+        if self.is_empty() {
+            return false;
+        }
         self.size -= ONE;
         if let Some(pos) = self.first_false_pos {
             if pos == self.size {
                 self.first_false_pos.take();
             }
         }
+        true
     }
 
-    /// Toggles the top value on the condition stack
-    pub fn toggle(&mut self) {
+    /// Toggles the top value on the condition stack. Returns false if the stack is empty
+    pub fn toggle(&mut self) -> bool {
+        if self.is_empty() {
+            return false;
+        }
         match self.first_false_pos {
             Some(pos) => {
                 if pos == self.size - ONE {
@@ -150,12 +232,144 @@ impl ConditionStack {
                 self.first_false_pos = Some(self.size - ONE);
             }
         }
+        true
     }
 }
 
+/// Resource limits enforced while a script is executed
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScriptLimits {
+    pub max_ops: usize,
+    pub max_stack_size: usize,
+    pub max_item_size: usize,
+    pub max_stack_memory: usize,
+}
+
+impl Default for ScriptLimits {
+    fn default() -> Self {
+        Self {
+            max_ops: MAX_OPS_PER_SCRIPT as usize,
+            max_stack_size: MAX_STACK_SIZE as usize,
+            max_item_size: MAX_SCRIPT_ITEM_SIZE as usize,
+            max_stack_memory: MAX_STACK_MEMORY_BYTES,
+        }
+    }
+}
+
+/// Network version a script is executed under. All version-specific script
+/// behavior is decided here
+///
+/// * `V0`      - Legacy `NETWORK_VERSION_V0` scripts
+/// * `Temp`    - Temporary `NETWORK_VERSION_TEMP` scripts
+/// * `Current` - Scripts for the current `NETWORK_VERSION`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NetworkVersion {
+    V0,
+    Temp,
+    #[default]
+    Current,
+}
+
+impl NetworkVersion {
+    /// Gets the network version for an address version, None being the current version
+    ///
+    /// ### Arguments
+    ///
+    /// * `address_version` - Address version to get the network version of
+    pub fn from_address_version(address_version: Option<u64>) -> Self {
+        match address_version {
+            Some(NETWORK_VERSION_V0) => Self::V0,
+            Some(NETWORK_VERSION_TEMP) => Self::Temp,
+            _ => Self::Current,
+        }
+    }
+
+    /// Gets the address version for this network version, None being the current version
+    pub fn address_version(&self) -> Option<u64> {
+        match self {
+            Self::V0 => Some(NETWORK_VERSION_V0),
+            Self::Temp => Some(NETWORK_VERSION_TEMP),
+            Self::Current => None,
+        }
+    }
+
+    /// Gets the standard opcode hashing public keys into addresses
+    pub fn hash_opcode(&self) -> OpCodes {
+        match self {
+            Self::V0 => OpCodes::OP_HASH256_V0,
+            Self::Temp => OpCodes::OP_HASH256_TEMP,
+            Self::Current => OpCodes::OP_HASH256,
+        }
+    }
+
+    /// Whether scripts may use the opcodes in `DISABLED_OPCODES`
+    pub fn allows_disabled_opcodes(&self) -> bool {
+        matches!(self, Self::V0 | Self::Temp)
+    }
+
+    /// Gets the minimum number of signatures a standard multisig must require
+    pub fn min_multisig_threshold(&self) -> usize {
+        match self {
+            Self::V0 | Self::Temp => 0,
+            Self::Current => MIN_MULTISIG_THRESHOLD,
+        }
+    }
+}
+
+/// Context in which a script is executed
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScriptContext {
+    pub block_height: u64,
+    /// Address version of the script being executed, selecting the `NetworkVersion`
+    /// rules it runs under
+    pub address_version: Option<u64>,
+    /// Signature scheme the signature opcodes verify with
+    pub signature_scheme: SignatureSchemeId,
+    /// Template of the spending transaction, as computed by `Transaction::template_hash`.
+    /// OP_CHECKTEMPLATEVERIFY fails when no spending transaction is known
+    pub template_hash: Option<[u8; 32]>,
+    /// Id of the network spending signatures are made for, as mixed into
+    /// `construct_tx_in_signable_hash_with_network`
+    pub network_id: u64,
+}
+
+impl ScriptContext {
+    /// Gives the script access to the transaction spending it
+    ///
+    /// ### Arguments
+    ///
+    /// * `tx`  - Spending transaction
+    pub fn with_spending_tx(self, tx: &Transaction) -> Self {
+        Self {
+            template_hash: Some(tx.template_hash()),
+            ..self
+        }
+    }
+
+    /// Network version the script is executed under
+    pub fn network_version(&self) -> NetworkVersion {
+        NetworkVersion::from_address_version(self.address_version)
+    }
+
+    /// Whether scripts may use the opcodes in `DISABLED_OPCODES`
+    pub fn allows_disabled_opcodes(&self) -> bool {
+        self.network_version().allows_disabled_opcodes()
+    }
+
+    /// Whether small numbers must be pushed with their `OP_0..OP_16` opcodes for a
+    /// script to be standard. Legacy (V0 and TEMP) scripts opt out
+    pub fn requires_minimal_encoding(&self) -> bool {
+        !self.allows_disabled_opcodes()
+    }
+}
+
+/// An executed opcode paired with the main stack as it stood afterwards
+pub type ScriptStep = (OpCodes, Vec<StackEntry>);
+
 /// Scripts are defined as a sequence of stack entries
 /// NOTE: A tuple struct could probably work here as well
 #[derive(Clone, Debug, PartialOrd, Eq, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
 pub struct Script {
     pub stack: Vec<StackEntry>,
 }
@@ -172,178 +386,473 @@ impl Script {
         Self { stack: Vec::new() }
     }
 
-    /// Checks if a script is valid
-    pub fn is_valid(&self) -> bool {
-        let mut len = ZERO; // script length in bytes
-        let mut ops_count = ZERO; // number of opcodes in script
-        for entry in &self.stack {
-            match entry {
-                StackEntry::Op(_) => {
-                    len += ONE;
-                    ops_count += ONE;
-                }
-                StackEntry::Signature(_) => len += ED25519_SIGNATURE_LEN,
-                StackEntry::PubKey(_) => len += ED25519_PUBLIC_KEY_LEN,
-                StackEntry::PubKeyHash(s) | StackEntry::Bytes(s) => len += s.len(),
-                StackEntry::Num(_) => len += usize::BITS as usize / EIGHT,
-            };
-        }
+    /// Computes the script length in bytes and the number of opcodes in the script,
+    /// returning None if either overflows
+    fn len_and_ops_count(&self) -> Option<(usize, usize)> {
+        self.stack.iter().try_fold((ZERO, ZERO), checked_add_entry)
+    }
+
+    /// Computes the size in bytes of the serialized script: the byte contribution of
+    /// each entry, as counted towards `MAX_SCRIPT_SIZE`, plus its encoding overhead
+    pub fn serialized_size(&self) -> usize {
+        let overhead = |entry: &StackEntry| match entry {
+            // variant tag and opcode tag, less the single byte counted for the opcode
+            StackEntry::Op(_) => 2 * SERIALIZED_TAG_LEN - ONE,
+            StackEntry::Num(_) | StackEntry::SignedNum(_) => SERIALIZED_TAG_LEN,
+            // variant tag and length prefix
+            StackEntry::Signature(_)
+            | StackEntry::PubKey(_)
+            | StackEntry::PubKeyHash(_)
+            | StackEntry::Bytes(_) => SERIALIZED_TAG_LEN + SERIALIZED_LEN_PREFIX,
+        };
+        SERIALIZED_LEN_PREFIX
+            + self
+                .stack
+                .iter()
+                .map(|entry| entry_size(entry) + overhead(entry))
+                .sum::<usize>()
+    }
+
+    /// Checks the script length and the number of opcodes in the script against their limits
+    fn check_limits(&self) -> Result<(), ScriptError> {
+        let (len, ops_count) = match self.len_and_ops_count() {
+            Some(counts) => counts,
+            None => {
+                error_max_script_size();
+                return Err(ScriptError::MaxScriptSize);
+            }
+        };
         if len > MAX_SCRIPT_SIZE as usize {
             error_max_script_size();
-            return false;
+            return Err(ScriptError::MaxScriptSize);
         }
         if ops_count > MAX_OPS_PER_SCRIPT as usize {
             error_max_ops_script();
+            return Err(ScriptError::MaxOpsScript);
+        }
+        Ok(())
+    }
+
+    /// Checks whether the script contains any of the opcodes in `DISABLED_OPCODES`
+    pub fn uses_disabled_opcodes(&self) -> bool {
+        self.stack
+            .iter()
+            .any(|entry| matches!(entry, StackEntry::Op(op) if DISABLED_OPCODES.contains(op)))
+    }
+
+    /// Checks that no number from ZERO to SIXTEEN is pushed as a `Num` entry rather than
+    /// with its dedicated `OP_0..OP_16` opcode
+    pub fn is_minimally_encoded(&self) -> bool {
+        !self
+            .stack
+            .iter()
+            .any(|entry| matches!(entry, StackEntry::Num(n) if *n <= SIXTEEN))
+    }
+
+    /// Checks that the script's number encoding is standard within the given context
+    ///
+    /// ### Arguments
+    ///
+    /// * `ctx` - Context the script is executed in
+    pub fn has_standard_encoding(&self, ctx: &ScriptContext) -> bool {
+        !ctx.requires_minimal_encoding() || self.is_minimally_encoded()
+    }
+
+    /// Checks if a script is valid
+    pub fn is_valid(&self) -> bool {
+        self.check_limits().is_ok()
+    }
+
+    /// Checks whether the script meets relay policy under a network version, on top of
+    /// the consensus limits checked by `is_valid`. Standard scripts avoid disabled opcodes
+    /// and follow a recognised template: `pay2pkh` hashed for the version, multisig, asset
+    /// creation, a P2SH lock, or a P2SH unlock of a multisig redeem script whose signature
+    /// stack is push only and, where the version requires it, minimally encoded. The
+    /// templates themselves keep their `Num` entries. Multisig must require at least the
+    /// version's `min_multisig_threshold` signatures. Nodes may keep non-standard scripts
+    /// out of the mempool while still accepting them in blocks
+    ///
+    /// ### Arguments
+    ///
+    /// * `version` - Network version the script is relayed under
+    pub fn is_standard(&self, version: NetworkVersion) -> bool {
+        let ctx = ScriptContext {
+            address_version: version.address_version(),
+            ..Default::default()
+        };
+        if !self.is_valid() || (!ctx.allows_disabled_opcodes() && self.uses_disabled_opcodes()) {
             return false;
         }
-        true
+        let is_standard_multisig = |script: &Script| {
+            script
+                .is_multisig()
+                .is_some_and(|(m, _)| m >= version.min_multisig_threshold())
+        };
+        if self.is_p2pkh() {
+            return self.contains_op(version.hash_opcode());
+        }
+        if self.is_multisig().is_some() {
+            return is_standard_multisig(self);
+        }
+        if self.is_create() {
+            return true;
+        }
+        if let [StackEntry::PubKeyHash(hash)] = self.stack.as_slice() {
+            return hash.as_bytes().first() == Some(&P2SH_PREPEND);
+        }
+        match (self.p2sh_redeem(), self.stack.split_last()) {
+            (Some((redeem, _)), Some((_, sig_stack))) => {
+                let sig_stack = Script::from(sig_stack.to_vec());
+                is_standard_multisig(&redeem)
+                    && sig_stack.is_push_only()
+                    && sig_stack.has_standard_encoding(&ctx)
+            }
+            _ => false,
+        }
     }
 
     /// Interprets and executes a script
     pub fn interpret(&self) -> bool {
-        if !self.is_valid() {
-            return false;
+        self.interpret_with_context(&ScriptContext::default())
+    }
+
+    /// Interprets and executes a script within the given context
+    ///
+    /// ### Arguments
+    ///
+    /// * `ctx` - Context to execute the script in
+    pub fn interpret_with_context(&self, ctx: &ScriptContext) -> bool {
+        self.interpret_with_context_checked(ctx).is_ok()
+    }
+
+    /// Interprets and executes a script under the given network version
+    ///
+    /// ### Arguments
+    ///
+    /// * `version` - Network version to execute the script under
+    pub fn interpret_for_version(&self, version: NetworkVersion) -> bool {
+        let ctx = ScriptContext {
+            address_version: version.address_version(),
+            ..Default::default()
+        };
+        self.interpret_with_context(&ctx)
+    }
+
+    /// Interprets and executes a script, returning the reason for failure if any
+    pub fn interpret_checked(&self) -> Result<(), ScriptError> {
+        self.interpret_with_context_checked(&ScriptContext::default())
+    }
+
+    /// Interprets and executes a script within the given context, returning the
+    /// reason for failure if any
+    ///
+    /// ### Arguments
+    ///
+    /// * `ctx` - Context to execute the script in
+    pub fn interpret_with_context_checked(&self, ctx: &ScriptContext) -> Result<(), ScriptError> {
+        self.interpret_with_limits(ctx, &ScriptLimits::default())
+    }
+
+    /// Interprets and executes a script within the given context, aborting once the
+    /// number of executed opcodes, stack items or item size exceeds the given limits
+    ///
+    /// ### Arguments
+    ///
+    /// * `ctx`     - Context to execute the script in
+    /// * `limits`  - Resource limits to enforce during execution
+    pub fn interpret_with_limits(
+        &self,
+        ctx: &ScriptContext,
+        limits: &ScriptLimits,
+    ) -> Result<(), ScriptError> {
+        self.execute(ctx, limits, Vec::new(), None).map(|_| ())
+    }
+
+    /// Interprets and executes the script as a locking script, with the main stack seeded
+    /// by the entries an unlocking script left on it
+    ///
+    /// ### Arguments
+    ///
+    /// * `initial` - Entries to seed the main stack with, bottom first
+    pub fn interpret_with_initial_stack(&self, initial: Vec<StackEntry>) -> bool {
+        self.interpret_with_initial_stack_checked(initial).is_ok()
+    }
+
+    /// Interprets and executes the script as `interpret_with_initial_stack` does, returning
+    /// the reason for failure if any. The initial stack must itself respect the stack limits
+    ///
+    /// ### Arguments
+    ///
+    /// * `initial` - Entries to seed the main stack with, bottom first
+    pub fn interpret_with_initial_stack_checked(
+        &self,
+        initial: Vec<StackEntry>,
+    ) -> Result<(), ScriptError> {
+        let (ctx, limits) = (ScriptContext::default(), ScriptLimits::default());
+        self.execute(&ctx, &limits, initial, None).map(|_| ())
+    }
+
+    /// Interprets and executes a script, returning the final main stack on success
+    pub fn interpret_trace(&self) -> Result<Vec<StackEntry>, ScriptError> {
+        let stack = self.execute(
+            &ScriptContext::default(),
+            &ScriptLimits::default(),
+            Vec::new(),
+            None,
+        )?;
+        Ok(stack.main_stack)
+    }
+
+    /// Interprets and executes a script, recording each executed opcode together with
+    /// the main stack as it stood after that opcode ran. The steps recorded up to a
+    /// failure are returned alongside the result
+    pub fn interpret_with_steps(&self) -> (Vec<ScriptStep>, Result<Vec<StackEntry>, ScriptError>) {
+        let mut steps = Vec::new();
+        let result = self
+            .execute(
+                &ScriptContext::default(),
+                &ScriptLimits::default(),
+                Vec::new(),
+                Some(&mut steps),
+            )
+            .map(|stack| stack.main_stack);
+        (steps, result)
+    }
+
+    /// Executes a script, returning the final stack on success
+    ///
+    /// ### Arguments
+    ///
+    /// * `ctx`     - Context to execute the script in
+    /// * `limits`  - Resource limits to enforce during execution
+    /// * `initial` - Entries to seed the main stack with, bottom first
+    /// * `steps`   - Trace of executed opcodes and resulting main stacks, if wanted
+    fn execute(
+        &self,
+        ctx: &ScriptContext,
+        limits: &ScriptLimits,
+        initial: Vec<StackEntry>,
+        mut steps: Option<&mut Vec<ScriptStep>>,
+    ) -> Result<Stack, ScriptError> {
+        self.check_limits()?;
+        if !ctx.allows_disabled_opcodes() && self.uses_disabled_opcodes() {
+            error_disabled_opcode();
+            return Err(ScriptError::DisabledOpcode);
         }
         let mut stack = Stack::new();
+        for entry in initial {
+            if entry_size(&entry) > limits.max_item_size {
+                return Err(ScriptError::ItemSize);
+            }
+            stack.try_push(entry)?;
+        }
+        if !stack.is_within_size(limits.max_stack_size) {
+            return Err(ScriptError::MaxStackSize);
+        }
+        if !stack.is_within_memory(limits.max_stack_memory) {
+            return Err(ScriptError::MaxStackMemory);
+        }
         let mut cond_stack = ConditionStack::new();
-        let mut test_for_return = true;
-        for stack_entry in &self.stack {
+        let mut ops_count = ZERO; // number of executed opcodes
+        let mut entries = self.stack.iter();
+        while let Some(stack_entry) = entries.next() {
             match stack_entry.clone() {
                 /*---- OPCODE ----*/
                 StackEntry::Op(op) => {
                     if !cond_stack.all_true() && !op.is_conditional() {
-                        // skip opcode if latest condition check failed
+                        // skip opcode (and its pushdata operand) if latest condition check failed.
+                        // The operand must be data, so an opcode is never skipped along with it
+                        if op == OpCodes::OP_PUSHDATA {
+                            match entries.next() {
+                                Some(StackEntry::Op(_)) => {
+                                    error_item_type(OPPUSHDATA);
+                                    return Err(ScriptError::ItemType);
+                                }
+                                None => {
+                                    error_num_items(OPPUSHDATA);
+                                    return Err(ScriptError::NumItems);
+                                }
+                                Some(_) => (),
+                            }
+                        }
                         continue;
                     }
-                    match op {
+                    ops_count += ONE;
+                    if ops_count > limits.max_ops {
+                        error_resource_exhausted();
+                        return Err(ScriptError::ResourceExhausted);
+                    }
+                    let result = match op {
                         // constants
-                        OpCodes::OP_0 => test_for_return &= op_0(&mut stack),
-                        OpCodes::OP_1 => test_for_return &= op_1(&mut stack),
-                        OpCodes::OP_2 => test_for_return &= op_2(&mut stack),
-                        OpCodes::OP_3 => test_for_return &= op_3(&mut stack),
-                        OpCodes::OP_4 => test_for_return &= op_4(&mut stack),
-                        OpCodes::OP_5 => test_for_return &= op_5(&mut stack),
-                        OpCodes::OP_6 => test_for_return &= op_6(&mut stack),
-                        OpCodes::OP_7 => test_for_return &= op_7(&mut stack),
-                        OpCodes::OP_8 => test_for_return &= op_8(&mut stack),
-                        OpCodes::OP_9 => test_for_return &= op_9(&mut stack),
-                        OpCodes::OP_10 => test_for_return &= op_10(&mut stack),
-                        OpCodes::OP_11 => test_for_return &= op_11(&mut stack),
-                        OpCodes::OP_12 => test_for_return &= op_12(&mut stack),
-                        OpCodes::OP_13 => test_for_return &= op_13(&mut stack),
-                        OpCodes::OP_14 => test_for_return &= op_14(&mut stack),
-                        OpCodes::OP_15 => test_for_return &= op_15(&mut stack),
-                        OpCodes::OP_16 => test_for_return &= op_16(&mut stack),
-                        // flow control
-                        OpCodes::OP_NOP => test_for_return &= op_nop(&mut stack),
-                        OpCodes::OP_IF => test_for_return &= op_if(&mut stack, &mut cond_stack),
-                        OpCodes::OP_NOTIF => {
-                            test_for_return &= op_notif(&mut stack, &mut cond_stack)
+                        OpCodes::OP_0 => op_0(&mut stack),
+                        OpCodes::OP_PUSHDATA => {
+                            op_pushdata(&mut stack, entries.next(), limits.max_item_size)
                         }
-                        OpCodes::OP_ELSE => test_for_return &= op_else(&mut cond_stack),
-                        OpCodes::OP_ENDIF => test_for_return &= op_endif(&mut cond_stack),
-                        OpCodes::OP_VERIFY => test_for_return &= op_verify(&mut stack),
-                        OpCodes::OP_BURN => test_for_return &= op_burn(&mut stack),
+                        OpCodes::OP_1 => op_1(&mut stack),
+                        OpCodes::OP_2 => op_2(&mut stack),
+                        OpCodes::OP_3 => op_3(&mut stack),
+                        OpCodes::OP_4 => op_4(&mut stack),
+                        OpCodes::OP_5 => op_5(&mut stack),
+                        OpCodes::OP_6 => op_6(&mut stack),
+                        OpCodes::OP_7 => op_7(&mut stack),
+                        OpCodes::OP_8 => op_8(&mut stack),
+                        OpCodes::OP_9 => op_9(&mut stack),
+                        OpCodes::OP_10 => op_10(&mut stack),
+                        OpCodes::OP_11 => op_11(&mut stack),
+                        OpCodes::OP_12 => op_12(&mut stack),
+                        OpCodes::OP_13 => op_13(&mut stack),
+                        OpCodes::OP_14 => op_14(&mut stack),
+                        OpCodes::OP_15 => op_15(&mut stack),
+                        OpCodes::OP_16 => op_16(&mut stack),
+                        // flow control
+                        OpCodes::OP_NOP => op_nop(&mut stack),
+                        OpCodes::OP_IF => op_if(&mut stack, &mut cond_stack),
+                        OpCodes::OP_NOTIF => op_notif(&mut stack, &mut cond_stack),
+                        OpCodes::OP_ELSE => op_else(&mut cond_stack),
+                        OpCodes::OP_ENDIF => op_endif(&mut cond_stack),
+                        OpCodes::OP_VERIFY => op_verify(&mut stack),
+                        OpCodes::OP_BURN => op_burn(&mut stack),
                         // stack
-                        OpCodes::OP_TOALTSTACK => test_for_return &= op_toaltstack(&mut stack),
-                        OpCodes::OP_FROMALTSTACK => test_for_return &= op_fromaltstack(&mut stack),
-                        OpCodes::OP_2DROP => test_for_return &= op_2drop(&mut stack),
-                        OpCodes::OP_2DUP => test_for_return &= op_2dup(&mut stack),
-                        OpCodes::OP_3DUP => test_for_return &= op_3dup(&mut stack),
-                        OpCodes::OP_2OVER => test_for_return &= op_2over(&mut stack),
-                        OpCodes::OP_2ROT => test_for_return &= op_2rot(&mut stack),
-                        OpCodes::OP_2SWAP => test_for_return &= op_2swap(&mut stack),
-                        OpCodes::OP_IFDUP => test_for_return &= op_ifdup(&mut stack),
-                        OpCodes::OP_DEPTH => test_for_return &= op_depth(&mut stack),
-                        OpCodes::OP_DROP => test_for_return &= op_drop(&mut stack),
-                        OpCodes::OP_DUP => test_for_return &= op_dup(&mut stack),
-                        OpCodes::OP_NIP => test_for_return &= op_nip(&mut stack),
-                        OpCodes::OP_OVER => test_for_return &= op_over(&mut stack),
-                        OpCodes::OP_PICK => test_for_return &= op_pick(&mut stack),
-                        OpCodes::OP_ROLL => test_for_return &= op_roll(&mut stack),
-                        OpCodes::OP_ROT => test_for_return &= op_rot(&mut stack),
-                        OpCodes::OP_SWAP => test_for_return &= op_swap(&mut stack),
-                        OpCodes::OP_TUCK => test_for_return &= op_tuck(&mut stack),
+                        OpCodes::OP_TOALTSTACK => op_toaltstack(&mut stack),
+                        OpCodes::OP_FROMALTSTACK => op_fromaltstack(&mut stack),
+                        OpCodes::OP_2DROP => op_2drop(&mut stack),
+                        OpCodes::OP_2DUP => op_2dup(&mut stack),
+                        OpCodes::OP_3DUP => op_3dup(&mut stack),
+                        OpCodes::OP_2OVER => op_2over(&mut stack),
+                        OpCodes::OP_2ROT => op_2rot(&mut stack),
+                        OpCodes::OP_2SWAP => op_2swap(&mut stack),
+                        OpCodes::OP_IFDUP => op_ifdup(&mut stack),
+                        OpCodes::OP_DEPTH => op_depth(&mut stack),
+                        OpCodes::OP_DROP => op_drop(&mut stack),
+                        OpCodes::OP_DUP => op_dup(&mut stack),
+                        OpCodes::OP_NIP => op_nip(&mut stack),
+                        OpCodes::OP_OVER => op_over(&mut stack),
+                        OpCodes::OP_PICK => op_pick(&mut stack),
+                        OpCodes::OP_ROLL => op_roll(&mut stack),
+                        OpCodes::OP_ROT => op_rot(&mut stack),
+                        OpCodes::OP_SWAP => op_swap(&mut stack),
+                        OpCodes::OP_TUCK => op_tuck(&mut stack),
                         // splice
-                        OpCodes::OP_CAT => test_for_return &= op_cat(&mut stack),
-                        OpCodes::OP_SUBSTR => test_for_return &= op_substr(&mut stack),
-                        OpCodes::OP_LEFT => test_for_return &= op_left(&mut stack),
-                        OpCodes::OP_RIGHT => test_for_return &= op_right(&mut stack),
-                        OpCodes::OP_SIZE => test_for_return &= op_size(&mut stack),
+                        OpCodes::OP_CAT => op_cat(&mut stack),
+                        OpCodes::OP_SUBSTR => op_substr(&mut stack),
+                        OpCodes::OP_LEFT => op_left(&mut stack),
+                        OpCodes::OP_RIGHT => op_right(&mut stack),
+                        OpCodes::OP_SIZE => op_size(&mut stack),
+                        OpCodes::OP_REVERSE => op_reverse(&mut stack),
+                        OpCodes::OP_SPLIT => op_split(&mut stack),
+                        OpCodes::OP_NUM2BIN => op_num2bin(&mut stack),
+                        OpCodes::OP_BIN2NUM => op_bin2num(&mut stack),
                         // bitwise logic
-                        OpCodes::OP_INVERT => test_for_return &= op_invert(&mut stack),
-                        OpCodes::OP_AND => test_for_return &= op_and(&mut stack),
-                        OpCodes::OP_OR => test_for_return &= op_or(&mut stack),
-                        OpCodes::OP_XOR => test_for_return &= op_xor(&mut stack),
-                        OpCodes::OP_EQUAL => test_for_return &= op_equal(&mut stack),
-                        OpCodes::OP_EQUALVERIFY => test_for_return &= op_equalverify(&mut stack),
+                        OpCodes::OP_INVERT => op_invert(&mut stack),
+                        OpCodes::OP_AND => op_and(&mut stack),
+                        OpCodes::OP_OR => op_or(&mut stack),
+                        OpCodes::OP_XOR => op_xor(&mut stack),
+                        OpCodes::OP_EQUAL => op_equal(&mut stack),
+                        OpCodes::OP_EQUALVERIFY => op_equalverify(&mut stack),
                         // arithmetic
-                        OpCodes::OP_1ADD => test_for_return &= op_1add(&mut stack),
-                        OpCodes::OP_1SUB => test_for_return &= op_1sub(&mut stack),
-                        OpCodes::OP_2MUL => test_for_return &= op_2mul(&mut stack),
-                        OpCodes::OP_2DIV => test_for_return &= op_2div(&mut stack),
-                        OpCodes::OP_NOT => test_for_return &= op_not(&mut stack),
-                        OpCodes::OP_0NOTEQUAL => test_for_return &= op_0notequal(&mut stack),
-                        OpCodes::OP_ADD => test_for_return &= op_add(&mut stack),
-                        OpCodes::OP_SUB => test_for_return &= op_sub(&mut stack),
-                        OpCodes::OP_MUL => test_for_return &= op_mul(&mut stack),
-                        OpCodes::OP_DIV => test_for_return &= op_div(&mut stack),
-                        OpCodes::OP_MOD => test_for_return &= op_mod(&mut stack),
-                        OpCodes::OP_LSHIFT => test_for_return &= op_lshift(&mut stack),
-                        OpCodes::OP_RSHIFT => test_for_return &= op_rshift(&mut stack),
-                        OpCodes::OP_BOOLAND => test_for_return &= op_booland(&mut stack),
-                        OpCodes::OP_BOOLOR => test_for_return &= op_boolor(&mut stack),
-                        OpCodes::OP_NUMEQUAL => test_for_return &= op_numequal(&mut stack),
-                        OpCodes::OP_NUMEQUALVERIFY => {
-                            test_for_return &= op_numequalverify(&mut stack)
-                        }
-                        OpCodes::OP_NUMNOTEQUAL => test_for_return &= op_numnotequal(&mut stack),
-                        OpCodes::OP_LESSTHAN => test_for_return &= op_lessthan(&mut stack),
-                        OpCodes::OP_GREATERTHAN => test_for_return &= op_greaterthan(&mut stack),
-                        OpCodes::OP_LESSTHANOREQUAL => {
-                            test_for_return &= op_lessthanorequal(&mut stack)
+                        OpCodes::OP_1ADD => op_1add(&mut stack),
+                        OpCodes::OP_1SUB => op_1sub(&mut stack),
+                        OpCodes::OP_2MUL => op_2mul(&mut stack),
+                        OpCodes::OP_2DIV => op_2div(&mut stack),
+                        OpCodes::OP_NOT => op_not(&mut stack),
+                        OpCodes::OP_0NOTEQUAL => op_0notequal(&mut stack),
+                        OpCodes::OP_ADD => op_add(&mut stack),
+                        OpCodes::OP_SUB => op_sub(&mut stack),
+                        OpCodes::OP_MUL => op_mul(&mut stack),
+                        OpCodes::OP_DIV => op_div(&mut stack),
+                        OpCodes::OP_MOD => op_mod(&mut stack),
+                        OpCodes::OP_LSHIFT => op_lshift(&mut stack),
+                        OpCodes::OP_RSHIFT => op_rshift(&mut stack),
+                        OpCodes::OP_BOOLAND => op_booland(&mut stack),
+                        OpCodes::OP_BOOLOR => op_boolor(&mut stack),
+                        OpCodes::OP_NUMEQUAL => op_numequal(&mut stack),
+                        OpCodes::OP_NUMEQUALVERIFY => op_numequalverify(&mut stack),
+                        OpCodes::OP_NUMNOTEQUAL => op_numnotequal(&mut stack),
+                        OpCodes::OP_LESSTHAN => op_lessthan(&mut stack),
+                        OpCodes::OP_GREATERTHAN => op_greaterthan(&mut stack),
+                        OpCodes::OP_LESSTHANOREQUAL => op_lessthanorequal(&mut stack),
+                        OpCodes::OP_GREATERTHANOREQUAL => op_greaterthanorequal(&mut stack),
+                        OpCodes::OP_LESSTHANVERIFY => op_lessthanverify(&mut stack),
+                        OpCodes::OP_GREATERTHANVERIFY => op_greaterthanverify(&mut stack),
+                        OpCodes::OP_MIN => op_min(&mut stack),
+                        OpCodes::OP_MAX => op_max(&mut stack),
+                        OpCodes::OP_WITHIN => op_within(&mut stack),
+                        OpCodes::OP_LESSTHAN_SIGNED => op_lessthan_signed(&mut stack),
+                        OpCodes::OP_GREATERTHAN_SIGNED => op_greaterthan_signed(&mut stack),
+                        OpCodes::OP_LESSTHANOREQUAL_SIGNED => op_lessthanorequal_signed(&mut stack),
+                        OpCodes::OP_GREATERTHANOREQUAL_SIGNED => {
+                            op_greaterthanorequal_signed(&mut stack)
                         }
-                        OpCodes::OP_GREATERTHANOREQUAL => {
-                            test_for_return &= op_greaterthanorequal(&mut stack)
-                        }
-                        OpCodes::OP_MIN => test_for_return &= op_min(&mut stack),
-                        OpCodes::OP_MAX => test_for_return &= op_max(&mut stack),
-                        OpCodes::OP_WITHIN => test_for_return &= op_within(&mut stack),
                         // crypto
-                        OpCodes::OP_SHA3 => test_for_return &= op_sha3(&mut stack),
-                        OpCodes::OP_HASH256 => test_for_return &= op_hash256(&mut stack),
-                        OpCodes::OP_HASH256_V0 => test_for_return &= op_hash256_v0(&mut stack),
-                        OpCodes::OP_HASH256_TEMP => test_for_return &= op_hash256_temp(&mut stack),
-                        OpCodes::OP_CHECKSIG => test_for_return &= op_checksig(&mut stack),
-                        OpCodes::OP_CHECKSIGVERIFY => {
-                            test_for_return &= op_checksigverify(&mut stack)
-                        }
-                        OpCodes::OP_CHECKMULTISIG => {
-                            test_for_return &= op_checkmultisig(&mut stack)
-                        }
-                        OpCodes::OP_CHECKMULTISIGVERIFY => {
-                            test_for_return &= op_checkmultisigverify(&mut stack)
+                        OpCodes::OP_RIPEMD160 => op_ripemd160(&mut stack),
+                        OpCodes::OP_HASH160 => op_hash160(&mut stack),
+                        OpCodes::OP_SHA3 => op_sha3(&mut stack),
+                        OpCodes::OP_HASH256 => op_hash256(&mut stack),
+                        OpCodes::OP_HASH256_V0 => op_hash256_v0(&mut stack),
+                        OpCodes::OP_HASH256_TEMP => op_hash256_temp(&mut stack),
+                        OpCodes::OP_CHECKSIG => op_checksig(&mut stack, ctx),
+                        OpCodes::OP_CHECKSIGVERIFY => op_checksigverify(&mut stack, ctx),
+                        OpCodes::OP_CHECKMULTISIG => op_checkmultisig(&mut stack, ctx),
+                        OpCodes::OP_CHECKMULTISIGVERIFY => op_checkmultisigverify(&mut stack, ctx),
+                        OpCodes::OP_CHECKMULTISIG_ORDERED => {
+                            op_checkmultisig_ordered(&mut stack, ctx)
                         }
+                        OpCodes::OP_CHECKDATASIG => op_checkdatasig(&mut stack, ctx),
+                        OpCodes::OP_CODESEPARATOR => op_codeseparator(&mut stack),
+                        OpCodes::OP_CHECKAGGSIG => op_checkaggsig(&mut stack, ctx),
+                        // locktime
+                        OpCodes::OP_CHECKLOCKTIMEVERIFY => op_checklocktimeverify(&mut stack, ctx),
+                        // covenants
+                        OpCodes::OP_CHECKTEMPLATEVERIFY => op_checktemplateverify(&mut stack, ctx),
+                        // reserved
+                        OpCodes::OP_NOP1
+                        | OpCodes::OP_NOP2
+                        | OpCodes::OP_NOP3
+                        | OpCodes::OP_NOP4
+                        | OpCodes::OP_NOP5
+                        | OpCodes::OP_NOP6
+                        | OpCodes::OP_NOP7
+                        | OpCodes::OP_NOP8
+                        | OpCodes::OP_NOP9
+                        | OpCodes::OP_NOP10 => op_nop(&mut stack),
                         // smart data
-                        OpCodes::OP_CREATE => (),
+                        OpCodes::OP_CREATE => Ok(()),
+                    };
+                    result?;
+                    if let Some(steps) = steps.as_mut() {
+                        steps.push((op, stack.main_stack.clone()));
                     }
                 }
-                /*---- SIGNATURE | PUBKEY | PUBKEYHASH | NUM | BYTES ----*/
+                /*---- SIGNATURE | PUBKEY | PUBKEYHASH | NUM | BYTES | SIGNEDNUM ----*/
                 StackEntry::Signature(_)
                 | StackEntry::PubKey(_)
                 | StackEntry::PubKeyHash(_)
                 | StackEntry::Num(_)
-                | StackEntry::Bytes(_) => {
+                | StackEntry::Bytes(_)
+                | StackEntry::SignedNum(_) => {
                     if cond_stack.all_true() {
-                        test_for_return &= stack.push(stack_entry.clone())
+                        stack.try_push(stack_entry.clone())?;
                     }
                 }
             }
-            if !test_for_return || !stack.is_valid() {
-                return false;
+            if !stack.is_within_size(limits.max_stack_size) {
+                return Err(ScriptError::MaxStackSize);
+            }
+            if !stack.is_within_memory(limits.max_stack_memory) {
+                return Err(ScriptError::MaxStackMemory);
+            }
+            if matches!(stack.main_stack.last(), Some(entry) if entry_size(entry) > limits.max_item_size)
+            {
+                return Err(ScriptError::ItemSize);
             }
         }
-        test_for_return && stack.is_last_non_zero() && cond_stack.is_empty()
+        if !stack.is_last_non_zero() {
+            return Err(ScriptError::Verify);
+        }
+        if !cond_stack.is_empty() {
+            return Err(ScriptError::UnbalancedCondition);
+        }
+        Ok(stack)
     }
 
     /// Constructs a new script for coinbase
@@ -395,11 +904,7 @@ impl Script {
         pub_key: PublicKey,
         address_version: Option<u64>,
     ) -> Self {
-        let op_hash_256 = match address_version {
-            Some(NETWORK_VERSION_V0) => OpCodes::OP_HASH256_V0,
-            Some(NETWORK_VERSION_TEMP) => OpCodes::OP_HASH256_TEMP,
-            _ => OpCodes::OP_HASH256,
-        };
+        let op_hash_256 = NetworkVersion::from_address_version(address_version).hash_opcode();
         let stack = vec![
             StackEntry::Bytes(check_data),
             StackEntry::Signature(signature),
@@ -413,7 +918,9 @@ impl Script {
         Self { stack }
     }
 
-    /// Constructs one part of a multiparty transaction script
+    /// Constructs one part of a multiparty transaction script. The signature is checked
+    /// against the party's key directly, so unlike `pay2pkh` the script carries no address
+    /// and is the same for every network version
     ///
     /// ### Arguments
     ///
@@ -446,6 +953,27 @@ impl Script {
         Self { stack }
     }
 
+    /// Constructs a multisig locking script requiring signatures in the same order as the public keys
+    ///
+    /// ### Arguments
+    ///
+    /// * `m`           - Number of signatures required to unlock
+    /// * `n`           - Number of valid signatures total
+    /// * `check_data`  - Data to have checked against signatures
+    /// * `pub_keys`    - The constituent public keys, in signing order
+    pub fn multisig_lock_ordered(
+        m: usize,
+        n: usize,
+        check_data: String,
+        pub_keys: Vec<PublicKey>,
+    ) -> Self {
+        let mut stack = vec![StackEntry::Bytes(check_data), StackEntry::Num(m)];
+        stack.append(&mut pub_keys.iter().map(|e| StackEntry::PubKey(*e)).collect());
+        stack.push(StackEntry::Num(n));
+        stack.push(StackEntry::Op(OpCodes::OP_CHECKMULTISIG_ORDERED));
+        Self { stack }
+    }
+
     /// Constructs a multisig unlocking script
     ///
     /// ### Arguments
@@ -463,21 +991,61 @@ impl Script {
         Self { stack }
     }
 
-    /// Constructs a multisig validation script
+    /// Merges partial multisig unlocking scripts, each holding a subset of the signatures
+    /// over the same check data, into a single unlocking script. Signatures keep the
+    /// order they first appear in, and duplicates are dropped
+    ///
+    /// ### Arguments
+    ///
+    /// * `partials`    - Unlocking scripts built with `multisig_unlock`
+    pub fn merge_multisig_unlock(partials: &[Script]) -> Result<Script, ScriptError> {
+        let mut check_data = None;
+        let mut signatures: Vec<Signature> = Vec::new();
+        for partial in partials {
+            let (data, sigs) = match partial.stack.split_first() {
+                Some((StackEntry::Bytes(data), sigs)) => (data, sigs),
+                Some(_) => return Err(ScriptError::ItemType),
+                None => return Err(ScriptError::NumItems),
+            };
+            if *check_data.get_or_insert(data) != data {
+                return Err(ScriptError::NotEqualItems);
+            }
+            for sig in sigs {
+                match sig {
+                    StackEntry::Signature(sig) if signatures.contains(sig) => (),
+                    StackEntry::Signature(sig) => signatures.push(*sig),
+                    _ => return Err(ScriptError::ItemType),
+                }
+            }
+        }
+
+        match check_data {
+            Some(check_data) => Ok(Self::multisig_unlock(check_data.clone(), signatures)),
+            None => Err(ScriptError::NumItems),
+        }
+    }
+
+    /// Constructs a multisig validation script. The public keys are put in canonical
+    /// order by their address under `address_version`, as `construct_multisig_address` does,
+    /// so the script's key order may differ from the order they are given in. The signatures
+    /// are kept in the order given
     ///
     /// ### Arguments
     ///
-    /// * `m`           - Number of signatures to assure validity
-    /// * `n`           - Number of public keys that are valid
-    /// * `signatures`  - Signatures to validate
-    /// * `pub_keys`    - Public keys to validate
+    /// * `m`               - Number of signatures to assure validity
+    /// * `n`               - Number of public keys that are valid
+    /// * `signatures`      - Signatures to validate
+    /// * `pub_keys`        - Public keys to validate, in any order
+    /// * `address_version` - Network version of the public keys' addresses
     pub fn multisig_validation(
         m: usize,
         n: usize,
         check_data: String,
         signatures: Vec<Signature>,
-        pub_keys: Vec<PublicKey>,
+        mut pub_keys: Vec<PublicKey>,
+        address_version: Option<u64>,
     ) -> Self {
+        pub_keys.sort_by_cached_key(|pk| construct_address_for(pk, address_version));
         let mut stack = vec![StackEntry::Bytes(check_data)];
         stack.append(
             &mut signatures
@@ -491,6 +1059,630 @@ impl Script {
         stack.push(StackEntry::Op(OpCodes::OP_CHECKMULTISIG));
         Self { stack }
     }
+
+    /// Constructs a locking script requiring a number within `[min, max)`. The unlocking
+    /// script is the single entry `StackEntry::num(n)`, placed before the locking script
+    ///
+    /// Example: [Num(n)] ++ [Num(min), Num(max), OP_WITHIN] -> [1] if min <= n < max
+    ///
+    /// ### Arguments
+    ///
+    /// * `min` - Lower bound, inclusive
+    /// * `max` - Upper bound, exclusive
+    pub fn numeric_bound_lock(min: usize, max: usize) -> Self {
+        let stack = vec![
+            StackEntry::Num(min),
+            StackEntry::Num(max),
+            StackEntry::Op(OpCodes::OP_WITHIN),
+        ];
+        Self { stack }
+    }
+
+    /// Constructs an escrow locking script, unlocked by m of the public keys at any time,
+    /// or by `fallback_m` of them once the block height reaches `timeout_height`. The
+    /// unlocking script is the check data and signatures, followed by `OP_1` to take the
+    /// multisig branch or `OP_0` to take the fallback branch, placed before the locking script
+    ///
+    /// Example: [data, sig1, sig2, OP_1] ++ [OP_IF, Num(m), pk1, pk2, pk3, Num(n), OP_CHECKMULTISIG,
+    ///          OP_ELSE, Num(timeout_height), OP_CHECKLOCKTIMEVERIFY, OP_DROP,
+    ///          Num(fallback_m), pk1, pk2, pk3, Num(n), OP_CHECKMULTISIG, OP_ENDIF]
+    ///
+    /// ### Arguments
+    ///
+    /// * `m`               - Number of signatures required to unlock before the timeout
+    /// * `pub_keys`        - The constituent public keys
+    /// * `timeout_height`  - Block height from which the fallback branch unlocks
+    /// * `fallback_m`      - Number of signatures required to unlock after the timeout
+    pub fn escrow_lock(
+        m: usize,
+        pub_keys: &[PublicKey],
+        timeout_height: u64,
+        fallback_m: usize,
+    ) -> Self {
+        let n = pub_keys.len();
+        let multisig = |m| {
+            let mut stack = vec![StackEntry::Num(m)];
+            stack.extend(pub_keys.iter().map(|pk| StackEntry::PubKey(*pk)));
+            stack.push(StackEntry::Num(n));
+            stack.push(StackEntry::Op(OpCodes::OP_CHECKMULTISIG));
+            stack
+        };
+
+        let mut stack = vec![StackEntry::Op(OpCodes::OP_IF)];
+        stack.extend(multisig(m));
+        stack.push(StackEntry::Op(OpCodes::OP_ELSE));
+        stack.push(StackEntry::Num(timeout_height as usize));
+        stack.push(StackEntry::Op(OpCodes::OP_CHECKLOCKTIMEVERIFY));
+        stack.push(StackEntry::Op(OpCodes::OP_DROP));
+        stack.extend(multisig(fallback_m));
+        stack.push(StackEntry::Op(OpCodes::OP_ENDIF));
+        Self { stack }
+    }
+
+    /// Constructs a data-carrier script embedding arbitrary data after `OP_BURN`, which
+    /// plays the part of Bitcoin's `OP_RETURN`. Outputs holding it are provably unspendable
+    ///
+    /// ### Arguments
+    ///
+    /// * `data`    - Data to embed
+    pub fn data_carrier(data: Vec<u8>) -> Self {
+        let stack = vec![
+            StackEntry::Op(OpCodes::OP_BURN),
+            StackEntry::Bytes(hex::encode(data)),
+        ];
+        Self { stack }
+    }
+
+    /// Constructs the locking script of a receipt burn: a lone `OP_BURN`, so outputs holding
+    /// it are provably unspendable and the receipts they carry leave circulation
+    pub fn receipt_burn_lock() -> Self {
+        let stack = vec![StackEntry::Op(OpCodes::OP_BURN)];
+        Self { stack }
+    }
+
+    /// Gets the data embedded by a script built with `data_carrier`
+    pub fn data_carrier_payload(&self) -> Option<Vec<u8>> {
+        match self.stack.as_slice() {
+            [StackEntry::Op(OpCodes::OP_BURN), StackEntry::Bytes(data)] => hex::decode(data).ok(),
+            _ => None,
+        }
+    }
+
+    /// Constructs a pay to script hash locking script, committing to the redeem script
+    /// by its P2SH address. This address is what the paying `TxOut` holds as its
+    /// `script_public_key`
+    ///
+    /// ### Arguments
+    ///
+    /// * `redeem`  - Redeem script to commit to
+    pub fn p2sh_lock(redeem: &Script) -> Self {
+        let stack = vec![StackEntry::PubKeyHash(construct_p2sh_address(redeem))];
+        Self { stack }
+    }
+
+    /// Constructs a pay to script hash unlocking script. The redeem script is pushed
+    /// serialized after the signature stack, so it must fit in a single stack item
+    ///
+    /// ### Arguments
+    ///
+    /// * `redeem`      - Redeem script committed to by the locking script
+    /// * `sig_stack`   - Entries satisfying the redeem script
+    pub fn p2sh_unlock(redeem: &Script, sig_stack: Vec<StackEntry>) -> Self {
+        let mut stack = sig_stack;
+        stack.push(StackEntry::Bytes(hex::encode(redeem.to_bytes())));
+        Self { stack }
+    }
+
+    /// Splits a pay to script hash unlocking script into its redeem script and the
+    /// script to execute, made of the signature stack followed by the redeem script
+    pub fn p2sh_redeem(&self) -> Option<(Script, Script)> {
+        let (last, sig_stack) = self.stack.split_last()?;
+        let redeem: Script = match last {
+            StackEntry::Bytes(b) => deserialize(&hex::decode(b).ok()?).ok()?,
+            _ => return None,
+        };
+        let mut stack = sig_stack.to_vec();
+        stack.extend(redeem.stack.iter().cloned());
+        Some((redeem, Script { stack }))
+    }
+
+    /// Checks whether the script has the pay to public key hash shape built by `pay2pkh`
+    pub fn is_p2pkh(&self) -> bool {
+        matches!(
+            self.stack.as_slice(),
+            [
+                StackEntry::Bytes(_),
+                StackEntry::Signature(_),
+                StackEntry::PubKey(_),
+                StackEntry::Op(OpCodes::OP_DUP),
+                StackEntry::Op(
+                    OpCodes::OP_HASH256 | OpCodes::OP_HASH256_V0 | OpCodes::OP_HASH256_TEMP
+                ),
+                StackEntry::PubKeyHash(_),
+                StackEntry::Op(OpCodes::OP_EQUALVERIFY),
+                StackEntry::Op(OpCodes::OP_CHECKSIG),
+            ]
+        )
+    }
+
+    /// Checks whether the script is an unlocking script in the exact form built by `pay2pkh`,
+    /// `new_for_coinbase`, `new_create_asset` or `p2sh_unlock`, with a push only,
+    /// minimally encoded P2SH signature stack. Signatures only
+    /// commit to the spent `OutPoint`, so an unlocking script in any other form could be
+    /// altered without invalidating it
+    pub fn is_canonical_unlock(&self) -> bool {
+        if self.is_p2pkh() || self.is_create() {
+            return true;
+        }
+        if let [block_number] = self.stack.as_slice() {
+            return block_number.as_num().is_some();
+        }
+        match (self.p2sh_redeem(), self.stack.split_last()) {
+            (Some((redeem, _)), Some((StackEntry::Bytes(b), sig_stack))) => {
+                let sig_stack = Script::from(sig_stack.to_vec());
+                *b == hex::encode(redeem.to_bytes())
+                    && sig_stack.is_push_only()
+                    && sig_stack.is_minimally_encoded()
+            }
+            _ => false,
+        }
+    }
+
+    /// Checks whether the script has the m-of-n multisig shape built by `multisig_lock`,
+    /// `multisig_lock_ordered` or `multisig_validation`, returning m and n if so
+    pub fn is_multisig(&self) -> Option<(usize, usize)> {
+        let rest = match self.stack.split_last()? {
+            (StackEntry::Op(OpCodes::OP_CHECKMULTISIG), rest)
+            | (StackEntry::Op(OpCodes::OP_CHECKMULTISIG_ORDERED), rest) => rest,
+            _ => return None,
+        };
+        let (n, rest) = rest.split_last()?;
+        let n = n.as_num()?;
+        let (rest, pub_keys) = rest.split_at(rest.len().checked_sub(n)?);
+        let (m, rest) = rest.split_last()?;
+        let m = m.as_num()?;
+        let sigs = match rest.split_first()? {
+            (StackEntry::Bytes(_), sigs) => sigs,
+            _ => return None,
+        };
+
+        if m > n
+            || !pub_keys.iter().all(|e| matches!(e, StackEntry::PubKey(_)))
+            || !sigs.iter().all(|e| matches!(e, StackEntry::Signature(_)))
+        {
+            return None;
+        }
+        Some((m, n))
+    }
+
+    /// Checks whether the script has the asset creation shape built by `new_create_asset`
+    pub fn is_create(&self) -> bool {
+        matches!(
+            self.stack.as_slice(),
+            [
+                StackEntry::Op(OpCodes::OP_CREATE),
+                block_number,
+                StackEntry::Op(OpCodes::OP_DROP),
+                StackEntry::Bytes(_),
+                StackEntry::Signature(_),
+                StackEntry::PubKey(_),
+                StackEntry::Op(OpCodes::OP_CHECKSIG),
+            ] if block_number.as_num().is_some()
+        )
+    }
+
+    /// Checks whether the script only pushes data, allowing the `OP_0..OP_16` opcodes
+    /// as the pushes of small numbers
+    pub fn is_push_only(&self) -> bool {
+        self.stack
+            .iter()
+            .all(|entry| !matches!(entry, StackEntry::Op(op) if op.small_num_value().is_none()))
+    }
+
+    /// Gets the distinct opcodes the script uses
+    pub fn opcodes_used(&self) -> BTreeSet<OpCodes> {
+        self.stack
+            .iter()
+            .filter_map(|entry| match entry {
+                StackEntry::Op(op) => Some(*op),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Checks whether the script uses an opcode
+    ///
+    /// ### Arguments
+    ///
+    /// * `op`  - Opcode to look for
+    pub fn contains_op(&self, op: OpCodes) -> bool {
+        self.stack.contains(&StackEntry::Op(op))
+    }
+
+    /// Serializes the script to JSON, with opcodes as mnemonics and keys and signatures as hex
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+
+    /// Deserializes a script from JSON produced by `to_json`
+    ///
+    /// ### Arguments
+    ///
+    /// * `json`    - JSON representation of the script
+    pub fn from_json(json: &str) -> serde_json::Result<Script> {
+        serde_json::from_str(json)
+    }
+
+    /// Renders the script as human-readable assembly, truncating long values
+    ///
+    /// Example: OP_DUP OP_HASH256 <pubkeyhash:a1b2c3d4..e5f6a7b8> OP_EQUALVERIFY OP_CHECKSIG
+    pub fn to_asm(&self) -> String {
+        self.render_asm(false)
+    }
+
+    /// Renders the script as human-readable assembly, keeping values in full
+    /// so that the output can be parsed back with `from_asm`
+    pub fn to_asm_full(&self) -> String {
+        self.render_asm(true)
+    }
+
+    /// Renders the script as assembly. Values that could not be read back as a single
+    /// token are rendered as the hex of their UTF-8 bytes, prefixed with `ASM_HEX_PREFIX`
+    ///
+    /// ### Arguments
+    ///
+    /// * `full`    - Whether long values are kept in full
+    fn render_asm(&self, full: bool) -> String {
+        let value = |kind: &str, v: String| {
+            let v = if asm_is_token_safe(&v) {
+                v
+            } else {
+                format!("{ASM_HEX_PREFIX}{}", encode(v))
+            };
+            if full || v.len() <= TWO * ASM_TRUNCATE_LEN {
+                format!("<{kind}:{v}>")
+            } else {
+                let (start, end) = (&v[..ASM_TRUNCATE_LEN], &v[v.len() - ASM_TRUNCATE_LEN..]);
+                format!("<{kind}:{start}..{end}>")
+            }
+        };
+        self.stack
+            .iter()
+            .map(|entry| match entry {
+                StackEntry::Op(op) => op.to_string(),
+                StackEntry::Num(n) => n.to_string(),
+                StackEntry::SignedNum(n) => format!("{n:+}"),
+                StackEntry::Bytes(s) => value("bytes", s.clone()),
+                StackEntry::PubKeyHash(s) => value("pubkeyhash", s.clone()),
+                StackEntry::PubKey(pk) => value("pubkey", encode(pk)),
+                StackEntry::Signature(sig) => value("signature", encode(sig)),
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Parses a script from assembly as produced by `to_asm_full`
+    ///
+    /// ### Arguments
+    ///
+    /// * `asm` - Script assembly to parse
+    pub fn from_asm(asm: &str) -> Result<Script, ScriptError> {
+        let stack = asm
+            .split_whitespace()
+            .map(|token| {
+                asm_entry(token).ok_or_else(|| {
+                    error!("{ERROR_INVALID_ASM}: {token}");
+                    ScriptError::InvalidAsm
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Script { stack })
+    }
+
+    /// Serializes the script for sending over the wire
+    pub fn to_bytes(&self) -> Vec<u8> {
+        serialize(self).unwrap_or_default()
+    }
+
+    /// Deserializes a script read off the wire, rejecting it if it is malformed or exceeds
+    /// the script limits. Public keys and signatures of the wrong length fail to deserialize
+    ///
+    /// ### Arguments
+    ///
+    /// * `bytes`   - Serialized script as produced by `to_bytes`
+    pub fn from_bytes(bytes: &[u8]) -> Result<Script, ScriptError> {
+        let script: Script = deserialize(bytes).map_err(|e| {
+            error!("{ERROR_INVALID_ENCODING}: {e}");
+            ScriptError::InvalidEncoding
+        })?;
+        script.check_limits()?;
+        Ok(script)
+    }
+
+    /// Serializes the script in the compact wire format: each opcode takes a single byte,
+    /// while other entries take a tag byte followed by their data, with variable-length data
+    /// prefixed by a Bitcoin-style varint length. Hex strings are carried as raw bytes, so a
+    /// P2PKH script takes 141 bytes rather than the 254 bytes produced by `to_bytes`
+    pub fn to_compact_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        for entry in &self.stack {
+            match entry {
+                StackEntry::Op(op) => bytes.push(*op as u8),
+                StackEntry::Signature(sig) => {
+                    bytes.push(COMPACT_TAG_SIGNATURE);
+                    bytes.extend_from_slice(sig.as_ref());
+                }
+                StackEntry::PubKey(pk) => {
+                    bytes.push(COMPACT_TAG_PUBKEY);
+                    bytes.extend_from_slice(pk.as_ref());
+                }
+                StackEntry::PubKeyHash(h) => push_compact_str(
+                    &mut bytes,
+                    (COMPACT_TAG_PUBKEY_HASH_HEX, COMPACT_TAG_PUBKEY_HASH_TEXT),
+                    h,
+                ),
+                StackEntry::Bytes(b) => push_compact_str(
+                    &mut bytes,
+                    (COMPACT_TAG_BYTES_HEX, COMPACT_TAG_BYTES_TEXT),
+                    b,
+                ),
+                StackEntry::Num(n) => {
+                    bytes.push(COMPACT_TAG_NUM);
+                    push_varint(&mut bytes, *n as u64);
+                }
+                StackEntry::SignedNum(n) => {
+                    // zigzag encoding keeps small negative numbers small
+                    bytes.push(COMPACT_TAG_SIGNED_NUM);
+                    push_varint(&mut bytes, ((n << 1) ^ (n >> 63)) as u64);
+                }
+            }
+        }
+        bytes
+    }
+
+    /// Deserializes a script in the compact wire format, rejecting it if it is malformed or
+    /// exceeds the script limits
+    ///
+    /// ### Arguments
+    ///
+    /// * `bytes`   - Serialized script as produced by `to_compact_bytes`
+    pub fn from_compact_bytes(bytes: &[u8]) -> Result<Script, ScriptError> {
+        let mut reader = bytes;
+        let mut stack = Vec::new();
+        while let Some((&tag, rest)) = reader.split_first() {
+            reader = rest;
+            let entry = compact_entry(tag, &mut reader).ok_or_else(|| {
+                error!("{ERROR_INVALID_ENCODING}: malformed compact entry {tag:#04x}");
+                ScriptError::InvalidEncoding
+            })?;
+            stack.push(entry);
+        }
+        let script = Script { stack };
+        script.check_limits()?;
+        Ok(script)
+    }
+}
+
+/// Appends a string entry in the compact wire format, as raw bytes if it is lowercase hex
+///
+/// ### Arguments
+///
+/// * `bytes`   - Buffer to append to
+/// * `tags`    - Tags for the hex and text forms of the entry
+/// * `value`   - String to append
+fn push_compact_str(bytes: &mut Vec<u8>, (hex_tag, text_tag): (u8, u8), value: &str) {
+    let (tag, data) = match compact_hex(value) {
+        Some(raw) => (hex_tag, raw),
+        None => (text_tag, value.as_bytes().to_vec()),
+    };
+    bytes.push(tag);
+    push_varint(bytes, data.len() as u64);
+    bytes.extend_from_slice(&data);
+}
+
+/// Decodes a string carried as raw bytes in the compact wire format: one that is lowercase
+/// hex, so re-encodes to itself
+///
+/// ### Arguments
+///
+/// * `value`   - String to decode
+fn compact_hex(value: &str) -> Option<Vec<u8>> {
+    hex::decode(value).ok().filter(|raw| encode(raw) == value)
+}
+
+/// Appends a Bitcoin-style varint
+///
+/// ### Arguments
+///
+/// * `bytes`   - Buffer to append to
+/// * `n`       - Number to append
+fn push_varint(bytes: &mut Vec<u8>, n: u64) {
+    match n {
+        0..=0xfc => bytes.push(n as u8),
+        0xfd..=0xffff => {
+            bytes.push(0xfd);
+            bytes.extend_from_slice(&(n as u16).to_le_bytes());
+        }
+        0x10000..=0xffff_ffff => {
+            bytes.push(0xfe);
+            bytes.extend_from_slice(&(n as u32).to_le_bytes());
+        }
+        _ => {
+            bytes.push(0xff);
+            bytes.extend_from_slice(&n.to_le_bytes());
+        }
+    }
+}
+
+/// Parses a single stack entry in the compact wire format, advancing the reader past it
+///
+/// ### Arguments
+///
+/// * `tag`     - Opcode or tag byte of the entry
+/// * `reader`  - Remaining serialized bytes
+fn compact_entry(tag: u8, reader: &mut &[u8]) -> Option<StackEntry> {
+    match tag {
+        COMPACT_TAG_SIGNATURE => Signature::from_slice(take_bytes(reader, ED25519_SIGNATURE_LEN)?)
+            .map(StackEntry::Signature),
+        COMPACT_TAG_PUBKEY => PublicKey::from_slice(take_bytes(reader, ED25519_PUBLIC_KEY_LEN)?)
+            .map(StackEntry::PubKey),
+        COMPACT_TAG_PUBKEY_HASH_HEX => Some(StackEntry::PubKeyHash(encode(take_data(reader)?))),
+        COMPACT_TAG_PUBKEY_HASH_TEXT => {
+            compact_text(take_data(reader)?).map(StackEntry::PubKeyHash)
+        }
+        COMPACT_TAG_BYTES_HEX => Some(StackEntry::Bytes(encode(take_data(reader)?))),
+        COMPACT_TAG_BYTES_TEXT => compact_text(take_data(reader)?).map(StackEntry::Bytes),
+        COMPACT_TAG_NUM => usize::try_from(read_varint(reader)?)
+            .ok()
+            .map(StackEntry::Num),
+        COMPACT_TAG_SIGNED_NUM => {
+            let n = read_varint(reader)?;
+            Some(StackEntry::SignedNum((n >> 1) as i64 ^ -((n & 1) as i64)))
+        }
+        _ => OpCodes::ALL
+            .iter()
+            .find(|op| **op as u8 == tag)
+            .cloned()
+            .map(StackEntry::Op),
+    }
+}
+
+/// Parses the text form of a string entry, rejecting strings that must be carried as raw
+/// bytes, so that each script has a single compact encoding
+///
+/// ### Arguments
+///
+/// * `data`    - UTF-8 bytes of the string
+fn compact_text(data: &[u8]) -> Option<String> {
+    let text = String::from_utf8(data.to_vec()).ok()?;
+    compact_hex(&text).is_none().then_some(text)
+}
+
+/// Reads a Bitcoin-style varint, advancing the reader past it. Varints that could have
+/// been encoded in fewer bytes are rejected, so that each script has a single compact encoding
+///
+/// ### Arguments
+///
+/// * `reader`  - Remaining serialized bytes
+fn read_varint(reader: &mut &[u8]) -> Option<u64> {
+    let (width, min) = match take_bytes(reader, 1)?[0] {
+        0xfd => (2, 0xfd),
+        0xfe => (4, 0x10000),
+        0xff => (8, 0x1_0000_0000),
+        n => return Some(n as u64),
+    };
+    let mut buf = [0u8; 8];
+    buf[..width].copy_from_slice(take_bytes(reader, width)?);
+    Some(u64::from_le_bytes(buf)).filter(|n| *n >= min)
+}
+
+/// Checks whether any `COMPACT_TAG_*` value is also the byte of an opcode
+const fn compact_tags_collide() -> bool {
+    let tags = [
+        COMPACT_TAG_SIGNATURE,
+        COMPACT_TAG_PUBKEY,
+        COMPACT_TAG_PUBKEY_HASH_HEX,
+        COMPACT_TAG_PUBKEY_HASH_TEXT,
+        COMPACT_TAG_BYTES_HEX,
+        COMPACT_TAG_BYTES_TEXT,
+        COMPACT_TAG_NUM,
+        COMPACT_TAG_SIGNED_NUM,
+    ];
+    let mut i = 0;
+    while i < OpCodes::ALL.len() {
+        let mut j = 0;
+        while j < tags.len() {
+            if OpCodes::ALL[i] as u8 == tags[j] {
+                return true;
+            }
+            j += 1;
+        }
+        i += 1;
+    }
+    false
+}
+
+// The compact wire format tells entries apart from opcodes by their first byte
+const _: () = assert!(!compact_tags_collide());
+
+/// Reads varint-length-prefixed data, advancing the reader past it
+///
+/// ### Arguments
+///
+/// * `reader`  - Remaining serialized bytes
+fn take_data<'a>(reader: &mut &'a [u8]) -> Option<&'a [u8]> {
+    let len = usize::try_from(read_varint(reader)?).ok()?;
+    take_bytes(reader, len)
+}
+
+/// Reads `len` bytes, advancing the reader past them
+///
+/// ### Arguments
+///
+/// * `reader`  - Remaining serialized bytes
+/// * `len`     - Number of bytes to read
+fn take_bytes<'a>(reader: &mut &'a [u8], len: usize) -> Option<&'a [u8]> {
+    if reader.len() < len {
+        return None;
+    }
+    let (data, rest) = reader.split_at(len);
+    *reader = rest;
+    Some(data)
+}
+
+/// Checks whether a value can be rendered as-is in an assembly token and read back:
+/// printable ASCII other than the token delimiters, without the truncation marker or
+/// the hex prefix
+///
+/// ### Arguments
+///
+/// * `v`   - Value to check
+fn asm_is_token_safe(v: &str) -> bool {
+    !v.starts_with(ASM_HEX_PREFIX)
+        && !v.contains("..")
+        && v.bytes()
+            .all(|b| b.is_ascii_graphic() && b != b'<' && b != b'>')
+}
+
+/// Parses a single stack entry from its assembly token
+///
+/// ### Arguments
+///
+/// * `token`   - Assembly token to parse
+fn asm_entry(token: &str) -> Option<StackEntry> {
+    if let Some(op) = OpCodes::from_name(token) {
+        return Some(StackEntry::Op(op));
+    }
+    if token.starts_with(['+', '-']) {
+        // signed numbers always carry an explicit sign
+        return token.parse::<i64>().ok().map(StackEntry::SignedNum);
+    }
+    if let Ok(n) = token.parse::<usize>() {
+        return Some(StackEntry::Num(n));
+    }
+    let (kind, v) = token
+        .strip_prefix('<')?
+        .strip_suffix('>')?
+        .split_once(':')?;
+    if v.contains("..") {
+        // truncated values cannot be recovered
+        return None;
+    }
+    let text = || match v.strip_prefix(ASM_HEX_PREFIX) {
+        Some(h) => String::from_utf8(hex::decode(h).ok()?).ok(),
+        None => Some(v.to_owned()),
+    };
+    match kind {
+        "bytes" => Some(StackEntry::Bytes(text()?)),
+        "pubkeyhash" => Some(StackEntry::PubKeyHash(text()?)),
+        "pubkey" => Some(StackEntry::PubKey(PublicKey::from_slice(
+            &hex::decode(v).ok()?,
+        )?)),
+        "signature" => Some(StackEntry::Signature(Signature::from_slice(
+            &hex::decode(v).ok()?,
+        )?)),
+        _ => None,
+    }
 }
 
 impl From<Vec<StackEntry>> for Script {
@@ -499,3 +1691,168 @@ impl From<Vec<StackEntry>> for Script {
         Script { stack: s }
     }
 }
+
+/// Errors raised when building or executing a script
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScriptError {
+    // opcodes
+    EmptyCondition,
+    Verify,
+    Burn,
+    NumItems,
+    ItemType,
+    ItemIndex,
+    ItemSize,
+    NotEqualItems,
+    ArithmeticOverflow,
+    DivZero,
+    InvalidSignature,
+    InvalidMultisignature,
+    NumPubkeys,
+    NumSignatures,
+    Locktime,
+    TemplateMismatch,
+    // script
+    MaxScriptSize,
+    MaxStackSize,
+    MaxStackMemory,
+    MaxOpsScript,
+    UnbalancedCondition,
+    InvalidAsm,
+    InvalidEncoding,
+    ResourceExhausted,
+    DisabledOpcode,
+    NonPushUnlock,
+}
+
+impl fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::EmptyCondition => write!(f, "{ERROR_EMPTY_CONDITION}"),
+            Self::Verify => write!(f, "{ERROR_VERIFY}"),
+            Self::Burn => write!(f, "{ERROR_BURN}"),
+            Self::NumItems => write!(f, "{ERROR_NUM_ITEMS}"),
+            Self::ItemType => write!(f, "{ERROR_ITEM_TYPE}"),
+            Self::ItemIndex => write!(f, "{ERROR_ITEM_INDEX}"),
+            Self::ItemSize => write!(f, "{ERROR_ITEM_SIZE}"),
+            Self::NotEqualItems => write!(f, "{ERROR_NOT_EQUAL_ITEMS}"),
+            Self::ArithmeticOverflow => write!(f, "{ERROR_OVERFLOW}"),
+            Self::DivZero => write!(f, "{ERROR_DIV_ZERO}"),
+            Self::InvalidSignature => write!(f, "{ERROR_INVALID_SIGNATURE}"),
+            Self::InvalidMultisignature => write!(f, "{ERROR_INVALID_MULTISIGNATURE}"),
+            Self::NumPubkeys => write!(f, "{ERROR_NUM_PUBKEYS}"),
+            Self::NumSignatures => write!(f, "{ERROR_NUM_SIGNATURES}"),
+            Self::Locktime => write!(f, "{ERROR_LOCKTIME}"),
+            Self::TemplateMismatch => write!(f, "{ERROR_TEMPLATE_MISMATCH}"),
+            Self::MaxScriptSize => write!(f, "{ERROR_MAX_SCRIPT_SIZE}"),
+            Self::MaxStackSize => write!(f, "{ERROR_MAX_STACK_SIZE}"),
+            Self::MaxStackMemory => write!(f, "{ERROR_MAX_STACK_MEMORY}"),
+            Self::MaxOpsScript => write!(f, "{ERROR_MAX_OPS_SCRIPT}"),
+            Self::UnbalancedCondition => write!(f, "{ERROR_UNBALANCED_CONDITION}"),
+            Self::InvalidAsm => write!(f, "{ERROR_INVALID_ASM}"),
+            Self::InvalidEncoding => write!(f, "{ERROR_INVALID_ENCODING}"),
+            Self::ResourceExhausted => write!(f, "{ERROR_RESOURCE_EXHAUSTED}"),
+            Self::DisabledOpcode => write!(f, "{ERROR_DISABLED_OPCODE}"),
+            Self::NonPushUnlock => write!(f, "{ERROR_NON_PUSH_UNLOCK}"),
+        }
+    }
+}
+
+impl error::Error for ScriptError {}
+
+/// Builder for scripts made of arbitrary sequences of stack entries
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ScriptBuilder {
+    stack: Vec<StackEntry>,
+}
+
+impl ScriptBuilder {
+    /// Creates a new, empty script builder
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Appends an opcode to the script
+    ///
+    /// ### Arguments
+    ///
+    /// * `op`  - Opcode to append
+    pub fn push_op(mut self, op: OpCodes) -> Self {
+        self.stack.push(StackEntry::Op(op));
+        self
+    }
+
+    /// Appends a number to the script
+    ///
+    /// ### Arguments
+    ///
+    /// * `num` - Number to append
+    pub fn push_num(mut self, num: usize) -> Self {
+        self.stack.push(StackEntry::Num(num));
+        self
+    }
+
+    /// Appends a signed number to the script
+    ///
+    /// ### Arguments
+    ///
+    /// * `num` - Signed number to append
+    pub fn push_signed_num(mut self, num: i64) -> Self {
+        self.stack.push(StackEntry::SignedNum(num));
+        self
+    }
+
+    /// Appends a byte string to the script
+    ///
+    /// ### Arguments
+    ///
+    /// * `bytes`   - Byte string to append
+    pub fn push_bytes(mut self, bytes: String) -> Self {
+        self.stack.push(StackEntry::Bytes(bytes));
+        self
+    }
+
+    /// Appends a public key hash to the script
+    ///
+    /// ### Arguments
+    ///
+    /// * `pub_key_hash`    - Public key hash to append
+    pub fn push_pubkey_hash(mut self, pub_key_hash: String) -> Self {
+        self.stack.push(StackEntry::PubKeyHash(pub_key_hash));
+        self
+    }
+
+    /// Appends a public key to the script
+    ///
+    /// ### Arguments
+    ///
+    /// * `pub_key` - Public key to append
+    pub fn push_pubkey(mut self, pub_key: PublicKey) -> Self {
+        self.stack.push(StackEntry::PubKey(pub_key));
+        self
+    }
+
+    /// Appends a signature to the script
+    ///
+    /// ### Arguments
+    ///
+    /// * `signature`   - Signature to append
+    pub fn push_signature(mut self, signature: Signature) -> Self {
+        self.stack.push(StackEntry::Signature(signature));
+        self
+    }
+
+    /// Builds the script, checking item size, script size and opcode limits
+    pub fn build(self) -> Result<Script, ScriptError> {
+        let script = Script::from(self.stack);
+        if script
+            .stack
+            .iter()
+            .any(|entry| entry_size(entry) > MAX_SCRIPT_ITEM_SIZE as usize)
+        {
+            return Err(ScriptError::ItemSize);
+        }
+        script.check_limits()?;
+        Ok(script)
+    }
+}
@@ -8,6 +8,7 @@ use std::fmt;
 
 /// Stack entry enum
 #[derive(Debug, Clone, Eq, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
 pub enum StackEntry {
     Op(OpCodes),
     Signature(Signature),
@@ -15,14 +16,44 @@ pub enum StackEntry {
     PubKeyHash(String),
     Num(usize),
     Bytes(String),
+    /// Signed number for scripts that need arithmetic below ZERO. Arithmetic
+    /// opcodes never mix it with `Num`. Comparison opcodes compare `Num`s as
+    /// unsigned, except for the `_SIGNED` family, which compares all numbers as signed
+    SignedNum(i64),
+}
+
+impl StackEntry {
+    /// Creates the minimally encoded entry pushing a number: its `OP_0..OP_16` opcode
+    /// for numbers up to SIXTEEN, and a `Num` entry otherwise
+    ///
+    /// ### Arguments
+    ///
+    /// * `n`   - Number to push
+    pub fn num(n: usize) -> StackEntry {
+        match OpCodes::small_num(n) {
+            Some(op) => StackEntry::Op(op),
+            None => StackEntry::Num(n),
+        }
+    }
+
+    /// Gets the number pushed by a `Num` entry or an `OP_0..OP_16` opcode
+    pub fn as_num(&self) -> Option<usize> {
+        match self {
+            StackEntry::Num(n) => Some(*n),
+            StackEntry::Op(op) => op.small_num_value(),
+            _ => None,
+        }
+    }
 }
 
 /// Opcodes enum
 #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
 pub enum OpCodes {
     // constants
     OP_0 = 0x00,
+    OP_PUSHDATA = 0x4c,
     OP_1 = 0x51,
     OP_2 = 0x52,
     OP_3 = 0x53,
@@ -73,6 +104,10 @@ pub enum OpCodes {
     OP_LEFT = 0x80,
     OP_RIGHT = 0x81,
     OP_SIZE = 0x82,
+    OP_REVERSE = 0xc5,
+    OP_SPLIT = 0xc6,
+    OP_NUM2BIN = 0xca,
+    OP_BIN2NUM = 0xcb,
     // bitwise logic
     OP_INVERT = 0x83,
     OP_AND = 0x84,
@@ -103,10 +138,18 @@ pub enum OpCodes {
     OP_GREATERTHAN = 0xa0,
     OP_LESSTHANOREQUAL = 0xa1,
     OP_GREATERTHANOREQUAL = 0xa2,
+    OP_LESSTHANVERIFY = 0xc7,
+    OP_GREATERTHANVERIFY = 0xc8,
     OP_MIN = 0xa3,
     OP_MAX = 0xa4,
     OP_WITHIN = 0xa5,
+    OP_LESSTHAN_SIGNED = 0xcd,
+    OP_GREATERTHAN_SIGNED = 0xce,
+    OP_LESSTHANOREQUAL_SIGNED = 0xcf,
+    OP_GREATERTHANOREQUAL_SIGNED = 0xd0,
     // crypto
+    OP_RIPEMD160 = 0xa6,
+    OP_HASH160 = 0xa7,
     OP_SHA3 = 0xa9,
     OP_HASH256 = 0xaa,
     OP_HASH256_V0 = 0xc1,
@@ -115,11 +158,179 @@ pub enum OpCodes {
     OP_CHECKSIGVERIFY = 0xad,
     OP_CHECKMULTISIG = 0xae,
     OP_CHECKMULTISIGVERIFY = 0xaf,
+    OP_CHECKMULTISIG_ORDERED = 0xc3,
+    OP_CHECKDATASIG = 0xc4,
+    OP_CODESEPARATOR = 0xab,
+    OP_CHECKAGGSIG = 0xc9,
+    // locktime
+    OP_CHECKLOCKTIMEVERIFY = 0xb1,
+    // covenants
+    OP_CHECKTEMPLATEVERIFY = 0xcc,
+    // reserved (0xb1 is taken by OP_CHECKLOCKTIMEVERIFY)
+    OP_NOP1 = 0xb0,
+    OP_NOP2 = 0xb2,
+    OP_NOP3 = 0xb3,
+    OP_NOP4 = 0xb4,
+    OP_NOP5 = 0xb5,
+    OP_NOP6 = 0xb6,
+    OP_NOP7 = 0xb7,
+    OP_NOP8 = 0xb8,
+    OP_NOP9 = 0xb9,
+    OP_NOP10 = 0xba,
     // smart data
     OP_CREATE = 0xc0,
 }
 
 impl OpCodes {
+    /// All opcodes, in declaration order
+    pub const ALL: &'static [OpCodes] = &[
+        OpCodes::OP_0,
+        OpCodes::OP_PUSHDATA,
+        OpCodes::OP_1,
+        OpCodes::OP_2,
+        OpCodes::OP_3,
+        OpCodes::OP_4,
+        OpCodes::OP_5,
+        OpCodes::OP_6,
+        OpCodes::OP_7,
+        OpCodes::OP_8,
+        OpCodes::OP_9,
+        OpCodes::OP_10,
+        OpCodes::OP_11,
+        OpCodes::OP_12,
+        OpCodes::OP_13,
+        OpCodes::OP_14,
+        OpCodes::OP_15,
+        OpCodes::OP_16,
+        OpCodes::OP_NOP,
+        OpCodes::OP_IF,
+        OpCodes::OP_NOTIF,
+        OpCodes::OP_ELSE,
+        OpCodes::OP_ENDIF,
+        OpCodes::OP_VERIFY,
+        OpCodes::OP_BURN,
+        OpCodes::OP_TOALTSTACK,
+        OpCodes::OP_FROMALTSTACK,
+        OpCodes::OP_2DROP,
+        OpCodes::OP_2DUP,
+        OpCodes::OP_3DUP,
+        OpCodes::OP_2OVER,
+        OpCodes::OP_2ROT,
+        OpCodes::OP_2SWAP,
+        OpCodes::OP_IFDUP,
+        OpCodes::OP_DEPTH,
+        OpCodes::OP_DROP,
+        OpCodes::OP_DUP,
+        OpCodes::OP_NIP,
+        OpCodes::OP_OVER,
+        OpCodes::OP_PICK,
+        OpCodes::OP_ROLL,
+        OpCodes::OP_ROT,
+        OpCodes::OP_SWAP,
+        OpCodes::OP_TUCK,
+        OpCodes::OP_CAT,
+        OpCodes::OP_SUBSTR,
+        OpCodes::OP_LEFT,
+        OpCodes::OP_RIGHT,
+        OpCodes::OP_SIZE,
+        OpCodes::OP_REVERSE,
+        OpCodes::OP_SPLIT,
+        OpCodes::OP_NUM2BIN,
+        OpCodes::OP_BIN2NUM,
+        OpCodes::OP_INVERT,
+        OpCodes::OP_AND,
+        OpCodes::OP_OR,
+        OpCodes::OP_XOR,
+        OpCodes::OP_EQUAL,
+        OpCodes::OP_EQUALVERIFY,
+        OpCodes::OP_1ADD,
+        OpCodes::OP_1SUB,
+        OpCodes::OP_2MUL,
+        OpCodes::OP_2DIV,
+        OpCodes::OP_NOT,
+        OpCodes::OP_0NOTEQUAL,
+        OpCodes::OP_ADD,
+        OpCodes::OP_SUB,
+        OpCodes::OP_MUL,
+        OpCodes::OP_DIV,
+        OpCodes::OP_MOD,
+        OpCodes::OP_LSHIFT,
+        OpCodes::OP_RSHIFT,
+        OpCodes::OP_BOOLAND,
+        OpCodes::OP_BOOLOR,
+        OpCodes::OP_NUMEQUAL,
+        OpCodes::OP_NUMEQUALVERIFY,
+        OpCodes::OP_NUMNOTEQUAL,
+        OpCodes::OP_LESSTHAN,
+        OpCodes::OP_GREATERTHAN,
+        OpCodes::OP_LESSTHANOREQUAL,
+        OpCodes::OP_GREATERTHANOREQUAL,
+        OpCodes::OP_LESSTHANVERIFY,
+        OpCodes::OP_GREATERTHANVERIFY,
+        OpCodes::OP_MIN,
+        OpCodes::OP_MAX,
+        OpCodes::OP_WITHIN,
+        OpCodes::OP_LESSTHAN_SIGNED,
+        OpCodes::OP_GREATERTHAN_SIGNED,
+        OpCodes::OP_LESSTHANOREQUAL_SIGNED,
+        OpCodes::OP_GREATERTHANOREQUAL_SIGNED,
+        OpCodes::OP_RIPEMD160,
+        OpCodes::OP_HASH160,
+        OpCodes::OP_SHA3,
+        OpCodes::OP_HASH256,
+        OpCodes::OP_HASH256_V0,
+        OpCodes::OP_HASH256_TEMP,
+        OpCodes::OP_CHECKSIG,
+        OpCodes::OP_CHECKSIGVERIFY,
+        OpCodes::OP_CHECKMULTISIG,
+        OpCodes::OP_CHECKMULTISIGVERIFY,
+        OpCodes::OP_CHECKMULTISIG_ORDERED,
+        OpCodes::OP_CHECKDATASIG,
+        OpCodes::OP_CODESEPARATOR,
+        OpCodes::OP_CHECKAGGSIG,
+        OpCodes::OP_CHECKLOCKTIMEVERIFY,
+        OpCodes::OP_CHECKTEMPLATEVERIFY,
+        OpCodes::OP_NOP1,
+        OpCodes::OP_NOP2,
+        OpCodes::OP_NOP3,
+        OpCodes::OP_NOP4,
+        OpCodes::OP_NOP5,
+        OpCodes::OP_NOP6,
+        OpCodes::OP_NOP7,
+        OpCodes::OP_NOP8,
+        OpCodes::OP_NOP9,
+        OpCodes::OP_NOP10,
+        OpCodes::OP_CREATE,
+    ];
+
+    /// Returns the opcode with the given mnemonic, e.g. "OP_DUP"
+    ///
+    /// ### Arguments
+    ///
+    /// * `name`  - Mnemonic of the opcode
+    pub fn from_name(name: &str) -> Option<OpCodes> {
+        Self::ALL.iter().find(|op| op.to_string() == name).cloned()
+    }
+
+    /// Returns the `OP_0..OP_16` opcode pushing a number, if it has one
+    ///
+    /// ### Arguments
+    ///
+    /// * `n`   - Number to push
+    pub fn small_num(n: usize) -> Option<OpCodes> {
+        match n {
+            0 => Some(OpCodes::OP_0),
+            // OP_1..OP_16 follow OP_0 and OP_PUSHDATA
+            1..=16 => Some(Self::ALL[n + 1]),
+            _ => None,
+        }
+    }
+
+    /// Returns the number pushed by an `OP_0..OP_16` opcode
+    pub fn small_num_value(&self) -> Option<usize> {
+        (0..=16).find(|n| Self::small_num(*n).as_ref() == Some(self))
+    }
+
     /// Returns true if the opcode is a conditional
     pub fn is_conditional(&self) -> bool {
         matches!(
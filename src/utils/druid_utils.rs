@@ -1,11 +1,60 @@
 use crate::primitives::asset::Asset;
-use crate::primitives::druid::DruidExpectation;
+use crate::primitives::druid::{construct_druid, DruidExpectation};
 use crate::primitives::transaction::Transaction;
 use crate::utils::transaction_utils::construct_tx_ins_address;
 use std::collections::BTreeSet;
 use std::iter::Extend;
+use std::{error, fmt};
+
+/// Errors raised when verifying the expectations of a DRUID
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DruidError {
+    ParticipantMismatch { declared: usize, found: usize },
+    AddressMismatch(DruidExpectation),
+    ValueMismatch(DruidExpectation),
+    UnmetExpectation(DruidExpectation),
+}
+
+impl fmt::Display for DruidError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::ParticipantMismatch { declared, found } => write!(
+                f,
+                "DRUID declares {declared} participants but {found} transactions match"
+            ),
+            Self::AddressMismatch(e) => write!(
+                f,
+                "Asset {:?} from {} was not sent to expected address {}",
+                e.asset, e.from, e.to
+            ),
+            Self::ValueMismatch(e) => write!(
+                f,
+                "Asset sent from {} to {} does not match expected {:?}",
+                e.from, e.to, e.asset
+            ),
+            Self::UnmetExpectation(e) => write!(
+                f,
+                "No transaction sends {:?} from {} to {}",
+                e.asset, e.from, e.to
+            ),
+        }
+    }
+}
+
+impl error::Error for DruidError {}
+
+/// Expectations and transfers gathered from the transactions matching a DRUID
+#[derive(Default)]
+struct DruidMatches<'a> {
+    expects: BTreeSet<&'a DruidExpectation>,
+    tx_source: BTreeSet<(String, &'a String, &'a Asset)>,
+    tx_hashes: BTreeSet<String>,
+    participants: BTreeSet<usize>,
+}
 
-/// Verifies that all DDE transaction expectations are met for DRUID-matching transactions
+/// Verifies that all DDE transaction expectations are met for DRUID-matching transactions,
+/// and that the number of distinct matching transactions is the declared participant count.
+/// Expectations are vacuously met when no transaction carries the DRUID
 ///
 /// ### Arguments
 ///
@@ -15,8 +64,72 @@ pub fn druid_expectations_are_met<'a>(
     druid: &str,
     transactions: impl Iterator<Item = &'a Transaction>,
 ) -> bool {
-    let mut expects = BTreeSet::new();
-    let mut tx_source = BTreeSet::new();
+    druid_expectations_are_met_checked(druid, transactions).is_ok()
+}
+
+/// Verifies that all DDE transaction expectations are met for DRUID-matching transactions,
+/// returning the reason for failure if any. Expectations are checked in order, and the
+/// first unmet one is reported
+///
+/// ### Arguments
+///
+/// * `druid`           - DRUID to match all transactions on
+/// * `transactions`    - Transactions to verify
+pub fn druid_expectations_are_met_checked<'a>(
+    druid: &str,
+    transactions: impl Iterator<Item = &'a Transaction>,
+) -> Result<(), DruidError> {
+    let DruidMatches {
+        expects,
+        tx_source,
+        tx_hashes,
+        participants,
+    } = match_druid(druid, transactions);
+
+    if let Some(declared) = participants.iter().find(|p| **p != tx_hashes.len()) {
+        return Err(DruidError::ParticipantMismatch {
+            declared: *declared,
+            found: tx_hashes.len(),
+        });
+    }
+
+    match expects.iter().find(|e| !expectation_met(e, &tx_source)) {
+        Some(e) => Err(unmet_expectation_error(e, &tx_source)),
+        None => Ok(()),
+    }
+}
+
+/// Lists every expectation of the DRUID-matching transactions that no transaction meets,
+/// rather than only the first as `druid_expectations_are_met_checked` does. The number of
+/// participants is not checked
+///
+/// ### Arguments
+///
+/// * `druid`   - DRUID to match all transactions on
+/// * `txs`     - Transactions to check
+pub fn unmet_expectations(druid: &str, txs: &[Transaction]) -> Vec<DruidExpectation> {
+    let DruidMatches {
+        expects, tx_source, ..
+    } = match_druid(druid, txs.iter());
+
+    expects
+        .into_iter()
+        .filter(|e| !expectation_met(e, &tx_source))
+        .cloned()
+        .collect()
+}
+
+/// Gathers the expectations and transfers of the transactions matching a DRUID
+///
+/// ### Arguments
+///
+/// * `druid`           - DRUID to match all transactions on
+/// * `transactions`    - Transactions to gather from
+fn match_druid<'a>(
+    druid: &str,
+    transactions: impl Iterator<Item = &'a Transaction>,
+) -> DruidMatches<'a> {
+    let mut matches = DruidMatches::default();
 
     for tx in transactions {
         if let Some(druid_info) = &tx.druid_info {
@@ -24,18 +137,40 @@ pub fn druid_expectations_are_met<'a>(
 
             // Ensure match with passed DRUID
             if druid_info.druid == druid {
-                expects.extend(druid_info.expectations.iter());
+                matches.tx_hashes.insert(tx.tx_hash());
+                matches.participants.insert(druid_info.participants);
+                matches.expects.extend(druid_info.expectations.iter());
 
                 for out in &tx.outputs {
                     if let Some(pk) = &out.script_public_key {
-                        tx_source.insert((ins.clone(), pk, &out.value));
+                        matches.tx_source.insert((ins.clone(), pk, &out.value));
                     }
                 }
             }
         }
     }
+    matches
+}
 
-    expects.iter().all(|e| expectation_met(e, &tx_source))
+/// Derives the DRUID shared by a set of DDE transactions from the participant count and
+/// the combined expectations they declare, ignoring any DRUID they already carry. Outputs
+/// such as change do not affect the result, which matches `DdeValues::expected_druid`
+/// for transactions that each declare all the expectations. Returns None if there are no
+/// transactions or one carries no DDE values
+///
+/// ### Arguments
+///
+/// * `txs` - Participating transactions, in any order
+pub fn compute_druid(txs: &[Transaction]) -> Option<String> {
+    if txs.is_empty() {
+        return None;
+    }
+
+    let mut expectations = BTreeSet::new();
+    for tx in txs {
+        expectations.extend(tx.druid_info.as_ref()?.expectations.iter());
+    }
+    Some(construct_druid(txs.len(), expectations.into_iter()))
 }
 
 /// Predicate for expected transaction presence in the transaction set
@@ -48,9 +183,35 @@ fn expectation_met(e: &DruidExpectation, tx_source: &BTreeSet<(String, &String,
     tx_source.get(&(e.from.clone(), &e.to, &e.asset)).is_some()
 }
 
+/// Classifies an unmet expectation by the closest transaction source
+///
+/// ### Arguments
+///
+/// * `e`           - The unmet expectation
+/// * `tx_source`   - The source transaction source it was matched against
+fn unmet_expectation_error(
+    e: &DruidExpectation,
+    tx_source: &BTreeSet<(String, &String, &Asset)>,
+) -> DruidError {
+    if tx_source
+        .iter()
+        .any(|(from, to, _)| *from == e.from && **to == e.to)
+    {
+        DruidError::ValueMismatch(e.clone())
+    } else if tx_source
+        .iter()
+        .any(|(from, _, asset)| *from == e.from && **asset == e.asset)
+    {
+        DruidError::AddressMismatch(e.clone())
+    } else {
+        DruidError::UnmetExpectation(e.clone())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::crypto::sign_ed25519 as sign;
     use crate::primitives::asset::{Asset, DataAsset, TokenAmount};
     use crate::primitives::druid::{DdeValues, DruidExpectation};
     use crate::primitives::transaction::*;
@@ -115,6 +276,63 @@ mod tests {
         vec![alice_tx, bob_tx]
     }
 
+    /// Util function to create a valid 3-party cyclic DDE swap (A -> B, B -> C, C -> A)
+    fn create_cyclic_dde_txs() -> Vec<Transaction> {
+        let druid = "VALUE".to_owned();
+        let addrs = ["1111".to_owned(), "2222".to_owned(), "3333".to_owned()];
+        let assets = [
+            Asset::token_u64(10),
+            Asset::Data(DataAsset {
+                data: "453094573049875".as_bytes().to_vec(),
+                amount: 1,
+            }),
+            Asset::receipt(1, Some("drs_tx_hash".to_owned()), None),
+        ];
+
+        // Each participant spends a distinct input, so has a distinct from address
+        let tx_inputs: Vec<Vec<TxIn>> = (0..addrs.len())
+            .map(|i| {
+                let (pk, sk) = sign::gen_keypair();
+                let previous_out = OutPoint::new(format!("party_{i}"), 0);
+                let signable_hash = construct_tx_in_signable_hash(&previous_out);
+                construct_payment_tx_ins(vec![TxConstructor {
+                    previous_out,
+                    signatures: vec![sign::sign_detached(signable_hash.as_bytes(), &sk)],
+                    pub_keys: vec![pk],
+                    address_version: None,
+                }])
+            })
+            .collect();
+
+        // Participant i sends its asset to participant i + 1
+        let expects: Vec<DruidExpectation> = (0..addrs.len())
+            .map(|i| DruidExpectation {
+                from: construct_tx_ins_address(&tx_inputs[i]),
+                to: addrs[(i + 1) % addrs.len()].clone(),
+                asset: assets[i].clone(),
+            })
+            .collect();
+
+        tx_inputs
+            .into_iter()
+            .zip(expects.iter())
+            .map(|(tx_ins, e)| {
+                let tx_out = TxOut {
+                    value: e.asset.clone(),
+                    script_public_key: Some(e.to.clone()),
+                    ..Default::default()
+                };
+                construct_dde_tx(
+                    druid.clone(),
+                    tx_ins,
+                    vec![tx_out],
+                    addrs.len(),
+                    expects.clone(),
+                )
+            })
+            .collect()
+    }
+
     /// Util function to create valid receipt-based payment tx's
     fn create_rb_payment_txs() -> (Transaction, Transaction) {
         // Arrange
@@ -138,7 +356,8 @@ mod tests {
                 // constructors with enough money for amount and excess, caller responsibility.
                 construct_payment_tx_ins(vec![])
             };
-            let excess_tx_out = TxOut::new_token_amount(sender_address_excess, amount - payment);
+            let (_, change) = amount.split_payment(payment).unwrap();
+            let excess_tx_out = TxOut::new_token_amount(sender_address_excess, change);
 
             let expectation = DruidExpectation {
                 from: from_addr.clone(),
@@ -195,6 +414,39 @@ mod tests {
         assert!(druid_expectations_are_met("VALUE", txs.iter()));
     }
 
+    #[test]
+    /// Checks that correctly paired swap transactions compute the DRUID their expectations derive
+    fn should_compute_matching_druid_for_paired_dde_txs() {
+        let mut txs = create_dde_txs();
+        let expected: Vec<String> = txs
+            .iter()
+            .map(|tx| tx.druid_info.as_ref().unwrap().expected_druid())
+            .collect();
+        let druid = expected[0].clone();
+        for tx in &mut txs {
+            tx.druid_info.as_mut().unwrap().druid = druid.clone();
+        }
+        let reversed: Vec<Transaction> = txs.iter().rev().cloned().collect();
+
+        assert_eq!(expected[1], druid);
+        assert_eq!(compute_druid(&txs), Some(druid.clone()));
+        assert_eq!(compute_druid(&reversed), Some(druid.clone()));
+        assert_ne!(compute_druid(&txs[..1]), Some(druid.clone()));
+        assert_eq!(compute_druid(&[]), None);
+        assert!(druid_expectations_are_met(&druid, txs.iter()));
+
+        // change outputs are not expectations
+        txs[0]
+            .outputs
+            .push(TxOut::new_token_amount("4444".to_owned(), TokenAmount(5)));
+        assert_eq!(compute_druid(&txs), Some(druid.clone()));
+        assert!(druid_expectations_are_met(&druid, txs.iter()));
+
+        // transactions without DDE values have no DRUID
+        txs[1].druid_info = None;
+        assert_eq!(compute_druid(&txs), None);
+    }
+
     #[test]
     /// Checks that DDE transactions with non-matching expects fail
     fn should_fail_dde_tx_value_expect_mismatch() {
@@ -205,6 +457,7 @@ mod tests {
         let druid_info = change_tx.druid_info.clone();
         let mut expects = druid_info.unwrap().expectations;
         expects[0].to = "60764505679457".to_string();
+        let unmet = expects[0].clone();
 
         // New druid info
         let nm_druid_info = DdeValues {
@@ -214,10 +467,136 @@ mod tests {
         };
         change_tx.druid_info = Some(nm_druid_info);
 
-        assert!(!druid_expectations_are_met(
-            "VALUE",
-            vec![orig_tx, change_tx].iter()
-        ));
+        let txs = [orig_tx, change_tx];
+        assert!(!druid_expectations_are_met("VALUE", txs.iter()));
+        assert_eq!(
+            druid_expectations_are_met_checked("VALUE", txs.iter()),
+            Err(DruidError::AddressMismatch(unmet))
+        );
+    }
+
+    #[test]
+    /// Checks that every unmet expectation of an incomplete swap is listed
+    fn should_list_all_unmet_dde_expectations() {
+        let mut txs = create_dde_txs();
+        let expects = txs[0].druid_info.clone().unwrap().expectations;
+        assert!(unmet_expectations("VALUE", &txs).is_empty());
+
+        // Redirect both payments away from their expected recipients
+        for tx in &mut txs {
+            tx.outputs[0].script_public_key = Some("60764505679457".to_owned());
+        }
+
+        let mut unmet = unmet_expectations("VALUE", &txs);
+        unmet.sort();
+        let mut expected = expects;
+        expected.sort();
+
+        assert_eq!(unmet.len(), 2);
+        assert_eq!(unmet, expected);
+        assert!(unmet_expectations("OTHER", &txs).is_empty());
+    }
+
+    #[test]
+    /// Checks that a DRUID no transaction carries has no expectations, so is vacuously met
+    fn should_pass_dde_tx_no_matching_druid() {
+        let txs = create_dde_txs();
+        assert!(druid_expectations_are_met("OTHER", txs.iter()));
+        assert_eq!(
+            druid_expectations_are_met_checked("OTHER", txs.iter()),
+            Ok(())
+        );
+    }
+
+    #[test]
+    /// Checks that DDE transactions declaring more participants than present fail
+    fn should_fail_dde_tx_participants_too_high() {
+        let txs = set_participants(create_dde_txs(), 3);
+        assert!(!druid_expectations_are_met("VALUE", txs.iter()));
+        assert_eq!(
+            druid_expectations_are_met_checked("VALUE", txs.iter()),
+            Err(DruidError::ParticipantMismatch {
+                declared: 3,
+                found: 2
+            })
+        );
+    }
+
+    #[test]
+    /// Checks that DDE transactions declaring fewer participants than present fail
+    fn should_fail_dde_tx_participants_too_low() {
+        let txs = set_participants(create_dde_txs(), 1);
+        assert!(!druid_expectations_are_met("VALUE", txs.iter()));
+        assert_eq!(
+            druid_expectations_are_met_checked("VALUE", txs.iter()),
+            Err(DruidError::ParticipantMismatch {
+                declared: 1,
+                found: 2
+            })
+        );
+    }
+
+    #[test]
+    /// Checks that a 3-party cyclic DDE swap is verified regardless of transaction order
+    fn should_pass_matching_cyclic_dde_tx_valid() {
+        let mut txs = create_cyclic_dde_txs();
+        assert!(druid_expectations_are_met("VALUE", txs.iter()));
+
+        txs.reverse();
+        assert!(druid_expectations_are_met("VALUE", txs.iter()));
+
+        txs.rotate_left(1);
+        assert!(druid_expectations_are_met("VALUE", txs.iter()));
+    }
+
+    #[test]
+    /// Checks that a 3-party cyclic DDE swap missing a participant fails
+    fn should_fail_cyclic_dde_tx_missing_participant() {
+        let mut txs = create_cyclic_dde_txs();
+        let missing = txs.pop().unwrap().druid_info.unwrap().expectations;
+        let unmet = missing.into_iter().find(|e| e.to == "1111").unwrap();
+
+        assert!(!druid_expectations_are_met("VALUE", txs.iter()));
+        assert_eq!(
+            druid_expectations_are_met_checked("VALUE", txs.iter()),
+            Err(DruidError::ParticipantMismatch {
+                declared: 3,
+                found: 2
+            })
+        );
+
+        let txs = set_participants(txs, 2);
+        assert!(!druid_expectations_are_met("VALUE", txs.iter()));
+        assert_eq!(
+            druid_expectations_are_met_checked("VALUE", txs.iter()),
+            Err(DruidError::UnmetExpectation(unmet))
+        );
+    }
+
+    #[test]
+    /// Checks that a 3-party cyclic DDE swap with a single mismatched leg fails
+    fn should_fail_cyclic_dde_tx_value_expect_mismatch() {
+        let mut txs = create_cyclic_dde_txs();
+        txs[1].outputs[0].script_public_key = Some("1111".to_owned());
+        let unmet = txs[1].druid_info.as_ref().unwrap().expectations[1].clone();
+
+        assert!(!druid_expectations_are_met("VALUE", txs.iter()));
+        assert_eq!(
+            druid_expectations_are_met_checked("VALUE", txs.iter()),
+            Err(DruidError::AddressMismatch(unmet))
+        );
+    }
+
+    /// Util function to override the declared participant count of DDE tx's
+    fn set_participants(txs: Vec<Transaction>, participants: usize) -> Vec<Transaction> {
+        txs.into_iter()
+            .map(|mut tx| {
+                if let Some(druid_info) = tx.druid_info.as_mut() {
+                    druid_info.participants = participants;
+                }
+                tx
+            })
+            .collect()
     }
 
     #[test]
@@ -240,10 +619,15 @@ mod tests {
         recv_tx.druid_info = Some(druid_info);
 
         // Non-matching druid
-        assert!(!druid_expectations_are_met(
-            "VALUE",
-            vec![send_tx, recv_tx].iter()
-        ));
+        let txs = [send_tx, recv_tx];
+        assert!(!druid_expectations_are_met("VALUE", txs.iter()));
+        assert_eq!(
+            druid_expectations_are_met_checked("VALUE", txs.iter()),
+            Err(DruidError::ParticipantMismatch {
+                declared: 2,
+                found: 1
+            })
+        );
     }
 
     #[test]
@@ -251,12 +635,15 @@ mod tests {
     fn should_fail_rb_payment_addr_mismatch() {
         let (send_tx, mut recv_tx) = create_rb_payment_txs();
         recv_tx.outputs[0].script_public_key = Some("11145".to_string());
+        let unmet = send_tx.druid_info.as_ref().unwrap().expectations[0].clone();
 
         // Non-matching address expectation
-        assert!(!druid_expectations_are_met(
-            "VALUE",
-            vec![send_tx, recv_tx].iter()
-        ));
+        let txs = [send_tx, recv_tx];
+        assert!(!druid_expectations_are_met("VALUE", txs.iter()));
+        assert_eq!(
+            druid_expectations_are_met_checked("VALUE", txs.iter()),
+            Err(DruidError::AddressMismatch(unmet))
+        );
     }
 
     #[test]
@@ -264,12 +651,15 @@ mod tests {
     fn should_fail_rb_payment_value_expect_mismatch() {
         let (mut send_tx, recv_tx) = create_rb_payment_txs();
         send_tx.outputs[0].value = Asset::token_u64(10);
-
-        // Non-matching address expectation
-        assert!(!druid_expectations_are_met(
-            "VALUE",
-            vec![send_tx, recv_tx].iter()
-        ));
+        let unmet = recv_tx.druid_info.as_ref().unwrap().expectations[0].clone();
+
+        // Non-matching value expectation
+        let txs = [send_tx, recv_tx];
+        assert!(!druid_expectations_are_met("VALUE", txs.iter()));
+        assert_eq!(
+            druid_expectations_are_met_checked("VALUE", txs.iter()),
+            Err(DruidError::ValueMismatch(unmet))
+        );
     }
 
     #[test]
@@ -277,11 +667,14 @@ mod tests {
     fn should_fail_rb_payment_drs_expect_mismatch() {
         let (send_tx, mut recv_tx) = create_rb_payment_txs();
         recv_tx.outputs[0].value = Asset::receipt(1, Some("invalid_drs_tx_hash".to_string()), None);
-
-        // Non-matching address expectation
-        assert!(!druid_expectations_are_met(
-            "VALUE",
-            vec![send_tx, recv_tx].iter()
-        ));
+        let unmet = send_tx.druid_info.as_ref().unwrap().expectations[0].clone();
+
+        // Non-matching DRS expectation
+        let txs = [send_tx, recv_tx];
+        assert!(!druid_expectations_are_met("VALUE", txs.iter()));
+        assert_eq!(
+            druid_expectations_are_met_checked("VALUE", txs.iter()),
+            Err(DruidError::ValueMismatch(unmet))
+        );
     }
 }
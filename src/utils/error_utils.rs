@@ -67,6 +67,14 @@ pub fn error_num_signatures(op: &str) {
     error!("{op}: {ERROR_NUM_SIGNATURES}")
 }
 
+pub fn error_locktime(op: &str) {
+    error!("{op}: {ERROR_LOCKTIME}")
+}
+
+pub fn error_template_mismatch(op: &str) {
+    error!("{op}: {ERROR_TEMPLATE_MISMATCH}")
+}
+
 // script
 
 pub fn error_max_script_size() {
@@ -77,6 +85,22 @@ pub fn error_max_stack_size() {
     error!("{ERROR_MAX_STACK_SIZE}")
 }
 
+pub fn error_max_stack_memory() {
+    error!("{ERROR_MAX_STACK_MEMORY}")
+}
+
 pub fn error_max_ops_script() {
     error!("{ERROR_MAX_OPS_SCRIPT}")
 }
+
+pub fn error_resource_exhausted() {
+    error!("{ERROR_RESOURCE_EXHAUSTED}")
+}
+
+pub fn error_disabled_opcode() {
+    error!("{ERROR_DISABLED_OPCODE}")
+}
+
+pub fn error_non_push_unlock() {
+    error!("{ERROR_NON_PUSH_UNLOCK}")
+}
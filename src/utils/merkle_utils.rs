@@ -0,0 +1,191 @@
+use crate::crypto::sha3_256::{self, Output, Sha3_256};
+
+/// A step in a Merkle proof: the sibling hash and whether it sits on the left
+pub type MerkleProofStep = (String, bool);
+
+/// Builds the hex encoded Merkle root of the passed transaction hashes. When a level
+/// has an odd number of nodes, the last node is paired with itself
+///
+/// ### Arguments
+///
+/// * `tx_hashes`   - Transaction hashes to commit to, in block order
+pub fn merkle_root(tx_hashes: &[String]) -> String {
+    if tx_hashes.is_empty() {
+        return String::new();
+    }
+
+    let mut level = leaf_level(tx_hashes);
+    while level.len() > 1 {
+        level = next_level(&level);
+    }
+    hex::encode(level[0])
+}
+
+/// Builds the proof that the transaction hash at `index` is committed to by
+/// the Merkle root of `tx_hashes`
+///
+/// ### Arguments
+///
+/// * `tx_hashes`   - Transaction hashes committed to, in block order
+/// * `index`       - Index of the transaction hash to prove
+pub fn merkle_proof(tx_hashes: &[String], index: usize) -> Option<Vec<MerkleProofStep>> {
+    if index >= tx_hashes.len() {
+        return None;
+    }
+
+    let mut proof = Vec::new();
+    let mut level = leaf_level(tx_hashes);
+    let mut index = index;
+    while level.len() > 1 {
+        let sibling_is_left = index % 2 == 1;
+        let sibling = if sibling_is_left {
+            level[index - 1]
+        } else {
+            *level.get(index + 1).unwrap_or(&level[index])
+        };
+        proof.push((hex::encode(sibling), sibling_is_left));
+
+        level = next_level(&level);
+        index /= 2;
+    }
+    Some(proof)
+}
+
+/// Verifies that a Merkle proof commits the transaction hash to the given root
+///
+/// ### Arguments
+///
+/// * `tx_hash`     - Transaction hash to verify
+/// * `proof`       - Proof as produced by `merkle_proof`
+/// * `root`        - Hex encoded Merkle root to verify against
+pub fn verify_merkle_proof(tx_hash: &str, proof: &[MerkleProofStep], root: &str) -> bool {
+    let mut node = sha3_256::digest(tx_hash.as_bytes());
+    for (sibling, sibling_is_left) in proof {
+        let sibling = match hex::decode(sibling) {
+            Ok(sibling) => sibling,
+            Err(_) => return false,
+        };
+        node = if *sibling_is_left {
+            hash_pair(&sibling, &node)
+        } else {
+            hash_pair(&node, &sibling)
+        };
+    }
+    hex::encode(node) == root
+}
+
+/// Hashes each transaction hash into a leaf node
+///
+/// ### Arguments
+///
+/// * `tx_hashes`   - Transaction hashes to hash
+fn leaf_level(tx_hashes: &[String]) -> Vec<Output<Sha3_256>> {
+    tx_hashes
+        .iter()
+        .map(|h| sha3_256::digest(h.as_bytes()))
+        .collect()
+}
+
+/// Hashes pairs of nodes into the level above, duplicating an odd last node
+///
+/// ### Arguments
+///
+/// * `level`   - Nodes of the current level
+fn next_level(level: &[Output<Sha3_256>]) -> Vec<Output<Sha3_256>> {
+    level
+        .chunks(2)
+        .map(|pair| hash_pair(&pair[0], pair.get(1).unwrap_or(&pair[0])))
+        .collect()
+}
+
+/// Hashes two child nodes into their parent node
+///
+/// ### Arguments
+///
+/// * `left`    - Left child node
+/// * `right`   - Right child node
+fn hash_pair(left: &[u8], right: &[u8]) -> Output<Sha3_256> {
+    sha3_256::digest_all([left, right].iter().copied())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Util function to create distinct transaction hashes
+    fn create_tx_hashes(count: usize) -> Vec<String> {
+        (0..count).map(|i| format!("g{:031x}", i)).collect()
+    }
+
+    #[test]
+    /// Checks that the Merkle root is stable and changes with the committed hashes
+    fn should_construct_stable_merkle_roots() {
+        let roots: Vec<String> = (1..=4).map(|n| merkle_root(&create_tx_hashes(n))).collect();
+
+        for (n, root) in (1..=4).zip(roots.iter()) {
+            assert_eq!(&merkle_root(&create_tx_hashes(n)), root);
+            assert_eq!(root.len(), 64);
+        }
+        for (i, root) in roots.iter().enumerate() {
+            assert!(roots[i + 1..].iter().all(|other| other != root));
+        }
+        assert_eq!(merkle_root(&[]), String::new());
+    }
+
+    #[test]
+    /// Checks that a single hash is its own leaf and an odd last hash is duplicated
+    fn should_apply_leaf_and_odd_count_rules() {
+        let hashes = create_tx_hashes(3);
+        let mut padded = hashes.clone();
+        padded.push(hashes[2].clone());
+
+        assert_eq!(
+            merkle_root(&hashes[..1]),
+            hex::encode(sha3_256::digest(hashes[0].as_bytes()))
+        );
+        assert_eq!(merkle_root(&hashes), merkle_root(&padded));
+    }
+
+    #[test]
+    /// Checks that proofs for every leaf verify against the root
+    fn should_verify_merkle_proofs() {
+        for n in 1..=4 {
+            let hashes = create_tx_hashes(n);
+            let root = merkle_root(&hashes);
+
+            for (i, hash) in hashes.iter().enumerate() {
+                let proof = merkle_proof(&hashes, i).unwrap();
+                assert!(verify_merkle_proof(hash, &proof, &root));
+            }
+            assert_eq!(merkle_proof(&hashes, n), None);
+        }
+    }
+
+    #[test]
+    /// Checks that tampered proofs, hashes or roots fail verification
+    fn should_fail_tampered_merkle_proof() {
+        let hashes = create_tx_hashes(4);
+        let root = merkle_root(&hashes);
+        let proof = merkle_proof(&hashes, 1).unwrap();
+
+        let mut bad_sibling = proof.clone();
+        bad_sibling[0].0 = hex::encode(sha3_256::digest(b"tampered"));
+
+        let mut bad_side = proof.clone();
+        bad_side[1].1 = !bad_side[1].1;
+
+        let mut bad_hex = proof.clone();
+        bad_hex[0].0 = "not_hex".to_owned();
+
+        assert!(!verify_merkle_proof(&hashes[1], &bad_sibling, &root));
+        assert!(!verify_merkle_proof(&hashes[1], &bad_side, &root));
+        assert!(!verify_merkle_proof(&hashes[1], &bad_hex, &root));
+        assert!(!verify_merkle_proof(&hashes[1], &proof[..1], &root));
+        assert!(!verify_merkle_proof(&hashes[0], &proof, &root));
+        assert!(!verify_merkle_proof(
+            &hashes[1],
+            &proof,
+            &merkle_root(&hashes[..3])
+        ));
+    }
+}
@@ -7,9 +7,11 @@ use crate::primitives::asset::TokenAmount;
 
 pub mod druid_utils;
 pub mod error_utils;
+pub mod merkle_utils;
 pub mod script_utils;
 pub mod test_utils;
 pub mod transaction_utils;
+pub mod validation_cache;
 
 // ------- FUNCTIONS ------- //
 
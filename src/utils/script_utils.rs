@@ -4,27 +4,117 @@ use crate::crypto::sha3_256;
 use crate::crypto::sign_ed25519::{
     self as sign, PublicKey, Signature, ED25519_PUBLIC_KEY_LEN, ED25519_SIGNATURE_LEN,
 };
-use crate::primitives::asset::{Asset, AssetValues, ReceiptAsset, TokenAmount};
+use crate::crypto::signature_scheme::SignatureSchemeId;
+use crate::crypto::{constant_time_eq, ripemd160, sha256};
+use crate::primitives::asset::{
+    Asset, AssetValues, DataAsset, MetadataError, ReceiptAsset, TokenAmount,
+};
 use crate::primitives::druid::DruidExpectation;
 use crate::primitives::transaction::*;
+use crate::primitives::utxo::UtxoSet;
 use crate::script::interface_ops::*;
-use crate::script::lang::{ConditionStack, Script, Stack};
+use crate::script::lang::{
+    entry_size, ConditionStack, NetworkVersion, Script, ScriptBuilder, ScriptContext, ScriptError,
+    ScriptLimits, Stack,
+};
 use crate::script::{OpCodes, StackEntry};
 use crate::utils::error_utils::*;
 use crate::utils::transaction_utils::{
-    construct_address, construct_tx_in_signable_asset_hash, construct_tx_in_signable_hash,
+    construct_address, construct_tx_hash, construct_tx_in_signable_asset_hash,
+    construct_tx_in_signable_hash, construct_tx_in_signable_hash_with_network,
+    parse_address_version,
 };
+use crate::utils::validation_cache::ValidationCache;
 use bincode::serialize;
 use bytes::Bytes;
 use hex::encode;
 use std::collections::{BTreeMap, BTreeSet};
-use std::thread::current;
+use std::thread::{self, current};
+use std::{error, fmt};
 use tracing::{debug, error, info, trace};
 
 use super::transaction_utils::construct_p2sh_address;
 
+/// Reasons a transaction fails verification
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationError {
+    InvalidReceiptOnSpend,
+    NoInputs,
+    DuplicateInputs,
+    MissingPreviousOut,
+    InvalidCreateScript,
+    MissingInput(OutPoint),
+    InvalidScript(OutPoint),
+    LockedInput(OutPoint),
+    InputsOverflow,
+    InvalidOutputs,
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::InvalidReceiptOnSpend => {
+                write!(f, "On-spent receipts need a DRS and no metadata")
+            }
+            Self::NoInputs => write!(f, "Transaction has no inputs"),
+            Self::DuplicateInputs => write!(f, "Inputs spend the same output twice"),
+            Self::MissingPreviousOut => write!(f, "Input has no previous output"),
+            Self::InvalidCreateScript => write!(f, "Input does not create the output asset"),
+            Self::MissingInput(out_point) => {
+                write!(f, "Input {out_point:?} is not in the UTXO set")
+            }
+            Self::InvalidScript(out_point) => {
+                write!(f, "Input does not unlock {out_point:?}")
+            }
+            Self::LockedInput(out_point) => {
+                write!(f, "Input {out_point:?} is locked until a later block")
+            }
+            Self::InputsOverflow => write!(f, "Input amounts overflow"),
+            Self::InvalidOutputs => write!(f, "Outputs are not covered by the inputs"),
+        }
+    }
+}
+
+impl error::Error for ValidationError {}
+
+/// Reasons a block fails verification
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BlockError {
+    InvalidCoinbase,
+    DoubleSpend(usize, OutPoint),
+    InvalidTransaction(usize, ValidationError),
+    FeesOverflow,
+    CoinbaseAmount {
+        expected: TokenAmount,
+        actual: TokenAmount,
+    },
+}
+
+impl fmt::Display for BlockError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::InvalidCoinbase => write!(f, "Coinbase transaction is not valid"),
+            Self::DoubleSpend(i, out_point) => {
+                write!(
+                    f,
+                    "Transaction {i} spends {out_point:?}, already spent in the block"
+                )
+            }
+            Self::InvalidTransaction(i, e) => write!(f, "Transaction {i} is not valid: {e}"),
+            Self::FeesOverflow => write!(f, "Transaction fees overflow"),
+            Self::CoinbaseAmount { expected, actual } => write!(
+                f,
+                "Coinbase pays {actual}, expected subsidy plus fees of {expected}"
+            ),
+        }
+    }
+}
+
+impl error::Error for BlockError {}
+
 /// Verifies that all incoming transactions are allowed to be spent. Returns false if a single
-/// transaction doesn't verify
+/// transaction doesn't verify. No block height is known, so `TxOut` locktimes are not
+/// checked; use `tx_is_valid_with_context` to enforce them
 ///
 /// TODO: Currently assumes p2pkh and p2sh, abstract to all tx types
 ///
@@ -35,76 +125,625 @@ pub fn tx_is_valid<'a>(
     tx: &Transaction,
     is_in_utxo: impl Fn(&OutPoint) -> Option<&'a TxOut> + 'a,
 ) -> bool {
+    tx_is_valid_with_fee(tx, is_in_utxo, TokenAmount(0))
+}
+
+/// Verifies that all incoming transactions are allowed to be spent at the context's block
+/// height. A `TxOut` may only be spent once the block height reaches its locktime, which
+/// for coinbase outputs is their creation height plus `COINBASE_MATURITY`. P2PKH
+/// signatures must be made for the context's network id
+///
+/// ### Arguments
+///
+/// * `tx`          - Transaction to verify
+/// * `ctx`         - Context the transaction is verified in
+/// * `is_in_utxo`  - Lookup for the `TxOut` referenced by an `OutPoint`
+pub fn tx_is_valid_with_context<'a>(
+    tx: &Transaction,
+    ctx: &ScriptContext,
+    is_in_utxo: impl Fn(&OutPoint) -> Option<&'a TxOut> + 'a,
+) -> bool {
+    tx_is_valid_with_fee_and_scripts(tx, is_in_utxo, TokenAmount(0), true, Some(ctx)).is_ok()
+}
+
+/// Verifies that all incoming transactions are allowed to be spent, with the `Token`s
+/// spent by the `TxIn`s covering the `TxOut`s plus the expected fee. Returns false if a
+/// single transaction doesn't verify
+///
+/// ### Arguments
+///
+/// * `tx`              - Transaction to verify
+/// * `is_in_utxo`      - Lookup for the `TxOut` referenced by an `OutPoint`
+/// * `expected_fee`    - Fee expected to be paid in `Token`s
+pub fn tx_is_valid_with_fee<'a>(
+    tx: &Transaction,
+    is_in_utxo: impl Fn(&OutPoint) -> Option<&'a TxOut> + 'a,
+    expected_fee: TokenAmount,
+) -> bool {
+    tx_is_valid_with_fee_and_scripts(tx, is_in_utxo, expected_fee, true, None).is_ok()
+}
+
+/// Verifies a transaction as `tx_is_valid` does, reusing the cached validity of its
+/// input scripts when known. UTXO membership and amounts are always checked, as they
+/// depend on the UTXO set at the time of the call
+///
+/// ### Arguments
+///
+/// * `cache`       - Cache of script validity results
+/// * `tx`          - Transaction to verify
+/// * `is_in_utxo`  - Lookup for the `TxOut` referenced by an `OutPoint`
+pub fn tx_is_valid_cached<'a>(
+    cache: &mut ValidationCache,
+    tx: &Transaction,
+    is_in_utxo: impl Fn(&OutPoint) -> Option<&'a TxOut> + 'a,
+) -> bool {
+    let tx_hash = tx.tx_hash();
+    let scripts_valid = match cache.get(&tx_hash) {
+        Some(valid) => valid,
+        None => match tx_scripts_are_valid(tx, &is_in_utxo) {
+            Some(valid) => {
+                cache.insert(tx_hash, valid);
+                valid
+            }
+            None => {
+                error!("UTXO DOESN'T CONTAIN THIS TX");
+                return false;
+            }
+        },
+    };
+
+    scripts_valid
+        && tx_is_valid_with_fee_and_scripts(tx, is_in_utxo, TokenAmount(0), false, None).is_ok()
+}
+
+/// Verifies that every `TxIn` unlocks the `TxOut` it spends. Returns None if a spent
+/// `TxOut` is not found, as the result then depends on the UTXO set
+///
+/// ### Arguments
+///
+/// * `tx`          - Transaction to verify
+/// * `is_in_utxo`  - Lookup for the `TxOut` referenced by an `OutPoint`
+fn tx_scripts_are_valid<'a>(
+    tx: &Transaction,
+    is_in_utxo: &impl Fn(&OutPoint) -> Option<&'a TxOut>,
+) -> Option<bool> {
+    // Create scripts are checked along with the rest of the transaction
+    if tx.is_create_tx() {
+        return Some(true);
+    }
+
+    let ctx = ScriptContext::default().with_spending_tx(tx);
+    for tx_in in &tx.inputs {
+        let tx_out_point = match &tx_in.previous_out {
+            Some(tx_out_point) => tx_out_point,
+            None => return Some(false),
+        };
+        let tx_out = is_in_utxo(tx_out_point)?;
+        if !txin_unlocks_txout_with_context(tx_in, tx_out, tx_out_point, &ctx) {
+            return Some(false);
+        }
+    }
+    Some(true)
+}
+
+/// Verifies a transaction as `tx_is_valid_with_fee` does, optionally skipping the
+/// `TxIn` script checks when their result is already known. Returns the reason the
+/// transaction doesn't verify, if any
+///
+/// ### Arguments
+///
+/// * `tx`              - Transaction to verify
+/// * `is_in_utxo`      - Lookup for the `TxOut` referenced by an `OutPoint`
+/// * `expected_fee`    - Fee expected to be paid in `Token`s
+/// * `check_scripts`   - Whether to check that each `TxIn` unlocks its `TxOut`
+/// * `ctx`             - Context the transaction is verified in, if its block height is known
+fn tx_is_valid_with_fee_and_scripts<'a>(
+    tx: &Transaction,
+    is_in_utxo: impl Fn(&OutPoint) -> Option<&'a TxOut> + 'a,
+    expected_fee: TokenAmount,
+    check_scripts: bool,
+    ctx: Option<&ScriptContext>,
+) -> Result<(), ValidationError> {
+    // Create transactions mint their output rather than spending any
+    if tx.is_create_tx() {
+        return create_tx_is_valid(tx);
+    }
+
     let mut tx_ins_spent: AssetValues = Default::default();
-    // TODO: Add support for `Data` asset variant
     // `Receipt` assets MUST have an a DRS value associated with them when they are getting on-spent
-    if tx.outputs.iter().any(|out| {
-        (out.value.is_receipt()
-            && (out.value.get_drs_tx_hash().is_none() || out.value.get_metadata().is_some()))
-    }) {
+    if tx
+        .outputs
+        .iter()
+        .flat_map(|out| out.value.members())
+        .any(|asset| {
+            asset.is_receipt()
+                && (asset.get_drs_tx_hash().is_none() || asset.get_metadata().is_some())
+        })
+    {
         error!("ON-SPENDING NEEDS EMPTY METADATA AND NON-EMPTY DRS SPECIFICATION");
-        return false;
+        return Err(ValidationError::InvalidReceiptOnSpend);
+    }
+
+    // Only DDE transactions may be constructed without inputs
+    if tx.inputs.is_empty() && tx.druid_info.is_none() {
+        error!("TX HAS NO INPUTS");
+        return Err(ValidationError::NoInputs);
+    }
+
+    // The same `OutPoint` may only be spent once within a transaction
+    let mut tx_out_points = BTreeSet::new();
+    if !tx
+        .inputs
+        .iter()
+        .all(|tx_in| tx_out_points.insert(&tx_in.previous_out))
+    {
+        error!("TX INPUTS CONTAIN DUPLICATE OUTPOINTS");
+        return Err(ValidationError::DuplicateInputs);
     }
 
+    // Ensure every input is in the `UTXO` set before verifying any scripts, so
+    // transactions spending missing outputs fail without signature verification
+    let mut tx_ins_outs = Vec::with_capacity(tx.inputs.len());
     for tx_in in &tx.inputs {
-        // Ensure the transaction is in the `UTXO` set
-        let tx_out_point = tx_in.previous_out.as_ref().unwrap().clone();
+        let tx_out_point = match &tx_in.previous_out {
+            Some(tx_out_point) => tx_out_point.clone(),
+            None => {
+                error!("TX INPUT HAS NO PREVIOUS OUTPOINT");
+                return Err(ValidationError::MissingPreviousOut);
+            }
+        };
 
         let tx_out = if let Some(tx_out) = is_in_utxo(&tx_out_point) {
             tx_out
         } else {
             error!("UTXO DOESN'T CONTAIN THIS TX");
-            return false;
+            return Err(ValidationError::MissingInput(tx_out_point));
         };
+        tx_ins_outs.push((tx_in, tx_out_point, tx_out));
+    }
 
-        // At this point `TxIn` will be valid
-        let tx_out_pk = tx_out.script_public_key.as_ref();
-        let tx_out_hash = construct_tx_in_signable_hash(&tx_out_point);
+    let script_ctx = ctx.copied().unwrap_or_default().with_spending_tx(tx);
+    for (tx_in, tx_out_point, tx_out) in tx_ins_outs {
+        if ctx.is_some_and(|ctx| tx_out.locktime > ctx.block_height) {
+            error!("TX INPUT IS LOCKED UNTIL A LATER BLOCK");
+            return Err(ValidationError::LockedInput(tx_out_point));
+        }
 
-        if let Some(pk) = tx_out_pk {
-            // Check will need to include other signature types here
-            if !tx_has_valid_p2pkh_sig(&tx_in.script_signature, &tx_out_hash, pk)
-                && !tx_has_valid_p2sh_script(&tx_in.script_signature, pk)
-            {
-                return false;
-            }
-        } else {
-            return false;
+        if check_scripts
+            && !txin_unlocks_txout_with_context(tx_in, tx_out, &tx_out_point, &script_ctx)
+        {
+            return Err(ValidationError::InvalidScript(tx_out_point));
         }
 
         let asset = tx_out.value.clone().with_fixed_hash(&tx_out_point);
-        tx_ins_spent.update_add(&asset);
+        if !tx_ins_spent.checked_update_add(&asset) {
+            error!("TX INPUTS OVERFLOW");
+            return Err(ValidationError::InputsOverflow);
+        }
+    }
+
+    if !tx_outs_are_valid_with_fee(&tx.outputs, tx_ins_spent, expected_fee) {
+        return Err(ValidationError::InvalidOutputs);
+    }
+    Ok(())
+}
+
+/// Verifies that a create transaction's input carries a valid create script for the
+/// asset it mints. Receipt metadata is limited by the version of the creator's address,
+/// which the created asset is paid to
+///
+/// ### Arguments
+///
+/// * `tx`  - Create transaction to verify
+fn create_tx_is_valid(tx: &Transaction) -> Result<(), ValidationError> {
+    let tx_out = &tx.outputs[0];
+    let version = match &tx_out.script_public_key {
+        Some(address) if address_is_well_formed(address) => parse_address_version(address),
+        _ => {
+            error!("CREATED ASSET HAS NO VALID ADDRESS");
+            return Err(ValidationError::InvalidOutputs);
+        }
+    };
+
+    let script = &tx.inputs[0].script_signature;
+    if !tx_has_valid_create_script_for_version(script, &tx_out.value, version) {
+        error!("INPUT DOES NOT CREATE THE OUTPUT ASSET");
+        return Err(ValidationError::InvalidCreateScript);
+    }
+    Ok(())
+}
+
+/// Verifies that a `TxIn`'s unlocking script unlocks the `TxOut` it spends, either
+/// as a P2PKH spend or as a P2SH spend
+///
+/// ### Arguments
+///
+/// * `tx_in`       - Input spending the output
+/// * `tx_out`      - Output being spent
+/// * `outpoint`    - `OutPoint` referencing the output being spent
+pub fn txin_unlocks_txout(tx_in: &TxIn, tx_out: &TxOut, outpoint: &OutPoint) -> bool {
+    txin_unlocks_txout_with_context(tx_in, tx_out, outpoint, &ScriptContext::default())
+}
+
+/// Verifies that a `TxIn`'s unlocking script unlocks the `TxOut` it spends in the
+/// given context. P2PKH signatures must be made for the context's network id, and
+/// OP_CHECKTEMPLATEVERIFY checks the context's spending transaction
+///
+/// ### Arguments
+///
+/// * `tx_in`       - Input spending the output
+/// * `tx_out`      - Output being spent
+/// * `outpoint`    - `OutPoint` referencing the output being spent
+/// * `ctx`         - Context the input is verified in
+pub fn txin_unlocks_txout_with_context(
+    tx_in: &TxIn,
+    tx_out: &TxOut,
+    outpoint: &OutPoint,
+    ctx: &ScriptContext,
+) -> bool {
+    let pk = match &tx_out.script_public_key {
+        Some(pk) => pk,
+        None => return false,
+    };
+    if script_has_create_op(&tx_in.script_signature) {
+        trace!(
+            "OP_CREATE in spending script: {:?}",
+            tx_in.script_signature.stack
+        );
+        return false;
+    }
+    let tx_out_hash = construct_tx_in_signable_hash_with_network(outpoint, ctx.network_id);
+
+    // Check will need to include other signature types here
+    tx_has_valid_p2pkh_sig(&tx_in.script_signature, &tx_out_hash, pk, ctx)
+        || tx_has_valid_p2sh_script_with_context(&tx_in.script_signature, pk, ctx)
+}
+
+/// Checks whether a script, or the redeem script it carries as a P2SH unlocking
+/// script, contains `OP_CREATE`. It is only valid in creation scripts, which are
+/// checked by `tx_has_valid_create_script`
+///
+/// ### Arguments
+///
+/// * `script`  - Script to check
+fn script_has_create_op(script: &Script) -> bool {
+    script.contains_op(OpCodes::OP_CREATE)
+        || script
+            .p2sh_redeem()
+            .is_some_and(|(redeem, _)| redeem.contains_op(OpCodes::OP_CREATE))
+}
+
+/// Verifies a batch of transactions against a shared UTXO set. Each transaction's
+/// effects are applied to a working copy of the set before the next is verified, so
+/// later transactions may spend outputs created by earlier ones, while outputs spent
+/// twice within the batch are rejected. Returns false if a single transaction doesn't verify
+///
+/// ### Arguments
+///
+/// * `txs`         - Transactions to verify, in order
+/// * `base_utxo`   - UTXO set the batch is verified against
+pub fn txs_are_valid(txs: &[Transaction], base_utxo: &UtxoSet) -> bool {
+    let mut utxo = base_utxo.clone();
+
+    for tx in txs {
+        if !tx_is_valid(tx, utxo.lookup_fn()) {
+            return false;
+        }
+        utxo.apply_tx(tx);
+    }
+
+    true
+}
+
+/// Verifies a batch of transactions against a shared UTXO set as `txs_are_valid` does,
+/// returning the index of each transaction paired with the reason it doesn't verify, if
+/// any. Only the effects of valid transactions are applied to the working copy of the set
+///
+/// ### Arguments
+///
+/// * `txs`     - Transactions to verify, in order
+/// * `utxo`    - UTXO set the batch is verified against
+pub fn validate_batch(
+    txs: &[Transaction],
+    utxo: &UtxoSet,
+) -> Vec<(usize, Result<(), ValidationError>)> {
+    let mut working = utxo.clone();
+
+    txs.iter()
+        .enumerate()
+        .map(|(i, tx)| {
+            let result = tx_is_valid_with_fee_and_scripts(
+                tx,
+                working.lookup_fn(),
+                TokenAmount(0),
+                true,
+                None,
+            );
+            if result.is_ok() {
+                working.apply_tx(tx);
+            }
+            (i, result)
+        })
+        .collect()
+}
+
+/// Verifies a batch of transactions against a shared UTXO set as `txs_are_valid` does,
+/// returning the validity of each transaction. Transactions are grouped into levels by
+/// the in-batch outputs they spend, and each level is verified in parallel once the
+/// levels it depends on have been applied. A transaction is invalid if it spends the
+/// output of a later transaction in the batch, or an output already spent by an earlier one
+///
+/// ### Arguments
+///
+/// * `txs`     - Transactions to verify, in order
+/// * `utxo`    - UTXO set the batch is verified against
+pub fn validate_transactions_parallel(txs: &[Transaction], utxo: &UtxoSet) -> Vec<bool> {
+    let tx_indexes: BTreeMap<String, usize> = txs
+        .iter()
+        .enumerate()
+        .map(|(i, tx)| (construct_tx_hash(tx), i))
+        .collect();
+
+    let mut results = vec![true; txs.len()];
+    let mut levels = vec![0; txs.len()];
+    for (i, tx) in txs.iter().enumerate() {
+        for out_point in tx.inputs.iter().filter_map(|i| i.previous_out.as_ref()) {
+            match tx_indexes.get(&out_point.t_hash) {
+                Some(&j) if j >= i => results[i] = false,
+                Some(&j) => levels[i] = levels[i].max(levels[j] + 1),
+                None => (),
+            }
+        }
+    }
+
+    let mut working = utxo.clone();
+    let max_level = levels.iter().copied().max().unwrap_or_default();
+    for level in 0..=max_level {
+        let batch: Vec<usize> = (0..txs.len())
+            .filter(|i| levels[*i] == level && results[*i])
+            .collect();
+        let valid = txs_are_valid_parallel(batch.iter().map(|i| &txs[*i]).collect(), &working);
+
+        // Apply in batch order, so the first of conflicting spends wins
+        for (i, valid) in batch.into_iter().zip(valid) {
+            let tx = &txs[i];
+            results[i] = valid
+                && tx
+                    .inputs
+                    .iter()
+                    .filter_map(|i| i.previous_out.as_ref())
+                    .all(|out_point| working.contains(out_point));
+            if results[i] {
+                working.apply_tx(tx);
+            }
+        }
+    }
+
+    results
+}
+
+/// Verifies independent transactions against the same UTXO set, spreading them
+/// across the available threads
+///
+/// ### Arguments
+///
+/// * `txs`     - Transactions to verify
+/// * `utxo`    - UTXO set the transactions are verified against
+fn txs_are_valid_parallel(txs: Vec<&Transaction>, utxo: &UtxoSet) -> Vec<bool> {
+    let threads = thread::available_parallelism().map_or(1, |n| n.get());
+    let chunk_size = txs.len().div_ceil(threads).max(1);
+
+    thread::scope(|s| {
+        let handles: Vec<_> = txs
+            .chunks(chunk_size)
+            .map(|chunk| {
+                let handle = s.spawn(move || {
+                    chunk
+                        .iter()
+                        .map(|tx| tx_is_valid(tx, utxo.lookup_fn()))
+                        .collect::<Vec<_>>()
+                });
+                (handle, chunk.len())
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .flat_map(|(handle, len)| handle.join().unwrap_or_else(|_| vec![false; len]))
+            .collect()
+    })
+}
+
+/// Verifies that a coinbase transaction has a single input without a previous
+/// `OutPoint`, whose script encodes the expected block height, and that its output
+/// is locked until the expected height plus `COINBASE_MATURITY`
+///
+/// ### Arguments
+///
+/// * `tx`              - Coinbase transaction to verify
+/// * `expected_height` - Block height the coinbase should be for
+pub fn tx_is_coinbase_valid(tx: &Transaction, expected_height: u64) -> bool {
+    if !tx.is_coinbase() {
+        trace!("Transaction is not a coinbase");
+        return false;
+    }
+
+    let expected_script = Script::new_for_coinbase(expected_height);
+    if tx.inputs[0].script_signature != expected_script {
+        trace!(
+            "Invalid coinbase script: {:?}, expected height: {}",
+            tx.inputs[0].script_signature.stack,
+            expected_height
+        );
+        return false;
+    }
+
+    let maturity = expected_height.saturating_add(COINBASE_MATURITY);
+    if tx.outputs.iter().any(|tx_out| tx_out.locktime < maturity) {
+        trace!("Coinbase output is spendable before height: {}", maturity);
+        return false;
+    }
+
+    true
+}
+
+/// Verifies a block's transactions: each transaction must verify against the UTXO set
+/// as updated by the transactions before it, no output may be spent twice within the
+/// block, and the coinbase must be valid for the block height and pay exactly the
+/// subsidy plus the fees of the other transactions
+///
+/// ### Arguments
+///
+/// * `coinbase`    - Coinbase transaction of the block
+/// * `txs`         - Other transactions of the block, in order
+/// * `utxo`        - UTXO set the block is verified against
+/// * `height`      - Height of the block
+/// * `subsidy`     - Block reward the coinbase may claim on top of the fees
+pub fn validate_block(
+    coinbase: &Transaction,
+    txs: &[Transaction],
+    utxo: &UtxoSet,
+    height: u64,
+    subsidy: TokenAmount,
+) -> Result<(), BlockError> {
+    if !tx_is_coinbase_valid(coinbase, height) {
+        return Err(BlockError::InvalidCoinbase);
+    }
+
+    let ctx = ScriptContext {
+        block_height: height,
+        ..Default::default()
+    };
+    // Outputs spent earlier in the block are caught as double spends before any lookup,
+    // so overlaying the outputs created so far is enough to see the updated UTXO set
+    let mut created = BTreeMap::new();
+    let mut spent = BTreeSet::new();
+    let mut fees = TokenAmount(0);
+    for (i, tx) in txs.iter().enumerate() {
+        if let Some(out_point) = tx.spent_outpoints().find(|o| !spent.insert(*o)) {
+            error!("BLOCK SPENDS THE SAME OUTPOINT TWICE");
+            return Err(BlockError::DoubleSpend(i, out_point.clone()));
+        }
+
+        let lookup = |o: &OutPoint| created.get(o).or_else(|| utxo.get(o));
+
+        // A fee that underflows leaves the outputs uncovered, which the checks below reject
+        let fee = tx
+            .input_totals_with(lookup)
+            .tokens
+            .checked_sub(tx.output_totals().tokens)
+            .unwrap_or_default();
+        tx_is_valid_with_fee_and_scripts(tx, lookup, fee, true, Some(&ctx))
+            .map_err(|e| BlockError::InvalidTransaction(i, e))?;
+        fees = fees.checked_add(fee).ok_or(BlockError::FeesOverflow)?;
+
+        let tx_hash = construct_tx_hash(tx);
+        for (n, tx_out) in tx.outputs.iter().enumerate() {
+            if !tx_out.is_receipt_burn() {
+                created.insert(OutPoint::new(tx_hash.clone(), n as i32), tx_out.clone());
+            }
+        }
     }
 
-    tx_outs_are_valid(&tx.outputs, tx_ins_spent)
+    let expected = subsidy.checked_add(fees).ok_or(BlockError::FeesOverflow)?;
+    let actual = coinbase.output_totals().tokens;
+    if actual != expected {
+        error!("COINBASE DOES NOT PAY THE SUBSIDY PLUS FEES");
+        return Err(BlockError::CoinbaseAmount { expected, actual });
+    }
+    Ok(())
 }
 
 /// Verifies that the outgoing `TxOut`s are valid. Returns false if a single
 /// transaction doesn't verify.
 ///
-/// TODO: Abstract to data assets
-///
 /// ### Arguments
 ///
 /// * `tx_outs` - `TxOut`s to verify
 /// * `tx_ins_spent` - Total amount spendable from `TxIn`s
 pub fn tx_outs_are_valid(tx_outs: &[TxOut], tx_ins_spent: AssetValues) -> bool {
+    tx_outs_are_valid_with_fee(tx_outs, tx_ins_spent, TokenAmount(0))
+}
+
+/// Verifies that the outgoing `TxOut`s are valid, with the `Token`s spent by the
+/// `TxIn`s equal to the `TxOut` `Token`s plus the fee. `Receipt` and `Data` assets
+/// must still be conserved exactly. Returns false if a single transaction doesn't verify.
+///
+/// ### Arguments
+///
+/// * `tx_outs`         - `TxOut`s to verify
+/// * `tx_ins_spent`    - Total amount spendable from `TxIn`s
+/// * `fee`             - Fee paid in `Token`s
+pub fn tx_outs_are_valid_with_fee(
+    tx_outs: &[TxOut],
+    tx_ins_spent: AssetValues,
+    fee: TokenAmount,
+) -> bool {
     let mut tx_outs_spent: AssetValues = Default::default();
 
     for tx_out in tx_outs {
+        // Data-carrier outputs are unspendable, so must carry zero value
+        if let Some(data) = tx_out.data_carrier_payload() {
+            if data.len() > MAX_OP_RETURN_BYTES || tx_out.value != Asset::default() {
+                trace!("Data-carrier output is too large or carries value");
+                return false;
+            }
+            continue;
+        }
+
+        // Receipt burn outputs are unspendable, so may only carry receipts. These still
+        // count towards the outputs, as the burned part of each DRS group
+        let is_receipt_burn = tx_out.is_receipt_burn();
+        if is_receipt_burn && !tx_out.value.members().iter().all(Asset::is_receipt) {
+            trace!("Receipt burn output carries other assets");
+            return false;
+        }
+
         // Addresses must have valid length
         if let Some(addr) = &tx_out.script_public_key {
-            if !address_has_valid_length(addr) {
-                trace!("Address has invalid length");
+            if !is_receipt_burn && !address_is_well_formed(addr) {
+                trace!("Address is not well formed");
                 return false;
             }
         }
 
-        tx_outs_spent.update_add(&tx_out.value);
+        // `Bundle` assets must hold distinct assets
+        if !tx_out.value.bundle_is_well_formed() {
+            trace!("Bundle asset is not well formed");
+            return false;
+        }
+
+        // `Data` assets must conform to the network size constraint
+        for asset in tx_out.value.members().iter() {
+            if let Asset::Data(d) = asset {
+                if !data_asset_has_valid_size(d) {
+                    trace!("Data asset is too large");
+                    return false;
+                }
+            }
+        }
+
+        if !tx_outs_spent.checked_update_add(&tx_out.value) {
+            trace!("Outputs overflow");
+            return false;
+        }
+    }
+    if !tx_outs_spent.checked_update_add(&Asset::Token(fee)) {
+        trace!("Outputs and fee overflow");
+        return false;
+    }
+
+    // Ensure that the `TxIn`s correlate with the `TxOut`s and fee. `Receipt`s are
+    // totalled per `drs_tx_hash`, so each DRS group must balance on its own
+    if !tx_outs_spent.is_equal(&tx_ins_spent) {
+        debug!(
+            "Outputs and fee do not match inputs: {}",
+            tx_outs_spent.diff(&tx_ins_spent)
+        );
+        return false;
     }
 
-    // Ensure that the `TxIn`s correlate with the `TxOut`s
-    tx_outs_spent.is_equal(&tx_ins_spent)
+    true
 }
 
 /// Checks whether a create transaction has a valid input script
@@ -114,19 +753,48 @@ pub fn tx_outs_are_valid(tx_outs: &[TxOut], tx_ins_spent: AssetValues) -> bool {
 /// * `script`      - Script to validate
 /// * `asset`       - Asset to be created
 pub fn tx_has_valid_create_script(script: &Script, asset: &Asset) -> bool {
+    tx_has_valid_create_script_for_version(script, asset, None)
+}
+
+/// Checks whether a create transaction has a valid input script, validating receipt
+/// metadata against the limit of the creating script's version
+///
+/// ### Arguments
+///
+/// * `script`      - Script to validate
+/// * `asset`       - Asset to be created
+/// * `version`     - Address version of the creating script, None being the current version
+pub fn tx_has_valid_create_script_for_version(
+    script: &Script,
+    asset: &Asset,
+    version: Option<u64>,
+) -> bool {
     let mut it = script.stack.iter();
     let asset_hash = construct_tx_in_signable_asset_hash(asset);
 
+    // Bundles only carry assets that already exist
+    if let Asset::Bundle(_) = asset {
+        trace!("Bundle assets cannot be created");
+        return false;
+    }
+
     if let Asset::Receipt(r) = asset {
-        if !receipt_has_valid_size(r) {
-            trace!("Receipt metadata is too large");
+        if let Err(e) = r.validate_metadata_for_version(version) {
+            trace!("Invalid receipt metadata: {}", e);
+            return false;
+        }
+    }
+
+    if let Asset::Data(d) = asset {
+        if !data_asset_has_valid_size(d) {
+            trace!("Data asset is too large");
             return false;
         }
     }
 
     if let (
         Some(StackEntry::Op(OpCodes::OP_CREATE)),
-        Some(StackEntry::Num(_)),
+        Some(block_number),
         Some(StackEntry::Op(OpCodes::OP_DROP)),
         Some(StackEntry::Bytes(b)),
         Some(StackEntry::Signature(_)),
@@ -143,7 +811,11 @@ pub fn tx_has_valid_create_script(script: &Script, asset: &Asset) -> bool {
         it.next(),
         it.next(),
     ) {
-        if b == &asset_hash && script.interpret() {
+        if block_number.as_num().is_some()
+            && b == &asset_hash
+            && create_sig_is_valid(script, asset)
+            && script.interpret()
+        {
             return true;
         }
     }
@@ -152,6 +824,46 @@ pub fn tx_has_valid_create_script(script: &Script, asset: &Asset) -> bool {
     false
 }
 
+/// Verifies the signature of a create script directly, outside the interpreter: it
+/// must be a signature by the script's public key over the asset hash.
+/// A defense-in-depth cross-check on `tx_has_valid_create_script`
+///
+/// ### Arguments
+///
+/// * `script`      - Create script carrying the signature and public key
+/// * `asset`       - Asset to be created
+pub fn create_sig_is_valid(script: &Script, asset: &Asset) -> bool {
+    let mut it = script.stack.iter();
+
+    if let (
+        Some(StackEntry::Op(OpCodes::OP_CREATE)),
+        Some(block_number),
+        Some(StackEntry::Op(OpCodes::OP_DROP)),
+        Some(StackEntry::Bytes(_)),
+        Some(StackEntry::Signature(sig)),
+        Some(StackEntry::PubKey(pk)),
+        Some(StackEntry::Op(OpCodes::OP_CHECKSIG)),
+        None,
+    ) = (
+        it.next(),
+        it.next(),
+        it.next(),
+        it.next(),
+        it.next(),
+        it.next(),
+        it.next(),
+        it.next(),
+    ) {
+        if block_number.as_num().is_none() {
+            return false;
+        }
+        let asset_hash = construct_tx_in_signable_asset_hash(asset);
+        return sign::verify_message(asset_hash.as_bytes(), sig, pk);
+    }
+
+    false
+}
+
 /// Checks whether a transaction to spend tokens in P2PKH has a valid signature
 ///
 /// ### Arguments
@@ -159,7 +871,13 @@ pub fn tx_has_valid_create_script(script: &Script, asset: &Asset) -> bool {
 /// * `script`          - Script to validate
 /// * `outpoint_hash`   - Hash of the corresponding outpoint
 /// * `tx_out_pub_key`  - Public key of the previous tx_out
-fn tx_has_valid_p2pkh_sig(script: &Script, outpoint_hash: &str, tx_out_pub_key: &str) -> bool {
+/// * `ctx`             - Context the script is executed in
+fn tx_has_valid_p2pkh_sig(
+    script: &Script,
+    outpoint_hash: &str,
+    tx_out_pub_key: &str,
+    ctx: &ScriptContext,
+) -> bool {
     let mut it = script.stack.iter();
 
     if let (
@@ -185,7 +903,10 @@ fn tx_has_valid_p2pkh_sig(script: &Script, outpoint_hash: &str, tx_out_pub_key:
         it.next(),
         it.next(),
     ) {
-        if h == tx_out_pub_key && b == outpoint_hash && script.interpret() {
+        if constant_time_eq(h, tx_out_pub_key)
+            && constant_time_eq(b, outpoint_hash)
+            && script.interpret_with_context(ctx)
+        {
             return true;
         }
     }
@@ -206,11 +927,34 @@ fn tx_has_valid_p2pkh_sig(script: &Script, outpoint_hash: &str, tx_out_pub_key:
 /// * `script`          - Script to validate
 /// * `address`         - Address of the P2SH transaction
 pub fn tx_has_valid_p2sh_script(script: &Script, address: &str) -> bool {
-    let p2sh_address = construct_p2sh_address(script);
+    tx_has_valid_p2sh_script_with_context(script, address, &ScriptContext::default())
+}
 
-    if p2sh_address == address {
-        return script.interpret();
-    }
+/// Checks whether a transaction to spend tokens in P2SH has a valid hash and a script
+/// executing in the given context
+///
+/// ### Arguments
+///
+/// * `script`          - Script to validate
+/// * `address`         - Address of the P2SH transaction
+/// * `ctx`             - Context the script is executed in
+pub fn tx_has_valid_p2sh_script_with_context(
+    script: &Script,
+    address: &str,
+    ctx: &ScriptContext,
+) -> bool {
+    let p2sh_address = construct_p2sh_address(script);
+
+    if p2sh_address == address {
+        return script.interpret_with_context(ctx);
+    }
+
+    // Unlocking script built with `Script::p2sh_unlock`
+    if let Some((redeem, unlock_script)) = script.p2sh_redeem() {
+        if construct_p2sh_address(&redeem) == address {
+            return unlock_script.interpret_with_context(ctx);
+        }
+    }
 
     trace!(
         "Invalid P2SH script: {:?}, address: {}",
@@ -221,16 +965,43 @@ pub fn tx_has_valid_p2sh_script(script: &Script, address: &str) -> bool {
     false
 }
 
-/// Checks that a receipt's metadata conforms to the network size constraint
+/// Verifies a locking script against a separate unlocking script in two phases: the
+/// unlocking script is run to populate the stack, then the locking script is run against
+/// that stack. The unlocking script must only push data, so it cannot alter how the
+/// locking script is evaluated
 ///
 /// ### Arguments
 ///
-/// * `receipt` - Receipt to check
-fn receipt_has_valid_size(receipt: &ReceiptAsset) -> bool {
-    if let Some(metadata) = &receipt.metadata {
-        return metadata.len() <= MAX_METADATA_BYTES;
-    }
-    true
+/// * `unlock`  - Unlocking script providing the stack
+/// * `lock`    - Locking script to verify against the stack
+pub fn verify_scripts(unlock: &Script, lock: &Script) -> Result<(), ScriptError> {
+    if !unlock.is_push_only() {
+        error_non_push_unlock();
+        return Err(ScriptError::NonPushUnlock);
+    }
+    if !unlock.is_valid() {
+        return Err(ScriptError::MaxScriptSize);
+    }
+    // Small numbers pushed with their opcodes are run as the numbers they push
+    let initial = unlock
+        .stack
+        .iter()
+        .map(|entry| {
+            entry
+                .as_num()
+                .map_or_else(|| entry.clone(), StackEntry::Num)
+        })
+        .collect();
+    lock.interpret_with_initial_stack_checked(initial)
+}
+
+/// Checks that a data asset's payload conforms to the network size constraint
+///
+/// ### Arguments
+///
+/// * `data_asset` - Data asset to check
+fn data_asset_has_valid_size(data_asset: &DataAsset) -> bool {
+    data_asset.data.len() <= MAX_DATA_ASSET_BYTES
 }
 
 /// Checks that an address has a valid length
@@ -242,14 +1013,30 @@ fn address_has_valid_length(address: &str) -> bool {
     address.len() == 32 || address.len() == 64
 }
 
+/// Checks that an address has a valid length and is hex encoded, allowing for the
+/// `P2SH_PREPEND` character at the start of P2SH addresses
+///
+/// ### Arguments
+///
+/// * `address` - Address to check
+pub fn address_is_well_formed(address: &str) -> bool {
+    let hex_part = match address.as_bytes().first() {
+        Some(&P2SH_PREPEND) => &address[1..],
+        _ => address,
+    };
+    address_has_valid_length(address) && hex_part.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::constants::RECEIPT_ACCEPT_VAL;
-    use crate::primitives::asset::{Asset, DataAsset};
+    use crate::primitives::asset::{metadata_limit_for_version, Asset, BundleMember};
     use crate::primitives::druid::DdeValues;
     use crate::primitives::transaction::OutPoint;
-    use crate::utils::test_utils::generate_tx_with_ins_and_outs_assets;
+    use crate::utils::test_utils::{
+        generate_tx_with_ins_and_outs_assets, generate_tx_with_ins_and_outs_mixed_assets,
+    };
     use crate::utils::transaction_utils::*;
 
     /*---- CONSTANTS OPS ----*/
@@ -260,17 +1047,42 @@ mod tests {
         /// op_0([]) -> [0]
         let mut stack = Stack::new();
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(0)];
-        op_0(&mut stack);
+        op_0(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v)
     }
 
+    #[test]
+    /// Test OP_PUSHDATA
+    fn test_pushdata() {
+        /// op_pushdata([], "a") -> ["a"]
+        let mut stack = Stack::new();
+        let data = StackEntry::Bytes("a".to_string());
+        let mut v: Vec<StackEntry> = vec![data.clone()];
+        op_pushdata(&mut stack, Some(&data), MAX_SCRIPT_ITEM_SIZE as usize).unwrap();
+        assert_eq!(stack.main_stack, v);
+        /// op_pushdata([], "a"*521) -> fail
+        let mut stack = Stack::new();
+        let data = StackEntry::Bytes("a".repeat(MAX_SCRIPT_ITEM_SIZE as usize + 1));
+        let b = op_pushdata(&mut stack, Some(&data), MAX_SCRIPT_ITEM_SIZE as usize);
+        assert_eq!(b, Err(ScriptError::ItemSize));
+        /// op_pushdata([], OP_1) -> fail
+        let mut stack = Stack::new();
+        let data = StackEntry::Op(OpCodes::OP_1);
+        let b = op_pushdata(&mut stack, Some(&data), MAX_SCRIPT_ITEM_SIZE as usize);
+        assert_eq!(b, Err(ScriptError::ItemType));
+        /// op_pushdata([]) -> fail
+        let mut stack = Stack::new();
+        let b = op_pushdata(&mut stack, None, MAX_SCRIPT_ITEM_SIZE as usize);
+        assert_eq!(b, Err(ScriptError::NumItems))
+    }
+
     #[test]
     /// Test OP_1
     fn test_1() {
         /// op_1([]) -> [1]
         let mut stack = Stack::new();
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(1)];
-        op_1(&mut stack);
+        op_1(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v)
     }
 
@@ -280,7 +1092,7 @@ mod tests {
         /// op_2([]) -> [2]
         let mut stack = Stack::new();
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(2)];
-        op_2(&mut stack);
+        op_2(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v)
     }
 
@@ -290,7 +1102,7 @@ mod tests {
         /// op_3([]) -> [3]
         let mut stack = Stack::new();
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(3)];
-        op_3(&mut stack);
+        op_3(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v)
     }
 
@@ -300,7 +1112,7 @@ mod tests {
         /// op_4([]) -> [4]
         let mut stack = Stack::new();
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(4)];
-        op_4(&mut stack);
+        op_4(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v)
     }
 
@@ -310,7 +1122,7 @@ mod tests {
         /// op_5([]) -> [5]
         let mut stack = Stack::new();
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(5)];
-        op_5(&mut stack);
+        op_5(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v)
     }
 
@@ -320,7 +1132,7 @@ mod tests {
         /// op_6([]) -> [6]
         let mut stack = Stack::new();
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(6)];
-        op_6(&mut stack);
+        op_6(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v)
     }
 
@@ -330,7 +1142,7 @@ mod tests {
         /// op_7([]) -> [7]
         let mut stack = Stack::new();
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(7)];
-        op_7(&mut stack);
+        op_7(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v)
     }
 
@@ -340,7 +1152,7 @@ mod tests {
         /// op_8([]) -> [8]
         let mut stack = Stack::new();
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(8)];
-        op_8(&mut stack);
+        op_8(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v)
     }
 
@@ -350,7 +1162,7 @@ mod tests {
         /// op_9([]) -> [9]
         let mut stack = Stack::new();
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(9)];
-        op_9(&mut stack);
+        op_9(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v)
     }
 
@@ -360,7 +1172,7 @@ mod tests {
         /// op_10([]) -> [10]
         let mut stack = Stack::new();
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(10)];
-        op_10(&mut stack);
+        op_10(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v)
     }
 
@@ -370,7 +1182,7 @@ mod tests {
         /// op_11([]) -> [11]
         let mut stack = Stack::new();
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(11)];
-        op_11(&mut stack);
+        op_11(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v)
     }
 
@@ -380,7 +1192,7 @@ mod tests {
         /// op_12([]) -> [12]
         let mut stack = Stack::new();
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(12)];
-        op_12(&mut stack);
+        op_12(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v)
     }
 
@@ -390,7 +1202,7 @@ mod tests {
         /// op_13([]) -> [13]
         let mut stack = Stack::new();
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(13)];
-        op_13(&mut stack);
+        op_13(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v)
     }
 
@@ -400,7 +1212,7 @@ mod tests {
         /// op_14([]) -> [14]
         let mut stack = Stack::new();
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(14)];
-        op_14(&mut stack);
+        op_14(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v)
     }
 
@@ -410,7 +1222,7 @@ mod tests {
         /// op_15([]) -> [15]
         let mut stack = Stack::new();
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(15)];
-        op_15(&mut stack);
+        op_15(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v)
     }
 
@@ -420,7 +1232,7 @@ mod tests {
         /// op_16([]) -> [16]
         let mut stack = Stack::new();
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(16)];
-        op_16(&mut stack);
+        op_16(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v)
     }
 
@@ -433,7 +1245,7 @@ mod tests {
         let mut stack = Stack::new();
         stack.push(StackEntry::Num(1));
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(1)];
-        op_nop(&mut stack);
+        op_nop(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v)
     }
 
@@ -445,7 +1257,7 @@ mod tests {
         stack.push(StackEntry::Num(1));
         let mut cond_stack = ConditionStack::new();
         let mut v: Vec<StackEntry> = vec![];
-        op_if(&mut stack, &mut cond_stack);
+        op_if(&mut stack, &mut cond_stack).unwrap();
         assert_eq!(stack.main_stack, v);
         assert_eq!(cond_stack.size, 1);
         assert_eq!(cond_stack.first_false_pos, None);
@@ -454,7 +1266,7 @@ mod tests {
         stack.push(StackEntry::Num(0));
         let mut cond_stack = ConditionStack::new();
         let mut v: Vec<StackEntry> = vec![];
-        op_if(&mut stack, &mut cond_stack);
+        op_if(&mut stack, &mut cond_stack).unwrap();
         assert_eq!(stack.main_stack, v);
         assert_eq!(cond_stack.size, 1);
         assert_eq!(cond_stack.first_false_pos, Some(0));
@@ -465,7 +1277,7 @@ mod tests {
         cond_stack.size = 1;
         cond_stack.first_false_pos = Some(0);
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(1)];
-        op_if(&mut stack, &mut cond_stack);
+        op_if(&mut stack, &mut cond_stack).unwrap();
         assert_eq!(stack.main_stack, v);
         assert_eq!(cond_stack.size, 2);
         assert_eq!(cond_stack.first_false_pos, Some(0));
@@ -474,12 +1286,12 @@ mod tests {
         stack.push(StackEntry::Bytes(String::new()));
         let mut cond_stack = ConditionStack::new();
         let b = op_if(&mut stack, &mut cond_stack);
-        assert!(!b);
+        assert_eq!(b, Err(ScriptError::ItemType));
         /// error num items
         let mut stack = Stack::new();
         let mut cond_stack = ConditionStack::new();
         let b = op_if(&mut stack, &mut cond_stack);
-        assert!(!b)
+        assert_eq!(b, Err(ScriptError::NumItems))
     }
 
     #[test]
@@ -490,7 +1302,7 @@ mod tests {
         stack.push(StackEntry::Num(0));
         let mut cond_stack = ConditionStack::new();
         let mut v: Vec<StackEntry> = vec![];
-        op_notif(&mut stack, &mut cond_stack);
+        op_notif(&mut stack, &mut cond_stack).unwrap();
         assert_eq!(stack.main_stack, v);
         assert_eq!(cond_stack.size, 1);
         assert_eq!(cond_stack.first_false_pos, None);
@@ -499,7 +1311,7 @@ mod tests {
         stack.push(StackEntry::Num(1));
         let mut cond_stack = ConditionStack::new();
         let mut v: Vec<StackEntry> = vec![];
-        op_notif(&mut stack, &mut cond_stack);
+        op_notif(&mut stack, &mut cond_stack).unwrap();
         assert_eq!(stack.main_stack, v);
         assert_eq!(cond_stack.size, 1);
         assert_eq!(cond_stack.first_false_pos, Some(0));
@@ -510,7 +1322,7 @@ mod tests {
         cond_stack.size = 1;
         cond_stack.first_false_pos = Some(0);
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(0)];
-        op_notif(&mut stack, &mut cond_stack);
+        op_notif(&mut stack, &mut cond_stack).unwrap();
         assert_eq!(stack.main_stack, v);
         assert_eq!(cond_stack.size, 2);
         assert_eq!(cond_stack.first_false_pos, Some(0));
@@ -519,12 +1331,12 @@ mod tests {
         stack.push(StackEntry::Bytes(String::new()));
         let mut cond_stack = ConditionStack::new();
         let b = op_notif(&mut stack, &mut cond_stack);
-        assert!(!b);
+        assert_eq!(b, Err(ScriptError::ItemType));
         /// error num items
         let mut stack = Stack::new();
         let mut cond_stack = ConditionStack::new();
         let b = op_notif(&mut stack, &mut cond_stack);
-        assert!(!b)
+        assert_eq!(b, Err(ScriptError::NumItems))
     }
 
     #[test]
@@ -534,27 +1346,27 @@ mod tests {
         let mut cond_stack = ConditionStack::new();
         cond_stack.size = 1;
         cond_stack.first_false_pos = None;
-        op_else(&mut cond_stack);
+        op_else(&mut cond_stack).unwrap();
         assert_eq!(cond_stack.size, 1);
         assert_eq!(cond_stack.first_false_pos, Some(0));
         /// op_else({1,0}) -> {1,None}
         let mut cond_stack = ConditionStack::new();
         cond_stack.size = 1;
         cond_stack.first_false_pos = Some(0);
-        op_else(&mut cond_stack);
+        op_else(&mut cond_stack).unwrap();
         assert_eq!(cond_stack.size, 1);
         assert_eq!(cond_stack.first_false_pos, None);
         /// op_else({2,0}) -> {2,0}
         let mut cond_stack = ConditionStack::new();
         cond_stack.size = 2;
         cond_stack.first_false_pos = Some(0);
-        op_else(&mut cond_stack);
+        op_else(&mut cond_stack).unwrap();
         assert_eq!(cond_stack.size, 2);
         assert_eq!(cond_stack.first_false_pos, Some(0));
         /// empty condition stack
         let mut cond_stack = ConditionStack::new();
         let b = op_else(&mut cond_stack);
-        assert!(!b)
+        assert_eq!(b, Err(ScriptError::EmptyCondition))
     }
 
     #[test]
@@ -564,27 +1376,27 @@ mod tests {
         let mut cond_stack = ConditionStack::new();
         cond_stack.size = 1;
         cond_stack.first_false_pos = None;
-        op_endif(&mut cond_stack);
+        op_endif(&mut cond_stack).unwrap();
         assert_eq!(cond_stack.size, 0);
         assert_eq!(cond_stack.first_false_pos, None);
         /// op_endif({1,0}) -> {0,None}
         let mut cond_stack = ConditionStack::new();
         cond_stack.size = 1;
         cond_stack.first_false_pos = Some(0);
-        op_endif(&mut cond_stack);
+        op_endif(&mut cond_stack).unwrap();
         assert_eq!(cond_stack.size, 0);
         assert_eq!(cond_stack.first_false_pos, None);
         /// op_endif({2,0}) -> {1,0}
         let mut cond_stack = ConditionStack::new();
         cond_stack.size = 2;
         cond_stack.first_false_pos = Some(0);
-        op_endif(&mut cond_stack);
+        op_endif(&mut cond_stack).unwrap();
         assert_eq!(cond_stack.size, 1);
         assert_eq!(cond_stack.first_false_pos, Some(0));
         /// empty condition stack
         let mut cond_stack = ConditionStack::new();
         let b = op_endif(&mut cond_stack);
-        assert!(!b)
+        assert_eq!(b, Err(ScriptError::EmptyCondition))
     }
 
     #[test]
@@ -594,17 +1406,27 @@ mod tests {
         let mut stack = Stack::new();
         stack.push(StackEntry::Num(1));
         let mut v: Vec<StackEntry> = vec![];
-        op_verify(&mut stack);
+        op_verify(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_verify([0]) -> fail
         let mut stack = Stack::new();
         stack.push(StackEntry::Num(0));
         let b = op_verify(&mut stack);
-        assert!(!b);
+        assert_eq!(b, Err(ScriptError::Verify));
+        /// op_verify([-1]) -> []
+        let mut stack = Stack::new();
+        stack.push(StackEntry::SignedNum(-1));
+        op_verify(&mut stack).unwrap();
+        assert_eq!(stack.main_stack, v);
+        /// op_verify([+0]) -> fail
+        let mut stack = Stack::new();
+        stack.push(StackEntry::SignedNum(0));
+        let b = op_verify(&mut stack);
+        assert_eq!(b, Err(ScriptError::Verify));
         /// op_verify([]) -> fail
         let mut stack = Stack::new();
         let b = op_verify(&mut stack);
-        assert!(!b)
+        assert_eq!(b, Err(ScriptError::NumItems))
     }
 
     #[test]
@@ -614,11 +1436,11 @@ mod tests {
         let mut stack = Stack::new();
         stack.push(StackEntry::Num(1));
         let b = op_burn(&mut stack);
-        assert!(!b);
+        assert_eq!(b, Err(ScriptError::Burn));
         /// op_burn([]) -> fail
         let mut stack = Stack::new();
         let b = op_burn(&mut stack);
-        assert!(!b)
+        assert_eq!(b, Err(ScriptError::Burn))
     }
 
     /*---- STACK OPS ----*/
@@ -631,13 +1453,13 @@ mod tests {
         stack.push(StackEntry::Num(1));
         let mut v1: Vec<StackEntry> = vec![];
         let mut v2: Vec<StackEntry> = vec![StackEntry::Num(1)];
-        op_toaltstack(&mut stack);
+        op_toaltstack(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v1);
         assert_eq!(stack.alt_stack, v2);
         /// op_toaltstack([], []) -> fail
         let mut stack = Stack::new();
         let b = op_toaltstack(&mut stack);
-        assert!(!b)
+        assert_eq!(b, Err(ScriptError::NumItems))
     }
 
     #[test]
@@ -648,13 +1470,13 @@ mod tests {
         stack.alt_stack.push(StackEntry::Num(1));
         let mut v1: Vec<StackEntry> = vec![StackEntry::Num(1)];
         let mut v2: Vec<StackEntry> = vec![];
-        op_fromaltstack(&mut stack);
+        op_fromaltstack(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v1);
         assert_eq!(stack.alt_stack, v2);
         /// op_fromaltstack([], []) -> fail
         let mut stack = Stack::new();
         let b = op_fromaltstack(&mut stack);
-        assert!(!b)
+        assert_eq!(b, Err(ScriptError::NumItems))
     }
 
     #[test]
@@ -666,13 +1488,13 @@ mod tests {
             stack.push(StackEntry::Num(i));
         }
         let mut v: Vec<StackEntry> = vec![];
-        op_2drop(&mut stack);
+        op_2drop(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_2drop([1]) -> fail
         let mut stack = Stack::new();
         stack.push(StackEntry::Num(1));
         let b = op_2drop(&mut stack);
-        assert!(!b)
+        assert_eq!(b, Err(ScriptError::NumItems))
     }
 
     #[test]
@@ -690,13 +1512,13 @@ mod tests {
         for i in 1..=2 {
             v.push(StackEntry::Num(i));
         }
-        op_2dup(&mut stack);
+        op_2dup(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_2dup([1]) -> fail
         let mut stack = Stack::new();
         stack.push(StackEntry::Num(1));
         let b = op_2dup(&mut stack);
-        assert!(!b)
+        assert_eq!(b, Err(ScriptError::NumItems))
     }
 
     #[test]
@@ -714,7 +1536,7 @@ mod tests {
         for i in 1..=3 {
             v.push(StackEntry::Num(i));
         }
-        op_3dup(&mut stack);
+        op_3dup(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_3dup([1,2]) -> fail
         let mut stack = Stack::new();
@@ -722,7 +1544,7 @@ mod tests {
             stack.push(StackEntry::Num(i));
         }
         let b = op_3dup(&mut stack);
-        assert!(!b)
+        assert_eq!(b, Err(ScriptError::NumItems))
     }
 
     #[test]
@@ -740,7 +1562,7 @@ mod tests {
         for i in 1..=2 {
             v.push(StackEntry::Num(i));
         }
-        op_2over(&mut stack);
+        op_2over(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_2over([1,2,3]) -> fail
         let mut stack = Stack::new();
@@ -748,7 +1570,7 @@ mod tests {
             stack.push(StackEntry::Num(i));
         }
         let b = op_2over(&mut stack);
-        assert!(!b)
+        assert_eq!(b, Err(ScriptError::NumItems))
     }
 
     #[test]
@@ -766,7 +1588,7 @@ mod tests {
         for i in 1..=2 {
             v.push(StackEntry::Num(i));
         }
-        op_2rot(&mut stack);
+        op_2rot(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_2rot([1,2,3,4,5]) -> fail
         let mut stack = Stack::new();
@@ -774,7 +1596,7 @@ mod tests {
             stack.push(StackEntry::Num(i));
         }
         let b = op_2rot(&mut stack);
-        assert!(!b)
+        assert_eq!(b, Err(ScriptError::NumItems))
     }
 
     #[test]
@@ -792,7 +1614,7 @@ mod tests {
         for i in 1..=2 {
             v.push(StackEntry::Num(i));
         }
-        op_2swap(&mut stack);
+        op_2swap(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_2swap([1,2,3]) -> fail
         let mut stack = Stack::new();
@@ -800,7 +1622,7 @@ mod tests {
             stack.push(StackEntry::Num(i));
         }
         let b = op_2swap(&mut stack);
-        assert!(!b)
+        assert_eq!(b, Err(ScriptError::NumItems))
     }
 
     #[test]
@@ -813,18 +1635,24 @@ mod tests {
         for i in 1..=2 {
             v.push(StackEntry::Num(1));
         }
-        op_ifdup(&mut stack);
+        op_ifdup(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_ifdup([0]) -> [0]
         let mut stack = Stack::new();
         stack.push(StackEntry::Num(0));
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(0)];
-        op_ifdup(&mut stack);
+        op_ifdup(&mut stack).unwrap();
+        assert_eq!(stack.main_stack, v);
+        /// op_ifdup([+0]) -> [+0]
+        let mut stack = Stack::new();
+        stack.push(StackEntry::SignedNum(0));
+        let mut v: Vec<StackEntry> = vec![StackEntry::SignedNum(0)];
+        op_ifdup(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_ifdup([]) -> fail
         let mut stack = Stack::new();
         let b = op_ifdup(&mut stack);
-        assert!(!b)
+        assert_eq!(b, Err(ScriptError::NumItems))
     }
 
     #[test]
@@ -840,12 +1668,12 @@ mod tests {
             v.push(StackEntry::Num(1));
         }
         v.push(StackEntry::Num(4));
-        op_depth(&mut stack);
+        op_depth(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_depth([]) -> [0]
         let mut stack = Stack::new();
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(0)];
-        op_depth(&mut stack);
+        op_depth(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v)
     }
 
@@ -854,14 +1682,14 @@ mod tests {
     fn test_drop() {
         /// op_drop([1]) -> []
         let mut stack = Stack::new();
-        let mut v: Vec<StackEntry> = vec![StackEntry::Num(1)];
+        stack.push(StackEntry::Num(1));
         let mut v: Vec<StackEntry> = vec![];
-        op_drop(&mut stack);
+        op_drop(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_drop([]) -> fail
         let mut stack = Stack::new();
         let b = op_drop(&mut stack);
-        assert!(!b)
+        assert_eq!(b, Err(ScriptError::NumItems))
     }
 
     #[test]
@@ -874,12 +1702,12 @@ mod tests {
         for i in 1..=2 {
             v.push(StackEntry::Num(1));
         }
-        op_dup(&mut stack);
+        op_dup(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_dup([]) -> fail
         let mut stack = Stack::new();
         let b = op_dup(&mut stack);
-        assert!(!b)
+        assert_eq!(b, Err(ScriptError::NumItems))
     }
 
     #[test]
@@ -891,13 +1719,13 @@ mod tests {
             stack.push(StackEntry::Num(i));
         }
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(2)];
-        op_nip(&mut stack);
+        op_nip(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_nip([1]) -> fail
         let mut stack = Stack::new();
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(1)];
         let b = op_nip(&mut stack);
-        assert!(!b)
+        assert_eq!(b, Err(ScriptError::NumItems))
     }
 
     #[test]
@@ -913,13 +1741,13 @@ mod tests {
             v.push(StackEntry::Num(i));
         }
         v.push(StackEntry::Num(1));
-        op_over(&mut stack);
+        op_over(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_over([1]) -> fail
         let mut stack = Stack::new();
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(1)];
         let b = op_over(&mut stack);
-        assert!(!b)
+        assert_eq!(b, Err(ScriptError::NumItems))
     }
 
     #[test]
@@ -936,7 +1764,7 @@ mod tests {
             v.push(StackEntry::Num(i));
         }
         v.push(StackEntry::Num(1));
-        op_pick(&mut stack);
+        op_pick(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_pick([1,2,3,4,0]) -> [1,2,3,4,4]
         let mut stack = Stack::new();
@@ -949,26 +1777,46 @@ mod tests {
             v.push(StackEntry::Num(i));
         }
         v.push(StackEntry::Num(4));
-        op_pick(&mut stack);
+        op_pick(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_pick([1]) -> fail
         let mut stack = Stack::new();
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(1)];
         let b = op_pick(&mut stack);
-        assert!(!b);
+        assert_eq!(b, Err(ScriptError::NumItems));
         /// op_pick([1,"hello"]) -> fail
         let mut stack = Stack::new();
         stack.push(StackEntry::Num(1));
         stack.push(StackEntry::Bytes("hello".to_string()));
         let b = op_pick(&mut stack);
-        assert!(!b);
+        assert_eq!(b, Err(ScriptError::ItemType));
         /// op_pick([1,1]) -> fail
         let mut stack = Stack::new();
         for i in 1..=2 {
             stack.push(StackEntry::Num(i));
         }
         let b = op_pick(&mut stack);
-        assert!(!b)
+        assert_eq!(b, Err(ScriptError::ItemIndex));
+        /// op_pick([1,2,3,4,4]) -> fail
+        let mut stack = Stack::new();
+        for i in 1..=4 {
+            stack.push(StackEntry::Num(i));
+        }
+        stack.push(StackEntry::Num(4));
+        let b = op_pick(&mut stack);
+        assert_eq!(b, Err(ScriptError::ItemIndex));
+        /// op_pick([1,MAX_STACK_SIZE+1]) -> fail
+        let mut stack = Stack::new();
+        stack.push(StackEntry::Num(1));
+        stack.push(StackEntry::Num(MAX_STACK_SIZE as usize + 1));
+        let b = op_pick(&mut stack);
+        assert_eq!(b, Err(ScriptError::ItemIndex));
+        /// op_pick([1,usize::MAX]) -> fail
+        let mut stack = Stack::new();
+        stack.push(StackEntry::Num(1));
+        stack.push(StackEntry::Num(usize::MAX));
+        let b = op_pick(&mut stack);
+        assert_eq!(b, Err(ScriptError::ItemIndex))
     }
 
     #[test]
@@ -985,7 +1833,7 @@ mod tests {
             v.push(StackEntry::Num(i));
         }
         v.push(StackEntry::Num(1));
-        op_roll(&mut stack);
+        op_roll(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_roll([1,2,3,4,0]) -> [1,2,3,4]
         let mut stack = Stack::new();
@@ -997,26 +1845,46 @@ mod tests {
         for i in 1..=4 {
             v.push(StackEntry::Num(i));
         }
-        op_roll(&mut stack);
+        op_roll(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_roll([1]) -> fail
         let mut stack = Stack::new();
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(1)];
         let b = op_roll(&mut stack);
-        assert!(!b);
+        assert_eq!(b, Err(ScriptError::NumItems));
         /// op_roll([1,"hello"]) -> fail
         let mut stack = Stack::new();
         stack.push(StackEntry::Num(1));
         stack.push(StackEntry::Bytes("hello".to_string()));
         let b = op_roll(&mut stack);
-        assert!(!b);
+        assert_eq!(b, Err(ScriptError::ItemType));
         /// op_roll([1,1]) -> fail
         let mut stack = Stack::new();
         for i in 1..=2 {
             stack.push(StackEntry::Num(i));
         }
         let b = op_roll(&mut stack);
-        assert!(!b)
+        assert_eq!(b, Err(ScriptError::ItemIndex));
+        /// op_roll([1,2,3,4,4]) -> fail
+        let mut stack = Stack::new();
+        for i in 1..=4 {
+            stack.push(StackEntry::Num(i));
+        }
+        stack.push(StackEntry::Num(4));
+        let b = op_roll(&mut stack);
+        assert_eq!(b, Err(ScriptError::ItemIndex));
+        /// op_roll([1,MAX_STACK_SIZE+1]) -> fail
+        let mut stack = Stack::new();
+        stack.push(StackEntry::Num(1));
+        stack.push(StackEntry::Num(MAX_STACK_SIZE as usize + 1));
+        let b = op_roll(&mut stack);
+        assert_eq!(b, Err(ScriptError::ItemIndex));
+        /// op_roll([1,usize::MAX]) -> fail
+        let mut stack = Stack::new();
+        stack.push(StackEntry::Num(1));
+        stack.push(StackEntry::Num(usize::MAX));
+        let b = op_roll(&mut stack);
+        assert_eq!(b, Err(ScriptError::ItemIndex))
     }
 
     #[test]
@@ -1032,7 +1900,7 @@ mod tests {
             v.push(StackEntry::Num(i));
         }
         v.push(StackEntry::Num(1));
-        op_rot(&mut stack);
+        op_rot(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_rot([1,2]) -> fail
         let mut stack = Stack::new();
@@ -1040,7 +1908,7 @@ mod tests {
             stack.push(StackEntry::Num(i));
         }
         let b = op_rot(&mut stack);
-        assert!(!b)
+        assert_eq!(b, Err(ScriptError::NumItems))
     }
 
     #[test]
@@ -1052,13 +1920,13 @@ mod tests {
             stack.push(StackEntry::Num(i));
         }
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(2), StackEntry::Num(1)];
-        op_swap(&mut stack);
+        op_swap(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_swap([1]) -> fail
         let mut stack = Stack::new();
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(1)];
         let b = op_swap(&mut stack);
-        assert!(!b)
+        assert_eq!(b, Err(ScriptError::NumItems))
     }
 
     #[test]
@@ -1073,13 +1941,13 @@ mod tests {
         for i in 1..=2 {
             v.push(StackEntry::Num(i));
         }
-        op_tuck(&mut stack);
+        op_tuck(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_tuck([1]) -> fail
         let mut stack = Stack::new();
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(1)];
         let b = op_tuck(&mut stack);
-        assert!(!b)
+        assert_eq!(b, Err(ScriptError::NumItems))
     }
 
     /*---- SPLICE OPS ----*/
@@ -1092,14 +1960,14 @@ mod tests {
         stack.push(StackEntry::Bytes("hello".to_string()));
         stack.push(StackEntry::Bytes("world".to_string()));
         let mut v: Vec<StackEntry> = vec![StackEntry::Bytes("helloworld".to_string())];
-        op_cat(&mut stack);
+        op_cat(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_cat(["hello",""]) -> ["hello"]
         let mut stack = Stack::new();
         stack.push(StackEntry::Bytes("hello".to_string()));
         stack.push(StackEntry::Bytes("".to_string()));
         let mut v: Vec<StackEntry> = vec![StackEntry::Bytes("hello".to_string())];
-        op_cat(&mut stack);
+        op_cat(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_cat(["a","a"*MAX_SCRIPT_ITEM_SIZE]) -> fail
         let mut stack = Stack::new();
@@ -1110,18 +1978,18 @@ mod tests {
         }
         stack.push(StackEntry::Bytes(s.to_string()));
         let b = op_cat(&mut stack);
-        assert!(!b);
+        assert_eq!(b, Err(ScriptError::ItemSize));
         /// op_cat(["hello"]) -> fail
         let mut stack = Stack::new();
         stack.push(StackEntry::Bytes("hello".to_string()));
         let b = op_cat(&mut stack);
-        assert!(!b);
+        assert_eq!(b, Err(ScriptError::NumItems));
         /// op_cat(["hello", 1]) -> fail
         let mut stack = Stack::new();
         stack.push(StackEntry::Bytes("hello".to_string()));
         stack.push(StackEntry::Num(1));
         let b = op_cat(&mut stack);
-        assert!(!b)
+        assert_eq!(b, Err(ScriptError::ItemType))
     }
 
     #[test]
@@ -1134,7 +2002,7 @@ mod tests {
             stack.push(StackEntry::Num(i));
         }
         let mut v: Vec<StackEntry> = vec![StackEntry::Bytes("el".to_string())];
-        op_substr(&mut stack);
+        op_substr(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_substr(["hello",0,0]) -> [""]
         let mut stack = Stack::new();
@@ -1143,7 +2011,7 @@ mod tests {
             stack.push(StackEntry::Num(0));
         }
         let mut v: Vec<StackEntry> = vec![StackEntry::Bytes("".to_string())];
-        op_substr(&mut stack);
+        op_substr(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_substr(["hello",0,5]) -> ["hello"]
         let mut stack = Stack::new();
@@ -1151,7 +2019,7 @@ mod tests {
         stack.push(StackEntry::Num(0));
         stack.push(StackEntry::Num(5));
         let mut v: Vec<StackEntry> = vec![StackEntry::Bytes("hello".to_string())];
-        op_substr(&mut stack);
+        op_substr(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_substr(["hello",5,0]) -> fail
         let mut stack = Stack::new();
@@ -1159,34 +2027,34 @@ mod tests {
         stack.push(StackEntry::Num(5));
         stack.push(StackEntry::Num(0));
         let b = op_substr(&mut stack);
-        assert!(!b);
+        assert_eq!(b, Err(ScriptError::ItemIndex));
         /// op_substr(["hello",1,5]) -> fail
         let mut stack = Stack::new();
         stack.push(StackEntry::Bytes("hello".to_string()));
         stack.push(StackEntry::Num(1));
         stack.push(StackEntry::Num(5));
         let b = op_substr(&mut stack);
-        assert!(!b);
+        assert_eq!(b, Err(ScriptError::ItemIndex));
         /// op_substr(["hello",1]) -> fail
         let mut stack = Stack::new();
         stack.push(StackEntry::Bytes("hello".to_string()));
         stack.push(StackEntry::Num(1));
         let b = op_substr(&mut stack);
-        assert!(!b);
+        assert_eq!(b, Err(ScriptError::ItemType));
         /// op_substr(["hello",1,usize::MAX]) -> fail
         let mut stack = Stack::new();
         stack.push(StackEntry::Bytes("hello".to_string()));
         stack.push(StackEntry::Num(1));
         stack.push(StackEntry::Num(usize::MAX));
         let b = op_substr(&mut stack);
-        assert!(!b);
+        assert_eq!(b, Err(ScriptError::ItemIndex));
         /// op_substr(["hello",1,""]) -> fail
         let mut stack = Stack::new();
         stack.push(StackEntry::Bytes("hello".to_string()));
         stack.push(StackEntry::Num(1));
         stack.push(StackEntry::Bytes("".to_string()));
         let b = op_substr(&mut stack);
-        assert!(!b)
+        assert_eq!(b, Err(ScriptError::ItemType))
     }
 
     #[test]
@@ -1197,33 +2065,33 @@ mod tests {
         stack.push(StackEntry::Bytes("hello".to_string()));
         stack.push(StackEntry::Num(2));
         let mut v: Vec<StackEntry> = vec![StackEntry::Bytes("he".to_string())];
-        op_left(&mut stack);
+        op_left(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_left(["hello",0]) -> [""]
         let mut stack = Stack::new();
         stack.push(StackEntry::Bytes("hello".to_string()));
         stack.push(StackEntry::Num(0));
         let mut v: Vec<StackEntry> = vec![StackEntry::Bytes("".to_string())];
-        op_left(&mut stack);
+        op_left(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_left(["hello",5]) -> ["hello"]
         let mut stack = Stack::new();
         stack.push(StackEntry::Bytes("hello".to_string()));
         stack.push(StackEntry::Num(5));
         let mut v: Vec<StackEntry> = vec![StackEntry::Bytes("hello".to_string())];
-        op_left(&mut stack);
+        op_left(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_left(["hello",""]) -> fail
         let mut stack = Stack::new();
         stack.push(StackEntry::Bytes("hello".to_string()));
         stack.push(StackEntry::Bytes("".to_string()));
         let b = op_left(&mut stack);
-        assert!(!b);
+        assert_eq!(b, Err(ScriptError::ItemType));
         /// op_left(["hello"]) -> fail
         let mut stack = Stack::new();
         stack.push(StackEntry::Bytes("hello".to_string()));
         let b = op_left(&mut stack);
-        assert!(!b)
+        assert_eq!(b, Err(ScriptError::ItemType))
     }
 
     #[test]
@@ -1234,33 +2102,33 @@ mod tests {
         stack.push(StackEntry::Bytes("hello".to_string()));
         stack.push(StackEntry::Num(0));
         let mut v: Vec<StackEntry> = vec![StackEntry::Bytes("hello".to_string())];
-        op_right(&mut stack);
+        op_right(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_right(["hello",2]) -> ["llo"]
         let mut stack = Stack::new();
         stack.push(StackEntry::Bytes("hello".to_string()));
         stack.push(StackEntry::Num(2));
         let mut v: Vec<StackEntry> = vec![StackEntry::Bytes("llo".to_string())];
-        op_right(&mut stack);
+        op_right(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_right(["hello",5]) -> [""]
         let mut stack = Stack::new();
         stack.push(StackEntry::Bytes("hello".to_string()));
         stack.push(StackEntry::Num(5));
         let mut v: Vec<StackEntry> = vec![StackEntry::Bytes("".to_string())];
-        op_right(&mut stack);
+        op_right(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_right(["hello",""]) -> fail
         let mut stack = Stack::new();
         stack.push(StackEntry::Bytes("hello".to_string()));
         stack.push(StackEntry::Bytes("".to_string()));
         let b = op_right(&mut stack);
-        assert!(!b);
+        assert_eq!(b, Err(ScriptError::ItemType));
         /// op_right(["hello"]) -> fail
         let mut stack = Stack::new();
         stack.push(StackEntry::Bytes("hello".to_string()));
         let b = op_right(&mut stack);
-        assert!(!b)
+        assert_eq!(b, Err(ScriptError::ItemType))
     }
 
     #[test]
@@ -1271,23 +2139,186 @@ mod tests {
         stack.push(StackEntry::Bytes("hello".to_string()));
         let mut v: Vec<StackEntry> =
             vec![StackEntry::Bytes("hello".to_string()), StackEntry::Num(5)];
-        op_size(&mut stack);
+        op_size(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_size([""]) -> ["",0]
         let mut stack = Stack::new();
         stack.push(StackEntry::Bytes("".to_string()));
         let mut v: Vec<StackEntry> = vec![StackEntry::Bytes("".to_string()), StackEntry::Num(0)];
-        op_size(&mut stack);
+        op_size(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_size([1]) -> fail
         let mut stack = Stack::new();
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(1)];
         let b = op_size(&mut stack);
-        assert!(!b);
+        assert_eq!(b, Err(ScriptError::NumItems));
         /// op_size([]) -> fail
         let mut stack = Stack::new();
         let b = op_size(&mut stack);
-        assert!(!b)
+        assert_eq!(b, Err(ScriptError::NumItems))
+    }
+
+    #[test]
+    /// Test OP_REVERSE
+    fn test_reverse() {
+        /// op_reverse(["0a1b2c"]) -> ["2c1b0a"]
+        let mut stack = Stack::new();
+        stack.push(StackEntry::Bytes("0a1b2c".to_string()));
+        let mut v: Vec<StackEntry> = vec![StackEntry::Bytes("2c1b0a".to_string())];
+        op_reverse(&mut stack).unwrap();
+        assert_eq!(stack.main_stack, v);
+        /// op_reverse(["hello"]) -> fail
+        let mut stack = Stack::new();
+        stack.push(StackEntry::Bytes("hello".to_string()));
+        let b = op_reverse(&mut stack);
+        assert_eq!(b, Err(ScriptError::ItemType));
+        /// op_reverse([""]) -> [""]
+        let mut stack = Stack::new();
+        stack.push(StackEntry::Bytes("".to_string()));
+        let mut v: Vec<StackEntry> = vec![StackEntry::Bytes("".to_string())];
+        op_reverse(&mut stack).unwrap();
+        assert_eq!(stack.main_stack, v);
+        /// op_reverse([1]) -> fail
+        let mut stack = Stack::new();
+        stack.push(StackEntry::Num(1));
+        let b = op_reverse(&mut stack);
+        assert_eq!(b, Err(ScriptError::ItemType));
+        /// op_reverse([]) -> fail
+        let mut stack = Stack::new();
+        let b = op_reverse(&mut stack);
+        assert_eq!(b, Err(ScriptError::NumItems))
+    }
+
+    #[test]
+    /// Test OP_SPLIT
+    fn test_split() {
+        /// op_split(["hello",2]) -> ["he","llo"]
+        let mut stack = Stack::new();
+        stack.push(StackEntry::Bytes("hello".to_string()));
+        stack.push(StackEntry::Num(2));
+        let mut v: Vec<StackEntry> = vec![
+            StackEntry::Bytes("he".to_string()),
+            StackEntry::Bytes("llo".to_string()),
+        ];
+        op_split(&mut stack).unwrap();
+        assert_eq!(stack.main_stack, v);
+        /// op_split(["hello",0]) -> ["","hello"]
+        let mut stack = Stack::new();
+        stack.push(StackEntry::Bytes("hello".to_string()));
+        stack.push(StackEntry::Num(0));
+        let mut v: Vec<StackEntry> = vec![
+            StackEntry::Bytes("".to_string()),
+            StackEntry::Bytes("hello".to_string()),
+        ];
+        op_split(&mut stack).unwrap();
+        assert_eq!(stack.main_stack, v);
+        /// op_split(["hello",5]) -> ["hello",""]
+        let mut stack = Stack::new();
+        stack.push(StackEntry::Bytes("hello".to_string()));
+        stack.push(StackEntry::Num(5));
+        let mut v: Vec<StackEntry> = vec![
+            StackEntry::Bytes("hello".to_string()),
+            StackEntry::Bytes("".to_string()),
+        ];
+        op_split(&mut stack).unwrap();
+        assert_eq!(stack.main_stack, v);
+        /// op_split(["hello",6]) -> fail
+        let mut stack = Stack::new();
+        stack.push(StackEntry::Bytes("hello".to_string()));
+        stack.push(StackEntry::Num(6));
+        let b = op_split(&mut stack);
+        assert_eq!(b, Err(ScriptError::ItemIndex));
+        /// op_split(["hello",""]) -> fail
+        let mut stack = Stack::new();
+        stack.push(StackEntry::Bytes("hello".to_string()));
+        stack.push(StackEntry::Bytes("".to_string()));
+        let b = op_split(&mut stack);
+        assert_eq!(b, Err(ScriptError::ItemType));
+        /// op_split([2]) -> fail
+        let mut stack = Stack::new();
+        stack.push(StackEntry::Num(2));
+        let b = op_split(&mut stack);
+        assert_eq!(b, Err(ScriptError::NumItems))
+    }
+
+    #[test]
+    /// Test OP_NUM2BIN
+    fn test_num2bin() {
+        /// op_num2bin([258,4]) -> ["02010000"]
+        let mut stack = Stack::new();
+        stack.push(StackEntry::Num(258));
+        stack.push(StackEntry::Num(4));
+        let mut v: Vec<StackEntry> = vec![StackEntry::Bytes("02010000".to_string())];
+        op_num2bin(&mut stack).unwrap();
+        assert_eq!(stack.main_stack, v);
+        /// op_num2bin([0,0]) -> [""]
+        let mut stack = Stack::new();
+        stack.push(StackEntry::Num(0));
+        stack.push(StackEntry::Num(0));
+        let mut v: Vec<StackEntry> = vec![StackEntry::Bytes("".to_string())];
+        op_num2bin(&mut stack).unwrap();
+        assert_eq!(stack.main_stack, v);
+        /// op_num2bin([256,1]) -> fail
+        let mut stack = Stack::new();
+        stack.push(StackEntry::Num(256));
+        stack.push(StackEntry::Num(1));
+        let b = op_num2bin(&mut stack);
+        assert_eq!(b, Err(ScriptError::ArithmeticOverflow));
+        /// op_num2bin([1,MAX_SCRIPT_ITEM_SIZE/2+1]) -> fail
+        let mut stack = Stack::new();
+        stack.push(StackEntry::Num(1));
+        stack.push(StackEntry::Num(MAX_SCRIPT_ITEM_SIZE as usize / 2 + 1));
+        let b = op_num2bin(&mut stack);
+        assert_eq!(b, Err(ScriptError::ItemSize));
+        /// op_num2bin(["hello",1]) -> fail
+        let mut stack = Stack::new();
+        stack.push(StackEntry::Bytes("hello".to_string()));
+        stack.push(StackEntry::Num(1));
+        let b = op_num2bin(&mut stack);
+        assert_eq!(b, Err(ScriptError::ItemType));
+        /// op_num2bin([1]) -> fail
+        let mut stack = Stack::new();
+        stack.push(StackEntry::Num(1));
+        let b = op_num2bin(&mut stack);
+        assert_eq!(b, Err(ScriptError::NumItems))
+    }
+
+    #[test]
+    /// Test OP_BIN2NUM
+    fn test_bin2num() {
+        /// op_bin2num(op_num2bin([n,w])) -> [n]
+        for (n, w) in [(0, 0), (1, 1), (255, 1), (258, 2), (usize::MAX, 8)] {
+            let mut stack = Stack::new();
+            stack.push(StackEntry::Num(n));
+            stack.push(StackEntry::Num(w));
+            op_num2bin(&mut stack).unwrap();
+            op_bin2num(&mut stack).unwrap();
+            assert_eq!(stack.main_stack, vec![StackEntry::Num(n)]);
+        }
+        /// op_bin2num(["0100"]) -> fail
+        let mut stack = Stack::new();
+        stack.push(StackEntry::Bytes("0100".to_string()));
+        let b = op_bin2num(&mut stack);
+        assert_eq!(b, Err(ScriptError::ItemType));
+        /// op_bin2num(["010000000000000001"]) -> fail
+        let mut stack = Stack::new();
+        stack.push(StackEntry::Bytes("010000000000000001".to_string()));
+        let b = op_bin2num(&mut stack);
+        assert_eq!(b, Err(ScriptError::ArithmeticOverflow));
+        /// op_bin2num(["hello"]) -> fail
+        let mut stack = Stack::new();
+        stack.push(StackEntry::Bytes("hello".to_string()));
+        let b = op_bin2num(&mut stack);
+        assert_eq!(b, Err(ScriptError::ItemType));
+        /// op_bin2num([1]) -> fail
+        let mut stack = Stack::new();
+        stack.push(StackEntry::Num(1));
+        let b = op_bin2num(&mut stack);
+        assert_eq!(b, Err(ScriptError::ItemType));
+        /// op_bin2num([]) -> fail
+        let mut stack = Stack::new();
+        let b = op_bin2num(&mut stack);
+        assert_eq!(b, Err(ScriptError::NumItems))
     }
 
     /*---- BITWISE LOGIC OPS ----*/
@@ -1299,12 +2330,12 @@ mod tests {
         let mut stack = Stack::new();
         stack.push(StackEntry::Num(0));
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(usize::MAX)];
-        op_invert(&mut stack);
+        op_invert(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_invert([]) -> fail
         let mut stack = Stack::new();
         let b = op_invert(&mut stack);
-        assert!(!b)
+        assert_eq!(b, Err(ScriptError::NumItems))
     }
 
     #[test]
@@ -1316,13 +2347,13 @@ mod tests {
             stack.push(StackEntry::Num(i));
         }
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(0)];
-        op_and(&mut stack);
+        op_and(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_and([1]) -> fail
         let mut stack = Stack::new();
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(1)];
         let b = op_and(&mut stack);
-        assert!(!b)
+        assert_eq!(b, Err(ScriptError::NumItems))
     }
 
     #[test]
@@ -1334,13 +2365,13 @@ mod tests {
             stack.push(StackEntry::Num(i));
         }
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(3)];
-        op_or(&mut stack);
+        op_or(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_or([1]) -> fail
         let mut stack = Stack::new();
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(1)];
         let b = op_or(&mut stack);
-        assert!(!b)
+        assert_eq!(b, Err(ScriptError::NumItems))
     }
 
     #[test]
@@ -1352,7 +2383,7 @@ mod tests {
             stack.push(StackEntry::Num(i));
         }
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(3)];
-        op_xor(&mut stack);
+        op_xor(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_xor([1,1]) -> [0]
         let mut stack = Stack::new();
@@ -1360,13 +2391,13 @@ mod tests {
             stack.push(StackEntry::Num(1));
         }
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(0)];
-        op_xor(&mut stack);
+        op_xor(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_xor([1]) -> fail
         let mut stack = Stack::new();
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(1)];
         let b = op_xor(&mut stack);
-        assert!(!b)
+        assert_eq!(b, Err(ScriptError::NumItems))
     }
 
     #[test]
@@ -1378,7 +2409,7 @@ mod tests {
             stack.push(StackEntry::Bytes("hello".to_string()));
         }
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(1)];
-        op_equal(&mut stack);
+        op_equal(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_equal([1,2]) -> [0]
         let mut stack = Stack::new();
@@ -1386,13 +2417,13 @@ mod tests {
             stack.push(StackEntry::Num(i));
         }
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(0)];
-        op_equal(&mut stack);
+        op_equal(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_equal([1]) -> fail
         let mut stack = Stack::new();
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(1)];
         let b = op_equal(&mut stack);
-        assert!(!b)
+        assert_eq!(b, Err(ScriptError::NumItems))
     }
 
     #[test]
@@ -1404,7 +2435,7 @@ mod tests {
             stack.push(StackEntry::Bytes("hello".to_string()));
         }
         let mut v: Vec<StackEntry> = vec![];
-        op_equalverify(&mut stack);
+        op_equalverify(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_equalverify([1,2]) -> fail
         let mut stack = Stack::new();
@@ -1412,12 +2443,12 @@ mod tests {
             stack.push(StackEntry::Num(i));
         }
         let b = op_equalverify(&mut stack);
-        assert!(!b);
+        assert_eq!(b, Err(ScriptError::NotEqualItems));
         /// op_equalverify([1]) -> fail
         let mut stack = Stack::new();
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(1)];
         let b = op_equalverify(&mut stack);
-        assert!(!b)
+        assert_eq!(b, Err(ScriptError::NumItems))
     }
 
     /*---- ARITHMETIC OPS ----*/
@@ -1429,17 +2460,17 @@ mod tests {
         let mut stack = Stack::new();
         stack.push(StackEntry::Num(1));
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(2)];
-        op_1add(&mut stack);
+        op_1add(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_1add([usize::MAX]) -> fail
         let mut stack = Stack::new();
         stack.push(StackEntry::Num(usize::MAX));
         let b = op_1add(&mut stack);
-        assert!(!b);
+        assert_eq!(b, Err(ScriptError::ArithmeticOverflow));
         /// op_1add([]) -> fail
         let mut stack = Stack::new();
         let b = op_1add(&mut stack);
-        assert!(!b)
+        assert_eq!(b, Err(ScriptError::NumItems))
     }
 
     #[test]
@@ -1449,17 +2480,17 @@ mod tests {
         let mut stack = Stack::new();
         stack.push(StackEntry::Num(1));
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(0)];
-        op_1sub(&mut stack);
+        op_1sub(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_1sub([0]) -> fail
         let mut stack = Stack::new();
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(0)];
         let b = op_1sub(&mut stack);
-        assert!(!b);
+        assert_eq!(b, Err(ScriptError::NumItems));
         /// op_1sub([]) -> fail
         let mut stack = Stack::new();
         let b = op_1sub(&mut stack);
-        assert!(!b)
+        assert_eq!(b, Err(ScriptError::NumItems))
     }
 
     #[test]
@@ -1469,17 +2500,17 @@ mod tests {
         let mut stack = Stack::new();
         stack.push(StackEntry::Num(1));
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(2)];
-        op_2mul(&mut stack);
+        op_2mul(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_2mul([usize::MAX]) -> fail
         let mut stack = Stack::new();
         stack.push(StackEntry::Num(usize::MAX));
         let b = op_2mul(&mut stack);
-        assert!(!b);
+        assert_eq!(b, Err(ScriptError::ArithmeticOverflow));
         /// op_2mul([]) -> fail
         let mut stack = Stack::new();
         let b = op_2mul(&mut stack);
-        assert!(!b)
+        assert_eq!(b, Err(ScriptError::NumItems))
     }
 
     #[test]
@@ -1489,12 +2520,12 @@ mod tests {
         let mut stack = Stack::new();
         stack.push(StackEntry::Num(1));
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(0)];
-        op_2div(&mut stack);
+        op_2div(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_2div([]) -> fail
         let mut stack = Stack::new();
         let b = op_2div(&mut stack);
-        assert!(!b)
+        assert_eq!(b, Err(ScriptError::NumItems))
     }
 
     #[test]
@@ -1504,18 +2535,18 @@ mod tests {
         let mut stack = Stack::new();
         stack.push(StackEntry::Num(0));
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(1)];
-        op_not(&mut stack);
+        op_not(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_not([1]) -> [0]
         let mut stack = Stack::new();
         stack.push(StackEntry::Num(1));
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(0)];
-        op_not(&mut stack);
+        op_not(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_not([]) -> fail
         let mut stack = Stack::new();
         let b = op_not(&mut stack);
-        assert!(!b)
+        assert_eq!(b, Err(ScriptError::NumItems))
     }
 
     #[test]
@@ -1525,18 +2556,18 @@ mod tests {
         let mut stack = Stack::new();
         stack.push(StackEntry::Num(1));
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(1)];
-        op_0notequal(&mut stack);
+        op_0notequal(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_0notequal([0]) -> [0]
         let mut stack = Stack::new();
         stack.push(StackEntry::Num(0));
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(0)];
-        op_0notequal(&mut stack);
+        op_0notequal(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_0notequal([]) -> fail
         let mut stack = Stack::new();
         let b = op_0notequal(&mut stack);
-        assert!(!b)
+        assert_eq!(b, Err(ScriptError::NumItems))
     }
 
     #[test]
@@ -1548,19 +2579,19 @@ mod tests {
             stack.push(StackEntry::Num(i));
         }
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(3)];
-        op_add(&mut stack);
+        op_add(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_add([1,usize::MAX]) -> fail
         let mut stack = Stack::new();
         stack.push(StackEntry::Num(1));
         stack.push(StackEntry::Num(usize::MAX));
         let b = op_add(&mut stack);
-        assert!(!b);
+        assert_eq!(b, Err(ScriptError::ArithmeticOverflow));
         /// op_add([1]) -> fail
         let mut stack = Stack::new();
         stack.push(StackEntry::Num(1));
         let b = op_add(&mut stack);
-        assert!(!b)
+        assert_eq!(b, Err(ScriptError::NumItems))
     }
 
     #[test]
@@ -1571,19 +2602,84 @@ mod tests {
         stack.push(StackEntry::Num(1));
         stack.push(StackEntry::Num(0));
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(1)];
-        op_sub(&mut stack);
+        op_sub(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_sub([0,1]) -> fail
         let mut stack = Stack::new();
         stack.push(StackEntry::Num(0));
         stack.push(StackEntry::Num(1));
         let b = op_sub(&mut stack);
-        assert!(!b);
+        assert_eq!(b, Err(ScriptError::ArithmeticOverflow));
         /// op_sub([1]) -> fail
         let mut stack = Stack::new();
         stack.push(StackEntry::Num(1));
         let b = op_sub(&mut stack);
-        assert!(!b)
+        assert_eq!(b, Err(ScriptError::NumItems))
+    }
+
+    #[test]
+    /// Test OP_ADD and OP_SUB on signed numbers
+    fn test_signed_add_sub() {
+        /// op_sub([+0,+1]) -> [-1]
+        let mut stack = Stack::new();
+        stack.push(StackEntry::SignedNum(0));
+        stack.push(StackEntry::SignedNum(1));
+        let mut v: Vec<StackEntry> = vec![StackEntry::SignedNum(-1)];
+        op_sub(&mut stack).unwrap();
+        assert_eq!(stack.main_stack, v);
+        /// op_add([-1,+1]) -> [+0]
+        let mut stack = Stack::new();
+        stack.push(StackEntry::SignedNum(-1));
+        stack.push(StackEntry::SignedNum(1));
+        let mut v: Vec<StackEntry> = vec![StackEntry::SignedNum(0)];
+        op_add(&mut stack).unwrap();
+        assert_eq!(stack.main_stack, v);
+        /// op_sub([i64::MIN,+1]) -> fail
+        let mut stack = Stack::new();
+        stack.push(StackEntry::SignedNum(i64::MIN));
+        stack.push(StackEntry::SignedNum(1));
+        let b = op_sub(&mut stack);
+        assert_eq!(b, Err(ScriptError::ArithmeticOverflow));
+        /// op_add([i64::MAX,+1]) -> fail
+        let mut stack = Stack::new();
+        stack.push(StackEntry::SignedNum(i64::MAX));
+        stack.push(StackEntry::SignedNum(1));
+        let b = op_add(&mut stack);
+        assert_eq!(b, Err(ScriptError::ArithmeticOverflow));
+        /// op_sub([0,+1]) -> fail
+        let mut stack = Stack::new();
+        stack.push(StackEntry::Num(0));
+        stack.push(StackEntry::SignedNum(1));
+        let b = op_sub(&mut stack);
+        assert_eq!(b, Err(ScriptError::ItemType));
+        /// op_add([+1,1]) -> fail
+        let mut stack = Stack::new();
+        stack.push(StackEntry::SignedNum(1));
+        stack.push(StackEntry::Num(1));
+        let b = op_add(&mut stack);
+        assert_eq!(b, Err(ScriptError::ItemType))
+    }
+
+    #[test]
+    fn test_interpret_signed_num() {
+        // +0 +1 OP_SUB -1 OP_EQUAL
+        let script = ScriptBuilder::new()
+            .push_signed_num(0)
+            .push_signed_num(1)
+            .push_op(OpCodes::OP_SUB)
+            .push_signed_num(-1)
+            .push_op(OpCodes::OP_EQUAL)
+            .build()
+            .unwrap();
+        assert_eq!(script.to_asm(), "+0 +1 OP_SUB -1 OP_EQUAL");
+        assert_eq!(Script::from_asm(&script.to_asm_full()), Ok(script.clone()));
+        assert!(script.interpret());
+        // +1 +1 OP_SUB leaves a signed ZERO
+        let script = Script::from_asm("+1 +1 OP_SUB").unwrap();
+        assert_eq!(script.interpret_checked(), Err(ScriptError::Verify));
+        // 1 +1 OP_ADD
+        let script = Script::from_asm("1 +1 OP_ADD").unwrap();
+        assert_eq!(script.interpret_checked(), Err(ScriptError::ItemType));
     }
 
     #[test]
@@ -1595,19 +2691,19 @@ mod tests {
             stack.push(StackEntry::Num(i));
         }
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(2)];
-        op_mul(&mut stack);
+        op_mul(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_mul([2,usize::MAX]) -> fail
         let mut stack = Stack::new();
         stack.push(StackEntry::Num(2));
         stack.push(StackEntry::Num(usize::MAX));
         let b = op_mul(&mut stack);
-        assert!(!b);
+        assert_eq!(b, Err(ScriptError::ArithmeticOverflow));
         /// op_mul([1]) -> fail
         let mut stack = Stack::new();
         stack.push(StackEntry::Num(1));
         let b = op_mul(&mut stack);
-        assert!(!b)
+        assert_eq!(b, Err(ScriptError::NumItems))
     }
 
     #[test]
@@ -1619,19 +2715,19 @@ mod tests {
             stack.push(StackEntry::Num(i));
         }
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(0)];
-        op_div(&mut stack);
+        op_div(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_div([1,0]) -> fail
         let mut stack = Stack::new();
         stack.push(StackEntry::Num(1));
         stack.push(StackEntry::Num(0));
         let b = op_div(&mut stack);
-        assert!(!b);
+        assert_eq!(b, Err(ScriptError::DivZero));
         /// op_div([1]) -> fail
         let mut stack = Stack::new();
         stack.push(StackEntry::Num(1));
         let b = op_div(&mut stack);
-        assert!(!b)
+        assert_eq!(b, Err(ScriptError::NumItems))
     }
 
     #[test]
@@ -1643,19 +2739,19 @@ mod tests {
             stack.push(StackEntry::Num(i));
         }
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(1)];
-        op_mod(&mut stack);
+        op_mod(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_mod([1,0]) -> fail
         let mut stack = Stack::new();
         stack.push(StackEntry::Num(1));
         stack.push(StackEntry::Num(0));
         let b = op_mod(&mut stack);
-        assert!(!b);
+        assert_eq!(b, Err(ScriptError::DivZero));
         /// op_mod([1]) -> fail
         let mut stack = Stack::new();
         stack.push(StackEntry::Num(1));
         let b = op_mod(&mut stack);
-        assert!(!b)
+        assert_eq!(b, Err(ScriptError::NumItems))
     }
 
     #[test]
@@ -1667,19 +2763,19 @@ mod tests {
             stack.push(StackEntry::Num(i));
         }
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(4)];
-        op_lshift(&mut stack);
+        op_lshift(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_lshift([1,64]) -> fail
         let mut stack = Stack::new();
         stack.push(StackEntry::Num(1));
         stack.push(StackEntry::Num(64));
         let b = op_lshift(&mut stack);
-        assert!(!b);
+        assert_eq!(b, Err(ScriptError::ArithmeticOverflow));
         /// op_lshift([1]) -> fail
         let mut stack = Stack::new();
         stack.push(StackEntry::Num(1));
         let b = op_lshift(&mut stack);
-        assert!(!b)
+        assert_eq!(b, Err(ScriptError::NumItems))
     }
 
     #[test]
@@ -1691,19 +2787,19 @@ mod tests {
             stack.push(StackEntry::Num(i));
         }
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(0)];
-        op_rshift(&mut stack);
+        op_rshift(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_rshift([1,64]) -> fail
         let mut stack = Stack::new();
         stack.push(StackEntry::Num(1));
         stack.push(StackEntry::Num(64));
         let b = op_rshift(&mut stack);
-        assert!(!b);
+        assert_eq!(b, Err(ScriptError::ArithmeticOverflow));
         /// op_rshift([1]) -> fail
         let mut stack = Stack::new();
         stack.push(StackEntry::Num(1));
         let b = op_rshift(&mut stack);
-        assert!(!b)
+        assert_eq!(b, Err(ScriptError::NumItems))
     }
 
     #[test]
@@ -1715,7 +2811,7 @@ mod tests {
             stack.push(StackEntry::Num(i));
         }
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(1)];
-        op_booland(&mut stack);
+        op_booland(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_booland([0,1]) -> [0]
         let mut stack = Stack::new();
@@ -1723,13 +2819,13 @@ mod tests {
             stack.push(StackEntry::Num(i));
         }
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(0)];
-        op_booland(&mut stack);
+        op_booland(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_booland([1]) -> fail
         let mut stack = Stack::new();
         stack.push(StackEntry::Num(1));
         let b = op_booland(&mut stack);
-        assert!(!b)
+        assert_eq!(b, Err(ScriptError::NumItems))
     }
 
     #[test]
@@ -1741,7 +2837,7 @@ mod tests {
             stack.push(StackEntry::Num(i));
         }
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(1)];
-        op_boolor(&mut stack);
+        op_boolor(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_boolor([0,0]) -> [0]
         let mut stack = Stack::new();
@@ -1749,13 +2845,13 @@ mod tests {
             stack.push(StackEntry::Num(0));
         }
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(0)];
-        op_boolor(&mut stack);
+        op_boolor(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_boolor([1]) -> fail
         let mut stack = Stack::new();
         stack.push(StackEntry::Num(1));
         let b = op_boolor(&mut stack);
-        assert!(!b)
+        assert_eq!(b, Err(ScriptError::NumItems))
     }
 
     #[test]
@@ -1767,7 +2863,7 @@ mod tests {
             stack.push(StackEntry::Num(1));
         }
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(1)];
-        op_numequal(&mut stack);
+        op_numequal(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_numequal([1,2]) -> [0]
         let mut stack = Stack::new();
@@ -1775,13 +2871,13 @@ mod tests {
             stack.push(StackEntry::Num(i));
         }
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(0)];
-        op_numequal(&mut stack);
+        op_numequal(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_numequal([1]) -> fail
         let mut stack = Stack::new();
         stack.push(StackEntry::Num(1));
         let b = op_numequal(&mut stack);
-        assert!(!b)
+        assert_eq!(b, Err(ScriptError::NumItems))
     }
 
     #[test]
@@ -1793,7 +2889,7 @@ mod tests {
             stack.push(StackEntry::Num(1));
         }
         let mut v: Vec<StackEntry> = vec![];
-        op_numequalverify(&mut stack);
+        op_numequalverify(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_numequalverify([1,2]) -> fail
         let mut stack = Stack::new();
@@ -1801,12 +2897,12 @@ mod tests {
             stack.push(StackEntry::Num(i));
         }
         let b = op_numequalverify(&mut stack);
-        assert!(!b);
+        assert_eq!(b, Err(ScriptError::NotEqualItems));
         /// op_numequalverify([1]) -> fail
         let mut stack = Stack::new();
         stack.push(StackEntry::Num(1));
         let b = op_numequalverify(&mut stack);
-        assert!(!b)
+        assert_eq!(b, Err(ScriptError::NumItems))
     }
 
     #[test]
@@ -1818,7 +2914,7 @@ mod tests {
             stack.push(StackEntry::Num(i));
         }
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(1)];
-        op_numnotequal(&mut stack);
+        op_numnotequal(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_numnotequal([1,1]) -> [0]
         let mut stack = Stack::new();
@@ -1826,13 +2922,13 @@ mod tests {
             stack.push(StackEntry::Num(1));
         }
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(0)];
-        op_numnotequal(&mut stack);
+        op_numnotequal(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_numnotequal([1]) -> fail
         let mut stack = Stack::new();
         stack.push(StackEntry::Num(1));
         let b = op_numnotequal(&mut stack);
-        assert!(!b)
+        assert_eq!(b, Err(ScriptError::NumItems))
     }
 
     #[test]
@@ -1844,7 +2940,7 @@ mod tests {
             stack.push(StackEntry::Num(i));
         }
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(1)];
-        op_lessthan(&mut stack);
+        op_lessthan(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_lessthan([1,1]) -> [0]
         let mut stack = Stack::new();
@@ -1852,13 +2948,13 @@ mod tests {
             stack.push(StackEntry::Num(1));
         }
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(0)];
-        op_lessthan(&mut stack);
+        op_lessthan(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_lessthan([1]) -> fail
         let mut stack = Stack::new();
         stack.push(StackEntry::Num(1));
         let b = op_lessthan(&mut stack);
-        assert!(!b)
+        assert_eq!(b, Err(ScriptError::NumItems))
     }
 
     #[test]
@@ -1869,7 +2965,7 @@ mod tests {
         stack.push(StackEntry::Num(2));
         stack.push(StackEntry::Num(1));
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(1)];
-        op_greaterthan(&mut stack);
+        op_greaterthan(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_greaterthan([1,1]) -> [0]
         let mut stack = Stack::new();
@@ -1877,13 +2973,75 @@ mod tests {
             stack.push(StackEntry::Num(1));
         }
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(0)];
-        op_greaterthan(&mut stack);
+        op_greaterthan(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_greaterthan([1]) -> fail
         let mut stack = Stack::new();
         stack.push(StackEntry::Num(1));
         let b = op_greaterthan(&mut stack);
-        assert!(!b)
+        assert_eq!(b, Err(ScriptError::NumItems))
+    }
+
+    #[test]
+    /// Test OP_LESSTHANVERIFY
+    fn test_lessthanverify() {
+        /// op_lessthanverify([1,2]) -> []
+        let mut stack = Stack::new();
+        for i in 1..=2 {
+            stack.push(StackEntry::Num(i));
+        }
+        let mut v: Vec<StackEntry> = vec![];
+        op_lessthanverify(&mut stack).unwrap();
+        assert_eq!(stack.main_stack, v);
+        /// op_lessthanverify([1,1]) -> fail
+        let mut stack = Stack::new();
+        for i in 1..=2 {
+            stack.push(StackEntry::Num(1));
+        }
+        let b = op_lessthanverify(&mut stack);
+        assert_eq!(b, Err(ScriptError::Verify));
+        /// op_lessthanverify([2,1]) -> fail
+        let mut stack = Stack::new();
+        stack.push(StackEntry::Num(2));
+        stack.push(StackEntry::Num(1));
+        let b = op_lessthanverify(&mut stack);
+        assert_eq!(b, Err(ScriptError::Verify));
+        /// op_lessthanverify([1]) -> fail
+        let mut stack = Stack::new();
+        stack.push(StackEntry::Num(1));
+        let b = op_lessthanverify(&mut stack);
+        assert_eq!(b, Err(ScriptError::NumItems))
+    }
+
+    #[test]
+    /// Test OP_GREATERTHANVERIFY
+    fn test_greaterthanverify() {
+        /// op_greaterthanverify([2,1]) -> []
+        let mut stack = Stack::new();
+        stack.push(StackEntry::Num(2));
+        stack.push(StackEntry::Num(1));
+        let mut v: Vec<StackEntry> = vec![];
+        op_greaterthanverify(&mut stack).unwrap();
+        assert_eq!(stack.main_stack, v);
+        /// op_greaterthanverify([1,1]) -> fail
+        let mut stack = Stack::new();
+        for i in 1..=2 {
+            stack.push(StackEntry::Num(1));
+        }
+        let b = op_greaterthanverify(&mut stack);
+        assert_eq!(b, Err(ScriptError::Verify));
+        /// op_greaterthanverify([1,2]) -> fail
+        let mut stack = Stack::new();
+        for i in 1..=2 {
+            stack.push(StackEntry::Num(i));
+        }
+        let b = op_greaterthanverify(&mut stack);
+        assert_eq!(b, Err(ScriptError::Verify));
+        /// op_greaterthanverify([1]) -> fail
+        let mut stack = Stack::new();
+        stack.push(StackEntry::Num(1));
+        let b = op_greaterthanverify(&mut stack);
+        assert_eq!(b, Err(ScriptError::NumItems))
     }
 
     #[test]
@@ -1895,20 +3053,20 @@ mod tests {
             stack.push(StackEntry::Num(1));
         }
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(1)];
-        op_lessthanorequal(&mut stack);
+        op_lessthanorequal(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_lessthanorequal([2,1]) -> [0]
         let mut stack = Stack::new();
         stack.push(StackEntry::Num(2));
         stack.push(StackEntry::Num(1));
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(0)];
-        op_lessthanorequal(&mut stack);
+        op_lessthanorequal(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_lessthanorequal([1]) -> fail
         let mut stack = Stack::new();
         stack.push(StackEntry::Num(1));
         let b = op_lessthanorequal(&mut stack);
-        assert!(!b)
+        assert_eq!(b, Err(ScriptError::NumItems))
     }
 
     #[test]
@@ -1920,7 +3078,7 @@ mod tests {
             stack.push(StackEntry::Num(1));
         }
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(1)];
-        op_greaterthanorequal(&mut stack);
+        op_greaterthanorequal(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_greaterthanorequal([1,2]) -> [0]
         let mut stack = Stack::new();
@@ -1928,13 +3086,13 @@ mod tests {
             stack.push(StackEntry::Num(i));
         }
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(0)];
-        op_greaterthanorequal(&mut stack);
+        op_greaterthanorequal(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_greaterthanorequal([1]) -> fail
         let mut stack = Stack::new();
         stack.push(StackEntry::Num(1));
         let b = op_greaterthanorequal(&mut stack);
-        assert!(!b)
+        assert_eq!(b, Err(ScriptError::NumItems))
     }
 
     #[test]
@@ -1946,13 +3104,13 @@ mod tests {
             stack.push(StackEntry::Num(i));
         }
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(1)];
-        op_min(&mut stack);
+        op_min(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_min([1]) -> fail
         let mut stack = Stack::new();
         stack.push(StackEntry::Num(1));
         let b = op_min(&mut stack);
-        assert!(!b)
+        assert_eq!(b, Err(ScriptError::NumItems))
     }
 
     #[test]
@@ -1964,13 +3122,13 @@ mod tests {
             stack.push(StackEntry::Num(i));
         }
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(2)];
-        op_max(&mut stack);
+        op_max(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_max([1]) -> fail
         let mut stack = Stack::new();
         stack.push(StackEntry::Num(1));
         let b = op_max(&mut stack);
-        assert!(!b)
+        assert_eq!(b, Err(ScriptError::NumItems))
     }
 
     #[test]
@@ -1982,7 +3140,7 @@ mod tests {
         stack.push(StackEntry::Num(1));
         stack.push(StackEntry::Num(3));
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(1)];
-        op_within(&mut stack);
+        op_within(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_within([1,2,3]) -> [0]
         let mut stack = Stack::new();
@@ -1990,7 +3148,7 @@ mod tests {
             stack.push(StackEntry::Num(i));
         }
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(0)];
-        op_within(&mut stack);
+        op_within(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_within([1,2]) -> fail
         let mut stack = Stack::new();
@@ -1998,64 +3156,245 @@ mod tests {
             stack.push(StackEntry::Num(i));
         }
         let b = op_within(&mut stack);
-        assert!(!b)
+        assert_eq!(b, Err(ScriptError::NumItems))
+    }
+
+    #[test]
+    /// Test OP_LESSTHAN_SIGNED
+    fn test_lessthan_signed() {
+        /// op_lessthan_signed([-1,0]) -> [1]
+        let mut stack = Stack::new();
+        stack.push(StackEntry::SignedNum(-1));
+        stack.push(StackEntry::Num(0));
+        let mut v: Vec<StackEntry> = vec![StackEntry::Num(1)];
+        op_lessthan_signed(&mut stack).unwrap();
+        assert_eq!(stack.main_stack, v);
+        /// op_lessthan_signed([i64::MAX,0]) -> [0]
+        let mut stack = Stack::new();
+        stack.push(StackEntry::Num(i64::MAX as usize));
+        stack.push(StackEntry::Num(0));
+        op_lessthan_signed(&mut stack).unwrap();
+        assert_eq!(stack.main_stack, vec![StackEntry::Num(0)]);
+        /// op_lessthan_signed([usize::MAX,0]) -> fail
+        let mut stack = Stack::new();
+        stack.push(StackEntry::Num(usize::MAX));
+        stack.push(StackEntry::Num(0));
+        let b = op_lessthan_signed(&mut stack);
+        assert_eq!(b, Err(ScriptError::ArithmeticOverflow));
+        /// op_lessthan_signed([0,-1]) -> [0]
+        let mut stack = Stack::new();
+        stack.push(StackEntry::Num(0));
+        stack.push(StackEntry::SignedNum(-1));
+        let mut v: Vec<StackEntry> = vec![StackEntry::Num(0)];
+        op_lessthan_signed(&mut stack).unwrap();
+        assert_eq!(stack.main_stack, v);
+        /// op_lessthan_signed(["hello",0]) -> fail
+        let mut stack = Stack::new();
+        stack.push(StackEntry::Bytes("hello".to_string()));
+        stack.push(StackEntry::Num(0));
+        let b = op_lessthan_signed(&mut stack);
+        assert_eq!(b, Err(ScriptError::ItemType));
+        /// op_lessthan_signed([1]) -> fail
+        let mut stack = Stack::new();
+        stack.push(StackEntry::Num(1));
+        let b = op_lessthan_signed(&mut stack);
+        assert_eq!(b, Err(ScriptError::NumItems))
+    }
+
+    #[test]
+    /// Test OP_GREATERTHAN_SIGNED, OP_LESSTHANOREQUAL_SIGNED and OP_GREATERTHANOREQUAL_SIGNED
+    fn test_compare_signed() {
+        let ops = [
+            (op_greaterthan_signed as fn(&mut Stack) -> _, [0, 0, 1]),
+            (op_lessthanorequal_signed, [1, 1, 0]),
+            (op_greaterthanorequal_signed, [0, 1, 1]),
+        ];
+        for (op, expected) in ops {
+            /// op([-2,-1]), op([-1,-1]), op([1,-1])
+            for (n1, r) in IntoIterator::into_iter([-2, -1, 1]).zip(expected) {
+                let mut stack = Stack::new();
+                stack.push(StackEntry::SignedNum(n1));
+                stack.push(StackEntry::SignedNum(-1));
+                op(&mut stack).unwrap();
+                assert_eq!(stack.main_stack, vec![StackEntry::Num(r)]);
+            }
+            /// op([]) -> fail
+            let mut stack = Stack::new();
+            assert_eq!(op(&mut stack), Err(ScriptError::NumItems));
+        }
+    }
+
+    #[test]
+    fn test_script_compare_signed_below_zero() {
+        // current - locked is negative until the locked height is reached
+        let remaining = |current: i64| {
+            Script::from(vec![
+                StackEntry::SignedNum(current),
+                StackEntry::SignedNum(10),
+                StackEntry::Op(OpCodes::OP_SUB),
+                StackEntry::SignedNum(0),
+                StackEntry::Op(OpCodes::OP_GREATERTHANOREQUAL_SIGNED),
+            ])
+        };
+        assert!(!remaining(5).interpret());
+        assert!(remaining(10).interpret());
+        assert!(remaining(12).interpret());
+        // the same subtraction on unsigned numbers underflows
+        let v = vec![
+            StackEntry::Num(5),
+            StackEntry::Num(10),
+            StackEntry::Op(OpCodes::OP_SUB),
+        ];
+        let script = Script::from(v);
+        assert_eq!(
+            script.interpret_checked(),
+            Err(ScriptError::ArithmeticOverflow)
+        );
+        // OP_LESSTHAN compares as unsigned, OP_LESSTHAN_SIGNED as signed, with no
+        // signed value for numbers above i64::MAX
+        let compare = |n, op| Script::from(vec![n, StackEntry::Num(0), StackEntry::Op(op)]);
+        let max = StackEntry::Num(usize::MAX);
+        assert!(!compare(max.clone(), OpCodes::OP_LESSTHAN).interpret());
+        assert_eq!(
+            compare(max, OpCodes::OP_LESSTHAN_SIGNED).interpret_checked(),
+            Err(ScriptError::ArithmeticOverflow)
+        );
+        assert!(compare(StackEntry::SignedNum(-1), OpCodes::OP_LESSTHAN_SIGNED).interpret());
     }
 
     /*---- CRYPTO OPS ----*/
 
     #[test]
-    /// Test OP_SHA3
-    fn test_sha3() {
-        /// op_sha3([sig]) -> [sha3_256(sig)]
+    /// Test OP_RIPEMD160
+    fn test_ripemd160() {
+        /// op_ripemd160([sig]) -> [ripemd160(sig)]
         let (pk, sk) = sign::gen_keypair();
         let msg = hex::encode(vec![0, 0, 0]);
         let sig = sign::sign_detached(msg.as_bytes(), &sk);
-        let h = hex::encode(sha3_256::digest(sig.as_ref()));
+        let h = hex::encode(ripemd160::digest(sig.as_ref()));
         let mut stack = Stack::new();
         stack.push(StackEntry::Signature(sig));
         let mut v: Vec<StackEntry> = vec![StackEntry::Bytes(h)];
-        op_sha3(&mut stack);
+        op_ripemd160(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
-        /// op_sha3([pk]) -> [sha3_256(pk)]
-        let h = hex::encode(sha3_256::digest(pk.as_ref()));
+        /// op_ripemd160([pk]) -> [ripemd160(pk)]
+        let h = hex::encode(ripemd160::digest(pk.as_ref()));
         let mut stack = Stack::new();
         stack.push(StackEntry::PubKey(pk));
         let mut v: Vec<StackEntry> = vec![StackEntry::Bytes(h)];
-        op_sha3(&mut stack);
+        op_ripemd160(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
-        /// op_sha3(["hello"]) -> [sha3_256("hello")]
+        /// op_ripemd160(["hello"]) -> [ripemd160("hello")]
         let s = "hello".to_string();
-        let h = hex::encode(sha3_256::digest(s.as_bytes()));
+        let h = "108f07b8382412612c048d07d13f814118445acd".to_string();
         let mut stack = Stack::new();
         stack.push(StackEntry::Bytes(s));
         let mut v: Vec<StackEntry> = vec![StackEntry::Bytes(h)];
-        op_sha3(&mut stack);
+        op_ripemd160(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
-        /// op_sha3([1]) -> fail
+        /// op_ripemd160([1]) -> fail
         let mut stack = Stack::new();
         stack.push(StackEntry::Num(1));
-        let b = op_sha3(&mut stack);
-        assert!(!b);
-        /// op_sha3([]) -> fail
+        let b = op_ripemd160(&mut stack);
+        assert_eq!(b, Err(ScriptError::ItemType));
+        /// op_ripemd160([]) -> fail
         let mut stack = Stack::new();
-        let b = op_sha3(&mut stack);
-        assert!(!b)
+        let b = op_ripemd160(&mut stack);
+        assert_eq!(b, Err(ScriptError::NumItems))
     }
 
     #[test]
-    /// Test OP_HASH256
-    fn test_hash256() {
-        /// op_hash256([pk]) -> [addr]
+    /// Test OP_HASH160
+    fn test_hash160() {
+        /// op_hash160([sig]) -> [ripemd160(sha256(sig))]
         let (pk, sk) = sign::gen_keypair();
+        let msg = hex::encode(vec![0, 0, 0]);
+        let sig = sign::sign_detached(msg.as_bytes(), &sk);
+        let h = hex::encode(ripemd160::digest(sha256::digest(sig.as_ref()).as_ref()));
+        let mut stack = Stack::new();
+        stack.push(StackEntry::Signature(sig));
+        let mut v: Vec<StackEntry> = vec![StackEntry::Bytes(h)];
+        op_hash160(&mut stack).unwrap();
+        assert_eq!(stack.main_stack, v);
+        /// op_hash160([pk]) -> [ripemd160(sha256(pk))]
+        let h = hex::encode(ripemd160::digest(sha256::digest(pk.as_ref()).as_ref()));
         let mut stack = Stack::new();
         stack.push(StackEntry::PubKey(pk));
-        let mut v: Vec<StackEntry> = vec![StackEntry::PubKeyHash(construct_address(&pk))];
-        op_hash256(&mut stack);
+        let mut v: Vec<StackEntry> = vec![StackEntry::Bytes(h)];
+        op_hash160(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
-        /// op_hash256([]) -> fail
+        /// op_hash160(["hello"]) -> [ripemd160(sha256("hello"))]
+        let s = "hello".to_string();
+        let h = "b6a9c8c230722b7c748331a8b450f05566dc7d0f".to_string();
         let mut stack = Stack::new();
-        let b = op_hash256(&mut stack);
-        assert!(!b)
+        stack.push(StackEntry::Bytes(s));
+        let mut v: Vec<StackEntry> = vec![StackEntry::Bytes(h)];
+        op_hash160(&mut stack).unwrap();
+        assert_eq!(stack.main_stack, v);
+        /// op_hash160([1]) -> fail
+        let mut stack = Stack::new();
+        stack.push(StackEntry::Num(1));
+        let b = op_hash160(&mut stack);
+        assert_eq!(b, Err(ScriptError::ItemType));
+        /// op_hash160([]) -> fail
+        let mut stack = Stack::new();
+        let b = op_hash160(&mut stack);
+        assert_eq!(b, Err(ScriptError::NumItems))
+    }
+
+    #[test]
+    /// Test OP_SHA3
+    fn test_sha3() {
+        /// op_sha3([sig]) -> [sha3_256(sig)]
+        let (pk, sk) = sign::gen_keypair();
+        let msg = hex::encode(vec![0, 0, 0]);
+        let sig = sign::sign_detached(msg.as_bytes(), &sk);
+        let h = hex::encode(sha3_256::digest(sig.as_ref()));
+        let mut stack = Stack::new();
+        stack.push(StackEntry::Signature(sig));
+        let mut v: Vec<StackEntry> = vec![StackEntry::Bytes(h)];
+        op_sha3(&mut stack).unwrap();
+        assert_eq!(stack.main_stack, v);
+        /// op_sha3([pk]) -> [sha3_256(pk)]
+        let h = hex::encode(sha3_256::digest(pk.as_ref()));
+        let mut stack = Stack::new();
+        stack.push(StackEntry::PubKey(pk));
+        let mut v: Vec<StackEntry> = vec![StackEntry::Bytes(h)];
+        op_sha3(&mut stack).unwrap();
+        assert_eq!(stack.main_stack, v);
+        /// op_sha3(["hello"]) -> [sha3_256("hello")]
+        let s = "hello".to_string();
+        let h = hex::encode(sha3_256::digest(s.as_bytes()));
+        let mut stack = Stack::new();
+        stack.push(StackEntry::Bytes(s));
+        let mut v: Vec<StackEntry> = vec![StackEntry::Bytes(h)];
+        op_sha3(&mut stack).unwrap();
+        assert_eq!(stack.main_stack, v);
+        /// op_sha3([1]) -> fail
+        let mut stack = Stack::new();
+        stack.push(StackEntry::Num(1));
+        let b = op_sha3(&mut stack);
+        assert_eq!(b, Err(ScriptError::ItemType));
+        /// op_sha3([]) -> fail
+        let mut stack = Stack::new();
+        let b = op_sha3(&mut stack);
+        assert_eq!(b, Err(ScriptError::NumItems))
+    }
+
+    #[test]
+    /// Test OP_HASH256
+    fn test_hash256() {
+        /// op_hash256([pk]) -> [addr]
+        let (pk, sk) = sign::gen_keypair();
+        let mut stack = Stack::new();
+        stack.push(StackEntry::PubKey(pk));
+        let mut v: Vec<StackEntry> = vec![StackEntry::PubKeyHash(construct_address(&pk))];
+        op_hash256(&mut stack).unwrap();
+        assert_eq!(stack.main_stack, v);
+        /// op_hash256([]) -> fail
+        let mut stack = Stack::new();
+        let b = op_hash256(&mut stack);
+        assert_eq!(b, Err(ScriptError::NumItems))
     }
 
     #[test]
@@ -2066,12 +3405,12 @@ mod tests {
         let mut stack = Stack::new();
         stack.push(StackEntry::PubKey(pk));
         let mut v: Vec<StackEntry> = vec![StackEntry::PubKeyHash(construct_address_v0(&pk))];
-        op_hash256_v0(&mut stack);
+        op_hash256_v0(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_hash256([]) -> fail
         let mut stack = Stack::new();
         let b = op_hash256_v0(&mut stack);
-        assert!(!b)
+        assert_eq!(b, Err(ScriptError::NumItems))
     }
 
     #[test]
@@ -2082,17 +3421,18 @@ mod tests {
         let mut stack = Stack::new();
         stack.push(StackEntry::PubKey(pk));
         let mut v: Vec<StackEntry> = vec![StackEntry::PubKeyHash(construct_address_temp(&pk))];
-        op_hash256_temp(&mut stack);
+        op_hash256_temp(&mut stack).unwrap();
         assert_eq!(stack.main_stack, v);
         /// op_hash256([]) -> fail
         let mut stack = Stack::new();
         let b = op_hash256_temp(&mut stack);
-        assert!(!b)
+        assert_eq!(b, Err(ScriptError::NumItems))
     }
 
     #[test]
     /// Test OP_CHECKSIG
     fn test_checksig() {
+        let ctx = ScriptContext::default();
         /// op_checksig([msg,sig,pk]) -> [1]
         let (pk, sk) = sign::gen_keypair();
         let msg = hex::encode(vec![0, 0, 0]);
@@ -2102,7 +3442,7 @@ mod tests {
         stack.push(StackEntry::Signature(sig));
         stack.push(StackEntry::PubKey(pk));
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(1)];
-        op_checksig(&mut stack);
+        op_checksig(&mut stack, &ctx).unwrap();
         assert_eq!(stack.main_stack, v);
         /// wrong message
         /// op_checksig([msg',sig,pk]) -> [0]
@@ -2112,7 +3452,7 @@ mod tests {
         stack.push(StackEntry::Signature(sig));
         stack.push(StackEntry::PubKey(pk));
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(0)];
-        op_checksig(&mut stack);
+        op_checksig(&mut stack, &ctx).unwrap();
         assert_eq!(stack.main_stack, v);
         /// wrong public key
         /// op_checksig([msg,sig,pk']) -> [0]
@@ -2123,20 +3463,201 @@ mod tests {
         stack.push(StackEntry::Signature(sig));
         stack.push(StackEntry::PubKey(pk));
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(0)];
-        op_checksig(&mut stack);
+        op_checksig(&mut stack, &ctx).unwrap();
         assert_eq!(stack.main_stack, v);
         /// no message
         /// op_checksig([sig,pk]) -> fail
         let mut stack = Stack::new();
         stack.push(StackEntry::Signature(sig));
         stack.push(StackEntry::PubKey(pk));
-        let b = op_checksig(&mut stack);
-        assert!(!b)
+        let b = op_checksig(&mut stack, &ctx);
+        assert_eq!(b, Err(ScriptError::NumItems))
+    }
+
+    #[test]
+    /// Test OP_CHECKSIG through the signature scheme abstraction
+    fn test_checksig_signature_scheme() {
+        let ctx = ScriptContext {
+            signature_scheme: SignatureSchemeId::Ed25519,
+            ..Default::default()
+        };
+        let scheme = ctx.signature_scheme.scheme();
+        assert_eq!(scheme.public_key_len(), ED25519_PUBLIC_KEY_LEN);
+        assert_eq!(scheme.signature_len(), ED25519_SIGNATURE_LEN);
+        /// op_checksig([msg,sig,pk]) -> [1]
+        let (pk, sk) = sign::gen_keypair();
+        let msg = hex::encode(vec![0, 0, 0]);
+        let sig = scheme.sign(msg.as_bytes(), sk.as_ref()).unwrap();
+        assert!(scheme.verify(&sig, msg.as_bytes(), pk.as_ref()));
+        assert!(!scheme.verify(&sig[1..], msg.as_bytes(), pk.as_ref()));
+        assert_eq!(scheme.sign(msg.as_bytes(), &[0; 4]), None);
+        let mut stack = Stack::new();
+        stack.push(StackEntry::Bytes(msg));
+        stack.push(StackEntry::Signature(Signature::from_slice(&sig).unwrap()));
+        stack.push(StackEntry::PubKey(pk));
+        let mut v: Vec<StackEntry> = vec![StackEntry::Num(1)];
+        op_checksig(&mut stack, &ctx).unwrap();
+        assert_eq!(stack.main_stack, v);
+    }
+
+    /// Util function to turn a signature non-canonical by adding the group order L to S.
+    /// The result never verifies, as the underlying verifier rejects unreduced S
+    fn malleate_signature(sig: &Signature) -> Signature {
+        let l = [
+            0xed, 0xd3, 0xf5, 0x5c, 0x1a, 0x63, 0x12, 0x58, 0xd6, 0x9c, 0xf7, 0xa2, 0xde, 0xf9,
+            0xde, 0x14, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x10,
+        ];
+        let mut bytes = sig.as_ref().to_vec();
+        let mut carry = 0;
+        for (b, l) in bytes[32..].iter_mut().zip(l.iter()) {
+            let sum = *b as u16 + *l as u16 + carry;
+            *b = sum as u8;
+            carry = sum >> 8;
+        }
+        Signature::from_slice(&bytes).unwrap()
+    }
+
+    #[test]
+    /// Test signatures with an unreduced S in OP_CHECKSIG, OP_CHECKSIGVERIFY and OP_CHECKMULTISIG
+    fn test_checksig_non_canonical() {
+        let ctx = ScriptContext::default();
+        let (pk, sk) = sign::gen_keypair();
+        let msg = hex::encode(vec![0, 0, 0]);
+        let sig = sign::sign_detached(msg.as_bytes(), &sk);
+        let bad_sig = malleate_signature(&sig);
+        /// op_checksig([msg,sig',pk]) -> [0]
+        let mut stack = Stack::new();
+        stack.push(StackEntry::Bytes(msg.clone()));
+        stack.push(StackEntry::Signature(bad_sig));
+        stack.push(StackEntry::PubKey(pk));
+        let mut v: Vec<StackEntry> = vec![StackEntry::Num(0)];
+        op_checksig(&mut stack, &ctx).unwrap();
+        assert_eq!(stack.main_stack, v);
+        /// op_checksigverify([msg,sig',pk]) -> fail
+        let mut stack = Stack::new();
+        stack.push(StackEntry::Bytes(msg.clone()));
+        stack.push(StackEntry::Signature(bad_sig));
+        stack.push(StackEntry::PubKey(pk));
+        let b = op_checksigverify(&mut stack, &ctx);
+        assert_eq!(b, Err(ScriptError::InvalidSignature));
+        /// op_checkmultisig([msg,sig',1,pk,1]) -> [0]
+        let mut stack = Stack::new();
+        stack.push(StackEntry::Bytes(msg.clone()));
+        stack.push(StackEntry::Signature(bad_sig));
+        stack.push(StackEntry::Num(1));
+        stack.push(StackEntry::PubKey(pk));
+        stack.push(StackEntry::Num(1));
+        let mut v: Vec<StackEntry> = vec![StackEntry::Num(0)];
+        op_checkmultisig(&mut stack, &ctx).unwrap();
+        assert_eq!(stack.main_stack, v);
+        /// ring rejects the unreduced S for legacy scripts too
+        let ctx = ScriptContext {
+            address_version: Some(NETWORK_VERSION_V0),
+            ..Default::default()
+        };
+        let mut stack = Stack::new();
+        stack.push(StackEntry::Bytes(msg.clone()));
+        stack.push(StackEntry::Signature(bad_sig));
+        stack.push(StackEntry::PubKey(pk));
+        let b = op_checksigverify(&mut stack, &ctx);
+        assert_eq!(b, Err(ScriptError::InvalidSignature));
+        assert!(sign::verify_detached(&sig, msg.as_bytes(), &pk));
+    }
+
+    #[test]
+    /// Test OP_CHECKDATASIG
+    fn test_checkdatasig() {
+        let ctx = ScriptContext::default();
+        /// op_checkdatasig([msg,sig,pk]) -> [1]
+        let (pk, sk) = sign::gen_keypair();
+        let data = b"price:42".to_vec();
+        let msg = hex::encode(&data);
+        let sig = sign::sign_detached(&data, &sk);
+        let mut stack = Stack::new();
+        stack.push(StackEntry::Bytes(msg.clone()));
+        stack.push(StackEntry::Signature(sig));
+        stack.push(StackEntry::PubKey(pk));
+        let mut v: Vec<StackEntry> = vec![StackEntry::Num(1)];
+        op_checkdatasig(&mut stack, &ctx).unwrap();
+        assert_eq!(stack.main_stack, v);
+        /// signature over the hex encoding
+        /// op_checkdatasig([msg,sig',pk]) -> [0]
+        let hex_sig = sign::sign_detached(msg.as_bytes(), &sk);
+        let mut stack = Stack::new();
+        stack.push(StackEntry::Bytes(msg.clone()));
+        stack.push(StackEntry::Signature(hex_sig));
+        stack.push(StackEntry::PubKey(pk));
+        let mut v: Vec<StackEntry> = vec![StackEntry::Num(0)];
+        op_checkdatasig(&mut stack, &ctx).unwrap();
+        assert_eq!(stack.main_stack, v);
+        /// wrong message
+        /// op_checkdatasig([msg',sig,pk]) -> [0]
+        let mut stack = Stack::new();
+        stack.push(StackEntry::Bytes(hex::encode(b"price:43")));
+        stack.push(StackEntry::Signature(sig));
+        stack.push(StackEntry::PubKey(pk));
+        let mut v: Vec<StackEntry> = vec![StackEntry::Num(0)];
+        op_checkdatasig(&mut stack, &ctx).unwrap();
+        assert_eq!(stack.main_stack, v);
+        /// wrong public key
+        /// op_checkdatasig([msg,sig,pk']) -> [0]
+        let (pk, sk) = sign::gen_keypair();
+        let mut stack = Stack::new();
+        stack.push(StackEntry::Bytes(msg.clone()));
+        stack.push(StackEntry::Signature(sig));
+        stack.push(StackEntry::PubKey(pk));
+        let mut v: Vec<StackEntry> = vec![StackEntry::Num(0)];
+        op_checkdatasig(&mut stack, &ctx).unwrap();
+        assert_eq!(stack.main_stack, v);
+        /// message not hex encoded
+        /// op_checkdatasig([msg',sig,pk]) -> fail
+        let mut stack = Stack::new();
+        stack.push(StackEntry::Bytes("price:42".to_owned()));
+        stack.push(StackEntry::Signature(sig));
+        stack.push(StackEntry::PubKey(pk));
+        let b = op_checkdatasig(&mut stack, &ctx);
+        assert_eq!(b, Err(ScriptError::ItemType));
+        /// no message
+        /// op_checkdatasig([sig,pk]) -> fail
+        let mut stack = Stack::new();
+        stack.push(StackEntry::Signature(sig));
+        stack.push(StackEntry::PubKey(pk));
+        let b = op_checkdatasig(&mut stack, &ctx);
+        assert_eq!(b, Err(ScriptError::NumItems))
+    }
+
+    #[test]
+    /// Test OP_CODESEPARATOR
+    fn test_codeseparator() {
+        /// op_codeseparator([1]) -> [1]
+        let mut stack = Stack::new();
+        stack.push(StackEntry::Num(1));
+        let mut v: Vec<StackEntry> = vec![StackEntry::Num(1)];
+        op_codeseparator(&mut stack).unwrap();
+        assert_eq!(stack.main_stack, v);
+        /// signature check is unaffected by a separator
+        let (pk, sk) = sign::gen_keypair();
+        let msg = hex::encode(vec![0, 0, 0]);
+        let sig = sign::sign_detached(msg.as_bytes(), &sk);
+        let v = vec![
+            StackEntry::Bytes(msg),
+            StackEntry::Signature(sig),
+            StackEntry::Op(OpCodes::OP_CODESEPARATOR),
+            StackEntry::PubKey(pk),
+            StackEntry::Op(OpCodes::OP_CHECKSIG),
+        ];
+        let script = Script::from(v);
+        assert!(script.interpret());
+        assert_eq!(
+            OpCodes::from_name("OP_CODESEPARATOR"),
+            Some(OpCodes::OP_CODESEPARATOR)
+        );
     }
 
     #[test]
     /// Test OP_CHECKSIGVERIFY
     fn test_checksigverify() {
+        let ctx = ScriptContext::default();
         /// op_checksigverify([msg,sig,pk]) -> []
         let (pk, sk) = sign::gen_keypair();
         let msg = hex::encode(vec![0, 0, 0]);
@@ -2146,7 +3667,7 @@ mod tests {
         stack.push(StackEntry::Signature(sig));
         stack.push(StackEntry::PubKey(pk));
         let mut v: Vec<StackEntry> = vec![];
-        op_checksigverify(&mut stack);
+        op_checksigverify(&mut stack, &ctx).unwrap();
         assert_eq!(stack.main_stack, v);
         /// wrong message
         /// op_checksigverify([msg',sig,pk]) -> fail
@@ -2155,8 +3676,8 @@ mod tests {
         stack.push(StackEntry::Bytes(msg));
         stack.push(StackEntry::Signature(sig));
         stack.push(StackEntry::PubKey(pk));
-        let b = op_checksigverify(&mut stack);
-        assert!(!b);
+        let b = op_checksigverify(&mut stack, &ctx);
+        assert_eq!(b, Err(ScriptError::InvalidSignature));
         /// wrong public key
         /// op_checksig([msg,sig,pk']) -> fail
         let (pk, sk) = sign::gen_keypair();
@@ -2165,20 +3686,21 @@ mod tests {
         stack.push(StackEntry::Bytes(msg));
         stack.push(StackEntry::Signature(sig));
         stack.push(StackEntry::PubKey(pk));
-        let b = op_checksigverify(&mut stack);
-        assert!(!b);
+        let b = op_checksigverify(&mut stack, &ctx);
+        assert_eq!(b, Err(ScriptError::InvalidSignature));
         /// no message
         /// op_checksigverify([sig,pk]) -> fail
         let mut stack = Stack::new();
         stack.push(StackEntry::Signature(sig));
         stack.push(StackEntry::PubKey(pk));
-        let b = op_checksigverify(&mut stack);
-        assert!(!b)
+        let b = op_checksigverify(&mut stack, &ctx);
+        assert_eq!(b, Err(ScriptError::NumItems))
     }
 
     #[test]
     /// Test OP_CHECKMULTISIG
     fn test_checkmultisig() {
+        let ctx = ScriptContext::default();
         /// 2-of-3 multisig
         /// op_checkmultisig([msg,sig1,sig2,2,pk1,pk2,pk3,3]) -> [1]
         let (pk1, sk1) = sign::gen_keypair();
@@ -2197,7 +3719,7 @@ mod tests {
         stack.push(StackEntry::PubKey(pk3));
         stack.push(StackEntry::Num(3));
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(1)];
-        op_checkmultisig(&mut stack);
+        op_checkmultisig(&mut stack, &ctx).unwrap();
         assert_eq!(stack.main_stack, v);
         /// 0-of-3 multisig
         /// op_checkmultisig([msg,0,pk1,pk2,pk3,3]) -> [1]
@@ -2210,7 +3732,7 @@ mod tests {
         stack.push(StackEntry::PubKey(pk3));
         stack.push(StackEntry::Num(3));
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(1)];
-        op_checkmultisig(&mut stack);
+        op_checkmultisig(&mut stack, &ctx).unwrap();
         assert_eq!(stack.main_stack, v);
         /// 0-of-0 multisig
         /// op_checkmultisig([msg,0,0]) -> [1]
@@ -2220,7 +3742,7 @@ mod tests {
         stack.push(StackEntry::Num(0));
         stack.push(StackEntry::Num(0));
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(1)];
-        op_checkmultisig(&mut stack);
+        op_checkmultisig(&mut stack, &ctx).unwrap();
         assert_eq!(stack.main_stack, v);
         /// 1-of-1 multisig
         /// op_checkmultisig([msg,sig1,1,pk1,1]) -> [1]
@@ -2232,7 +3754,7 @@ mod tests {
         stack.push(StackEntry::PubKey(pk1));
         stack.push(StackEntry::Num(1));
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(1)];
-        op_checkmultisig(&mut stack);
+        op_checkmultisig(&mut stack, &ctx).unwrap();
         assert_eq!(stack.main_stack, v);
         /// ordering is not relevant
         /// op_checkmultisig([msg,sig3,sig1,2,pk2,pk3,pk1,3]) -> [1]
@@ -2248,7 +3770,7 @@ mod tests {
         stack.push(StackEntry::PubKey(pk1));
         stack.push(StackEntry::Num(3));
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(1)];
-        op_checkmultisig(&mut stack);
+        op_checkmultisig(&mut stack, &ctx).unwrap();
         assert_eq!(stack.main_stack, v);
         /// wrong message
         /// op_checkmultisig([msg',sig1,sig2,2,pk1,pk2,pk3,3]) -> [0]
@@ -2263,7 +3785,7 @@ mod tests {
         stack.push(StackEntry::PubKey(pk3));
         stack.push(StackEntry::Num(3));
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(0)];
-        op_checkmultisig(&mut stack);
+        op_checkmultisig(&mut stack, &ctx).unwrap();
         assert_eq!(stack.main_stack, v);
         /// same signature twice
         /// op_checkmultisig([msg,sig1,sig1,2,pk1,pk2,pk3,3]) -> [0]
@@ -2278,22 +3800,22 @@ mod tests {
         stack.push(StackEntry::PubKey(pk3));
         stack.push(StackEntry::Num(3));
         let mut v: Vec<StackEntry> = vec![StackEntry::Num(0)];
-        op_checkmultisig(&mut stack);
+        op_checkmultisig(&mut stack, &ctx).unwrap();
         assert_eq!(stack.main_stack, v);
         /// too many pubkeys
         /// op_checkmultisig([MAX_PUB_KEYS_PER_MULTISIG+1]) -> fail
         let mut stack = Stack::new();
         stack.push(StackEntry::Num(MAX_PUB_KEYS_PER_MULTISIG as usize + ONE));
-        let b = op_checkmultisig(&mut stack);
-        assert!(!b);
+        let b = op_checkmultisig(&mut stack, &ctx);
+        assert_eq!(b, Err(ScriptError::NumPubkeys));
         /// not enough pubkeys
         /// op_checkmultisig([pk1,pk2,3]) -> fail
         let mut stack = Stack::new();
         stack.push(StackEntry::PubKey(pk1));
         stack.push(StackEntry::PubKey(pk2));
         stack.push(StackEntry::Num(3));
-        let b = op_checkmultisig(&mut stack);
-        assert!(!b);
+        let b = op_checkmultisig(&mut stack, &ctx);
+        assert_eq!(b, Err(ScriptError::NumPubkeys));
         /// too many signatures
         /// op_checkmultisig([4,pk1,pk2,pk3,3]) -> fail
         let mut stack = Stack::new();
@@ -2302,8 +3824,8 @@ mod tests {
         stack.push(StackEntry::PubKey(pk2));
         stack.push(StackEntry::PubKey(pk3));
         stack.push(StackEntry::Num(3));
-        let b = op_checkmultisig(&mut stack);
-        assert!(!b);
+        let b = op_checkmultisig(&mut stack, &ctx);
+        assert_eq!(b, Err(ScriptError::NumSignatures));
         /// not enough signatures
         /// op_checkmultisig([sig1,2,pk1,pk2,pk3,3]) -> fail
         let mut stack = Stack::new();
@@ -2313,8 +3835,8 @@ mod tests {
         stack.push(StackEntry::PubKey(pk2));
         stack.push(StackEntry::PubKey(pk3));
         stack.push(StackEntry::Num(3));
-        let b = op_checkmultisig(&mut stack);
-        assert!(!b);
+        let b = op_checkmultisig(&mut stack, &ctx);
+        assert_eq!(b, Err(ScriptError::NumSignatures));
         /// no message
         /// op_checkmultisig([sig1,sig2,2,pk1,pk2,pk3,3]) -> fail
         let mut stack = Stack::new();
@@ -2325,13 +3847,56 @@ mod tests {
         stack.push(StackEntry::PubKey(pk2));
         stack.push(StackEntry::PubKey(pk3));
         stack.push(StackEntry::Num(3));
-        let b = op_checkmultisig(&mut stack);
-        assert!(!b);
+        let b = op_checkmultisig(&mut stack, &ctx);
+        assert_eq!(b, Err(ScriptError::NumItems));
+    }
+
+    #[test]
+    /// Test OP_CHECKMULTISIG and OP_CHECKMULTISIGVERIFY with malformed m and n counts
+    fn test_checkmultisig_malformed_counts() {
+        let ctx = ScriptContext::default();
+        let (pk1, sk1) = sign::gen_keypair();
+        let (pk2, _) = sign::gen_keypair();
+        let msg = hex::encode(vec![0, 0, 0]);
+        let sig1 = sign::sign_detached(msg.as_bytes(), &sk1);
+        let count = StackEntry::Bytes(hex::encode(vec![2]));
+        /// bytes in place of n
+        /// op_checkmultisig([msg,sig1,1,pk1,pk2,b]) -> fail
+        let n_malformed = vec![
+            StackEntry::Bytes(msg.clone()),
+            StackEntry::Signature(sig1),
+            StackEntry::Num(1),
+            StackEntry::PubKey(pk1),
+            StackEntry::PubKey(pk2),
+            count.clone(),
+        ];
+        /// bytes in place of m
+        /// op_checkmultisig([msg,sig1,b,pk1,pk2,2]) -> fail
+        let m_malformed = vec![
+            StackEntry::Bytes(msg),
+            StackEntry::Signature(sig1),
+            count,
+            StackEntry::PubKey(pk1),
+            StackEntry::PubKey(pk2),
+            StackEntry::Num(2),
+        ];
+        for entries in [n_malformed, m_malformed] {
+            let mut stack = Stack::from(entries.clone());
+            let b = op_checkmultisig(&mut stack, &ctx);
+            assert_eq!(b, Err(ScriptError::ItemType));
+            let mut stack = Stack::from(entries.clone());
+            let b = op_checkmultisigverify(&mut stack, &ctx);
+            assert_eq!(b, Err(ScriptError::ItemType));
+            let mut script = Script::from(entries);
+            script.stack.push(StackEntry::Op(OpCodes::OP_CHECKMULTISIG));
+            assert!(!script.interpret());
+        }
     }
 
     #[test]
     /// Test OP_CHECKMULTISIGVERIFY
     fn test_checkmultisigverify() {
+        let ctx = ScriptContext::default();
         /// 2-of-3 multisig
         /// op_checkmultisigverify([msg,sig1,sig2,2,pk1,pk2,pk3,3]) -> []
         let (pk1, sk1) = sign::gen_keypair();
@@ -2350,7 +3915,7 @@ mod tests {
         stack.push(StackEntry::PubKey(pk3));
         stack.push(StackEntry::Num(3));
         let mut v: Vec<StackEntry> = vec![];
-        op_checkmultisigverify(&mut stack);
+        op_checkmultisigverify(&mut stack, &ctx).unwrap();
         assert_eq!(stack.main_stack, v);
         /// 0-of-3 multisig
         /// op_checkmultisigverify([msg,0,pk1,pk2,pk3,3]) -> []
@@ -2363,7 +3928,7 @@ mod tests {
         stack.push(StackEntry::PubKey(pk3));
         stack.push(StackEntry::Num(3));
         let mut v: Vec<StackEntry> = vec![];
-        op_checkmultisigverify(&mut stack);
+        op_checkmultisigverify(&mut stack, &ctx).unwrap();
         assert_eq!(stack.main_stack, v);
         /// 0-of-0 multisig
         /// op_checkmultisig([msg,0,0]) -> []
@@ -2373,7 +3938,7 @@ mod tests {
         stack.push(StackEntry::Num(0));
         stack.push(StackEntry::Num(0));
         let mut v: Vec<StackEntry> = vec![];
-        op_checkmultisigverify(&mut stack);
+        op_checkmultisigverify(&mut stack, &ctx).unwrap();
         assert_eq!(stack.main_stack, v);
         /// 1-of-1 multisig
         /// op_checkmultisigverify([msg,sig1,1,pk1,1]) -> []
@@ -2385,7 +3950,7 @@ mod tests {
         stack.push(StackEntry::PubKey(pk1));
         stack.push(StackEntry::Num(1));
         let mut v: Vec<StackEntry> = vec![];
-        op_checkmultisigverify(&mut stack);
+        op_checkmultisigverify(&mut stack, &ctx).unwrap();
         assert_eq!(stack.main_stack, v);
         /// ordering is not relevant
         /// op_checkmultisigverify([msg,sig3,sig1,2,pk2,pk3,pk1,3]) -> []
@@ -2401,7 +3966,7 @@ mod tests {
         stack.push(StackEntry::PubKey(pk1));
         stack.push(StackEntry::Num(3));
         let mut v: Vec<StackEntry> = vec![];
-        op_checkmultisigverify(&mut stack);
+        op_checkmultisigverify(&mut stack, &ctx).unwrap();
         assert_eq!(stack.main_stack, v);
         /// wrong message
         /// op_checkmultisigverify([msg',sig1,sig2,2,pk1,pk2,pk3,3]) -> fail
@@ -2415,8 +3980,8 @@ mod tests {
         stack.push(StackEntry::PubKey(pk2));
         stack.push(StackEntry::PubKey(pk3));
         stack.push(StackEntry::Num(3));
-        let b = op_checkmultisigverify(&mut stack);
-        assert!(!b);
+        let b = op_checkmultisigverify(&mut stack, &ctx);
+        assert_eq!(b, Err(ScriptError::InvalidMultisignature));
         /// same signature twice
         /// op_checkmultisigverify([msg,sig1,sig1,2,pk1,pk2,pk3,3]) -> fail
         let msg = hex::encode(vec![0, 0, 0]);
@@ -2429,22 +3994,23 @@ mod tests {
         stack.push(StackEntry::PubKey(pk2));
         stack.push(StackEntry::PubKey(pk3));
         stack.push(StackEntry::Num(3));
-        op_checkmultisigverify(&mut stack);
+        let b = op_checkmultisigverify(&mut stack, &ctx);
+        assert_eq!(b, Err(ScriptError::InvalidMultisignature));
         assert_eq!(stack.main_stack, v);
         /// too many pubkeys
         /// op_checkmultisigverify([MAX_PUB_KEYS_PER_MULTISIG+1]) -> fail
         let mut stack = Stack::new();
         stack.push(StackEntry::Num(MAX_PUB_KEYS_PER_MULTISIG as usize + ONE));
-        let b = op_checkmultisigverify(&mut stack);
-        assert!(!b);
+        let b = op_checkmultisigverify(&mut stack, &ctx);
+        assert_eq!(b, Err(ScriptError::NumPubkeys));
         /// not enough pubkeys
         /// op_checkmultisigverify([pk1,pk2,3]) -> fail
         let mut stack = Stack::new();
         stack.push(StackEntry::PubKey(pk1));
         stack.push(StackEntry::PubKey(pk2));
         stack.push(StackEntry::Num(3));
-        let b = op_checkmultisigverify(&mut stack);
-        assert!(!b);
+        let b = op_checkmultisigverify(&mut stack, &ctx);
+        assert_eq!(b, Err(ScriptError::NumPubkeys));
         /// too many signatures
         /// op_checkmultisigverify([4,pk1,pk2,pk3,3]) -> fail
         let mut stack = Stack::new();
@@ -2453,8 +4019,8 @@ mod tests {
         stack.push(StackEntry::PubKey(pk2));
         stack.push(StackEntry::PubKey(pk3));
         stack.push(StackEntry::Num(3));
-        let b = op_checkmultisigverify(&mut stack);
-        assert!(!b);
+        let b = op_checkmultisigverify(&mut stack, &ctx);
+        assert_eq!(b, Err(ScriptError::NumSignatures));
         /// not enough signatures
         /// op_checkmultisigverify([sig1,2,pk1,pk2,pk3,3]) -> fail
         let mut stack = Stack::new();
@@ -2464,8 +4030,8 @@ mod tests {
         stack.push(StackEntry::PubKey(pk2));
         stack.push(StackEntry::PubKey(pk3));
         stack.push(StackEntry::Num(3));
-        let b = op_checkmultisigverify(&mut stack);
-        assert!(!b);
+        let b = op_checkmultisigverify(&mut stack, &ctx);
+        assert_eq!(b, Err(ScriptError::NumItems));
         /// no message
         /// op_checkmultisigverify([sig1,sig2,2,pk1,pk2,pk3,3]) -> fail
         let mut stack = Stack::new();
@@ -2476,885 +4042,3996 @@ mod tests {
         stack.push(StackEntry::PubKey(pk2));
         stack.push(StackEntry::PubKey(pk3));
         stack.push(StackEntry::Num(3));
-        let b = op_checkmultisigverify(&mut stack);
-        assert!(!b);
+        let b = op_checkmultisigverify(&mut stack, &ctx);
+        assert_eq!(b, Err(ScriptError::NumItems));
     }
 
     #[test]
-    fn test_is_valid_script() {
-        // empty script
-        let v = vec![];
-        let script = Script::from(v);
-        assert!(script.is_valid());
-        // script length <= 10000 bytes
-        let v = vec![StackEntry::Bytes("a".repeat(500)); 20];
-        let script = Script::from(v);
-        assert!(script.is_valid());
-        // script length > 10000 bytes
-        let v = vec![StackEntry::Bytes("a".repeat(500)); 21];
-        let script = Script::from(v);
-        assert!(!script.is_valid());
-        // # opcodes <= 201
-        let v = vec![StackEntry::Op(OpCodes::OP_1); MAX_OPS_PER_SCRIPT as usize];
-        let script = Script::from(v);
-        assert!(script.is_valid());
-        // # opcodes > 201
-        let v = vec![StackEntry::Op(OpCodes::OP_1); (MAX_OPS_PER_SCRIPT + 1) as usize];
-        let script = Script::from(v);
-        assert!(!script.is_valid());
+    /// Test OP_CHECKAGGSIG
+    fn test_checkaggsig() {
+        let ctx = ScriptContext::default();
+        let keys: Vec<_> = (0..3).map(|_| sign::gen_keypair()).collect();
+        let pks: Vec<PublicKey> = keys.iter().map(|(pk, _)| *pk).collect();
+        let sks: Vec<_> = keys.iter().map(|(_, sk)| sk.clone()).collect();
+        let msg = hex::encode(vec![0, 0, 0]);
+        let sigs: Vec<Signature> = sks
+            .iter()
+            .map(|sk| sign::sign_detached(msg.as_bytes(), sk))
+            .collect();
+        let agg_sig = sign::sign_aggregate(msg.as_bytes(), &sks).unwrap();
+        let partial_sig = sign::sign_aggregate(msg.as_bytes(), &sks[..2]).unwrap();
+        let push_keys = |stack: &mut Stack| {
+            for pk in &pks {
+                stack.push(StackEntry::PubKey(*pk));
+            }
+            stack.push(StackEntry::Num(pks.len()));
+        };
+        /// op_checkaggsig([msg,sig,pk1,pk2,pk3,3]) and op_checkmultisig([msg,sig1,sig2,sig3,3,pk1,pk2,pk3,3])
+        for (msg, agg_sig, sigs, expected) in [
+            (&msg, agg_sig, &sigs[..], 1),
+            (&hex::encode(vec![0, 0, 1]), agg_sig, &sigs[..], 0),
+            (&msg, partial_sig, &[sigs[0], sigs[1], sigs[1]][..], 0),
+        ] {
+            let mut stack = Stack::new();
+            stack.push(StackEntry::Bytes(msg.clone()));
+            stack.push(StackEntry::Signature(agg_sig));
+            push_keys(&mut stack);
+            let mut v: Vec<StackEntry> = vec![StackEntry::Num(expected)];
+            op_checkaggsig(&mut stack, &ctx).unwrap();
+            assert_eq!(stack.main_stack, v);
+
+            let mut stack = Stack::new();
+            stack.push(StackEntry::Bytes(msg.clone()));
+            for sig in sigs {
+                stack.push(StackEntry::Signature(*sig));
+            }
+            stack.push(StackEntry::Num(sigs.len()));
+            push_keys(&mut stack);
+            op_checkmultisig(&mut stack, &ctx).unwrap();
+            assert_eq!(stack.main_stack, v);
+        }
+        /// wrong number of public keys
+        /// op_checkaggsig([msg,sig,pk1,pk2,pk3,2]) -> fail
+        let mut stack = Stack::new();
+        stack.push(StackEntry::Bytes(msg.clone()));
+        stack.push(StackEntry::Signature(agg_sig));
+        push_keys(&mut stack);
+        stack.pop();
+        stack.push(StackEntry::Num(2));
+        let b = op_checkaggsig(&mut stack, &ctx);
+        assert_eq!(b, Err(ScriptError::NumPubkeys));
+        /// no public keys
+        /// op_checkaggsig([msg,sig,0]) -> fail
+        let mut stack = Stack::new();
+        stack.push(StackEntry::Bytes(msg.clone()));
+        stack.push(StackEntry::Signature(agg_sig));
+        stack.push(StackEntry::Num(0));
+        let b = op_checkaggsig(&mut stack, &ctx);
+        assert_eq!(b, Err(ScriptError::NumPubkeys));
+        /// interpreted script
+        let mut script = Script::from(vec![StackEntry::Bytes(msg), StackEntry::Signature(agg_sig)]);
+        script
+            .stack
+            .extend(pks.iter().map(|pk| StackEntry::PubKey(*pk)));
+        script.stack.push(StackEntry::Num(pks.len()));
+        script.stack.push(StackEntry::Op(OpCodes::OP_CHECKAGGSIG));
+        assert_eq!(script.interpret_checked(), Err(ScriptError::DisabledOpcode));
+        let ctx = ScriptContext {
+            address_version: Some(NETWORK_VERSION_V0),
+            ..Default::default()
+        };
+        assert!(script.interpret_with_context(&ctx));
     }
 
     #[test]
-    fn test_is_valid_stack() {
-        // empty stack
-        let v = vec![];
-        let stack = Stack::from(v);
-        assert!(stack.is_valid());
-        // # items on interpreter stack <= 1000
-        let v = vec![StackEntry::Num(1); MAX_STACK_SIZE as usize];
-        let stack = Stack::from(v);
-        assert!(stack.is_valid());
-        // # items on interpreter stack > 1000
-        let v = vec![StackEntry::Num(1); (MAX_STACK_SIZE + 1) as usize];
-        let stack = Stack::from(v);
-        assert!(!stack.is_valid());
+    /// Test OP_CHECKMULTISIG_ORDERED
+    fn test_checkmultisig_ordered() {
+        let ctx = ScriptContext::default();
+        /// 2-of-3 multisig
+        /// op_checkmultisig_ordered([msg,sig1,sig2,2,pk1,pk2,pk3,3]) -> [1]
+        let (pk1, sk1) = sign::gen_keypair();
+        let (pk2, sk2) = sign::gen_keypair();
+        let (pk3, sk3) = sign::gen_keypair();
+        let msg = hex::encode(vec![0, 0, 0]);
+        let sig1 = sign::sign_detached(msg.as_bytes(), &sk1);
+        let sig2 = sign::sign_detached(msg.as_bytes(), &sk2);
+        let sig3 = sign::sign_detached(msg.as_bytes(), &sk3);
+        let mut stack = Stack::new();
+        stack.push(StackEntry::Bytes(msg.clone()));
+        stack.push(StackEntry::Signature(sig1));
+        stack.push(StackEntry::Signature(sig2));
+        stack.push(StackEntry::Num(2));
+        stack.push(StackEntry::PubKey(pk1));
+        stack.push(StackEntry::PubKey(pk2));
+        stack.push(StackEntry::PubKey(pk3));
+        stack.push(StackEntry::Num(3));
+        let mut v: Vec<StackEntry> = vec![StackEntry::Num(1)];
+        op_checkmultisig_ordered(&mut stack, &ctx).unwrap();
+        assert_eq!(stack.main_stack, v);
+        /// skipped public key
+        /// op_checkmultisig_ordered([msg,sig1,sig3,2,pk1,pk2,pk3,3]) -> [1]
+        let mut stack = Stack::new();
+        stack.push(StackEntry::Bytes(msg.clone()));
+        stack.push(StackEntry::Signature(sig1));
+        stack.push(StackEntry::Signature(sig3));
+        stack.push(StackEntry::Num(2));
+        stack.push(StackEntry::PubKey(pk1));
+        stack.push(StackEntry::PubKey(pk2));
+        stack.push(StackEntry::PubKey(pk3));
+        stack.push(StackEntry::Num(3));
+        let mut v: Vec<StackEntry> = vec![StackEntry::Num(1)];
+        op_checkmultisig_ordered(&mut stack, &ctx).unwrap();
+        assert_eq!(stack.main_stack, v);
+        /// wrong ordering
+        /// op_checkmultisig_ordered([msg,sig2,sig1,2,pk1,pk2,pk3,3]) -> [0]
+        let mut stack = Stack::new();
+        stack.push(StackEntry::Bytes(msg.clone()));
+        stack.push(StackEntry::Signature(sig2));
+        stack.push(StackEntry::Signature(sig1));
+        stack.push(StackEntry::Num(2));
+        stack.push(StackEntry::PubKey(pk1));
+        stack.push(StackEntry::PubKey(pk2));
+        stack.push(StackEntry::PubKey(pk3));
+        stack.push(StackEntry::Num(3));
+        let mut v: Vec<StackEntry> = vec![StackEntry::Num(0)];
+        op_checkmultisig_ordered(&mut stack, &ctx).unwrap();
+        assert_eq!(stack.main_stack, v);
+        /// same signature twice
+        /// op_checkmultisig_ordered([msg,sig1,sig1,2,pk1,pk2,pk3,3]) -> [0]
+        let mut stack = Stack::new();
+        stack.push(StackEntry::Bytes(msg));
+        stack.push(StackEntry::Signature(sig1));
+        stack.push(StackEntry::Signature(sig1));
+        stack.push(StackEntry::Num(2));
+        stack.push(StackEntry::PubKey(pk1));
+        stack.push(StackEntry::PubKey(pk2));
+        stack.push(StackEntry::PubKey(pk3));
+        stack.push(StackEntry::Num(3));
+        let mut v: Vec<StackEntry> = vec![StackEntry::Num(0)];
+        op_checkmultisig_ordered(&mut stack, &ctx).unwrap();
+        assert_eq!(stack.main_stack, v);
+        /// not enough signatures
+        /// op_checkmultisig_ordered([sig1,2,pk1,pk2,pk3,3]) -> fail
+        let mut stack = Stack::new();
+        stack.push(StackEntry::Signature(sig1));
+        stack.push(StackEntry::Num(2));
+        stack.push(StackEntry::PubKey(pk1));
+        stack.push(StackEntry::PubKey(pk2));
+        stack.push(StackEntry::PubKey(pk3));
+        stack.push(StackEntry::Num(3));
+        let b = op_checkmultisig_ordered(&mut stack, &ctx);
+        assert_eq!(b, Err(ScriptError::NumSignatures))
     }
 
     #[test]
-    fn test_interpret_script() {
-        // empty script
-        let v = vec![];
-        let script = Script::from(v);
-        assert!(script.interpret());
-        // OP_0
-        let v = vec![StackEntry::Op(OpCodes::OP_0)];
-        let script = Script::from(v);
-        assert!(!script.interpret());
-        // OP_1
-        let v = vec![StackEntry::Op(OpCodes::OP_1)];
-        let script = Script::from(v);
+    fn test_interpret_multisig_lock_ordered() {
+        let (pk1, sk1) = sign::gen_keypair();
+        let (pk2, sk2) = sign::gen_keypair();
+        let (pk3, _) = sign::gen_keypair();
+        let check_data = hex::encode(vec![0, 0, 0]);
+        let sig1 = sign::sign_detached(check_data.as_bytes(), &sk1);
+        let sig2 = sign::sign_detached(check_data.as_bytes(), &sk2);
+        let lock = Script::multisig_lock_ordered(2, 3, check_data, vec![pk1, pk2, pk3]);
+        // signatures in key order
+        let mut script = lock.clone();
+        let sigs = vec![StackEntry::Signature(sig1), StackEntry::Signature(sig2)];
+        script.stack.splice(1..1, sigs);
         assert!(script.interpret());
-        // OP_1 OP_2 OP_ADD OP_3 OP_EQUAL
+        // signatures out of key order
+        let mut script = lock;
+        let sigs = vec![StackEntry::Signature(sig2), StackEntry::Signature(sig1)];
+        script.stack.splice(1..1, sigs);
+        assert!(!script.interpret());
+    }
+
+    /*---- LOCKTIME OPS ----*/
+
+    #[test]
+    /// Test OP_CHECKLOCKTIMEVERIFY
+    fn test_checklocktimeverify() {
+        /// op_checklocktimeverify([10]) -> [10] if block_height >= 10
+        let ctx = ScriptContext {
+            block_height: 10,
+            ..Default::default()
+        };
+        let mut stack = Stack::new();
+        stack.push(StackEntry::Num(10));
+        let mut v: Vec<StackEntry> = vec![StackEntry::Num(10)];
+        let b = op_checklocktimeverify(&mut stack, &ctx);
+        assert!(b.is_ok());
+        assert_eq!(stack.main_stack, v);
+        /// op_checklocktimeverify([11]) -> fail if block_height < 11
+        let mut stack = Stack::new();
+        stack.push(StackEntry::Num(11));
+        let b = op_checklocktimeverify(&mut stack, &ctx);
+        assert_eq!(b, Err(ScriptError::Locktime));
+        /// op_checklocktimeverify(["hello"]) -> fail
+        let mut stack = Stack::new();
+        stack.push(StackEntry::Bytes("hello".to_string()));
+        let b = op_checklocktimeverify(&mut stack, &ctx);
+        assert_eq!(b, Err(ScriptError::ItemType));
+        /// op_checklocktimeverify([]) -> fail
+        let mut stack = Stack::new();
+        let b = op_checklocktimeverify(&mut stack, &ctx);
+        assert_eq!(b, Err(ScriptError::NumItems))
+    }
+
+    #[test]
+    fn test_interpret_script_with_locktime() {
+        // 10 OP_CHECKLOCKTIMEVERIFY
         let v = vec![
-            StackEntry::Op(OpCodes::OP_1),
-            StackEntry::Op(OpCodes::OP_2),
-            StackEntry::Op(OpCodes::OP_ADD),
-            StackEntry::Op(OpCodes::OP_3),
-            StackEntry::Op(OpCodes::OP_EQUAL),
+            StackEntry::Num(10),
+            StackEntry::Op(OpCodes::OP_CHECKLOCKTIMEVERIFY),
         ];
         let script = Script::from(v);
-        assert!(script.interpret());
-        // script length <= 10000 bytes
-        let v = vec![StackEntry::Bytes("a".repeat(500)); 20];
-        let script = Script::from(v);
-        assert!(script.interpret());
-        // script length > 10000 bytes
-        let v = vec![StackEntry::Bytes("a".repeat(500)); 21];
-        let script = Script::from(v);
+        // unlocked heights
+        assert!(script.interpret_with_context(&ScriptContext {
+            block_height: 10,
+            ..Default::default()
+        }));
+        assert!(script.interpret_with_context(&ScriptContext {
+            block_height: 11,
+            ..Default::default()
+        }));
+        // locked heights
+        assert!(!script.interpret_with_context(&ScriptContext {
+            block_height: 9,
+            ..Default::default()
+        }));
         assert!(!script.interpret());
-        // # opcodes <= 201
-        let v = vec![StackEntry::Op(OpCodes::OP_1); MAX_OPS_PER_SCRIPT as usize];
-        let script = Script::from(v);
-        assert!(script.interpret());
-        // # opcodes > 201
-        let v = vec![StackEntry::Op(OpCodes::OP_1); (MAX_OPS_PER_SCRIPT + 1) as usize];
+    }
+
+    /*---- COVENANT OPS ----*/
+
+    #[test]
+    /// Test OP_CHECKTEMPLATEVERIFY
+    fn test_checktemplateverify() {
+        let mut tx = Transaction::new();
+        tx.outputs = vec![TxOut::new_token_amount("a".repeat(64), TokenAmount(5))];
+        let ctx = ScriptContext::default().with_spending_tx(&tx);
+        let commitment = hex::encode(tx.template_hash());
+        /// op_checktemplateverify([h]) -> [h] if h == template_hash
+        let mut stack = Stack::new();
+        stack.push(StackEntry::Bytes(commitment.clone()));
+        let v = vec![StackEntry::Bytes(commitment.clone())];
+        let b = op_checktemplateverify(&mut stack, &ctx);
+        assert!(b.is_ok());
+        assert_eq!(stack.main_stack, v);
+        /// op_checktemplateverify([h]) -> fail if h != template_hash
+        let mut stack = Stack::new();
+        stack.push(StackEntry::Bytes(hex::encode([0; 32])));
+        let b = op_checktemplateverify(&mut stack, &ctx);
+        assert_eq!(b, Err(ScriptError::TemplateMismatch));
+        /// op_checktemplateverify([h]) -> fail if no spending transaction is known
+        let mut stack = Stack::new();
+        stack.push(StackEntry::Bytes(commitment));
+        let b = op_checktemplateverify(&mut stack, &ScriptContext::default());
+        assert_eq!(b, Err(ScriptError::TemplateMismatch));
+        /// op_checktemplateverify(["hello"]) -> fail
+        let mut stack = Stack::new();
+        stack.push(StackEntry::Bytes("hello".to_string()));
+        let b = op_checktemplateverify(&mut stack, &ctx);
+        assert_eq!(b, Err(ScriptError::ItemType));
+        /// op_checktemplateverify([]) -> fail
+        let mut stack = Stack::new();
+        let b = op_checktemplateverify(&mut stack, &ctx);
+        assert_eq!(b, Err(ScriptError::NumItems))
+    }
+
+    #[test]
+    fn test_interpret_script_with_template() {
+        let mut tx = Transaction::new();
+        tx.outputs = vec![TxOut::new_token_amount("a".repeat(64), TokenAmount(5))];
+        // <template_hash> OP_CHECKTEMPLATEVERIFY
+        let v = vec![
+            StackEntry::Bytes(hex::encode(tx.template_hash())),
+            StackEntry::Op(OpCodes::OP_CHECKTEMPLATEVERIFY),
+        ];
         let script = Script::from(v);
+        // committed template
+        let ctx = ScriptContext::default().with_spending_tx(&tx);
+        assert!(script.interpret_with_context(&ctx));
+        // altered amount and address
+        let mut altered = tx.clone();
+        altered.outputs[0].value = Asset::token_u64(6);
+        let ctx = ScriptContext::default().with_spending_tx(&altered);
+        assert_eq!(
+            script.interpret_with_context_checked(&ctx),
+            Err(ScriptError::TemplateMismatch)
+        );
+        let mut altered = tx.clone();
+        altered.outputs[0].script_public_key = Some("b".repeat(64));
+        let ctx = ScriptContext::default().with_spending_tx(&altered);
+        assert!(!script.interpret_with_context(&ctx));
         assert!(!script.interpret());
-        // # items on interpreter stack <= 1000
-        let v = vec![StackEntry::Num(1); MAX_STACK_SIZE as usize];
+    }
+
+    #[test]
+    fn test_interpret_script_escrow_lock() {
+        let keys: Vec<_> = (0..3).map(|_| sign::gen_keypair()).collect();
+        let pub_keys: Vec<PublicKey> = keys.iter().map(|(pk, _)| *pk).collect();
+        let check_data = hex::encode(vec![0, 0, 0]);
+        let sigs: Vec<Signature> = keys
+            .iter()
+            .map(|(_, sk)| sign::sign_detached(check_data.as_bytes(), sk))
+            .collect();
+        let lock = Script::escrow_lock(2, &pub_keys, 10, 1);
+        let at_height = |block_height| ScriptContext {
+            block_height,
+            ..Default::default()
+        };
+        let escrow = |sigs: &[Signature], branch: usize| {
+            let mut script = Script::multisig_unlock(check_data.clone(), sigs.to_vec());
+            script.stack.push(StackEntry::num(branch));
+            script.stack.extend(lock.stack.iter().cloned());
+            script
+        };
+        // 2-of-3 branch, before and after the timeout
+        let script = escrow(&sigs[..2], 1);
+        assert!(script.interpret_with_context(&at_height(0)));
+        assert!(script.interpret_with_context(&at_height(10)));
+        assert!(!escrow(&sigs[..1], 1).interpret_with_context(&at_height(10)));
+        // 1-of-3 fallback branch, only from the timeout
+        let script = escrow(&sigs[2..], 0);
+        assert!(!script.interpret_with_context(&at_height(9)));
+        assert!(script.interpret_with_context(&at_height(10)));
+        assert!(script.interpret_with_context(&at_height(11)));
+        assert_eq!(
+            script.interpret_with_context_checked(&at_height(9)),
+            Err(ScriptError::Locktime)
+        );
+        // signature from an outside key
+        let (_, other_sk) = sign::gen_keypair();
+        let other_sig = sign::sign_detached(check_data.as_bytes(), &other_sk);
+        assert!(!escrow(&[other_sig], 0).interpret_with_context(&at_height(10)));
+    }
+
+    #[test]
+    fn test_interpret_script_unbalanced_condition() {
+        // OP_ENDIF
+        let v = vec![StackEntry::Op(OpCodes::OP_ENDIF)];
         let script = Script::from(v);
-        assert!(script.interpret());
-        // # items on interpreter stack > 1000
-        let v = vec![StackEntry::Num(1); (MAX_STACK_SIZE + 1) as usize];
+        assert!(!script.interpret());
+        // OP_ELSE
+        let v = vec![StackEntry::Op(OpCodes::OP_ELSE)];
         let script = Script::from(v);
         assert!(!script.interpret());
+        // 1 OP_IF OP_ENDIF OP_ENDIF
+        let v = vec![
+            StackEntry::Num(1),
+            StackEntry::Op(OpCodes::OP_IF),
+            StackEntry::Op(OpCodes::OP_ENDIF),
+            StackEntry::Op(OpCodes::OP_ENDIF),
+        ];
+        let script = Script::from(v);
+        assert_eq!(script.interpret_checked(), Err(ScriptError::EmptyCondition));
+        // empty condition stack
+        let mut cond_stack = ConditionStack::new();
+        assert!(!cond_stack.pop());
+        assert!(!cond_stack.toggle());
+        assert!(cond_stack.is_empty());
     }
 
     #[test]
-    fn test_conditionals() {
-        // OP_1 OP_IF OP_2 OP_ELSE OP_3 OP_ENDIF
+    fn test_interpret_script_checked() {
+        // 1 OP_ADD
+        let v = vec![StackEntry::Num(1), StackEntry::Op(OpCodes::OP_ADD)];
+        let script = Script::from(v);
+        assert_eq!(script.interpret_checked(), Err(ScriptError::NumItems));
+        // 1 usize::MAX OP_ADD
         let v = vec![
-            StackEntry::Op(OpCodes::OP_1),
-            StackEntry::Op(OpCodes::OP_IF),
-            StackEntry::Op(OpCodes::OP_2),
-            StackEntry::Op(OpCodes::OP_ELSE),
-            StackEntry::Op(OpCodes::OP_3),
-            StackEntry::Op(OpCodes::OP_ENDIF),
+            StackEntry::Num(1),
+            StackEntry::Num(usize::MAX),
+            StackEntry::Op(OpCodes::OP_ADD),
         ];
         let script = Script::from(v);
-        assert!(script.interpret());
-        // OP_1 OP_IF OP_0 OP_ELSE OP_3 OP_ENDIF
+        assert_eq!(
+            script.interpret_checked(),
+            Err(ScriptError::ArithmeticOverflow)
+        );
+        // 1 OP_BURN
+        let v = vec![StackEntry::Num(1), StackEntry::Op(OpCodes::OP_BURN)];
+        let script = Script::from(v);
+        assert_eq!(script.interpret_checked(), Err(ScriptError::Burn));
+        // 0
+        let v = vec![StackEntry::Num(0)];
+        let script = Script::from(v);
+        assert_eq!(script.interpret_checked(), Err(ScriptError::Verify));
+        // 1 1 OP_IF
         let v = vec![
-            StackEntry::Op(OpCodes::OP_1),
+            StackEntry::Num(1),
+            StackEntry::Num(1),
             StackEntry::Op(OpCodes::OP_IF),
-            StackEntry::Op(OpCodes::OP_0),
-            StackEntry::Op(OpCodes::OP_ELSE),
-            StackEntry::Op(OpCodes::OP_3),
-            StackEntry::Op(OpCodes::OP_ENDIF),
         ];
         let script = Script::from(v);
-        assert!(!script.interpret());
-        // OP_0 OP_IF OP_2 OP_ELSE OP_3 OP_ENDIF
+        assert_eq!(
+            script.interpret_checked(),
+            Err(ScriptError::UnbalancedCondition)
+        );
+        // 1 2 OP_ADD 3 OP_EQUAL
         let v = vec![
-            StackEntry::Op(OpCodes::OP_0),
-            StackEntry::Op(OpCodes::OP_IF),
-            StackEntry::Op(OpCodes::OP_2),
-            StackEntry::Op(OpCodes::OP_ELSE),
-            StackEntry::Op(OpCodes::OP_3),
-            StackEntry::Op(OpCodes::OP_ENDIF),
+            StackEntry::Num(1),
+            StackEntry::Num(2),
+            StackEntry::Op(OpCodes::OP_ADD),
+            StackEntry::Num(3),
+            StackEntry::Op(OpCodes::OP_EQUAL),
         ];
         let script = Script::from(v);
-        assert!(script.interpret());
-        // OP_0 OP_IF OP_2 OP_ELSE OP_0 OP_ENDIF
+        assert_eq!(script.interpret_checked(), Ok(()));
+    }
+
+    #[test]
+    fn test_interpret_script_with_initial_stack() {
+        let (pk, sk) = sign::gen_keypair();
+        let (other_pk, _) = sign::gen_keypair();
+        let check_data = hex::encode(vec![0, 0, 0]);
+        let sig = sign::sign_detached(check_data.as_bytes(), &sk);
+        // OP_DUP OP_HASH256 <pubkeyhash> OP_EQUALVERIFY OP_CHECKSIG
+        let lock = Script::from(vec![
+            StackEntry::Op(OpCodes::OP_DUP),
+            StackEntry::Op(OpCodes::OP_HASH256),
+            StackEntry::PubKeyHash(construct_address(&pk)),
+            StackEntry::Op(OpCodes::OP_EQUALVERIFY),
+            StackEntry::Op(OpCodes::OP_CHECKSIG),
+        ]);
+        let unlock = |pk| {
+            vec![
+                StackEntry::Bytes(check_data.clone()),
+                StackEntry::Signature(sig),
+                StackEntry::PubKey(pk),
+            ]
+        };
+        // matches the full pay2pkh script
+        assert!(lock.interpret_with_initial_stack(unlock(pk)));
+        assert!(Script::pay2pkh(check_data.clone(), sig, pk, None).interpret());
+        // wrong public key
+        assert_eq!(
+            lock.interpret_with_initial_stack_checked(unlock(other_pk)),
+            Err(ScriptError::NotEqualItems)
+        );
+        // empty initial stack
+        assert_eq!(
+            lock.interpret_with_initial_stack_checked(Vec::new()),
+            Err(ScriptError::NumItems)
+        );
+        // opcodes cannot seed the stack
+        let initial = vec![StackEntry::Op(OpCodes::OP_1)];
+        let b = lock.interpret_with_initial_stack_checked(initial);
+        assert_eq!(b, Err(ScriptError::ItemType));
+        // initial stack exceeding the stack limits
+        let initial = vec![StackEntry::Num(1); MAX_STACK_SIZE as usize + 1];
+        let b = Script::new().interpret_with_initial_stack_checked(initial);
+        assert_eq!(b, Err(ScriptError::MaxStackSize));
+        let initial = vec![StackEntry::Bytes(
+            "a".repeat(MAX_SCRIPT_ITEM_SIZE as usize + 1),
+        )];
+        let b = Script::new().interpret_with_initial_stack_checked(initial);
+        assert_eq!(b, Err(ScriptError::ItemSize));
+    }
+
+    #[test]
+    fn test_verify_scripts() {
+        let (pk, sk) = sign::gen_keypair();
+        let check_data = hex::encode(vec![0, 0, 0]);
+        let sig = sign::sign_detached(check_data.as_bytes(), &sk);
+        let p2pkh = Script::pay2pkh(check_data.clone(), sig, pk, None);
+        let (unlock, lock) = p2pkh.stack.split_at(3);
+        let (unlock, lock) = (Script::from(unlock.to_vec()), Script::from(lock.to_vec()));
+        // data only unlock
+        assert_eq!(verify_scripts(&unlock, &lock), Ok(()));
+        // signature over other data
+        let other_sig = sign::sign_detached(b"other", &sk);
+        let mut bad_unlock = unlock.clone();
+        bad_unlock.stack[1] = StackEntry::Signature(other_sig);
+        let b = verify_scripts(&bad_unlock, &lock);
+        assert_eq!(b, Err(ScriptError::Verify));
+        // unlock containing opcodes, even ones that leave the stack unchanged
+        let mut op_unlock = unlock.clone();
+        op_unlock.stack.push(StackEntry::Op(OpCodes::OP_NOP));
+        let b = verify_scripts(&op_unlock, &lock);
+        assert_eq!(b, Err(ScriptError::NonPushUnlock));
+        let mut op_unlock = unlock.clone();
+        op_unlock.stack.insert(0, StackEntry::Op(OpCodes::OP_DUP));
+        let b = verify_scripts(&op_unlock, &lock);
+        assert_eq!(b, Err(ScriptError::NonPushUnlock));
+        // small numbers pushed with their opcodes are data
+        let mut num_unlock = unlock;
+        num_unlock.stack.insert(0, StackEntry::Op(OpCodes::OP_1));
+        assert!(num_unlock.is_push_only());
+        assert_eq!(verify_scripts(&num_unlock, &lock), Ok(()));
+    }
+
+    #[test]
+    fn test_interpret_script_with_limits() {
+        // 1 OP_DUP OP_DUP OP_DUP OP_DROP OP_DROP OP_DROP
+        let mut v = vec![StackEntry::Num(1)];
+        v.extend(vec![StackEntry::Op(OpCodes::OP_DUP); 3]);
+        v.extend(vec![StackEntry::Op(OpCodes::OP_DROP); 3]);
+        let script = Script::from(v);
+        let ctx = ScriptContext::default();
+        // default limits
+        assert_eq!(
+            script.interpret_with_limits(&ctx, &ScriptLimits::default()),
+            Ok(())
+        );
+        // op budget covers all executed opcodes
+        let limits = ScriptLimits {
+            max_ops: 6,
+            ..Default::default()
+        };
+        assert_eq!(script.interpret_with_limits(&ctx, &limits), Ok(()));
+        // op budget exhausted
+        let limits = ScriptLimits {
+            max_ops: 5,
+            ..Default::default()
+        };
+        assert_eq!(
+            script.interpret_with_limits(&ctx, &limits),
+            Err(ScriptError::ResourceExhausted)
+        );
+        // stack budget exceeded
+        let limits = ScriptLimits {
+            max_stack_size: 3,
+            ..Default::default()
+        };
+        assert_eq!(
+            script.interpret_with_limits(&ctx, &limits),
+            Err(ScriptError::MaxStackSize)
+        );
+        // item size budget exceeded
+        let v = vec![StackEntry::Bytes("a".repeat(10))];
+        let script = Script::from(v);
+        let limits = ScriptLimits {
+            max_item_size: 9,
+            ..Default::default()
+        };
+        assert_eq!(
+            script.interpret_with_limits(&ctx, &limits),
+            Err(ScriptError::ItemSize)
+        );
+        // skipped opcodes are not counted
+        // 0 OP_IF OP_DUP OP_DUP OP_ENDIF 1
         let v = vec![
-            StackEntry::Op(OpCodes::OP_0),
+            StackEntry::Num(0),
             StackEntry::Op(OpCodes::OP_IF),
-            StackEntry::Op(OpCodes::OP_2),
-            StackEntry::Op(OpCodes::OP_ELSE),
-            StackEntry::Op(OpCodes::OP_0),
+            StackEntry::Op(OpCodes::OP_DUP),
+            StackEntry::Op(OpCodes::OP_DUP),
             StackEntry::Op(OpCodes::OP_ENDIF),
+            StackEntry::Num(1),
         ];
         let script = Script::from(v);
-        assert!(!script.interpret());
-        // OP_0 OP_NOTIF OP_2 OP_ELSE OP_0 OP_ENDIF
+        let limits = ScriptLimits {
+            max_ops: 2,
+            ..Default::default()
+        };
+        assert_eq!(script.interpret_with_limits(&ctx, &limits), Ok(()));
+    }
+
+    #[test]
+    fn test_interpret_reserved_nops() {
+        // OP_NOP1 ... OP_NOP10
+        let v: Vec<StackEntry> = (1..=10)
+            .map(|i| StackEntry::Op(OpCodes::from_name(&format!("OP_NOP{i}")).unwrap()))
+            .collect();
+        let script = Script::from(v);
+        assert!(script.interpret());
+        // # opcodes <= 201
+        let v = vec![StackEntry::Op(OpCodes::OP_NOP4); MAX_OPS_PER_SCRIPT as usize];
+        let script = Script::from(v);
+        assert!(script.interpret());
+        // # opcodes > 201
+        let v = vec![StackEntry::Op(OpCodes::OP_NOP4); MAX_OPS_PER_SCRIPT as usize + 1];
+        let script = Script::from(v);
+        assert_eq!(script.interpret_checked(), Err(ScriptError::MaxOpsScript));
+    }
+
+    #[test]
+    fn test_interpret_adversarial_corpus() {
+        let max_item = "a".repeat(MAX_SCRIPT_ITEM_SIZE as usize);
+        let corpus = vec![
+            // Unbalanced conditionals
+            vec![StackEntry::Num(1), StackEntry::Op(OpCodes::OP_IF)],
+            vec![StackEntry::Op(OpCodes::OP_ENDIF)],
+            vec![
+                StackEntry::Num(1),
+                StackEntry::Op(OpCodes::OP_ELSE),
+                StackEntry::Num(1),
+            ],
+            vec![
+                StackEntry::Num(0),
+                StackEntry::Op(OpCodes::OP_NOTIF),
+                StackEntry::Num(1),
+                StackEntry::Op(OpCodes::OP_IF),
+                StackEntry::Num(1),
+            ],
+            // Huge OP_PICK / OP_ROLL indices
+            vec![
+                StackEntry::Num(1),
+                StackEntry::Num(usize::MAX),
+                StackEntry::Op(OpCodes::OP_PICK),
+            ],
+            vec![
+                StackEntry::Num(1),
+                StackEntry::Num(usize::MAX),
+                StackEntry::Op(OpCodes::OP_ROLL),
+            ],
+            // Max-size items
+            vec![
+                StackEntry::Bytes(max_item.clone()),
+                StackEntry::Bytes(max_item.clone()),
+                StackEntry::Op(OpCodes::OP_CAT),
+            ],
+            vec![
+                StackEntry::Bytes(max_item),
+                StackEntry::Num(usize::MAX),
+                StackEntry::Op(OpCodes::OP_SPLIT),
+            ],
+            // Overflowing arithmetic
+            vec![
+                StackEntry::Num(usize::MAX),
+                StackEntry::Num(1),
+                StackEntry::Op(OpCodes::OP_ADD),
+            ],
+            vec![
+                StackEntry::Num(usize::MAX),
+                StackEntry::Op(OpCodes::OP_1ADD),
+            ],
+            // Indices inside a multibyte character
+            vec![
+                StackEntry::Bytes("é".to_string()),
+                StackEntry::Num(1),
+                StackEntry::Num(1),
+                StackEntry::Op(OpCodes::OP_SUBSTR),
+            ],
+            vec![
+                StackEntry::Bytes("é".to_string()),
+                StackEntry::Num(1),
+                StackEntry::Op(OpCodes::OP_LEFT),
+            ],
+            vec![
+                StackEntry::Bytes("é".to_string()),
+                StackEntry::Num(1),
+                StackEntry::Op(OpCodes::OP_RIGHT),
+            ],
+        ];
+        // V0 runs the disabled opcodes the default context rejects up front
+        for v in corpus {
+            let script = Script::from(v);
+            let result = std::panic::catch_unwind(|| script.interpret());
+            assert_eq!(result.ok(), Some(false));
+            let result =
+                std::panic::catch_unwind(|| script.interpret_for_version(NetworkVersion::V0));
+            assert_eq!(result.ok(), Some(false));
+        }
+    }
+
+    #[test]
+    fn test_interpret_pushdata() {
+        // OP_PUSHDATA "a"
         let v = vec![
-            StackEntry::Op(OpCodes::OP_0),
-            StackEntry::Op(OpCodes::OP_NOTIF),
-            StackEntry::Op(OpCodes::OP_2),
-            StackEntry::Op(OpCodes::OP_ELSE),
-            StackEntry::Op(OpCodes::OP_0),
-            StackEntry::Op(OpCodes::OP_ENDIF),
+            StackEntry::Op(OpCodes::OP_PUSHDATA),
+            StackEntry::Bytes("a".to_string()),
         ];
         let script = Script::from(v);
-        assert!(script.interpret());
-        // OP_0 OP_IF OP_2 OP_ENDIF
+        assert_eq!(
+            script.interpret_trace(),
+            Ok(vec![StackEntry::Bytes("a".to_string())])
+        );
+        // OP_PUSHDATA "a"*521
+        let oversized = "a".repeat(MAX_SCRIPT_ITEM_SIZE as usize + 1);
         let v = vec![
-            StackEntry::Op(OpCodes::OP_1),
-            StackEntry::Op(OpCodes::OP_IF),
-            StackEntry::Op(OpCodes::OP_0),
-            StackEntry::Op(OpCodes::OP_ENDIF),
+            StackEntry::Op(OpCodes::OP_PUSHDATA),
+            StackEntry::Bytes(oversized.clone()),
         ];
         let script = Script::from(v);
-        assert!(!script.interpret());
-        // OP_1 OP_IF OP_2 OP_IF OP_3 OP_ELSE OP_0 OP_ENDIF OP_ENDIF
+        assert_eq!(script.interpret_checked(), Err(ScriptError::ItemSize));
+        // inline "a"*521 is rejected by the stack itself
+        let mut stack = Stack::new();
+        let b = stack.try_push(StackEntry::Bytes(oversized.clone()));
+        assert_eq!(b, Err(ScriptError::ItemSize));
+        let script = Script::from(vec![StackEntry::Bytes(oversized)]);
+        assert_eq!(script.interpret_checked(), Err(ScriptError::ItemSize));
+        // OP_PUSHDATA with no operand
+        let script = Script::from(vec![StackEntry::Op(OpCodes::OP_PUSHDATA)]);
+        assert_eq!(script.interpret_checked(), Err(ScriptError::NumItems));
+        // OP_0 OP_IF OP_PUSHDATA "a" OP_ENDIF OP_1: operand skipped with the opcode
         let v = vec![
-            StackEntry::Op(OpCodes::OP_1),
-            StackEntry::Op(OpCodes::OP_IF),
-            StackEntry::Op(OpCodes::OP_2),
-            StackEntry::Op(OpCodes::OP_IF),
-            StackEntry::Op(OpCodes::OP_3),
-            StackEntry::Op(OpCodes::OP_ELSE),
             StackEntry::Op(OpCodes::OP_0),
+            StackEntry::Op(OpCodes::OP_IF),
+            StackEntry::Op(OpCodes::OP_PUSHDATA),
+            StackEntry::Bytes("a".to_string()),
             StackEntry::Op(OpCodes::OP_ENDIF),
-            StackEntry::Op(OpCodes::OP_ENDIF),
+            StackEntry::Op(OpCodes::OP_1),
         ];
         let script = Script::from(v);
-        assert!(script.interpret());
-        // OP_1 OP_IF OP_0 OP_IF OP_3 OP_ELSE OP_0 OP_ENDIF OP_ENDIF
+        assert_eq!(script.interpret_checked(), Ok(()));
+        // OP_0 OP_IF OP_PUSHDATA OP_ENDIF OP_ENDIF OP_1: an opcode is not an operand
         let v = vec![
-            StackEntry::Op(OpCodes::OP_1),
-            StackEntry::Op(OpCodes::OP_IF),
             StackEntry::Op(OpCodes::OP_0),
             StackEntry::Op(OpCodes::OP_IF),
-            StackEntry::Op(OpCodes::OP_3),
-            StackEntry::Op(OpCodes::OP_ELSE),
-            StackEntry::Op(OpCodes::OP_0),
+            StackEntry::Op(OpCodes::OP_PUSHDATA),
             StackEntry::Op(OpCodes::OP_ENDIF),
             StackEntry::Op(OpCodes::OP_ENDIF),
+            StackEntry::Op(OpCodes::OP_1),
         ];
         let script = Script::from(v);
-        assert!(!script.interpret());
-        // OP_0 OP_IF OP_2 OP_IF OP_3 OP_ELSE OP_4 OP_ENDIF OP_ELSE OP_0 OP_ENDIF
+        assert_eq!(script.interpret_checked(), Err(ScriptError::ItemType));
+        // OP_0 OP_IF OP_PUSHDATA with no operand
         let v = vec![
             StackEntry::Op(OpCodes::OP_0),
             StackEntry::Op(OpCodes::OP_IF),
-            StackEntry::Op(OpCodes::OP_2),
-            StackEntry::Op(OpCodes::OP_IF),
-            StackEntry::Op(OpCodes::OP_3),
-            StackEntry::Op(OpCodes::OP_ELSE),
-            StackEntry::Op(OpCodes::OP_4),
-            StackEntry::Op(OpCodes::OP_ENDIF),
-            StackEntry::Op(OpCodes::OP_ELSE),
-            StackEntry::Op(OpCodes::OP_0),
-            StackEntry::Op(OpCodes::OP_ENDIF),
+            StackEntry::Op(OpCodes::OP_PUSHDATA),
         ];
         let script = Script::from(v);
-        assert!(!script.interpret());
-        // OP_1 OP_IF OP_1
+        assert_eq!(script.interpret_checked(), Err(ScriptError::NumItems));
+    }
+
+    #[test]
+    fn test_interpret_disabled_opcodes() {
+        // OP_2 OP_3 OP_MUL
         let v = vec![
-            StackEntry::Op(OpCodes::OP_1),
-            StackEntry::Op(OpCodes::OP_IF),
-            StackEntry::Op(OpCodes::OP_1),
+            StackEntry::Op(OpCodes::OP_2),
+            StackEntry::Op(OpCodes::OP_3),
+            StackEntry::Op(OpCodes::OP_MUL),
         ];
         let script = Script::from(v);
-        assert!(!script.interpret());
-        // OP_1 OP_IF OP_1 OP_ELSE OP_3
+        assert!(script.uses_disabled_opcodes());
+        assert_eq!(script.interpret_checked(), Err(ScriptError::DisabledOpcode));
+        for address_version in [NETWORK_VERSION_V0, NETWORK_VERSION_TEMP] {
+            let ctx = ScriptContext {
+                address_version: Some(address_version),
+                ..Default::default()
+            };
+            assert!(script.interpret_with_context(&ctx));
+        }
+        // OP_0 OP_IF OP_CAT OP_ENDIF OP_1: rejected even in an unexecuted branch
         let v = vec![
-            StackEntry::Op(OpCodes::OP_1),
+            StackEntry::Op(OpCodes::OP_0),
             StackEntry::Op(OpCodes::OP_IF),
+            StackEntry::Op(OpCodes::OP_CAT),
+            StackEntry::Op(OpCodes::OP_ENDIF),
             StackEntry::Op(OpCodes::OP_1),
-            StackEntry::Op(OpCodes::OP_ELSE),
-            StackEntry::Op(OpCodes::OP_3),
         ];
         let script = Script::from(v);
-        assert!(!script.interpret());
-        // OP_2 OP_ELSE OP_3 OP_ENDIF
+        assert_eq!(script.interpret_checked(), Err(ScriptError::DisabledOpcode));
+        // OP_2 OP_3 OP_ADD
         let v = vec![
             StackEntry::Op(OpCodes::OP_2),
-            StackEntry::Op(OpCodes::OP_ELSE),
             StackEntry::Op(OpCodes::OP_3),
-            StackEntry::Op(OpCodes::OP_ENDIF),
+            StackEntry::Op(OpCodes::OP_ADD),
         ];
         let script = Script::from(v);
-        assert!(!script.interpret());
-        // OP_IF
-        let v = vec![StackEntry::Op(OpCodes::OP_IF)];
-        let script = Script::from(v);
-        assert!(!script.interpret());
-        // OP_NOTIF
-        let v = vec![StackEntry::Op(OpCodes::OP_NOTIF)];
-        let script = Script::from(v);
-        assert!(!script.interpret());
-        // OP_ELSE
-        let v = vec![StackEntry::Op(OpCodes::OP_ELSE)];
+        assert!(!script.uses_disabled_opcodes());
+        assert!(script.interpret());
+    }
+
+    #[test]
+    fn test_interpret_numeric_bound_lock() {
+        let lock = Script::numeric_bound_lock(5, 10);
+        let unlocked = |n: usize| {
+            let mut stack = vec![StackEntry::Num(n)];
+            stack.extend(lock.stack.iter().cloned());
+            Script::from(stack)
+        };
+        // in range
+        assert!(unlocked(5).interpret());
+        assert!(unlocked(9).interpret());
+        // out of range
+        assert_eq!(unlocked(4).interpret_checked(), Err(ScriptError::Verify));
+        assert_eq!(unlocked(10).interpret_checked(), Err(ScriptError::Verify));
+        // missing unlock
+        assert_eq!(lock.interpret_checked(), Err(ScriptError::NumItems));
+    }
+
+    #[test]
+    fn test_interpret_trace() {
+        // OP_1 OP_2 OP_ADD
+        let v = vec![
+            StackEntry::Op(OpCodes::OP_1),
+            StackEntry::Op(OpCodes::OP_2),
+            StackEntry::Op(OpCodes::OP_ADD),
+        ];
         let script = Script::from(v);
-        assert!(!script.interpret());
-        // OP_ENDIF
-        let v = vec![StackEntry::Op(OpCodes::OP_ENDIF)];
+        assert_eq!(script.interpret_trace(), Ok(vec![StackEntry::Num(3)]));
+        let (steps, result) = script.interpret_with_steps();
+        assert_eq!(result, Ok(vec![StackEntry::Num(3)]));
+        assert_eq!(
+            steps,
+            vec![
+                (OpCodes::OP_1, vec![StackEntry::Num(1)]),
+                (OpCodes::OP_2, vec![StackEntry::Num(1), StackEntry::Num(2)]),
+                (OpCodes::OP_ADD, vec![StackEntry::Num(3)]),
+            ]
+        );
+        // OP_1 OP_ADD
+        let v = vec![
+            StackEntry::Op(OpCodes::OP_1),
+            StackEntry::Op(OpCodes::OP_ADD),
+        ];
         let script = Script::from(v);
-        assert!(!script.interpret());
+        assert_eq!(script.interpret_trace(), Err(ScriptError::NumItems));
+        let (steps, result) = script.interpret_with_steps();
+        assert_eq!(result, Err(ScriptError::NumItems));
+        assert_eq!(steps, vec![(OpCodes::OP_1, vec![StackEntry::Num(1)])]);
     }
 
     #[test]
-    fn test_burn_script() {
-        let v = vec![StackEntry::Op(OpCodes::OP_BURN)];
+    fn test_is_valid_script() {
+        // empty script
+        let v = vec![];
         let script = Script::from(v);
-        assert!(!script.interpret());
+        assert!(script.is_valid());
+        // script length <= 10000 bytes
+        let v = vec![StackEntry::Bytes("a".repeat(500)); 20];
+        let script = Script::from(v);
+        assert!(script.is_valid());
+        // script length > 10000 bytes
+        let v = vec![StackEntry::Bytes("a".repeat(500)); 21];
+        let script = Script::from(v);
+        assert!(!script.is_valid());
+        // # opcodes <= 201
+        let v = vec![StackEntry::Op(OpCodes::OP_1); MAX_OPS_PER_SCRIPT as usize];
+        let script = Script::from(v);
+        assert!(script.is_valid());
+        // # opcodes > 201
+        let v = vec![StackEntry::Op(OpCodes::OP_1); (MAX_OPS_PER_SCRIPT + 1) as usize];
+        let script = Script::from(v);
+        assert!(!script.is_valid());
     }
 
-    /// Util function to create p2pkh TxIns
-    fn create_multisig_tx_ins(tx_values: Vec<TxConstructor>, m: usize) -> Vec<TxIn> {
-        let mut tx_ins = Vec::new();
-
-        for entry in tx_values {
-            let mut new_tx_in = TxIn::new();
-            new_tx_in.script_signature = Script::multisig_validation(
-                m,
-                entry.pub_keys.len(),
-                entry.previous_out.t_hash.clone(),
-                entry.signatures,
-                entry.pub_keys,
-            );
-            new_tx_in.previous_out = Some(entry.previous_out);
-
-            tx_ins.push(new_tx_in);
+    #[test]
+    fn test_script_asm_pay2pkh() {
+        let (pk, sk) = sign::gen_keypair();
+        let check_data = hex::encode(vec![0, 0, 0]);
+        let sig = sign::sign_detached(check_data.as_bytes(), &sk);
+        let script = Script::pay2pkh(check_data, sig, pk, None);
+        // full assembly round-trips
+        let asm = script.to_asm_full();
+        assert_eq!(Script::from_asm(&asm), Ok(script.clone()));
+        // truncated assembly is readable but cannot be parsed back
+        let asm = script.to_asm();
+        let pkh = construct_address(&pk);
+        let truncated_pkh = format!("{}..{}", &pkh[..8], &pkh[pkh.len() - 8..]);
+        assert!(asm.starts_with("<bytes:000000> <signature:"));
+        assert!(asm.ends_with(&format!(
+            "OP_DUP OP_HASH256 <pubkeyhash:{truncated_pkh}> OP_EQUALVERIFY OP_CHECKSIG"
+        )));
+        assert_eq!(Script::from_asm(&asm), Err(ScriptError::InvalidAsm));
+    }
+
+    #[test]
+    fn test_script_asm_unsafe_values() {
+        let values = ["ünïcödé façade wörds", "a b", "<x>", "0xab", "a..b", ""];
+        for v in values {
+            let script = Script::from(vec![
+                StackEntry::Bytes(v.to_owned()),
+                StackEntry::PubKeyHash(v.to_owned()),
+            ]);
+            // full assembly round-trips
+            let asm = script.to_asm_full();
+            assert_eq!(asm.split_whitespace().count(), 2);
+            assert_eq!(Script::from_asm(&asm), Ok(script.clone()));
+            // truncated assembly does not split multibyte characters
+            let asm = script.to_asm();
+            assert_eq!(asm.split_whitespace().count(), 2);
         }
-
-        tx_ins
+        // values unsafe as tokens are rendered as hex
+        let script = Script::from(vec![StackEntry::Bytes("é".to_owned())]);
+        assert_eq!(script.to_asm(), "<bytes:0xc3a9>");
+        // malformed hex values
+        let script = Script::from_asm("<bytes:0xc3>");
+        assert_eq!(script, Err(ScriptError::InvalidAsm));
     }
 
-    /// Util function to create multisig member TxIns
-    fn create_multisig_member_tx_ins(tx_values: Vec<TxConstructor>) -> Vec<TxIn> {
-        let mut tx_ins = Vec::new();
-
-        for entry in tx_values {
-            let mut new_tx_in = TxIn::new();
-            new_tx_in.script_signature = Script::member_multisig(
-                entry.previous_out.t_hash.clone(),
-                entry.pub_keys[0],
-                entry.signatures[0],
-            );
-            new_tx_in.previous_out = Some(entry.previous_out);
-
-            tx_ins.push(new_tx_in);
-        }
-
-        tx_ins
+    #[test]
+    fn test_script_asm_multisig_lock() {
+        let pks: Vec<PublicKey> = (0..3).map(|_| sign::gen_keypair().0).collect();
+        let script = Script::multisig_lock(2, 3, hex::encode(vec![0, 0, 0]), pks);
+        let asm = script.to_asm_full();
+        assert!(asm.starts_with("<bytes:000000> 2 <pubkey:"));
+        assert!(asm.ends_with("> 3 OP_CHECKMULTISIG"));
+        assert_eq!(Script::from_asm(&asm), Ok(script));
     }
 
     #[test]
-    /// Checks that a correct create script is validated as such
-    fn test_pass_create_script_valid() {
-        let asset = Asset::receipt(1, None, None);
-        let asset_hash = construct_tx_in_signable_asset_hash(&asset);
-        let (pk, sk) = sign::gen_keypair();
-        let signature = sign::sign_detached(asset_hash.as_bytes(), &sk);
-
-        let script = Script::new_create_asset(0, asset_hash, signature, pk);
-        assert!(tx_has_valid_create_script(&script, &asset));
+    fn test_merge_multisig_unlock() {
+        let keypairs: Vec<_> = (0..3).map(|_| sign::gen_keypair()).collect();
+        let check_data = hex::encode(vec![0, 0, 0]);
+        let sigs: Vec<Signature> = keypairs
+            .iter()
+            .map(|(_, sk)| sign::sign_detached(check_data.as_bytes(), sk))
+            .collect();
+        let lock = keypairs
+            .iter()
+            .fold(ScriptBuilder::new().push_num(2), |b, (pk, _)| {
+                b.push_pubkey(*pk)
+            })
+            .push_num(3)
+            .push_op(OpCodes::OP_CHECKMULTISIG)
+            .build()
+            .unwrap();
+        let unlock_with = |unlock: &Script| {
+            let mut stack = unlock.stack.clone();
+            stack.extend(lock.stack.iter().cloned());
+            Script::from(stack)
+        };
+        // two single-signature partials
+        let partials = vec![
+            Script::multisig_unlock(check_data.clone(), vec![sigs[0]]),
+            Script::multisig_unlock(check_data.clone(), vec![sigs[2]]),
+        ];
+        let merged = Script::merge_multisig_unlock(&partials).unwrap();
+        assert_eq!(
+            merged,
+            Script::multisig_unlock(check_data.clone(), vec![sigs[0], sigs[2]])
+        );
+        assert!(!unlock_with(&partials[0]).interpret());
+        assert!(unlock_with(&merged).interpret());
+        // duplicate signatures are dropped
+        let partials = vec![
+            Script::multisig_unlock(check_data.clone(), vec![sigs[0], sigs[1]]),
+            Script::multisig_unlock(check_data.clone(), vec![sigs[1]]),
+        ];
+        let merged = Script::merge_multisig_unlock(&partials).unwrap();
+        assert_eq!(
+            merged,
+            Script::multisig_unlock(check_data.clone(), vec![sigs[0], sigs[1]])
+        );
+        assert!(unlock_with(&merged).interpret());
+        // partials over different check data
+        let partials = vec![
+            Script::multisig_unlock(check_data.clone(), vec![sigs[0]]),
+            Script::multisig_unlock(hex::encode(vec![0, 0, 1]), vec![sigs[1]]),
+        ];
+        let b = Script::merge_multisig_unlock(&partials);
+        assert_eq!(b, Err(ScriptError::NotEqualItems));
+        // malformed partial
+        let partials = vec![
+            Script::multisig_unlock(check_data, vec![sigs[0]]),
+            lock.clone(),
+        ];
+        let b = Script::merge_multisig_unlock(&partials);
+        assert_eq!(b, Err(ScriptError::ItemType));
+        // no partials
+        let b = Script::merge_multisig_unlock(&[]);
+        assert_eq!(b, Err(ScriptError::NumItems));
     }
 
     #[test]
-    /// Checks that metadata is validated correctly if too large
-    fn test_fail_create_receipt_script_invalid() {
-        let metadata = String::from_utf8_lossy(&[0; MAX_METADATA_BYTES + 1]).to_string();
-        let asset = Asset::receipt(1, None, Some(metadata));
-        let asset_hash = construct_tx_in_signable_asset_hash(&asset);
+    fn test_script_interpret_for_version() {
+        let versions = [
+            NetworkVersion::V0,
+            NetworkVersion::Temp,
+            NetworkVersion::Current,
+        ];
+        for version in versions {
+            assert_eq!(
+                NetworkVersion::from_address_version(version.address_version()),
+                version
+            );
+        }
+        // disabled opcodes, allowed for legacy versions only
+        let script = Script::from(vec![
+            StackEntry::Bytes("a".to_owned()),
+            StackEntry::Bytes("b".to_owned()),
+            StackEntry::Op(OpCodes::OP_CAT),
+            StackEntry::Bytes("ab".to_owned()),
+            StackEntry::Op(OpCodes::OP_EQUAL),
+        ]);
+        let results: Vec<bool> = versions
+            .iter()
+            .map(|v| script.interpret_for_version(*v))
+            .collect();
+        assert_eq!(results, vec![true, true, false]);
         let (pk, sk) = sign::gen_keypair();
-        let signature = sign::sign_detached(asset_hash.as_bytes(), &sk);
-
-        let script = Script::new_create_asset(0, asset_hash, signature, pk);
-        assert!(!tx_has_valid_create_script(&script, &asset));
+        // standard hash opcodes
+        for version in versions {
+            let script = Script::pay2pkh(
+                hex::encode(vec![0, 0, 0]),
+                sign::sign_detached(b"", &sk),
+                pk,
+                version.address_version(),
+            );
+            assert_eq!(script.stack[4], StackEntry::Op(version.hash_opcode()));
+        }
     }
 
     #[test]
-    /// Checks whether addresses are validated correctly
-    fn test_validate_addresses_correctly() {
+    fn test_script_minimal_encoding() {
+        let ctx = ScriptContext::default();
+        let legacy_ctx = ScriptContext {
+            address_version: Some(NETWORK_VERSION_V0),
+            ..Default::default()
+        };
+        // OP_1 OP_16 17 OP_ADD
+        let script = ScriptBuilder::new()
+            .push_op(OpCodes::OP_1)
+            .push_op(OpCodes::OP_16)
+            .push_num(17)
+            .push_op(OpCodes::OP_ADD)
+            .build()
+            .unwrap();
+        assert!(script.is_minimally_encoded());
+        assert!(script.has_standard_encoding(&ctx));
+        // 1 where OP_1 is standard
+        let script = Script::from(vec![
+            StackEntry::Num(1),
+            StackEntry::Op(OpCodes::OP_16),
+            StackEntry::Op(OpCodes::OP_ADD),
+        ]);
+        assert!(script.interpret());
+        assert!(!script.is_minimally_encoded());
+        assert!(!script.has_standard_encoding(&ctx));
+        assert!(script.has_standard_encoding(&legacy_ctx));
+        // 0 where OP_0 is standard
+        let script = Script::from(vec![StackEntry::Num(0)]);
+        assert!(!script.is_minimally_encoded());
+        assert!(Script::from(vec![StackEntry::Op(OpCodes::OP_0)]).is_minimally_encoded());
+        // num picks the minimal encoding
+        assert_eq!(StackEntry::num(1), StackEntry::Op(OpCodes::OP_1));
+        assert_eq!(StackEntry::num(17), StackEntry::Num(17));
+        assert_eq!(StackEntry::num(16).as_num(), Some(16));
+        assert_eq!(StackEntry::Num(16).as_num(), Some(16));
+    }
+
+    #[test]
+    fn test_script_constructors_keep_num_encoding() {
         let (pk, _) = sign::gen_keypair();
-        let address = construct_address(&pk);
-
-        assert!(address_has_valid_length(&address));
-        assert!(address_has_valid_length(&hex::encode([0; 32])));
-        assert!(!address_has_valid_length(&hex::encode([0; 64])));
+        let check_data = hex::encode(vec![0, 0, 0]);
+        // stock constructors push small numbers as Num, keeping existing scripts and hashes
+        let script = Script::multisig_lock(2, 3, check_data.clone(), vec![pk; 3]);
+        assert_eq!(script.stack[1], StackEntry::Num(2));
+        assert_eq!(script.stack[5], StackEntry::Num(3));
+        assert!(!script.is_minimally_encoded());
+        assert!(script.is_standard(NetworkVersion::Current));
+        // existing coinbases at heights up to SIXTEEN stay valid
+        for height in 0..=16 {
+            let mut tx = construct_coinbase_tx(height, TokenAmount(10), construct_address(&pk));
+            tx.inputs[0].script_signature = Script::from(vec![StackEntry::Num(height as usize)]);
+            assert!(tx_is_coinbase_valid(&tx, height));
+        }
+        // detectors read numbers in either encoding
+        let legacy = Script::from(vec![
+            StackEntry::Bytes(check_data),
+            StackEntry::Num(1),
+            StackEntry::PubKey(pk),
+            StackEntry::Num(1),
+            StackEntry::Op(OpCodes::OP_CHECKMULTISIG),
+        ]);
+        assert_eq!(legacy.is_multisig(), Some((1, 1)));
+        assert!(!legacy.is_minimally_encoded());
     }
 
     #[test]
-    /// Checks that correct member multisig scripts are validated as such
-    fn test_pass_member_multisig_valid() {
-        test_pass_member_multisig_valid_common(None);
+    fn test_script_classification() {
+        let (pk, sk) = sign::gen_keypair();
+        let check_data = hex::encode(vec![0, 0, 0]);
+        let sig = sign::sign_detached(check_data.as_bytes(), &sk);
+        let pub_keys = vec![pk, sign::gen_keypair().0, sign::gen_keypair().0];
+        // pay2pkh, for every address version
+        for version in [None, Some(NETWORK_VERSION_V0), Some(NETWORK_VERSION_TEMP)] {
+            let script = Script::pay2pkh(check_data.clone(), sig, pk, version);
+            assert!(script.is_p2pkh());
+            assert_eq!(script.is_multisig(), None);
+            assert!(!script.is_create());
+        }
+        // multisig
+        let script = Script::multisig_lock(2, 3, check_data.clone(), pub_keys.clone());
+        assert_eq!(script.is_multisig(), Some((2, 3)));
+        assert!(!script.is_p2pkh());
+        let script = Script::multisig_lock_ordered(1, 3, check_data.clone(), pub_keys.clone());
+        assert_eq!(script.is_multisig(), Some((1, 3)));
+        let script = Script::multisig_validation(
+            1,
+            3,
+            check_data.clone(),
+            vec![sig],
+            pub_keys.clone(),
+            None,
+        );
+        assert_eq!(script.is_multisig(), Some((1, 3)));
+        // m > n or a public key short
+        let script = Script::multisig_lock(4, 3, check_data.clone(), pub_keys.clone());
+        assert_eq!(script.is_multisig(), None);
+        let script = Script::multisig_lock(2, 3, check_data.clone(), pub_keys[1..].to_vec());
+        assert_eq!(script.is_multisig(), None);
+        // create
+        let script = Script::new_create_asset(0, check_data.clone(), sig, pk);
+        assert!(script.is_create());
+        assert!(!script.is_p2pkh());
+        assert_eq!(script.is_multisig(), None);
+        // neither
+        for script in [
+            Script::new_for_coinbase(0),
+            Script::member_multisig(check_data, pk, sig),
+            Script::numeric_bound_lock(0, 10),
+            Script::new(),
+        ] {
+            assert!(!script.is_p2pkh());
+            assert_eq!(script.is_multisig(), None);
+            assert!(!script.is_create());
+        }
     }
 
     #[test]
-    /// Checks that correct member multisig scripts are validated as such
-    fn test_pass_member_multisig_valid_v0() {
-        test_pass_member_multisig_valid_common(Some(NETWORK_VERSION_V0));
+    fn test_script_is_standard() {
+        let (pk, sk) = sign::gen_keypair();
+        let check_data = hex::encode(vec![0, 0, 0]);
+        let sig = sign::sign_detached(check_data.as_bytes(), &sk);
+        let pub_keys = vec![pk, sign::gen_keypair().0, sign::gen_keypair().0];
+        let versions = [
+            NetworkVersion::V0,
+            NetworkVersion::Temp,
+            NetworkVersion::Current,
+        ];
+        let redeem = Script::multisig_lock(2, 3, String::new(), pub_keys.clone());
+        // pay2pkh, standard for its own version only
+        for version in versions {
+            let script = Script::pay2pkh(check_data.clone(), sig, pk, version.address_version());
+            let standard: Vec<bool> = versions.iter().map(|v| script.is_standard(*v)).collect();
+            let expected: Vec<bool> = versions.iter().map(|v| *v == version).collect();
+            assert_eq!(standard, expected);
+        }
+        // multisig, create and P2SH, standard for every version
+        for script in [
+            Script::multisig_lock(2, 3, check_data.clone(), pub_keys.clone()),
+            Script::multisig_lock_ordered(2, 3, check_data.clone(), pub_keys.clone()),
+            Script::multisig_validation(
+                1,
+                3,
+                check_data.clone(),
+                vec![sig],
+                pub_keys.clone(),
+                None,
+            ),
+            Script::new_create_asset(0, check_data.clone(), sig, pk),
+            Script::p2sh_lock(&redeem),
+            Script::p2sh_unlock(&redeem, vec![StackEntry::Signature(sig)]),
+        ] {
+            assert!(script.is_valid());
+            assert!(versions.iter().all(|v| script.is_standard(*v)));
+        }
+        // non-minimal P2SH signature stack, standard for legacy versions only
+        let script = Script::p2sh_unlock(&redeem, vec![StackEntry::Num(1)]);
+        assert!(script.is_standard(NetworkVersion::V0));
+        assert!(!script.is_standard(NetworkVersion::Current));
+        // valid but non-standard
+        let custom = ScriptBuilder::new()
+            .push_op(OpCodes::OP_1)
+            .push_op(OpCodes::OP_2)
+            .push_op(OpCodes::OP_ADD)
+            .push_op(OpCodes::OP_3)
+            .push_op(OpCodes::OP_EQUAL)
+            .build()
+            .unwrap();
+        assert!(custom.interpret());
+        for script in [
+            custom.clone(),
+            Script::from(vec![
+                StackEntry::Bytes(check_data),
+                StackEntry::Op(OpCodes::OP_CAT),
+            ]),
+            Script::p2sh_unlock(&custom, vec![]),
+            Script::p2sh_unlock(&redeem, vec![StackEntry::Op(OpCodes::OP_NOP)]),
+            Script::new_for_coinbase(0),
+            Script::numeric_bound_lock(0, 10),
+            Script::new(),
+        ] {
+            assert!(script.is_valid());
+            assert!(versions.iter().all(|v| !script.is_standard(*v)));
+        }
     }
 
     #[test]
-    /// Checks that correct member multisig scripts are validated as such
-    fn test_pass_member_multisig_valid_temp() {
-        test_pass_member_multisig_valid_common(Some(NETWORK_VERSION_TEMP));
+    fn test_script_is_standard_multisig_threshold() {
+        let check_data = hex::encode(vec![0, 0, 0]);
+        let pub_keys: Vec<PublicKey> = (0..3).map(|_| sign::gen_keypair().0).collect();
+        let lock = Script::multisig_lock(0, 3, check_data.clone(), pub_keys.clone());
+        let redeem = Script::multisig_lock(0, 3, String::new(), pub_keys.clone());
+        let validation = Script::multisig_validation(0, 3, check_data, vec![], pub_keys, None);
+        let unlock = Script::p2sh_unlock(&redeem, vec![]);
+        // 0-of-3 multisig, non-standard under the current version only
+        for script in [&lock, &validation, &unlock] {
+            assert!(script.is_valid());
+            assert!(script.is_standard(NetworkVersion::V0));
+            assert!(script.is_standard(NetworkVersion::Temp));
+            assert!(!script.is_standard(NetworkVersion::Current));
+        }
+        // still valid by consensus under legacy rules
+        assert!(validation.interpret_for_version(NetworkVersion::V0));
+        assert!(validation.interpret());
     }
 
-    fn test_pass_member_multisig_valid_common(address_version: Option<u64>) {
+    #[test]
+    fn test_script_opcodes_used() {
         let (pk, sk) = sign::gen_keypair();
-        let t_hash = hex::encode(vec![0, 0, 0]);
-        let signature = sign::sign_detached(t_hash.as_bytes(), &sk);
-
-        let tx_const = TxConstructor {
-            previous_out: OutPoint::new(t_hash, 0),
-            signatures: vec![signature],
-            pub_keys: vec![pk],
-            address_version,
-        };
-
-        let tx_ins = create_multisig_member_tx_ins(vec![tx_const]);
-
-        assert!(&tx_ins[0].clone().script_signature.interpret());
+        let check_data = hex::encode(vec![0, 0, 0]);
+        let sig = sign::sign_detached(check_data.as_bytes(), &sk);
+        // multisig lock
+        let script = Script::multisig_lock(2, 3, check_data.clone(), vec![pk; 3]);
+        assert_eq!(
+            script.opcodes_used(),
+            BTreeSet::from([OpCodes::OP_CHECKMULTISIG])
+        );
+        assert!(script.contains_op(OpCodes::OP_CHECKMULTISIG));
+        assert!(!script.contains_op(OpCodes::OP_CHECKSIG));
+        // pay2pkh
+        let script = Script::pay2pkh(check_data, sig, pk, None);
+        assert_eq!(
+            script.opcodes_used(),
+            BTreeSet::from([
+                OpCodes::OP_DUP,
+                OpCodes::OP_HASH256,
+                OpCodes::OP_EQUALVERIFY,
+                OpCodes::OP_CHECKSIG,
+            ])
+        );
+        assert!(script.contains_op(OpCodes::OP_CHECKSIG));
+        assert!(!script.contains_op(OpCodes::OP_CHECKMULTISIG));
+        // no opcodes, repeated opcodes
+        assert!(Script::new().opcodes_used().is_empty());
+        let script = Script::from_asm("OP_1 OP_DUP OP_DUP OP_ADD").unwrap();
+        assert_eq!(script.opcodes_used().len(), 3);
     }
 
     #[test]
-    /// Checks that incorrect member multisig scripts are validated as such
-    fn test_fail_member_multisig_invalid() {
-        test_fail_member_multisig_invalid_common(None);
+    fn test_script_from_asm() {
+        // opcodes and numbers
+        let script = Script::from_asm("1 2 OP_ADD 3 OP_EQUAL");
+        let v = vec![
+            StackEntry::Num(1),
+            StackEntry::Num(2),
+            StackEntry::Op(OpCodes::OP_ADD),
+            StackEntry::Num(3),
+            StackEntry::Op(OpCodes::OP_EQUAL),
+        ];
+        assert_eq!(script, Ok(Script::from(v)));
+        // unknown opcode
+        let script = Script::from_asm("1 OP_UNKNOWN");
+        assert_eq!(script, Err(ScriptError::InvalidAsm));
+        // malformed public key
+        let script = Script::from_asm("<pubkey:0000>");
+        assert_eq!(script, Err(ScriptError::InvalidAsm));
     }
 
     #[test]
-    /// Checks that incorrect member multisig scripts are validated as such
-    fn test_fail_member_multisig_invalid_v0() {
-        test_fail_member_multisig_invalid_common(Some(NETWORK_VERSION_V0));
+    fn test_script_bytes_round_trip() {
+        let (pk, sk) = sign::gen_keypair();
+        let check_data = hex::encode(vec![0, 0, 0]);
+        let sig = sign::sign_detached(check_data.as_bytes(), &sk);
+        let script = Script::pay2pkh(check_data, sig, pk, None);
+        // round trip
+        let bytes = script.to_bytes();
+        assert_eq!(bytes.len(), script.serialized_size());
+        assert_eq!(Script::from_bytes(&bytes), Ok(script));
+        // truncated bytes
+        let b = Script::from_bytes(&bytes[..bytes.len() - 1]);
+        assert_eq!(b, Err(ScriptError::InvalidEncoding));
+        // garbage bytes
+        let b = Script::from_bytes(&[0xff; 16]);
+        assert_eq!(b, Err(ScriptError::InvalidEncoding));
+    }
+
+    #[test]
+    fn test_script_compact_bytes_round_trip() {
+        let (pk, sk) = sign::gen_keypair();
+        let pks = (0..3).map(|_| sign::gen_keypair().0).collect();
+        let check_data = hex::encode(vec![0, 0, 0]);
+        let sig = sign::sign_detached(check_data.as_bytes(), &sk);
+        let scripts = vec![
+            Script::pay2pkh(check_data.clone(), sig, pk, None),
+            Script::multisig_lock(2, 3, check_data.clone(), pks),
+            Script::new_create_asset(0, check_data.clone(), sig, pk),
+            Script::from(vec![
+                StackEntry::Bytes("not hex".to_owned()),
+                StackEntry::Num(usize::MAX),
+                StackEntry::SignedNum(-1),
+                StackEntry::SignedNum(i64::MIN),
+            ]),
+        ];
+        // round trip, smaller than bincode
+        for script in scripts {
+            let bytes = script.to_compact_bytes();
+            assert!(bytes.len() < script.to_bytes().len());
+            assert_eq!(Script::from_compact_bytes(&bytes), Ok(script));
+        }
+        let script = Script::pay2pkh(check_data, sig, pk, None);
+        let bytes = script.to_compact_bytes();
+        assert_eq!((bytes.len(), script.to_bytes().len()), (141, 254));
+        // bytes truncated within an entry
+        for len in [1, 4, 6, 69, 100, 106, 107, 138] {
+            let b = Script::from_compact_bytes(&bytes[..len]);
+            assert_eq!(b, Err(ScriptError::InvalidEncoding));
+        }
+        // garbage bytes
+        let b = Script::from_compact_bytes(&[0xff; 16]);
+        assert_eq!(b, Err(ScriptError::InvalidEncoding));
+        let b = Script::from_compact_bytes(&[COMPACT_TAG_BYTES_HEX, 0xff, 0xff]);
+        assert_eq!(b, Err(ScriptError::InvalidEncoding));
+
+        // non-canonical encodings
+        let b = Script::from_compact_bytes(&[COMPACT_TAG_NUM, 0xfd, 0x01, 0x00]);
+        assert_eq!(b, Err(ScriptError::InvalidEncoding));
+        let b = Script::from_compact_bytes(&[COMPACT_TAG_NUM, 0xfe, 0xff, 0xff, 0x00, 0x00]);
+        assert_eq!(b, Err(ScriptError::InvalidEncoding));
+        let b = Script::from_compact_bytes(&[COMPACT_TAG_BYTES_TEXT, 0x02, b'0', b'0']);
+        assert_eq!(b, Err(ScriptError::InvalidEncoding));
+        let b = Script::from_compact_bytes(&[COMPACT_TAG_BYTES_TEXT, 0x02, b'0', b'Z']);
+        assert_eq!(
+            b,
+            Ok(Script::from(vec![StackEntry::Bytes("0Z".to_string())]))
+        );
     }
 
     #[test]
-    /// Checks that incorrect member multisig scripts are validated as such
-    fn test_fail_member_multisig_invalid_temp() {
-        test_fail_member_multisig_invalid_common(Some(NETWORK_VERSION_TEMP));
-    }
-
-    fn test_fail_member_multisig_invalid_common(address_version: Option<u64>) {
-        let (_pk, sk) = sign::gen_keypair();
-        let (pk, _sk) = sign::gen_keypair();
-        let t_hash = hex::encode(vec![0, 0, 0]);
-        let signature = sign::sign_detached(t_hash.as_bytes(), &sk);
-
-        let tx_const = TxConstructor {
-            previous_out: OutPoint::new(t_hash, 0),
-            signatures: vec![signature],
-            pub_keys: vec![pk],
-            address_version,
-        };
-
-        let tx_ins = create_multisig_member_tx_ins(vec![tx_const]);
-
-        assert!(!&tx_ins[0].clone().script_signature.interpret());
+    fn test_script_key_entry_lengths() {
+        let (pk, sk) = sign::gen_keypair();
+        let sig = sign::sign_detached(b"msg", &sk);
+        // well-formed entries
+        let mut stack = Stack::new();
+        assert!(stack.push(StackEntry::PubKey(pk)));
+        assert!(stack.push(StackEntry::Signature(sig)));
+        // wire bytes: stack length, variant index, then the key's length-prefixed bytes
+        let prefix = 8 + 4;
+        for (entry, len) in [
+            (StackEntry::PubKey(pk), ED25519_PUBLIC_KEY_LEN),
+            (StackEntry::Signature(sig), ED25519_SIGNATURE_LEN),
+        ] {
+            let script = Script::from(vec![entry]);
+            let bytes = script.to_bytes();
+            assert_eq!(bytes[prefix..prefix + 8], (len as u64).to_le_bytes());
+            assert_eq!(Script::from_bytes(&bytes), Ok(script));
+            // one byte short
+            let mut short = bytes.clone();
+            short[prefix..prefix + 8].copy_from_slice(&(len as u64 - 1).to_le_bytes());
+            short.pop();
+            let b = Script::from_bytes(&short);
+            assert_eq!(b, Err(ScriptError::InvalidEncoding));
+            // one byte long
+            let mut long = bytes;
+            long[prefix..prefix + 8].copy_from_slice(&(len as u64 + 1).to_le_bytes());
+            long.push(0);
+            let b = Script::from_bytes(&long);
+            assert_eq!(b, Err(ScriptError::InvalidEncoding));
+        }
+        // compact bytes with a short public key fail to interpret
+        let mut bytes = Script::from(vec![StackEntry::PubKey(pk)]).to_compact_bytes();
+        bytes.pop();
+        let b = Script::from_compact_bytes(&bytes).and_then(|s| s.interpret_checked());
+        assert_eq!(b, Err(ScriptError::InvalidEncoding));
     }
 
     #[test]
-    /// Checks that correct p2pkh transaction signatures are validated as such
-    fn test_pass_p2pkh_sig_valid() {
-        test_pass_p2pkh_sig_valid_common(None);
+    fn test_script_len_and_ops_count_limits() {
+        // script length at and past MAX_SCRIPT_SIZE
+        let script = Script::from(vec![StackEntry::Bytes(
+            "a".repeat(MAX_SCRIPT_SIZE as usize),
+        )]);
+        assert!(script.is_valid());
+        let script = Script::from(vec![StackEntry::Bytes(
+            "a".repeat(MAX_SCRIPT_SIZE as usize + 1),
+        )]);
+        assert!(!script.is_valid());
+        // # opcodes at and past MAX_OPS_PER_SCRIPT
+        let script = Script::from(vec![
+            StackEntry::Op(OpCodes::OP_1);
+            MAX_OPS_PER_SCRIPT as usize
+        ]);
+        assert!(script.is_valid());
+        let script = Script::from(vec![
+            StackEntry::Op(OpCodes::OP_1);
+            MAX_OPS_PER_SCRIPT as usize + 1
+        ]);
+        assert!(!script.is_valid());
     }
 
     #[test]
-    /// Checks that correct p2pkh transaction signatures are validated as such
-    fn test_pass_p2pkh_sig_valid_v0() {
-        test_pass_p2pkh_sig_valid_common(Some(NETWORK_VERSION_V0));
+    fn test_script_from_bytes_oversized() {
+        // script length > 10000 bytes
+        let script = Script::from(vec![StackEntry::Bytes("a".repeat(500)); 21]);
+        let b = Script::from_bytes(&script.to_bytes());
+        assert_eq!(b, Err(ScriptError::MaxScriptSize));
+        // # opcodes > 201
+        let script = Script::from(vec![
+            StackEntry::Op(OpCodes::OP_1);
+            MAX_OPS_PER_SCRIPT as usize + 1
+        ]);
+        let b = Script::from_bytes(&script.to_bytes());
+        assert_eq!(b, Err(ScriptError::MaxOpsScript));
     }
 
     #[test]
-    /// Checks that correct p2pkh transaction signatures are validated as such
-    fn test_pass_p2pkh_sig_valid_temp() {
-        test_pass_p2pkh_sig_valid_common(Some(NETWORK_VERSION_TEMP));
+    fn test_script_builder() {
+        // pay2pkh
+        let (pk, sk) = sign::gen_keypair();
+        let check_data = hex::encode(vec![0, 0, 0]);
+        let sig = sign::sign_detached(check_data.as_bytes(), &sk);
+        let script = ScriptBuilder::new()
+            .push_bytes(check_data.clone())
+            .push_signature(sig)
+            .push_pubkey(pk)
+            .push_op(OpCodes::OP_DUP)
+            .push_op(OpCodes::OP_HASH256)
+            .push_pubkey_hash(construct_address(&pk))
+            .push_op(OpCodes::OP_EQUALVERIFY)
+            .push_op(OpCodes::OP_CHECKSIG)
+            .build()
+            .unwrap();
+        assert_eq!(
+            script.stack,
+            Script::pay2pkh(check_data, sig, pk, None).stack
+        );
+        assert!(script.interpret());
+        // OP_1 OP_2 OP_ADD 3 OP_EQUAL
+        let script = ScriptBuilder::new()
+            .push_op(OpCodes::OP_1)
+            .push_op(OpCodes::OP_2)
+            .push_op(OpCodes::OP_ADD)
+            .push_num(3)
+            .push_op(OpCodes::OP_EQUAL)
+            .build()
+            .unwrap();
+        assert!(script.interpret());
+        // item size > 520 bytes
+        let script = ScriptBuilder::new()
+            .push_bytes("a".repeat(MAX_SCRIPT_ITEM_SIZE as usize + 1))
+            .build();
+        assert_eq!(script, Err(ScriptError::ItemSize));
+        // script length > 10000 bytes
+        let script = (0..21)
+            .fold(ScriptBuilder::new(), |b, _| b.push_bytes("a".repeat(500)))
+            .build();
+        assert_eq!(script, Err(ScriptError::MaxScriptSize));
+        // # opcodes > 201
+        let script = (0..MAX_OPS_PER_SCRIPT as usize + 1)
+            .fold(ScriptBuilder::new(), |b, _| b.push_op(OpCodes::OP_1))
+            .build();
+        assert_eq!(script, Err(ScriptError::MaxOpsScript));
     }
 
-    fn test_pass_p2pkh_sig_valid_common(address_version: Option<u64>) {
-        let (pk, sk) = sign::gen_keypair();
-        let outpoint = OutPoint {
-            t_hash: hex::encode(vec![0, 0, 0]),
+    #[test]
+    fn test_entry_size_accounting() {
+        // Num near usize::MAX is accounted for with the same size as any other Num
+        assert_eq!(
+            entry_size(&StackEntry::Num(usize::MAX)),
+            entry_size(&StackEntry::Num(0))
+        );
+        // pushing a Num near usize::MAX is accepted
+        let mut stack = Stack::new();
+        assert!(stack.push(StackEntry::Num(usize::MAX)));
+        assert_eq!(stack.main_stack, vec![StackEntry::Num(usize::MAX)]);
+        // script length == 10000 bytes
+        let num_entries = MAX_SCRIPT_SIZE as usize / entry_size(&StackEntry::Num(usize::MAX));
+        let v = vec![StackEntry::Num(usize::MAX); num_entries];
+        let script = Script::from(v);
+        assert!(script.is_valid());
+        // script length > 10000 bytes
+        let v = vec![StackEntry::Num(usize::MAX); num_entries + 1];
+        let script = Script::from(v);
+        assert!(!script.is_valid());
+        // pushing an item > 520 bytes is rejected
+        let mut stack = Stack::new();
+        assert!(!stack.push(StackEntry::Bytes(
+            "a".repeat(MAX_SCRIPT_ITEM_SIZE as usize + 1)
+        )));
+        assert!(!stack.push(StackEntry::PubKeyHash(
+            "a".repeat(MAX_SCRIPT_ITEM_SIZE as usize + 1)
+        )));
+        assert!(stack.main_stack.is_empty());
+    }
+
+    #[test]
+    fn test_is_valid_stack() {
+        // empty stack
+        let v = vec![];
+        let stack = Stack::from(v);
+        assert!(stack.is_valid());
+        // # items on interpreter stack <= 1000
+        let v = vec![StackEntry::Num(1); MAX_STACK_SIZE as usize];
+        let stack = Stack::from(v);
+        assert!(stack.is_valid());
+        // # items on interpreter stack > 1000
+        let v = vec![StackEntry::Num(1); (MAX_STACK_SIZE + 1) as usize];
+        let stack = Stack::from(v);
+        assert!(!stack.is_valid());
+    }
+
+    #[test]
+    fn test_is_valid_stack_with_alt_stack() {
+        let half = MAX_STACK_SIZE as usize / 2;
+        // # items on main and alt stacks combined <= 1000
+        let mut stack = Stack::from(vec![StackEntry::Num(1); half]);
+        stack.alt_stack = vec![StackEntry::Num(1); MAX_STACK_SIZE as usize - half];
+        assert!(stack.is_valid());
+        // # items on main and alt stacks combined > 1000, one over on either stack
+        let mut over_main = stack.clone();
+        over_main.main_stack.push(StackEntry::Num(1));
+        assert!(!over_main.is_valid());
+        let mut over_alt = stack.clone();
+        over_alt.alt_stack.push(StackEntry::Num(1));
+        assert!(!over_alt.is_valid());
+        // moving items to the alt stack keeps counting them
+        let num_moved = MAX_OPS_PER_SCRIPT as usize - 1;
+        let mut v = vec![StackEntry::Num(1); MAX_STACK_SIZE as usize];
+        v.extend(vec![StackEntry::Op(OpCodes::OP_TOALTSTACK); num_moved]);
+        let script = Script::from(v.clone());
+        assert!(script.interpret());
+        v.push(StackEntry::Num(1));
+        let script = Script::from(v);
+        assert_eq!(script.interpret_checked(), Err(ScriptError::MaxStackSize));
+    }
+
+    #[test]
+    fn test_is_valid_stack_memory() {
+        let max_item = StackEntry::Bytes("a".repeat(MAX_SCRIPT_ITEM_SIZE as usize));
+        let max_items = MAX_STACK_MEMORY_BYTES / MAX_SCRIPT_ITEM_SIZE as usize;
+        // # bytes on main and alt stacks combined <= MAX_STACK_MEMORY_BYTES
+        let mut stack = Stack::from(vec![max_item.clone(); max_items - 1]);
+        stack.alt_stack.push(max_item.clone());
+        assert!(stack.is_valid());
+        // # bytes > MAX_STACK_MEMORY_BYTES, # items < MAX_STACK_SIZE
+        stack.alt_stack.push(max_item.clone());
+        assert!(stack.main_stack.len() + stack.alt_stack.len() < MAX_STACK_SIZE as usize);
+        assert!(!stack.is_valid());
+        // duplicating max-size items trips the memory bound before the ops or item limits
+        let mut v = vec![max_item; 3];
+        v.extend(vec![StackEntry::Op(OpCodes::OP_3DUP); max_items / 3]);
+        let script = Script::from(v);
+        assert_eq!(script.interpret_checked(), Err(ScriptError::MaxStackMemory));
+    }
+
+    #[test]
+    fn test_interpret_script() {
+        // empty script
+        let v = vec![];
+        let script = Script::from(v);
+        assert!(script.interpret());
+        // OP_0
+        let v = vec![StackEntry::Op(OpCodes::OP_0)];
+        let script = Script::from(v);
+        assert!(!script.interpret());
+        // OP_1
+        let v = vec![StackEntry::Op(OpCodes::OP_1)];
+        let script = Script::from(v);
+        assert!(script.interpret());
+        // OP_1 OP_2 OP_ADD OP_3 OP_EQUAL
+        let v = vec![
+            StackEntry::Op(OpCodes::OP_1),
+            StackEntry::Op(OpCodes::OP_2),
+            StackEntry::Op(OpCodes::OP_ADD),
+            StackEntry::Op(OpCodes::OP_3),
+            StackEntry::Op(OpCodes::OP_EQUAL),
+        ];
+        let script = Script::from(v);
+        assert!(script.interpret());
+        // script length <= 10000 bytes
+        let v = vec![StackEntry::Bytes("a".repeat(500)); 20];
+        let script = Script::from(v);
+        assert!(script.interpret());
+        // script length > 10000 bytes
+        let v = vec![StackEntry::Bytes("a".repeat(500)); 21];
+        let script = Script::from(v);
+        assert!(!script.interpret());
+        // # opcodes <= 201
+        let v = vec![StackEntry::Op(OpCodes::OP_1); MAX_OPS_PER_SCRIPT as usize];
+        let script = Script::from(v);
+        assert!(script.interpret());
+        // # opcodes > 201
+        let v = vec![StackEntry::Op(OpCodes::OP_1); (MAX_OPS_PER_SCRIPT + 1) as usize];
+        let script = Script::from(v);
+        assert!(!script.interpret());
+        // # items on interpreter stack <= 1000
+        let v = vec![StackEntry::Num(1); MAX_STACK_SIZE as usize];
+        let script = Script::from(v);
+        assert!(script.interpret());
+        // # items on interpreter stack > 1000
+        let v = vec![StackEntry::Num(1); (MAX_STACK_SIZE + 1) as usize];
+        let script = Script::from(v);
+        assert!(!script.interpret());
+    }
+
+    #[test]
+    fn test_conditionals() {
+        // OP_1 OP_IF OP_2 OP_ELSE OP_3 OP_ENDIF
+        let v = vec![
+            StackEntry::Op(OpCodes::OP_1),
+            StackEntry::Op(OpCodes::OP_IF),
+            StackEntry::Op(OpCodes::OP_2),
+            StackEntry::Op(OpCodes::OP_ELSE),
+            StackEntry::Op(OpCodes::OP_3),
+            StackEntry::Op(OpCodes::OP_ENDIF),
+        ];
+        let script = Script::from(v);
+        assert!(script.interpret());
+        // OP_1 OP_IF OP_0 OP_ELSE OP_3 OP_ENDIF
+        let v = vec![
+            StackEntry::Op(OpCodes::OP_1),
+            StackEntry::Op(OpCodes::OP_IF),
+            StackEntry::Op(OpCodes::OP_0),
+            StackEntry::Op(OpCodes::OP_ELSE),
+            StackEntry::Op(OpCodes::OP_3),
+            StackEntry::Op(OpCodes::OP_ENDIF),
+        ];
+        let script = Script::from(v);
+        assert!(!script.interpret());
+        // OP_0 OP_IF OP_2 OP_ELSE OP_3 OP_ENDIF
+        let v = vec![
+            StackEntry::Op(OpCodes::OP_0),
+            StackEntry::Op(OpCodes::OP_IF),
+            StackEntry::Op(OpCodes::OP_2),
+            StackEntry::Op(OpCodes::OP_ELSE),
+            StackEntry::Op(OpCodes::OP_3),
+            StackEntry::Op(OpCodes::OP_ENDIF),
+        ];
+        let script = Script::from(v);
+        assert!(script.interpret());
+        // OP_0 OP_IF OP_2 OP_ELSE OP_0 OP_ENDIF
+        let v = vec![
+            StackEntry::Op(OpCodes::OP_0),
+            StackEntry::Op(OpCodes::OP_IF),
+            StackEntry::Op(OpCodes::OP_2),
+            StackEntry::Op(OpCodes::OP_ELSE),
+            StackEntry::Op(OpCodes::OP_0),
+            StackEntry::Op(OpCodes::OP_ENDIF),
+        ];
+        let script = Script::from(v);
+        assert!(!script.interpret());
+        // OP_0 OP_NOTIF OP_2 OP_ELSE OP_0 OP_ENDIF
+        let v = vec![
+            StackEntry::Op(OpCodes::OP_0),
+            StackEntry::Op(OpCodes::OP_NOTIF),
+            StackEntry::Op(OpCodes::OP_2),
+            StackEntry::Op(OpCodes::OP_ELSE),
+            StackEntry::Op(OpCodes::OP_0),
+            StackEntry::Op(OpCodes::OP_ENDIF),
+        ];
+        let script = Script::from(v);
+        assert!(script.interpret());
+        // OP_0 OP_IF OP_2 OP_ENDIF
+        let v = vec![
+            StackEntry::Op(OpCodes::OP_1),
+            StackEntry::Op(OpCodes::OP_IF),
+            StackEntry::Op(OpCodes::OP_0),
+            StackEntry::Op(OpCodes::OP_ENDIF),
+        ];
+        let script = Script::from(v);
+        assert!(!script.interpret());
+        // OP_1 OP_IF OP_2 OP_IF OP_3 OP_ELSE OP_0 OP_ENDIF OP_ENDIF
+        let v = vec![
+            StackEntry::Op(OpCodes::OP_1),
+            StackEntry::Op(OpCodes::OP_IF),
+            StackEntry::Op(OpCodes::OP_2),
+            StackEntry::Op(OpCodes::OP_IF),
+            StackEntry::Op(OpCodes::OP_3),
+            StackEntry::Op(OpCodes::OP_ELSE),
+            StackEntry::Op(OpCodes::OP_0),
+            StackEntry::Op(OpCodes::OP_ENDIF),
+            StackEntry::Op(OpCodes::OP_ENDIF),
+        ];
+        let script = Script::from(v);
+        assert!(script.interpret());
+        // OP_1 OP_IF OP_0 OP_IF OP_3 OP_ELSE OP_0 OP_ENDIF OP_ENDIF
+        let v = vec![
+            StackEntry::Op(OpCodes::OP_1),
+            StackEntry::Op(OpCodes::OP_IF),
+            StackEntry::Op(OpCodes::OP_0),
+            StackEntry::Op(OpCodes::OP_IF),
+            StackEntry::Op(OpCodes::OP_3),
+            StackEntry::Op(OpCodes::OP_ELSE),
+            StackEntry::Op(OpCodes::OP_0),
+            StackEntry::Op(OpCodes::OP_ENDIF),
+            StackEntry::Op(OpCodes::OP_ENDIF),
+        ];
+        let script = Script::from(v);
+        assert!(!script.interpret());
+        // OP_0 OP_IF OP_2 OP_IF OP_3 OP_ELSE OP_4 OP_ENDIF OP_ELSE OP_0 OP_ENDIF
+        let v = vec![
+            StackEntry::Op(OpCodes::OP_0),
+            StackEntry::Op(OpCodes::OP_IF),
+            StackEntry::Op(OpCodes::OP_2),
+            StackEntry::Op(OpCodes::OP_IF),
+            StackEntry::Op(OpCodes::OP_3),
+            StackEntry::Op(OpCodes::OP_ELSE),
+            StackEntry::Op(OpCodes::OP_4),
+            StackEntry::Op(OpCodes::OP_ENDIF),
+            StackEntry::Op(OpCodes::OP_ELSE),
+            StackEntry::Op(OpCodes::OP_0),
+            StackEntry::Op(OpCodes::OP_ENDIF),
+        ];
+        let script = Script::from(v);
+        assert!(!script.interpret());
+        // OP_1 OP_IF OP_1
+        let v = vec![
+            StackEntry::Op(OpCodes::OP_1),
+            StackEntry::Op(OpCodes::OP_IF),
+            StackEntry::Op(OpCodes::OP_1),
+        ];
+        let script = Script::from(v);
+        assert!(!script.interpret());
+        // OP_1 OP_IF OP_1 OP_ELSE OP_3
+        let v = vec![
+            StackEntry::Op(OpCodes::OP_1),
+            StackEntry::Op(OpCodes::OP_IF),
+            StackEntry::Op(OpCodes::OP_1),
+            StackEntry::Op(OpCodes::OP_ELSE),
+            StackEntry::Op(OpCodes::OP_3),
+        ];
+        let script = Script::from(v);
+        assert!(!script.interpret());
+        // OP_2 OP_ELSE OP_3 OP_ENDIF
+        let v = vec![
+            StackEntry::Op(OpCodes::OP_2),
+            StackEntry::Op(OpCodes::OP_ELSE),
+            StackEntry::Op(OpCodes::OP_3),
+            StackEntry::Op(OpCodes::OP_ENDIF),
+        ];
+        let script = Script::from(v);
+        assert!(!script.interpret());
+        // OP_IF
+        let v = vec![StackEntry::Op(OpCodes::OP_IF)];
+        let script = Script::from(v);
+        assert!(!script.interpret());
+        // OP_NOTIF
+        let v = vec![StackEntry::Op(OpCodes::OP_NOTIF)];
+        let script = Script::from(v);
+        assert!(!script.interpret());
+        // OP_ELSE
+        let v = vec![StackEntry::Op(OpCodes::OP_ELSE)];
+        let script = Script::from(v);
+        assert!(!script.interpret());
+        // OP_ENDIF
+        let v = vec![StackEntry::Op(OpCodes::OP_ENDIF)];
+        let script = Script::from(v);
+        assert!(!script.interpret());
+    }
+
+    #[test]
+    fn test_conditionals_alt_stack() {
+        // OP_1 OP_2 OP_0 OP_IF OP_TOALTSTACK OP_ENDIF
+        let v = vec![
+            StackEntry::Op(OpCodes::OP_1),
+            StackEntry::Op(OpCodes::OP_2),
+            StackEntry::Op(OpCodes::OP_0),
+            StackEntry::Op(OpCodes::OP_IF),
+            StackEntry::Op(OpCodes::OP_TOALTSTACK),
+            StackEntry::Op(OpCodes::OP_ENDIF),
+        ];
+        let script = Script::from(v);
+        assert_eq!(
+            script.interpret_trace(),
+            Ok(vec![StackEntry::Num(1), StackEntry::Num(2)])
+        );
+        // OP_1 OP_0 OP_IF OP_FROMALTSTACK OP_ENDIF
+        let v = vec![
+            StackEntry::Op(OpCodes::OP_1),
+            StackEntry::Op(OpCodes::OP_0),
+            StackEntry::Op(OpCodes::OP_IF),
+            StackEntry::Op(OpCodes::OP_FROMALTSTACK),
+            StackEntry::Op(OpCodes::OP_ENDIF),
+        ];
+        let script = Script::from(v);
+        assert_eq!(script.interpret_trace(), Ok(vec![StackEntry::Num(1)]));
+        // OP_2 OP_TOALTSTACK OP_0 OP_IF OP_1 OP_IF OP_FROMALTSTACK OP_ENDIF OP_ENDIF OP_1
+        let v = vec![
+            StackEntry::Op(OpCodes::OP_2),
+            StackEntry::Op(OpCodes::OP_TOALTSTACK),
+            StackEntry::Op(OpCodes::OP_0),
+            StackEntry::Op(OpCodes::OP_IF),
+            StackEntry::Op(OpCodes::OP_1),
+            StackEntry::Op(OpCodes::OP_IF),
+            StackEntry::Op(OpCodes::OP_FROMALTSTACK),
+            StackEntry::Op(OpCodes::OP_ENDIF),
+            StackEntry::Op(OpCodes::OP_ENDIF),
+            StackEntry::Op(OpCodes::OP_1),
+        ];
+        let script = Script::from(v);
+        assert_eq!(script.interpret_trace(), Ok(vec![StackEntry::Num(1)]));
+        // OP_2 OP_TOALTSTACK OP_0 OP_IF OP_0 OP_ELSE OP_FROMALTSTACK OP_ENDIF
+        let v = vec![
+            StackEntry::Op(OpCodes::OP_2),
+            StackEntry::Op(OpCodes::OP_TOALTSTACK),
+            StackEntry::Op(OpCodes::OP_0),
+            StackEntry::Op(OpCodes::OP_IF),
+            StackEntry::Op(OpCodes::OP_0),
+            StackEntry::Op(OpCodes::OP_ELSE),
+            StackEntry::Op(OpCodes::OP_FROMALTSTACK),
+            StackEntry::Op(OpCodes::OP_ENDIF),
+        ];
+        let script = Script::from(v);
+        assert_eq!(script.interpret_trace(), Ok(vec![StackEntry::Num(2)]));
+    }
+
+    #[test]
+    fn test_conditionals_skip_unexecuted_branch() {
+        // OP_0 OP_IF OP_BURN OP_ENDIF OP_1
+        let v = vec![
+            StackEntry::Op(OpCodes::OP_0),
+            StackEntry::Op(OpCodes::OP_IF),
+            StackEntry::Op(OpCodes::OP_BURN),
+            StackEntry::Op(OpCodes::OP_ENDIF),
+            StackEntry::Op(OpCodes::OP_1),
+        ];
+        let script = Script::from(v);
+        assert!(script.interpret());
+        let (steps, result) = script.interpret_with_steps();
+        let ops: Vec<OpCodes> = steps.into_iter().map(|(op, _)| op).collect();
+        assert_eq!(result, Ok(vec![StackEntry::Num(1)]));
+        assert_eq!(
+            ops,
+            vec![
+                OpCodes::OP_0,
+                OpCodes::OP_IF,
+                OpCodes::OP_ENDIF,
+                OpCodes::OP_1
+            ]
+        );
+        // OP_1 OP_IF OP_BURN OP_ENDIF OP_1
+        let v = vec![
+            StackEntry::Op(OpCodes::OP_1),
+            StackEntry::Op(OpCodes::OP_IF),
+            StackEntry::Op(OpCodes::OP_BURN),
+            StackEntry::Op(OpCodes::OP_ENDIF),
+            StackEntry::Op(OpCodes::OP_1),
+        ];
+        let script = Script::from(v);
+        assert_eq!(script.interpret_checked(), Err(ScriptError::Burn));
+        // OP_0 OP_NOTIF OP_1 OP_ELSE OP_0 OP_VERIFY <bytes> OP_ENDIF
+        let v = vec![
+            StackEntry::Op(OpCodes::OP_0),
+            StackEntry::Op(OpCodes::OP_NOTIF),
+            StackEntry::Op(OpCodes::OP_1),
+            StackEntry::Op(OpCodes::OP_ELSE),
+            StackEntry::Op(OpCodes::OP_0),
+            StackEntry::Op(OpCodes::OP_VERIFY),
+            StackEntry::Bytes("skipped".to_string()),
+            StackEntry::Op(OpCodes::OP_ENDIF),
+        ];
+        let script = Script::from(v);
+        assert_eq!(script.interpret_trace(), Ok(vec![StackEntry::Num(1)]));
+    }
+
+    #[test]
+    fn test_burn_script() {
+        let v = vec![StackEntry::Op(OpCodes::OP_BURN)];
+        let script = Script::from(v);
+        assert!(!script.interpret());
+    }
+
+    /// Util function to create p2pkh TxIns
+    fn create_multisig_tx_ins(tx_values: Vec<TxConstructor>, m: usize) -> Vec<TxIn> {
+        let mut tx_ins = Vec::new();
+
+        for entry in tx_values {
+            let mut new_tx_in = TxIn::new();
+            new_tx_in.script_signature = Script::multisig_validation(
+                m,
+                entry.pub_keys.len(),
+                entry.previous_out.t_hash.clone(),
+                entry.signatures,
+                entry.pub_keys,
+                entry.address_version,
+            );
+            new_tx_in.previous_out = Some(entry.previous_out);
+
+            tx_ins.push(new_tx_in);
+        }
+
+        tx_ins
+    }
+
+    /// Util function to create multisig member TxIns
+    fn create_multisig_member_tx_ins(tx_values: Vec<TxConstructor>) -> Vec<TxIn> {
+        let mut tx_ins = Vec::new();
+
+        for entry in tx_values {
+            let mut new_tx_in = TxIn::new();
+            new_tx_in.script_signature = Script::member_multisig(
+                entry.previous_out.t_hash.clone(),
+                entry.pub_keys[0],
+                entry.signatures[0],
+            );
+            new_tx_in.previous_out = Some(entry.previous_out);
+
+            tx_ins.push(new_tx_in);
+        }
+
+        tx_ins
+    }
+
+    #[test]
+    /// Checks that a correct create script is validated as such
+    fn test_pass_create_script_valid() {
+        let asset = Asset::receipt(1, None, None);
+        let asset_hash = construct_tx_in_signable_asset_hash(&asset);
+        let (pk, sk) = sign::gen_keypair();
+        let signature = sign::sign_detached(asset_hash.as_bytes(), &sk);
+
+        let script = Script::new_create_asset(0, asset_hash, signature, pk);
+        assert!(tx_has_valid_create_script(&script, &asset));
+    }
+
+    #[test]
+    /// Checks that create signatures are verified directly against the asset hash
+    fn test_create_sig_is_valid() {
+        let asset = Asset::receipt(1, None, None);
+        let other_asset = Asset::receipt(2, None, None);
+        let asset_hash = construct_tx_in_signable_asset_hash(&asset);
+        let other_asset_hash = construct_tx_in_signable_asset_hash(&other_asset);
+        let (pk, sk) = sign::gen_keypair();
+        let (other_pk, other_sk) = sign::gen_keypair();
+        let signature = sign::sign_detached(asset_hash.as_bytes(), &sk);
+
+        // Valid create
+        let script = Script::new_create_asset(0, asset_hash.clone(), signature, pk);
+        assert!(create_sig_is_valid(&script, &asset));
+
+        // Signature by a key other than the script's
+        let wrong_key_sig = sign::sign_detached(asset_hash.as_bytes(), &other_sk);
+        let script = Script::new_create_asset(0, asset_hash.clone(), wrong_key_sig, pk);
+        assert!(!create_sig_is_valid(&script, &asset));
+        assert!(!tx_has_valid_create_script(&script, &asset));
+
+        // Signature over a different asset
+        let other_asset_sig = sign::sign_detached(other_asset_hash.as_bytes(), &sk);
+        let script = Script::new_create_asset(0, asset_hash, other_asset_sig, pk);
+        assert!(!create_sig_is_valid(&script, &asset));
+        assert!(!tx_has_valid_create_script(&script, &asset));
+        let script = Script::new_create_asset(0, other_asset_hash, other_asset_sig, pk);
+        assert!(create_sig_is_valid(&script, &other_asset));
+        assert!(!create_sig_is_valid(&script, &asset));
+
+        // Not a create script
+        let script = Script::pay2pkh(String::new(), signature, other_pk, None);
+        assert!(!create_sig_is_valid(&script, &asset));
+    }
+
+    #[test]
+    /// Checks that metadata is validated correctly if too large
+    fn test_fail_create_receipt_script_invalid() {
+        let metadata = String::from_utf8_lossy(&[0; MAX_METADATA_BYTES + 1]).to_string();
+        let asset = Asset::receipt(1, None, Some(metadata));
+        let asset_hash = construct_tx_in_signable_asset_hash(&asset);
+        let (pk, sk) = sign::gen_keypair();
+        let signature = sign::sign_detached(asset_hash.as_bytes(), &sk);
+
+        let script = Script::new_create_asset(0, asset_hash, signature, pk);
+        assert!(!tx_has_valid_create_script(&script, &asset));
+    }
+
+    #[test]
+    /// Checks that metadata within the current limit is rejected under the stricter V0 limit
+    fn test_create_receipt_script_metadata_limit_for_version() {
+        let metadata = "a".repeat(MAX_METADATA_BYTES_V0 + 1);
+        let asset = Asset::receipt(1, None, Some(metadata));
+        let asset_hash = construct_tx_in_signable_asset_hash(&asset);
+        let (pk, sk) = sign::gen_keypair();
+        let signature = sign::sign_detached(asset_hash.as_bytes(), &sk);
+
+        let script = Script::new_create_asset(0, asset_hash, signature, pk);
+        assert_eq!(metadata_limit_for_version(None), MAX_METADATA_BYTES);
+        assert_eq!(
+            metadata_limit_for_version(Some(NETWORK_VERSION_V0)),
+            MAX_METADATA_BYTES_V0
+        );
+        assert!(tx_has_valid_create_script(&script, &asset));
+        assert!(tx_has_valid_create_script_for_version(
+            &script,
+            &asset,
+            Some(NETWORK_VERSION_TEMP)
+        ));
+        assert!(!tx_has_valid_create_script_for_version(
+            &script,
+            &asset,
+            Some(NETWORK_VERSION_V0)
+        ));
+    }
+
+    #[test]
+    /// Checks that create transactions are limited by the version of the creator's address
+    fn test_tx_is_valid_create_metadata_limit_for_version() {
+        //
+        // Arrange
+        //
+        let (pk, sk) = sign::gen_keypair();
+        let create = |metadata: String, address: String| {
+            let asset = Asset::receipt(1, None, Some(metadata));
+            let mut tx = construct_asset_create_tx(0, asset, pk, &sk);
+            tx.outputs[0].script_public_key = Some(address);
+            tx
+        };
+        let oversized_v0 = "a".repeat(MAX_METADATA_BYTES_V0 + 1);
+        let utxo_set = UtxoSet::new();
+
+        //
+        // Act
+        //
+        let current_result = tx_is_valid(
+            &create(oversized_v0.clone(), construct_address(&pk)),
+            utxo_set.lookup_fn(),
+        );
+        let v0_result = tx_is_valid(
+            &create(oversized_v0, construct_address_v0(&pk)),
+            utxo_set.lookup_fn(),
+        );
+        let v0_within_limit_result = tx_is_valid(
+            &create("a".repeat(MAX_METADATA_BYTES_V0), construct_address_v0(&pk)),
+            utxo_set.lookup_fn(),
+        );
+        let mut unsigned_tx = create(String::new(), construct_address(&pk));
+        unsigned_tx.outputs[0].value = Asset::receipt(2, None, None);
+        let unsigned_result = validate_batch(std::slice::from_ref(&unsigned_tx), &utxo_set);
+
+        //
+        // Assert
+        //
+        assert!(current_result);
+        assert!(!v0_result);
+        assert!(v0_within_limit_result);
+        assert_eq!(
+            unsigned_result,
+            vec![(0, Err(ValidationError::InvalidCreateScript))]
+        );
+    }
+
+    #[test]
+    /// Checks that receipt metadata is validated structurally when it is JSON
+    fn test_receipt_validate_metadata() {
+        let receipt = |metadata: &str| ReceiptAsset::new(1, None, Some(metadata.to_owned()));
+
+        // Absent or plain-text metadata
+        assert_eq!(ReceiptAsset::new(1, None, None).validate_metadata(), Ok(()));
+        assert_eq!(receipt("plain text").validate_metadata(), Ok(()));
+
+        // Valid JSON
+        let valid = "{\"name\":\"test\",\"description\":\"test\",\"image\":\"test\"}";
+        assert_eq!(receipt(valid).validate_metadata(), Ok(()));
+
+        // Oversized
+        let oversized = format!("{{\"name\":\"{}\"}}", "a".repeat(MAX_METADATA_BYTES));
+        assert_eq!(
+            receipt(&oversized).validate_metadata(),
+            Err(MetadataError::TooLarge)
+        );
+
+        // Malformed JSON
+        assert_eq!(
+            receipt("{\"name\":\"test\",").validate_metadata(),
+            Err(MetadataError::InvalidJson)
+        );
+
+        // Missing required key
+        assert_eq!(
+            receipt("{\"name\":\"test\"}").validate_metadata(),
+            Err(MetadataError::MissingKey("description".to_owned()))
+        );
+    }
+
+    #[test]
+    /// Checks that create scripts for receipts with malformed JSON metadata fail
+    fn test_fail_create_receipt_script_malformed_metadata() {
+        let metadata = "{\"name\":\"test\"".to_owned();
+        let asset = Asset::receipt(1, None, Some(metadata));
+        let asset_hash = construct_tx_in_signable_asset_hash(&asset);
+        let (pk, sk) = sign::gen_keypair();
+        let signature = sign::sign_detached(asset_hash.as_bytes(), &sk);
+
+        let script = Script::new_create_asset(0, asset_hash, signature, pk);
+        assert!(!tx_has_valid_create_script(&script, &asset));
+    }
+
+    #[test]
+    /// Checks whether addresses are validated correctly
+    fn test_validate_addresses_correctly() {
+        let (pk, _) = sign::gen_keypair();
+        let address = construct_address(&pk);
+
+        assert!(address_has_valid_length(&address));
+        assert!(address_has_valid_length(&hex::encode([0; 32])));
+        assert!(!address_has_valid_length(&hex::encode([0; 64])));
+        assert!(address_is_well_formed(&address));
+        assert!(address_is_well_formed(&construct_p2sh_address(
+            &Script::new()
+        )));
+        assert!(!address_is_well_formed(&"g".repeat(64)));
+        assert!(!address_is_well_formed(&"H".repeat(64)));
+        assert!(!address_is_well_formed(&hex::encode([0; 64])));
+    }
+
+    #[test]
+    /// Checks that correct member multisig scripts are validated as such
+    fn test_pass_member_multisig_valid() {
+        test_pass_member_multisig_valid_common(None);
+    }
+
+    #[test]
+    /// Checks that correct member multisig scripts are validated as such
+    fn test_pass_member_multisig_valid_v0() {
+        test_pass_member_multisig_valid_common(Some(NETWORK_VERSION_V0));
+    }
+
+    #[test]
+    /// Checks that correct member multisig scripts are validated as such
+    fn test_pass_member_multisig_valid_temp() {
+        test_pass_member_multisig_valid_common(Some(NETWORK_VERSION_TEMP));
+    }
+
+    fn test_pass_member_multisig_valid_common(address_version: Option<u64>) {
+        let (pk, sk) = sign::gen_keypair();
+        let t_hash = hex::encode(vec![0, 0, 0]);
+        let signature = sign::sign_detached(t_hash.as_bytes(), &sk);
+
+        let tx_const = TxConstructor {
+            previous_out: OutPoint::new(t_hash, 0),
+            signatures: vec![signature],
+            pub_keys: vec![pk],
+            address_version,
+        };
+
+        let tx_ins = create_multisig_member_tx_ins(vec![tx_const]);
+
+        assert!(&tx_ins[0].clone().script_signature.interpret());
+    }
+
+    #[test]
+    /// Checks that incorrect member multisig scripts are validated as such
+    fn test_fail_member_multisig_invalid() {
+        test_fail_member_multisig_invalid_common(None);
+    }
+
+    #[test]
+    /// Checks that incorrect member multisig scripts are validated as such
+    fn test_fail_member_multisig_invalid_v0() {
+        test_fail_member_multisig_invalid_common(Some(NETWORK_VERSION_V0));
+    }
+
+    #[test]
+    /// Checks that incorrect member multisig scripts are validated as such
+    fn test_fail_member_multisig_invalid_temp() {
+        test_fail_member_multisig_invalid_common(Some(NETWORK_VERSION_TEMP));
+    }
+
+    fn test_fail_member_multisig_invalid_common(address_version: Option<u64>) {
+        let (_pk, sk) = sign::gen_keypair();
+        let (pk, _sk) = sign::gen_keypair();
+        let t_hash = hex::encode(vec![0, 0, 0]);
+        let signature = sign::sign_detached(t_hash.as_bytes(), &sk);
+
+        let tx_const = TxConstructor {
+            previous_out: OutPoint::new(t_hash, 0),
+            signatures: vec![signature],
+            pub_keys: vec![pk],
+            address_version,
+        };
+
+        let tx_ins = create_multisig_member_tx_ins(vec![tx_const]);
+
+        assert!(!&tx_ins[0].clone().script_signature.interpret());
+    }
+
+    #[test]
+    /// Checks that correct p2pkh transaction signatures are validated as such
+    fn test_pass_p2pkh_sig_valid() {
+        test_pass_p2pkh_sig_valid_common(None);
+    }
+
+    #[test]
+    /// Checks that correct p2pkh transaction signatures are validated as such
+    fn test_pass_p2pkh_sig_valid_v0() {
+        test_pass_p2pkh_sig_valid_common(Some(NETWORK_VERSION_V0));
+    }
+
+    #[test]
+    /// Checks that correct p2pkh transaction signatures are validated as such
+    fn test_pass_p2pkh_sig_valid_temp() {
+        test_pass_p2pkh_sig_valid_common(Some(NETWORK_VERSION_TEMP));
+    }
+
+    fn test_pass_p2pkh_sig_valid_common(address_version: Option<u64>) {
+        let (pk, sk) = sign::gen_keypair();
+        let outpoint = OutPoint {
+            t_hash: hex::encode(vec![0, 0, 0]),
+            n: 0,
+        };
+
+        let hash_to_sign = construct_tx_in_signable_hash(&outpoint);
+        let signature = sign::sign_detached(hash_to_sign.as_bytes(), &sk);
+
+        let tx_const = TxConstructor {
+            previous_out: outpoint,
+            signatures: vec![signature],
+            pub_keys: vec![pk],
+            address_version,
+        };
+
+        let tx_ins = construct_payment_tx_ins(vec![tx_const]);
+        let tx_out_pk = construct_address_for(&pk, address_version);
+
+        assert!(tx_has_valid_p2pkh_sig(
+            &tx_ins[0].script_signature,
+            &hash_to_sign,
+            &tx_out_pk,
+            &ScriptContext::default()
+        ));
+    }
+
+    #[test]
+    /// Checks that invalid p2pkh transaction signatures are validated as such
+    fn test_fail_p2pkh_sig_invalid() {
+        test_fail_p2pkh_sig_invalid_common(None);
+    }
+
+    #[test]
+    /// Checks that invalid p2pkh transaction signatures are validated as such
+    fn test_fail_p2pkh_sig_invalid_v0() {
+        test_fail_p2pkh_sig_invalid_common(Some(NETWORK_VERSION_V0));
+    }
+
+    fn test_fail_p2pkh_sig_invalid_common(address_version: Option<u64>) {
+        let (pk, sk) = sign::gen_keypair();
+        let (second_pk, _s) = sign::gen_keypair();
+        let outpoint = OutPoint {
+            t_hash: hex::encode(vec![0, 0, 0]),
+            n: 0,
+        };
+
+        let hash_to_sign = construct_tx_in_signable_hash(&outpoint);
+        let signature = sign::sign_detached(hash_to_sign.as_bytes(), &sk);
+
+        let tx_const = TxConstructor {
+            previous_out: outpoint,
+            signatures: vec![signature],
+            pub_keys: vec![second_pk],
+            address_version,
+        };
+
+        let tx_ins = construct_payment_tx_ins(vec![tx_const]);
+        let tx_out_pk = construct_address(&pk);
+
+        assert!(!tx_has_valid_p2pkh_sig(
+            &tx_ins[0].script_signature,
+            &hash_to_sign,
+            &tx_out_pk,
+            &ScriptContext::default()
+        ));
+    }
+
+    #[test]
+    /// Checks that invalid p2pkh transaction signatures are validated as such
+    fn test_fail_p2pkh_sig_script_empty() {
+        test_fail_p2pkh_sig_script_empty_common(None);
+    }
+
+    #[test]
+    /// Checks that invalid p2pkh transaction signatures are validated as such
+    fn test_fail_p2pkh_sig_script_empty_v0() {
+        test_fail_p2pkh_sig_script_empty_common(Some(NETWORK_VERSION_V0));
+    }
+
+    #[test]
+    /// Checks that invalid p2pkh transaction signatures are validated as such
+    fn test_fail_p2pkh_sig_script_empty_temp() {
+        test_fail_p2pkh_sig_script_empty_common(Some(NETWORK_VERSION_V0));
+    }
+
+    fn test_fail_p2pkh_sig_script_empty_common(address_version: Option<u64>) {
+        let (pk, sk) = sign::gen_keypair();
+        let outpoint = OutPoint {
+            t_hash: hex::encode(vec![0, 0, 0]),
+            n: 0,
+        };
+
+        let hash_to_sign = construct_tx_in_signable_hash(&outpoint);
+        let signature = sign::sign_detached(hash_to_sign.as_bytes(), &sk);
+
+        let tx_const = TxConstructor {
+            previous_out: outpoint,
+            signatures: vec![signature],
+            pub_keys: vec![pk],
+            address_version,
+        };
+
+        let mut tx_ins = Vec::new();
+
+        for entry in vec![tx_const] {
+            let mut new_tx_in = TxIn::new();
+            new_tx_in.script_signature = Script::new();
+            new_tx_in.previous_out = Some(entry.previous_out);
+
+            tx_ins.push(new_tx_in);
+        }
+
+        let tx_out_pk = construct_address(&pk);
+
+        assert!(!tx_has_valid_p2pkh_sig(
+            &tx_ins[0].script_signature,
+            &hash_to_sign,
+            &tx_out_pk,
+            &ScriptContext::default()
+        ));
+    }
+
+    #[test]
+    /// Checks that invalid p2pkh transaction signatures are validated as such
+    fn test_fail_p2pkh_sig_script_invalid_struct() {
+        test_fail_p2pkh_sig_script_invalid_struct_common(None);
+    }
+
+    #[test]
+    /// Checks that invalid p2pkh transaction signatures are validated as such
+    fn test_fail_p2pkh_sig_script_invalid_struct_v0() {
+        test_fail_p2pkh_sig_script_invalid_struct_common(Some(NETWORK_VERSION_V0));
+    }
+
+    #[test]
+    /// Checks that invalid p2pkh transaction signatures are validated as such
+    fn test_fail_p2pkh_sig_script_invalid_struct_temp() {
+        test_fail_p2pkh_sig_script_invalid_struct_common(Some(NETWORK_VERSION_TEMP));
+    }
+
+    fn test_fail_p2pkh_sig_script_invalid_struct_common(address_version: Option<u64>) {
+        let (pk, sk) = sign::gen_keypair();
+        let outpoint = OutPoint {
+            t_hash: hex::encode(vec![0, 0, 0]),
             n: 0,
         };
 
-        let hash_to_sign = construct_tx_in_signable_hash(&outpoint);
-        let signature = sign::sign_detached(hash_to_sign.as_bytes(), &sk);
+        let hash_to_sign = construct_tx_in_signable_hash(&outpoint);
+        let signature = sign::sign_detached(hash_to_sign.as_bytes(), &sk);
+
+        let tx_const = TxConstructor {
+            previous_out: outpoint,
+            signatures: vec![signature],
+            pub_keys: vec![pk],
+            address_version,
+        };
+
+        let mut tx_ins = Vec::new();
+
+        for entry in vec![tx_const] {
+            let mut new_tx_in = TxIn::new();
+            new_tx_in.script_signature = Script::new();
+            new_tx_in
+                .script_signature
+                .stack
+                .push(StackEntry::Bytes("".to_string()));
+            new_tx_in.previous_out = Some(entry.previous_out);
+
+            tx_ins.push(new_tx_in);
+        }
+
+        let tx_out_pk = construct_address(&pk);
+
+        assert!(!tx_has_valid_p2pkh_sig(
+            &tx_ins[0].script_signature,
+            &hash_to_sign,
+            &tx_out_pk,
+            &ScriptContext::default()
+        ));
+    }
+
+    #[test]
+    /// Checks that correct multisig validation signatures are validated as such
+    fn test_pass_multisig_validation_valid() {
+        test_pass_multisig_validation_valid_common(None);
+    }
+
+    #[test]
+    /// Checks that correct multisig validation signatures are validated as such
+    fn test_pass_multisig_validation_valid_v0() {
+        test_pass_multisig_validation_valid_common(Some(NETWORK_VERSION_V0));
+    }
+
+    #[test]
+    /// Checks that correct multisig validation signatures are validated as such
+    fn test_pass_multisig_validation_valid_temp() {
+        test_pass_multisig_validation_valid_common(Some(NETWORK_VERSION_TEMP));
+    }
+
+    fn test_pass_multisig_validation_valid_common(address_version: Option<u64>) {
+        let (first_pk, first_sk) = sign::gen_keypair();
+        let (second_pk, second_sk) = sign::gen_keypair();
+        let (third_pk, third_sk) = sign::gen_keypair();
+        let check_data = hex::encode(vec![0, 0, 0]);
+
+        let m = 2;
+        let first_sig = sign::sign_detached(check_data.as_bytes(), &first_sk);
+        let second_sig = sign::sign_detached(check_data.as_bytes(), &second_sk);
+
+        let tx_const = TxConstructor {
+            previous_out: OutPoint::new(check_data, 0),
+            signatures: vec![first_sig, second_sig],
+            pub_keys: vec![first_pk, second_pk, third_pk],
+            address_version,
+        };
+
+        let tx_ins = create_multisig_tx_ins(vec![tx_const], m);
+
+        assert!(&tx_ins[0].script_signature.interpret());
+    }
+
+    #[test]
+    /// Checks that multisig scripts follow the hashing convention of their network version
+    fn test_pass_multisig_version_consistent() {
+        test_pass_multisig_version_consistent_common(None);
+    }
+
+    #[test]
+    /// Checks that multisig scripts follow the hashing convention of their network version
+    fn test_pass_multisig_version_consistent_v0() {
+        test_pass_multisig_version_consistent_common(Some(NETWORK_VERSION_V0));
+    }
+
+    #[test]
+    /// Checks that multisig scripts follow the hashing convention of their network version
+    fn test_pass_multisig_version_consistent_temp() {
+        test_pass_multisig_version_consistent_common(Some(NETWORK_VERSION_TEMP));
+    }
+
+    fn test_pass_multisig_version_consistent_common(address_version: Option<u64>) {
+        let keys: Vec<_> = (0..3).map(|_| sign::gen_keypair()).collect();
+        let pub_keys: Vec<PublicKey> = keys.iter().map(|(pk, _)| *pk).collect();
+        let check_data = hex::encode(vec![0, 0, 0]);
+        let signatures: Vec<Signature> = keys[..2]
+            .iter()
+            .map(|(_, sk)| sign::sign_detached(check_data.as_bytes(), sk))
+            .collect();
+
+        // Validation keys line up with the lock behind the versioned multisig address
+        let tx_const = TxConstructor {
+            previous_out: OutPoint::new(check_data.clone(), 0),
+            signatures: signatures.clone(),
+            pub_keys: pub_keys.clone(),
+            address_version,
+        };
+        let tx_ins = create_multisig_tx_ins(vec![tx_const], 2);
+        let validation = &tx_ins[0].script_signature;
+        let ordered_keys: Vec<PublicKey> = validation
+            .stack
+            .iter()
+            .filter_map(|entry| match entry {
+                StackEntry::PubKey(pk) => Some(*pk),
+                _ => None,
+            })
+            .collect();
+        let lock = Script::multisig_lock(2, 3, String::new(), ordered_keys);
+
+        assert!(validation.interpret());
+        assert_eq!(
+            construct_p2sh_address(&lock),
+            construct_multisig_address(2, &pub_keys, address_version)
+        );
+    }
+
+    #[test]
+    /// Validate tx_is_valid for multiple TxIn configurations
+    fn test_tx_is_valid() {
+        test_tx_is_valid_common(None, OpCodes::OP_HASH256);
+    }
+
+    #[test]
+    /// Validate tx_is_valid for multiple TxIn configurations
+    fn test_tx_is_valid_v0() {
+        test_tx_is_valid_common(Some(NETWORK_VERSION_V0), OpCodes::OP_HASH256_V0);
+    }
+
+    #[test]
+    /// Validate tx_is_valid for multiple TxIn configurations
+    fn test_tx_is_valid_temp() {
+        test_tx_is_valid_common(Some(NETWORK_VERSION_TEMP), OpCodes::OP_HASH256_TEMP);
+    }
+
+    fn test_tx_is_valid_common(address_version: Option<u64>, op_hash256: OpCodes) {
+        //
+        // Arrange
+        //
+        let (pk, sk) = sign::gen_keypair();
+        let tx_hash = hex::encode(vec![0, 0, 0]);
+        let tx_outpoint = OutPoint::new(tx_hash, 0);
+        let script_public_key = construct_address_for(&pk, address_version);
+        let tx_in_previous_out = TxOut::new_token_amount(script_public_key.clone(), TokenAmount(5));
+        let ongoing_tx_outs = vec![tx_in_previous_out.clone()];
+
+        let valid_bytes = construct_tx_in_signable_hash(&tx_outpoint);
+        let valid_sig = sign::sign_detached(valid_bytes.as_bytes(), &sk);
+
+        let mut utxo_set = UtxoSet::new();
+        utxo_set.insert(tx_outpoint.clone(), tx_in_previous_out.clone());
+
+        // Test cases:
+        let inputs = vec![
+            // 0. Happy case: valid test
+            (
+                vec![
+                    StackEntry::Bytes(valid_bytes),
+                    StackEntry::Signature(valid_sig),
+                    StackEntry::PubKey(pk),
+                    StackEntry::Op(OpCodes::OP_DUP),
+                    StackEntry::Op(op_hash256),
+                    StackEntry::PubKeyHash(script_public_key),
+                    StackEntry::Op(OpCodes::OP_EQUALVERIFY),
+                    StackEntry::Op(OpCodes::OP_CHECKSIG),
+                ],
+                true,
+            ),
+            // 2. Empty script
+            (vec![StackEntry::Bytes("".to_string())], false),
+        ];
+
+        //
+        // Act
+        //
+        let mut actual_result = Vec::new();
+        for (script, _) in &inputs {
+            let tx_ins = vec![TxIn {
+                script_signature: Script {
+                    stack: script.clone(),
+                },
+                previous_out: Some(tx_outpoint.clone()),
+            }];
+
+            let tx = Transaction {
+                inputs: tx_ins,
+                outputs: ongoing_tx_outs.clone(),
+                ..Default::default()
+            };
+
+            let result = tx_is_valid(&tx, utxo_set.lookup_fn());
+            actual_result.push(result);
+        }
+
+        //
+        // Assert
+        //
+        assert_eq!(
+            actual_result,
+            inputs.iter().map(|(_, e)| *e).collect::<Vec<bool>>(),
+        );
+    }
+
+    #[test]
+    /// ### Test Case 1
+    ///
+    ///  - *Tokens only*
+    /// -  *Success*
+    ///
+    /// 1. Inputs contain two `TxIn`s for `Token`s of amounts `3` and `2`
+    /// 2. Outputs contain `TxOut`s for `Token`s of amounts `3` and `2`
+    fn test_tx_drs_tokens_only_success() {
+        test_tx_drs_common(
+            &[(3, None, None), (2, None, None)],
+            &[(3, None), (2, None)],
+            true,
+        );
+    }
+
+    #[test]
+    /// ### Test Case 2
+    ///
+    ///  - *Tokens only*
+    /// -  *Failure*
+    ///
+    /// 1. Inputs contain two `TxIn`s for `Token`s of amounts `3` and `2`
+    /// 2. Outputs contain `TxOut`s for `Token`s of amounts `3` and `3`
+    /// 3. `TxIn` `Token`s amount does not match `TxOut` `Token`s amount
+    fn test_tx_drs_tokens_only_failure_amount_mismatch() {
+        test_tx_drs_common(
+            &[(3, None, None), (2, None, None)],
+            &[(3, None), (3, None)],
+            false,
+        );
+    }
+
+    #[test]
+    /// Checks that the diff of a transaction with a 1 token shortfall
+    /// reports its outputs as under its inputs by exactly 1 token
+    fn test_tx_amount_mismatch_diff() {
+        //
+        // Arrange
+        //
+        let (utxo, tx) = generate_tx_with_ins_and_outs_assets(
+            &[
+                (3, None, None),
+                (2, None, None),
+                (2, Some("drs_tx_hash"), None),
+            ],
+            &[(2, None), (2, None), (2, Some("drs_tx_hash"))],
+        );
+        let utxo = UtxoSet::from(utxo);
+
+        //
+        // Act
+        //
+        let diff = tx.output_totals().diff(&tx.input_totals(&utxo));
+
+        //
+        // Assert
+        //
+        assert!(!tx_is_valid(&tx, |v| utxo.get(v)));
+        assert_eq!(diff.under, AssetValues::token_u64(1));
+        assert!(diff.over.is_empty());
+        assert!(!diff.is_empty());
+        assert_eq!(diff.to_string(), "tokens under by 1");
+        assert!(tx
+            .input_totals(&utxo)
+            .diff(&tx.input_totals(&utxo))
+            .is_empty());
+    }
+
+    #[test]
+    /// ### Test Case 3
+    ///
+    ///  - *Receipts only*
+    /// -  *Failure*
+    ///
+    /// 1. Inputs contain two `TxIn`s for `Receipt`s of amount `3` and `2` with different `drs_tx_hash` values
+    /// 2. Outputs contain `TxOut`s for `Receipt`s of amount `3` and `3`
+    /// 3. `TxIn` DRS matches `TxOut` DRS for `Receipt`s; Amount of `Receipt`s spent does not match    
+    fn test_tx_drs_receipts_only_failure_amount_mismatch() {
+        test_tx_drs_common(
+            &[
+                (3, Some("drs_tx_hash_1"), None),
+                (2, Some("drs_tx_hash_2"), None),
+            ],
+            &[(3, Some("drs_tx_hash_1")), (3, Some("drs_tx_hash_2"))],
+            false,
+        );
+    }
+
+    #[test]
+    /// ### Test Case 4
+    ///
+    ///  - *Receipts only*
+    /// -  *Failure*
+    ///
+    /// 1. Inputs contain two `TxIn`s for `Receipt`s of amount `3` and `2` with different `drs_tx_hash` values
+    /// 2. Outputs contain `TxOut`s for `Receipt`s of amount `3` and `2`
+    /// 3. `TxIn` DRS does not match `TxOut` DRS for `Receipt`s; Amount of `Receipt`s spent matches     
+    fn test_tx_drs_receipts_only_failure_drs_mismatch() {
+        test_tx_drs_common(
+            &[
+                (3, Some("drs_tx_hash_1"), None),
+                (2, Some("drs_tx_hash_2"), None),
+            ],
+            &[(3, Some("drs_tx_hash_1")), (2, Some("invalid_drs_tx_hash"))],
+            false,
+        );
+    }
+
+    #[test]
+    /// ### Test Case 5
+    ///
+    ///  - *Receipts and Tokens*
+    /// -  *Success*
+    ///
+    /// 1. Inputs contain two `TxIn`s for `Receipt`s of amount `3` and `Token`s of amount `2`
+    /// 2. Outputs contain `TxOut`s for `Receipt`s of amount `3` and `Token`s of amount `2`
+    /// 3. `TxIn` DRS matches `TxOut` DRS for `Receipt`s; Amount of `Receipt`s and `Token`s spent matches      
+    fn test_tx_drs_receipts_and_tokens_success() {
+        test_tx_drs_common(
+            &[(3, Some("drs_tx_hash"), None), (2, None, None)],
+            &[(3, Some("drs_tx_hash")), (2, None)],
+            true,
+        );
+    }
+
+    #[test]
+    /// ### Test Case 6
+    ///
+    ///  - *Receipts and Tokens*
+    /// -  *Failure*
+    ///
+    /// 1. Inputs contain two `TxIn`s for `Receipt`s of amount `3` and `Token`s of amount `2`
+    /// 2. Outputs contain `TxOut`s for `Receipt`s of amount `2` and `Token`s of amount `2`
+    /// 3. `TxIn` DRS matches `TxOut` DRS for `Receipt`s; Amount of `Receipt`s spent does not match      
+    fn test_tx_drs_receipts_and_tokens_failure_amount_mismatch() {
+        test_tx_drs_common(
+            &[(3, Some("drs_tx_hash"), None), (2, None, None)],
+            &[(2, Some("drs_tx_hash")), (2, None)],
+            false,
+        );
+    }
+
+    #[test]
+    /// ### Test Case 7
+    ///
+    ///  - *Receipts and Tokens*
+    /// -  *Failure*
+    ///
+    /// 1. Inputs contain two `TxIn`s for `Receipt`s of amount `3` and `Token`s of amount `2`
+    /// 2. Outputs contain `TxOut`s for `Receipt`s of amount `1` and Tokens of amount `1`
+    /// 3. `TxIn` DRS does not match `TxOut` DRS for `Receipt`s; Amount of `Receipt`s and `Token`s spent does not match;
+    /// Metadata does not match                
+    fn test_tx_drs_receipts_and_tokens_failure_amount_and_drs_mismatch() {
+        let test_metadata: Option<String> = Some(
+            "{\"name\":\"test\",\"description\":\"test\",\"image\":\"test\",\"url\":\"test\"}"
+                .to_string(),
+        );
+
+        test_tx_drs_common(
+            &[
+                (3, Some("drs_tx_hash"), test_metadata.clone()),
+                (2, None, test_metadata),
+            ],
+            &[(1, Some("invalid_drs_tx_hash")), (1, None)],
+            false,
+        );
+    }
+
+    #[test]
+    /// ### Test Case 8
+    ///
+    ///  - *Tokens only*
+    /// -  *Success*
+    ///
+    /// 1. Inputs contain two `TxIn`s for `Token`s of amounts `3` and `2`
+    /// 2. Outputs contain `TxOut`s for `Token`s of amounts `3` and `1`
+    /// 3. Declared fee of `1` accounts for the difference
+    fn test_tx_fee_tokens_only_success() {
+        test_tx_fee_common(
+            &[(3, None, None), (2, None, None)],
+            &[(3, None), (1, None)],
+            TokenAmount(1),
+            true,
+        );
+    }
+
+    #[test]
+    /// ### Test Case 9
+    ///
+    ///  - *Tokens only*
+    /// -  *Failure*
+    ///
+    /// 1. Inputs contain two `TxIn`s for `Token`s of amounts `3` and `2`
+    /// 2. Outputs contain `TxOut`s for `Token`s of amounts `3` and `1`
+    /// 3. Declared fee of `2` is inconsistent with the difference
+    fn test_tx_fee_tokens_only_failure_fee_mismatch() {
+        test_tx_fee_common(
+            &[(3, None, None), (2, None, None)],
+            &[(3, None), (1, None)],
+            TokenAmount(2),
+            false,
+        );
+    }
+
+    #[test]
+    /// ### Test Case 10
+    ///
+    ///  - *Tokens only*
+    /// -  *Failure*
+    ///
+    /// 1. Inputs contain two `TxIn`s for `Token`s of amounts `3` and `2`
+    /// 2. Outputs contain `TxOut`s for `Token`s of amounts `3` and `1`
+    /// 3. No fee is declared, so `TxIn` `Token`s amount does not match `TxOut` `Token`s amount
+    fn test_tx_fee_tokens_only_failure_no_fee() {
+        test_tx_fee_common(
+            &[(3, None, None), (2, None, None)],
+            &[(3, None), (1, None)],
+            TokenAmount(0),
+            false,
+        );
+    }
+
+    #[test]
+    /// ### Test Case 11
+    ///
+    ///  - *Receipts and Tokens*
+    /// -  *Failure*
+    ///
+    /// 1. Inputs contain two `TxIn`s for `Receipt`s of amount `3` and `Token`s of amount `2`
+    /// 2. Outputs contain `TxOut`s for `Receipt`s of amount `2` and `Token`s of amount `1`
+    /// 3. Declared fee of `1` accounts for the `Token`s; `Receipt`s are not conserved
+    fn test_tx_fee_receipts_and_tokens_failure_receipt_mismatch() {
+        test_tx_fee_common(
+            &[(3, Some("drs_tx_hash"), None), (2, None, None)],
+            &[(2, Some("drs_tx_hash")), (1, None)],
+            TokenAmount(1),
+            false,
+        );
+    }
+
+    /// Test transaction validation with a declared fee for
+    /// multiple configurations of `TxIn` and `TxOut` values
+    fn test_tx_fee_common(
+        inputs: &[(u64, Option<&str>, Option<String>)],
+        outputs: &[(u64, Option<&str>)],
+        expected_fee: TokenAmount,
+        expected_result: bool,
+    ) {
+        ///
+        /// Arrange
+        ///
+        let (utxo, tx) = generate_tx_with_ins_and_outs_assets(inputs, outputs);
+
+        ///
+        /// Act
+        ///
+        let actual_result = tx_is_valid_with_fee(&tx, |v| utxo.get(v), expected_fee);
+
+        ///
+        /// Assert
+        ///
+        assert_eq!(actual_result, expected_result);
+    }
+
+    /// Test transaction validation with multiple different DRS
+    /// configurations for `TxIn` and `TxOut` values
+    fn test_tx_drs_common(
+        inputs: &[(u64, Option<&str>, Option<String>)],
+        outputs: &[(u64, Option<&str>)],
+        expected_result: bool,
+    ) {
+        ///
+        /// Arrange
+        ///
+        let (utxo, tx) = generate_tx_with_ins_and_outs_assets(inputs, outputs);
+
+        ///
+        /// Act
+        ///
+        let actual_result = tx_is_valid(&tx, |v| utxo.get(v));
 
-        let tx_const = TxConstructor {
-            previous_out: outpoint,
-            signatures: vec![signature],
-            pub_keys: vec![pk],
-            address_version,
-        };
+        ///
+        /// Assert
+        ///
+        assert_eq!(actual_result, expected_result);
+    }
 
-        let tx_ins = construct_payment_tx_ins(vec![tx_const]);
-        let tx_out_pk = construct_address_for(&pk, address_version);
+    #[test]
+    /// ### Test Case 12
+    ///
+    ///  - *Data, Receipts and Tokens*
+    /// -  *Success*
+    ///
+    /// 1. Inputs contain `TxIn`s for `Data` of amount `2`, `Receipt`s of amount `3` and `Token`s of amount `2`
+    /// 2. Outputs contain `TxOut`s for `Data` of amounts `1` and `1`, `Receipt`s of amount `3` and `Token`s of amount `2`
+    /// 3. `TxIn` data matches `TxOut` data; Amount of `Data`, `Receipt`s and `Token`s spent matches
+    fn test_tx_drs_data_receipts_and_tokens_success() {
+        test_tx_mixed_assets_common(
+            &[
+                data_asset("data_payload", 2),
+                Asset::receipt(3, Some("drs_tx_hash".to_owned()), None),
+                Asset::token_u64(2),
+            ],
+            &[
+                data_asset("data_payload", 1),
+                data_asset("data_payload", 1),
+                Asset::receipt(3, Some("drs_tx_hash".to_owned()), None),
+                Asset::token_u64(2),
+            ],
+            true,
+        );
+    }
 
-        assert!(tx_has_valid_p2pkh_sig(
-            &tx_ins[0].script_signature,
-            &hash_to_sign,
-            &tx_out_pk
+    #[test]
+    /// ### Test Case 13
+    ///
+    ///  - *Data, Receipts and Tokens*
+    /// -  *Failure*
+    ///
+    /// 1. Inputs contain `TxIn`s for `Data` of amount `2`, `Receipt`s of amount `3` and `Token`s of amount `2`
+    /// 2. Outputs contain `TxOut`s for `Data` of amount `3`, `Receipt`s of amount `3` and `Token`s of amount `2`
+    /// 3. `TxIn` data matches `TxOut` data; Amount of `Data` spent does not match
+    fn test_tx_drs_data_receipts_and_tokens_failure_amount_mismatch() {
+        test_tx_mixed_assets_common(
+            &[
+                data_asset("data_payload", 2),
+                Asset::receipt(3, Some("drs_tx_hash".to_owned()), None),
+                Asset::token_u64(2),
+            ],
+            &[
+                data_asset("data_payload", 3),
+                Asset::receipt(3, Some("drs_tx_hash".to_owned()), None),
+                Asset::token_u64(2),
+            ],
+            false,
+        );
+    }
+
+    #[test]
+    /// ### Test Case 14
+    ///
+    ///  - *Data and Tokens*
+    /// -  *Failure*
+    ///
+    /// 1. Inputs contain `TxIn`s for `Data` of amount `1` and `Token`s of amount `2`
+    /// 2. Outputs contain `TxOut`s for `Data` of amount `1` and `Token`s of amount `2`
+    /// 3. `TxIn` data does not match `TxOut` data; Amount of `Data` and `Token`s spent matches
+    fn test_tx_drs_data_and_tokens_failure_data_mismatch() {
+        test_tx_mixed_assets_common(
+            &[data_asset("data_payload", 1), Asset::token_u64(2)],
+            &[data_asset("invalid_data_payload", 1), Asset::token_u64(2)],
+            false,
+        );
+    }
+
+    #[test]
+    /// ### Test Case 15
+    ///
+    ///  - *Data only*
+    /// -  *Failure*
+    ///
+    /// 1. Inputs contain a `TxIn` for `Data` of amount `1` with a payload exceeding `MAX_DATA_ASSET_BYTES`
+    /// 2. Outputs contain a `TxOut` for the same `Data` of amount `1`
+    /// 3. `TxOut` data payload is too large
+    fn test_tx_drs_data_only_failure_size() {
+        let payload = "a".repeat(MAX_DATA_ASSET_BYTES + 1);
+        test_tx_mixed_assets_common(
+            &[data_asset(&payload, 1)],
+            &[data_asset(&payload, 1)],
+            false,
+        );
+    }
+
+    /// Util function to create a `Data` asset
+    fn data_asset(data: &str, amount: u64) -> Asset {
+        Asset::Data(DataAsset {
+            data: data.as_bytes().to_vec(),
+            amount,
+        })
+    }
+
+    #[test]
+    /// ### Test Case 16
+    ///
+    ///  - *Receipts only*
+    /// -  *Success*
+    ///
+    /// 1. Inputs contain a `TxIn` for `Receipt`s of amount `3` with metadata
+    /// 2. Outputs contain the `TxOut` produced by `Asset::on_spend_receipt`
+    /// 3. `TxIn` DRS matches `TxOut` DRS; Metadata is cleared on-spend
+    fn test_tx_drs_receipts_on_spend_success() {
+        let input = Asset::receipt(3, Some("drs_tx_hash".to_owned()), Some("test".to_owned()));
+        let output = input.on_spend_receipt("drs_tx_hash".to_owned());
+
+        assert_eq!(output.get_metadata(), None);
+        assert_eq!(output.receipt_amount(), 3);
+        test_tx_mixed_assets_common(&[input], &[output], true);
+    }
+
+    #[test]
+    /// ### Test Case 17
+    ///
+    ///  - *Receipts only*
+    /// -  *Failure*
+    ///
+    /// 1. Inputs contain a `TxIn` for `Receipt`s of amount `3` with metadata
+    /// 2. Outputs contain a `TxOut` for `Receipt`s of amount `3` retaining the metadata
+    /// 3. `TxIn` DRS matches `TxOut` DRS; Metadata is not cleared on-spend
+    fn test_tx_drs_receipts_on_spend_failure_metadata_retained() {
+        let input = Asset::receipt(3, Some("drs_tx_hash".to_owned()), Some("test".to_owned()));
+        let output = input.clone();
+
+        test_tx_mixed_assets_common(&[input], &[output], false);
+    }
+
+    #[test]
+    /// ### Test Case 18
+    ///
+    ///  - *Bundled Receipts and Tokens*
+    /// -  *Success*
+    ///
+    /// 1. Inputs contain `TxIn`s for `Token`s of amount `5` and `Receipt`s of amount `3`
+    /// 2. Outputs contain one `TxOut` for a `Bundle` of `Token`s of amount `4` and `Receipt`s of amount `3`,
+    ///    and one `TxOut` for `Token`s of amount `1`
+    /// 3. Amount of `Receipt`s and `Token`s spent matches across the bundle
+    fn test_tx_drs_bundle_receipts_and_tokens_success() {
+        let receipt = Asset::receipt(3, Some("drs_tx_hash".to_owned()), None);
+        let member = BundleMember::receipt(3, Some("drs_tx_hash".to_owned()), None);
+        let bundle = Asset::Bundle(vec![BundleMember::token_u64(4), member]);
+
+        assert_eq!(
+            bincode::deserialize::<Asset>(&bincode::serialize(&bundle).unwrap()).unwrap(),
+            bundle
+        );
+        test_tx_mixed_assets_common(
+            &[Asset::token_u64(5), receipt],
+            &[bundle, Asset::token_u64(1)],
+            true,
+        );
+    }
+
+    #[test]
+    /// ### Test Case 19
+    ///
+    ///  - *Bundled Receipts and Tokens*
+    /// -  *Failure*
+    ///
+    /// 1. Inputs contain `TxIn`s for `Token`s of amount `5` and `Receipt`s of amount `3`
+    /// 2. Outputs contain a `TxOut` for a `Bundle` that mismatches, is not well formed,
+    ///    or carries a `Receipt` with metadata
+    /// 3. Amount of `Token`s spent does not match, or the `Bundle` is invalid
+    fn test_tx_drs_bundle_failure() {
+        let inputs = [
+            Asset::token_u64(5),
+            Asset::receipt(3, Some("drs_tx_hash".to_owned()), None),
+        ];
+        let receipt = BundleMember::receipt(3, Some("drs_tx_hash".to_owned()), None);
+        let with_metadata =
+            BundleMember::receipt(3, Some("drs_tx_hash".to_owned()), Some("m".to_owned()));
+
+        for bundle in [
+            vec![BundleMember::token_u64(4), receipt.clone()],
+            vec![
+                BundleMember::token_u64(4),
+                BundleMember::token_u64(1),
+                receipt.clone(),
+            ],
+            vec![BundleMember::token_u64(5)],
+            vec![BundleMember::token_u64(5), with_metadata],
+        ] {
+            test_tx_mixed_assets_common(&inputs, &[Asset::Bundle(bundle)], false);
+        }
+    }
+
+    #[test]
+    /// ### Test Case 20
+    ///
+    ///  - *Receipts only*
+    /// -  *Failure*
+    ///
+    /// 1. Inputs contain two `TxIn`s for `Receipt`s of amount `3` and `2` with different `drs_tx_hash` values
+    /// 2. Outputs contain `TxOut`s for `Receipt`s of amount `2` and `3` with the same `drs_tx_hash` values
+    /// 3. Total amount of `Receipt`s spent matches; Amount per `drs_tx_hash` does not
+    fn test_tx_drs_receipts_only_failure_drs_group_mismatch() {
+        test_tx_drs_common(
+            &[
+                (3, Some("drs_tx_hash_1"), None),
+                (2, Some("drs_tx_hash_2"), None),
+            ],
+            &[(2, Some("drs_tx_hash_1")), (3, Some("drs_tx_hash_2"))],
+            false,
+        );
+        test_tx_drs_common(
+            &[
+                (3, Some("drs_tx_hash_1"), None),
+                (2, Some("drs_tx_hash_2"), None),
+            ],
+            &[(5, Some("drs_tx_hash_1"))],
+            false,
+        );
+    }
+
+    /// Test transaction validation with multiple different
+    /// asset configurations for `TxIn` and `TxOut` values
+    fn test_tx_mixed_assets_common(inputs: &[Asset], outputs: &[Asset], expected_result: bool) {
+        ///
+        /// Arrange
+        ///
+        let (utxo, tx) = generate_tx_with_ins_and_outs_mixed_assets(inputs, outputs);
+
+        ///
+        /// Act
+        ///
+        let actual_result = tx_is_valid(&tx, |v| utxo.get(v));
+
+        ///
+        /// Assert
+        ///
+        assert_eq!(actual_result, expected_result);
+    }
+
+    #[test]
+    /// Checks that a batch is valid when a later transaction spends an output of an earlier one
+    fn test_txs_are_valid_chained_spend() {
+        //
+        // Arrange
+        //
+        let (pk, sk) = sign::gen_keypair();
+        let spk = construct_address(&pk);
+        let base_out_point = OutPoint::new("tx_hash".to_owned(), 0);
+        let mut utxo_set = UtxoSet::new();
+        utxo_set.insert(
+            base_out_point.clone(),
+            TxOut::new_token_amount(spk.clone(), TokenAmount(10)),
+        );
+
+        let tx_a = construct_spending_tx(&base_out_point, 10, &spk, &pk, &sk);
+        let tx_a_out_point = OutPoint::new(construct_tx_hash(&tx_a), 0);
+        let tx_b = construct_spending_tx(&tx_a_out_point, 10, &spk, &pk, &sk);
+
+        //
+        // Act
+        //
+        let result_ordered = txs_are_valid(&[tx_a.clone(), tx_b.clone()], &utxo_set);
+        let result_reversed = txs_are_valid(&[tx_b, tx_a], &utxo_set);
+
+        //
+        // Assert
+        //
+        assert!(result_ordered);
+        assert!(!result_reversed);
+        assert!(!utxo_set.contains(&tx_a_out_point));
+    }
+
+    #[test]
+    /// Checks that parallel batch verification agrees with sequential verification
+    fn test_validate_transactions_parallel() {
+        //
+        // Arrange
+        //
+        let (pk, sk) = sign::gen_keypair();
+        let spk = construct_address(&pk);
+        let mut utxo_set = UtxoSet::new();
+        let base_out_points: Vec<OutPoint> = (0..4)
+            .map(|n| OutPoint::new("tx_hash".to_owned(), n))
+            .collect();
+        for out_point in &base_out_points {
+            utxo_set.insert(
+                out_point.clone(),
+                TxOut::new_token_amount(spk.clone(), TokenAmount(10)),
+            );
+        }
+
+        // Independent spends of the base outputs
+        let independent: Vec<Transaction> = base_out_points
+            .iter()
+            .map(|out_point| construct_spending_tx(out_point, 10, &spk, &pk, &sk))
+            .collect();
+
+        // A chain spending each previous transaction's output, next to an independent spend
+        let mut chained = vec![construct_spending_tx(
+            &base_out_points[0],
+            10,
+            &spk,
+            &pk,
+            &sk,
+        )];
+        for _ in 0..3 {
+            let out_point = OutPoint::new(construct_tx_hash(chained.last().unwrap()), 0);
+            chained.push(construct_spending_tx(&out_point, 10, &spk, &pk, &sk));
+        }
+        chained.push(construct_spending_tx(
+            &base_out_points[1],
+            10,
+            &spk,
+            &pk,
+            &sk,
+        ));
+
+        let mut reversed = chained.clone();
+        reversed.reverse();
+
+        let mut double_spend = independent.clone();
+        double_spend.push(construct_spending_tx(
+            &base_out_points[2],
+            5,
+            &spk,
+            &pk,
+            &sk,
         ));
+
+        //
+        // Act
+        //
+        let batches = [independent, chained, reversed, double_spend];
+        let results: Vec<Vec<bool>> = batches
+            .iter()
+            .map(|txs| validate_transactions_parallel(txs, &utxo_set))
+            .collect();
+
+        //
+        // Assert
+        //
+        for (txs, result) in batches.iter().zip(results.iter()) {
+            assert_eq!(result.len(), txs.len());
+            assert_eq!(result.iter().all(|v| *v), txs_are_valid(txs, &utxo_set));
+        }
+        assert_eq!(results[0], vec![true; 4]);
+        assert_eq!(results[1], vec![true; 5]);
+        assert_eq!(results[2], vec![true, false, false, false, true]);
+        assert_eq!(results[3], vec![true, true, true, true, false]);
+        assert!(validate_transactions_parallel(&[], &utxo_set).is_empty());
     }
 
     #[test]
-    /// Checks that invalid p2pkh transaction signatures are validated as such
-    fn test_fail_p2pkh_sig_invalid() {
-        test_fail_p2pkh_sig_invalid_common(None);
+    /// Checks that a batch is invalid when two transactions spend the same output
+    fn test_txs_are_valid_double_spend() {
+        //
+        // Arrange
+        //
+        let (pk, sk) = sign::gen_keypair();
+        let spk = construct_address(&pk);
+        let base_out_point = OutPoint::new("tx_hash".to_owned(), 0);
+        let mut utxo_set = UtxoSet::new();
+        utxo_set.insert(
+            base_out_point.clone(),
+            TxOut::new_token_amount(spk.clone(), TokenAmount(10)),
+        );
+
+        let tx_a = construct_spending_tx(&base_out_point, 10, &spk, &pk, &sk);
+        let (other_pk, _) = sign::gen_keypair();
+        let other_spk = construct_address(&other_pk);
+        let tx_b = construct_spending_tx(&base_out_point, 10, &other_spk, &pk, &sk);
+
+        //
+        // Act
+        //
+        let result_a = txs_are_valid(std::slice::from_ref(&tx_a), &utxo_set);
+        let result_b = txs_are_valid(std::slice::from_ref(&tx_b), &utxo_set);
+        let result_both = txs_are_valid(&[tx_a, tx_b], &utxo_set);
+
+        //
+        // Assert
+        //
+        assert!(result_a);
+        assert!(result_b);
+        assert!(!result_both);
+    }
+
+    #[test]
+    /// Checks that batch validation reports the index and reason of each failing transaction
+    fn test_validate_batch_partial_failure() {
+        //
+        // Arrange
+        //
+        let (pk, sk) = sign::gen_keypair();
+        let (other_pk, other_sk) = sign::gen_keypair();
+        let spk = construct_address(&pk);
+        let out_point_0 = OutPoint::new("tx_hash".to_owned(), 0);
+        let out_point_1 = OutPoint::new("tx_hash".to_owned(), 1);
+        let mut utxo_set = UtxoSet::new();
+        for out_point in [&out_point_0, &out_point_1] {
+            utxo_set.insert(
+                out_point.clone(),
+                TxOut::new_token_amount(spk.clone(), TokenAmount(10)),
+            );
+        }
+
+        let tx_a = construct_spending_tx(&out_point_0, 10, &spk, &pk, &sk);
+        let tx_a_out_point = OutPoint::new(construct_tx_hash(&tx_a), 0);
+        let txs = vec![
+            tx_a.clone(),
+            construct_spending_tx(&out_point_0, 10, &spk, &pk, &sk),
+            construct_spending_tx(&out_point_1, 20, &spk, &pk, &sk),
+            construct_spending_tx(&out_point_1, 10, &spk, &other_pk, &other_sk),
+            construct_spending_tx(&tx_a_out_point, 10, &spk, &pk, &sk),
+        ];
+
+        //
+        // Act
+        //
+        let results = validate_batch(&txs, &utxo_set);
+
+        //
+        // Assert
+        //
+        assert_eq!(
+            results,
+            vec![
+                (0, Ok(())),
+                (1, Err(ValidationError::MissingInput(out_point_0))),
+                (2, Err(ValidationError::InvalidOutputs)),
+                (3, Err(ValidationError::InvalidScript(out_point_1))),
+                (4, Ok(())),
+            ]
+        );
+        assert!(!txs_are_valid(&txs, &utxo_set));
+    }
+
+    #[test]
+    /// Checks that a transaction with a missing input fails before any script is verified
+    fn test_tx_is_valid_missing_input_fails_fast() {
+        //
+        // Arrange
+        //
+        let (pk, sk) = sign::gen_keypair();
+        let (other_pk, other_sk) = sign::gen_keypair();
+        let spk = construct_address(&pk);
+        let out_point = OutPoint::new("tx_hash".to_owned(), 0);
+        let missing_out_point = OutPoint::new("tx_hash".to_owned(), 1);
+        let mut utxo_set = UtxoSet::new();
+        utxo_set.insert(
+            out_point.clone(),
+            TxOut::new_token_amount(spk.clone(), TokenAmount(10)),
+        );
+
+        let valid_tx = construct_spending_tx(&out_point, 10, &spk, &pk, &sk);
+        let mut missing_tx = construct_spending_tx(&out_point, 10, &spk, &other_pk, &other_sk);
+        missing_tx.inputs.push(TxIn::new_from_input(
+            missing_out_point.clone(),
+            Script::new(),
+        ));
+
+        //
+        // Act
+        //
+        let results = validate_batch(&[missing_tx.clone(), valid_tx.clone()], &utxo_set);
+
+        //
+        // Assert
+        //
+        assert!(valid_tx.all_inputs_available(&utxo_set));
+        assert!(!missing_tx.all_inputs_available(&utxo_set));
+        assert!(!construct_tx_core(vec![TxIn::new()], vec![]).all_inputs_available(&utxo_set));
+        assert_eq!(
+            results,
+            vec![
+                (0, Err(ValidationError::MissingInput(missing_out_point))),
+                (1, Ok(())),
+            ]
+        );
+    }
+
+    #[test]
+    /// Checks that a transaction spending the same `OutPoint` twice is invalid
+    fn test_tx_is_valid_duplicate_out_points() {
+        //
+        // Arrange
+        //
+        let (mut utxo, mut tx) =
+            generate_tx_with_ins_and_outs_assets(&[(5, None, None)], &[(10, None)]);
+        let duplicate_tx_in = tx.inputs[0].clone();
+        tx.inputs.push(duplicate_tx_in);
+
+        //
+        // Act
+        //
+        let result = tx_is_valid(&tx, |v| utxo.get(v));
+
+        //
+        // Assert
+        //
+        assert!(!result);
     }
 
     #[test]
-    /// Checks that invalid p2pkh transaction signatures are validated as such
-    fn test_fail_p2pkh_sig_invalid_v0() {
-        test_fail_p2pkh_sig_invalid_common(Some(NETWORK_VERSION_V0));
+    /// Checks that coinbase transactions are validated against the block height
+    fn test_tx_is_coinbase_valid() {
+        //
+        // Arrange
+        //
+        let (pk, _) = sign::gen_keypair();
+        let coinbase =
+            |height: u64| construct_coinbase_tx(height, TokenAmount(10), construct_address(&pk));
+        let valid_tx = coinbase(10);
+        let wrong_height_tx = coinbase(11);
+        let mut immature_tx = coinbase(10);
+        immature_tx.outputs[0].locktime = 0;
+        let mut spurious_input_tx = coinbase(10);
+        spurious_input_tx.inputs.push(TxIn::new_from_input(
+            OutPoint::new("tx_hash".to_owned(), 0),
+            Script::new_for_coinbase(10),
+        ));
+        let mut previous_out_tx = coinbase(10);
+        previous_out_tx.inputs[0].previous_out = Some(OutPoint::new("tx_hash".to_owned(), 0));
+
+        //
+        // Act
+        //
+        let valid_result = tx_is_coinbase_valid(&valid_tx, 10);
+        let wrong_height_result = tx_is_coinbase_valid(&wrong_height_tx, 10);
+        let immature_result = tx_is_coinbase_valid(&immature_tx, 10);
+        let spurious_input_result = tx_is_coinbase_valid(&spurious_input_tx, 10);
+        let previous_out_result = tx_is_coinbase_valid(&previous_out_tx, 10);
+
+        //
+        // Assert
+        //
+        assert!(valid_result);
+        assert!(!wrong_height_result);
+        assert!(!immature_result);
+        assert!(!spurious_input_result);
+        assert!(!previous_out_result);
     }
 
-    fn test_fail_p2pkh_sig_invalid_common(address_version: Option<u64>) {
+    #[test]
+    /// Checks that a block verifies only if its coinbase pays exactly the subsidy plus
+    /// fees and no output is spent twice within it
+    fn test_validate_block() {
+        //
+        // Arrange
+        //
         let (pk, sk) = sign::gen_keypair();
-        let (second_pk, _s) = sign::gen_keypair();
-        let outpoint = OutPoint {
-            t_hash: hex::encode(vec![0, 0, 0]),
-            n: 0,
-        };
-
-        let hash_to_sign = construct_tx_in_signable_hash(&outpoint);
-        let signature = sign::sign_detached(hash_to_sign.as_bytes(), &sk);
+        let spk = construct_address(&pk);
+        let base_out_points: Vec<OutPoint> = (0..2)
+            .map(|n| OutPoint::new("tx_hash".to_owned(), n))
+            .collect();
+        let mut utxo_set = UtxoSet::new();
+        for out_point in &base_out_points {
+            utxo_set.insert(
+                out_point.clone(),
+                TxOut::new_token_amount(spk.clone(), TokenAmount(10)),
+            );
+        }
 
-        let tx_const = TxConstructor {
-            previous_out: outpoint,
-            signatures: vec![signature],
-            pub_keys: vec![second_pk],
-            address_version,
-        };
+        // Fees of 1 and 2, the second transaction spending the first's output
+        let tx_a = construct_spending_tx(&base_out_points[0], 9, &spk, &pk, &sk);
+        let tx_a_out_point = OutPoint::new(construct_tx_hash(&tx_a), 0);
+        let tx_b = construct_spending_tx(&tx_a_out_point, 7, &spk, &pk, &sk);
+        let txs = vec![tx_a.clone(), tx_b];
+        let coinbase = |amount| construct_coinbase_tx(10, TokenAmount(amount), spk.clone());
+        let double_spend = vec![
+            tx_a,
+            construct_spending_tx(&base_out_points[0], 8, &spk, &pk, &sk),
+        ];
 
-        let tx_ins = construct_payment_tx_ins(vec![tx_const]);
-        let tx_out_pk = construct_address(&pk);
+        //
+        // Act
+        //
+        let valid_result = validate_block(&coinbase(53), &txs, &utxo_set, 10, TokenAmount(50));
+        let over_claimed_result =
+            validate_block(&coinbase(54), &txs, &utxo_set, 10, TokenAmount(50));
+        let double_spend_result =
+            validate_block(&coinbase(53), &double_spend, &utxo_set, 10, TokenAmount(50));
+        let wrong_height_result =
+            validate_block(&coinbase(53), &txs, &utxo_set, 11, TokenAmount(50));
 
-        assert!(!tx_has_valid_p2pkh_sig(
-            &tx_ins[0].script_signature,
-            &hash_to_sign,
-            &tx_out_pk
-        ));
+        //
+        // Assert
+        //
+        assert_eq!(valid_result, Ok(()));
+        assert_eq!(
+            over_claimed_result,
+            Err(BlockError::CoinbaseAmount {
+                expected: TokenAmount(53),
+                actual: TokenAmount(54),
+            })
+        );
+        assert_eq!(
+            double_spend_result,
+            Err(BlockError::DoubleSpend(1, base_out_points[0].clone()))
+        );
+        assert_eq!(wrong_height_result, Err(BlockError::InvalidCoinbase));
     }
 
     #[test]
-    /// Checks that invalid p2pkh transaction signatures are validated as such
-    fn test_fail_p2pkh_sig_script_empty() {
-        test_fail_p2pkh_sig_script_empty_common(None);
-    }
+    /// Checks that receipts may be burned in part, with the burned and kept receipts of each
+    /// DRS group together matching the inputs, and that burned receipts leave the UTXO set
+    fn test_tx_is_valid_receipt_burn() {
+        //
+        // Arrange
+        //
+        let (pk, sk) = sign::gen_keypair();
+        let spk = construct_address(&pk);
+        let drs = "drs_tx_hash".to_owned();
+        let out_point = OutPoint::new("tx_hash".to_owned(), 0);
+        let mut utxo_set = UtxoSet::new();
+        utxo_set.insert(
+            out_point.clone(),
+            TxOut::new_asset(spk.clone(), Asset::receipt(5, Some(drs.clone()), None)),
+        );
+        let receipts =
+            |amount| TxOut::new_asset(spk.clone(), Asset::receipt(amount, Some(drs.clone()), None));
+        let spend = |outputs: Vec<TxOut>| {
+            let mut tx = construct_spending_tx(&out_point, 0, &spk, &pk, &sk);
+            tx.outputs = outputs;
+            tx
+        };
+        let mut token_burn = TxOut::new_receipt_burn(0, drs.clone());
+        token_burn.value = Asset::token_u64(0);
+
+        let partial_burn_tx = spend(vec![receipts(3), TxOut::new_receipt_burn(2, drs.clone())]);
+        let full_burn_tx = spend(vec![TxOut::new_receipt_burn(5, drs.clone())]);
+        let over_burn_tx = spend(vec![receipts(3), TxOut::new_receipt_burn(3, drs.clone())]);
+        let other_group_burn_tx = spend(vec![
+            receipts(3),
+            TxOut::new_receipt_burn(2, "other_drs_tx_hash".to_owned()),
+        ]);
+        let token_burn_tx = spend(vec![receipts(5), token_burn]);
 
-    #[test]
-    /// Checks that invalid p2pkh transaction signatures are validated as such
-    fn test_fail_p2pkh_sig_script_empty_v0() {
-        test_fail_p2pkh_sig_script_empty_common(Some(NETWORK_VERSION_V0));
-    }
+        //
+        // Act
+        //
+        let results = [
+            tx_is_valid(&partial_burn_tx, utxo_set.lookup_fn()),
+            tx_is_valid(&full_burn_tx, utxo_set.lookup_fn()),
+            tx_is_valid(&over_burn_tx, utxo_set.lookup_fn()),
+            tx_is_valid(&other_group_burn_tx, utxo_set.lookup_fn()),
+            tx_is_valid(&token_burn_tx, utxo_set.lookup_fn()),
+        ];
+        let mut burned_utxo_set = utxo_set.clone();
+        burned_utxo_set.apply_tx(&partial_burn_tx);
+        let remaining: Vec<&Asset> = burned_utxo_set.iter().map(|(_, o)| &o.value).collect();
 
-    #[test]
-    /// Checks that invalid p2pkh transaction signatures are validated as such
-    fn test_fail_p2pkh_sig_script_empty_temp() {
-        test_fail_p2pkh_sig_script_empty_common(Some(NETWORK_VERSION_V0));
+        //
+        // Assert
+        //
+        assert_eq!(results, [true, true, false, false, false]);
+        assert!(TxOut::new_receipt_burn(2, drs.clone()).is_receipt_burn());
+        assert!(!receipts(2).is_receipt_burn());
+        assert_eq!(remaining, vec![&Asset::receipt(3, Some(drs), None)]);
     }
 
-    fn test_fail_p2pkh_sig_script_empty_common(address_version: Option<u64>) {
+    #[test]
+    /// Checks that zero value data-carrier outputs within the size limit are accepted and unspendable
+    fn test_tx_is_valid_data_carrier() {
+        //
+        // Arrange
+        //
         let (pk, sk) = sign::gen_keypair();
-        let outpoint = OutPoint {
-            t_hash: hex::encode(vec![0, 0, 0]),
-            n: 0,
+        let spk = construct_address(&pk);
+        let out_point = OutPoint::new("tx_hash".to_owned(), 0);
+        let mut utxo_set = UtxoSet::new();
+        utxo_set.insert(
+            out_point.clone(),
+            TxOut::new_token_amount(spk.clone(), TokenAmount(10)),
+        );
+        let with_output = |tx_out: TxOut, amount: u64| {
+            let mut tx = construct_spending_tx(&out_point, amount, &spk, &pk, &sk);
+            tx.outputs.push(tx_out);
+            tx
         };
+        let carrier = TxOut::new_data_carrier(vec![7; MAX_OP_RETURN_BYTES]);
+        let mut valued_carrier = carrier.clone();
+        valued_carrier.value = Asset::token_u64(1);
+
+        let valid_tx = with_output(carrier.clone(), 10);
+        let oversized_tx = with_output(
+            TxOut::new_data_carrier(vec![7; MAX_OP_RETURN_BYTES + 1]),
+            10,
+        );
+        let valued_tx = with_output(valued_carrier, 9);
 
-        let hash_to_sign = construct_tx_in_signable_hash(&outpoint);
-        let signature = sign::sign_detached(hash_to_sign.as_bytes(), &sk);
-
-        let tx_const = TxConstructor {
-            previous_out: outpoint,
-            signatures: vec![signature],
-            pub_keys: vec![pk],
-            address_version,
-        };
+        let carrier_out_point = OutPoint::new(construct_tx_hash(&valid_tx), 1);
+        let mut spent_utxo_set = utxo_set.clone();
+        spent_utxo_set.insert(carrier_out_point.clone(), carrier.clone());
+        let spending_carrier_tx = construct_spending_tx(&carrier_out_point, 0, &spk, &pk, &sk);
 
-        let mut tx_ins = Vec::new();
+        //
+        // Act
+        //
+        let valid_result = tx_is_valid(&valid_tx, |v| utxo_set.get(v));
+        let oversized_result = tx_is_valid(&oversized_tx, |v| utxo_set.get(v));
+        let valued_result = tx_is_valid(&valued_tx, |v| utxo_set.get(v));
+        let spending_carrier_result = tx_is_valid(&spending_carrier_tx, |v| spent_utxo_set.get(v));
 
-        for entry in vec![tx_const] {
-            let mut new_tx_in = TxIn::new();
-            new_tx_in.script_signature = Script::new();
-            new_tx_in.previous_out = Some(entry.previous_out);
+        //
+        // Assert
+        //
+        assert_eq!(
+            carrier.data_carrier_payload(),
+            Some(vec![7; MAX_OP_RETURN_BYTES])
+        );
+        assert_eq!(
+            TxOut::new_token_amount(spk, TokenAmount(1)).data_carrier_payload(),
+            None
+        );
+        assert!(valid_result);
+        assert!(!oversized_result);
+        assert!(!valued_result);
+        assert!(!spending_carrier_result);
+    }
 
-            tx_ins.push(new_tx_in);
-        }
+    #[test]
+    /// Checks that a coinbase output can only be spent once it has matured, and that
+    /// locktimes are not checked when no block height is known
+    fn test_tx_is_valid_coinbase_maturity() {
+        //
+        // Arrange
+        //
+        let (pk, sk) = sign::gen_keypair();
+        let spk = construct_address(&pk);
+        let coinbase = construct_coinbase_tx(10, TokenAmount(10), spk.clone());
+        let out_point = OutPoint::new(construct_tx_hash(&coinbase), 0);
+        let mut utxo_set = UtxoSet::new();
+        utxo_set.insert(out_point.clone(), coinbase.outputs[0].clone());
+        let tx = construct_spending_tx(&out_point, 10, &spk, &pk, &sk);
+        let at_height = |block_height| ScriptContext {
+            block_height,
+            ..Default::default()
+        };
 
-        let tx_out_pk = construct_address(&pk);
+        //
+        // Act
+        //
+        let premature_result =
+            tx_is_valid_with_context(&tx, &at_height(10 + COINBASE_MATURITY - 1), |v| {
+                utxo_set.get(v)
+            });
+        let matured_result =
+            tx_is_valid_with_context(&tx, &at_height(10 + COINBASE_MATURITY), |v| utxo_set.get(v));
+        let unknown_height_result = tx_is_valid(&tx, |v| utxo_set.get(v));
+        let batch_result = validate_batch(std::slice::from_ref(&tx), &utxo_set);
 
-        assert!(!tx_has_valid_p2pkh_sig(
-            &tx_ins[0].script_signature,
-            &hash_to_sign,
-            &tx_out_pk
-        ));
+        //
+        // Assert
+        //
+        assert!(!premature_result);
+        assert!(matured_result);
+        assert!(unknown_height_result);
+        assert_eq!(batch_result, vec![(0, Ok(()))]);
     }
 
     #[test]
-    /// Checks that invalid p2pkh transaction signatures are validated as such
-    fn test_fail_p2pkh_sig_script_invalid_struct() {
-        test_fail_p2pkh_sig_script_invalid_struct_common(None);
-    }
+    /// Checks that `TokenAmount` arithmetic reports overflow and underflow
+    fn test_token_amount_checked_arithmetic() {
+        assert_eq!(TokenAmount(1).checked_sub(TokenAmount(2)), None);
+        assert_eq!(
+            TokenAmount(2).checked_sub(TokenAmount(1)),
+            Some(TokenAmount(1))
+        );
+        assert_eq!(
+            TokenAmount(1).saturating_sub(TokenAmount(2)),
+            TokenAmount(0)
+        );
+        assert_eq!(TokenAmount(u64::MAX).checked_add(TokenAmount(1)), None);
+        assert_eq!(
+            TokenAmount(1).checked_add(TokenAmount(2)),
+            Some(TokenAmount(3))
+        );
 
-    #[test]
-    /// Checks that invalid p2pkh transaction signatures are validated as such
-    fn test_fail_p2pkh_sig_script_invalid_struct_v0() {
-        test_fail_p2pkh_sig_script_invalid_struct_common(Some(NETWORK_VERSION_V0));
+        let mut totals = AssetValues::token_u64(u64::MAX);
+        assert!(!totals.checked_update_add(&Asset::token_u64(1)));
+        assert_eq!(totals, AssetValues::token_u64(u64::MAX));
+        totals.update_add(&Asset::token_u64(1));
+        assert_eq!(totals, AssetValues::token_u64(u64::MAX));
+        totals.update_sub(&Asset::token_u64(u64::MAX));
+        totals.update_sub(&Asset::token_u64(1));
+        assert!(totals.is_empty());
     }
 
     #[test]
-    /// Checks that invalid p2pkh transaction signatures are validated as such
-    fn test_fail_p2pkh_sig_script_invalid_struct_temp() {
-        test_fail_p2pkh_sig_script_invalid_struct_common(Some(NETWORK_VERSION_TEMP));
-    }
-
-    fn test_fail_p2pkh_sig_script_invalid_struct_common(address_version: Option<u64>) {
-        let (pk, sk) = sign::gen_keypair();
-        let outpoint = OutPoint {
-            t_hash: hex::encode(vec![0, 0, 0]),
-            n: 0,
-        };
+    /// Checks that a transaction whose outputs overflow is rejected rather than panicking
+    fn test_tx_is_valid_outputs_overflow() {
+        //
+        // Arrange
+        //
+        let (utxo, tx) = generate_tx_with_ins_and_outs_assets(
+            &[(1, None, None)],
+            &[(u64::MAX, None), (2, None)],
+        );
 
-        let hash_to_sign = construct_tx_in_signable_hash(&outpoint);
-        let signature = sign::sign_detached(hash_to_sign.as_bytes(), &sk);
+        //
+        // Act
+        //
+        let result = tx_is_valid(&tx, |v| utxo.get(v));
 
-        let tx_const = TxConstructor {
-            previous_out: outpoint,
-            signatures: vec![signature],
-            pub_keys: vec![pk],
-            address_version,
-        };
+        //
+        // Assert
+        //
+        assert!(!result);
+    }
 
-        let mut tx_ins = Vec::new();
+    #[test]
+    /// Checks that cached script results are reused while UTXO membership is always checked
+    fn test_tx_is_valid_cached() {
+        //
+        // Arrange
+        //
+        let (utxo, tx) = generate_tx_with_ins_and_outs_assets(&[(3, None, None)], &[(3, None)]);
+        let empty_utxo: BTreeMap<OutPoint, TxOut> = BTreeMap::new();
+        let mut cache = ValidationCache::new(10);
 
-        for entry in vec![tx_const] {
-            let mut new_tx_in = TxIn::new();
-            new_tx_in.script_signature = Script::new();
-            new_tx_in
-                .script_signature
-                .stack
-                .push(StackEntry::Bytes("".to_string()));
-            new_tx_in.previous_out = Some(entry.previous_out);
+        //
+        // Act
+        //
+        let result = tx_is_valid_cached(&mut cache, &tx, |v| utxo.get(v));
+        let cached = cache.get(&tx.tx_hash());
 
-            tx_ins.push(new_tx_in);
-        }
+        // A cached failure is reused without interpreting the scripts again
+        cache.insert(tx.tx_hash(), false);
+        let reused_result = tx_is_valid_cached(&mut cache, &tx, |v| utxo.get(v));
 
-        let tx_out_pk = construct_address(&pk);
+        // A cached success does not vouch for UTXO membership
+        cache.insert(tx.tx_hash(), true);
+        let missing_result = tx_is_valid_cached(&mut cache, &tx, |v| empty_utxo.get(v));
 
-        assert!(!tx_has_valid_p2pkh_sig(
-            &tx_ins[0].script_signature,
-            &hash_to_sign,
-            &tx_out_pk
-        ));
+        //
+        // Assert
+        //
+        assert!(result);
+        assert_eq!(cached, Some(true));
+        assert!(!reused_result);
+        assert!(!missing_result);
+        assert_eq!(cache.len(), 1);
     }
 
     #[test]
-    /// Checks that correct multisig validation signatures are validated as such
-    fn test_pass_multisig_validation_valid() {
-        test_pass_multisig_validation_valid_common(None);
-    }
+    /// Checks that an input without a previous `OutPoint` is rejected rather than panicking
+    fn test_tx_is_valid_missing_previous_out() {
+        //
+        // Arrange
+        //
+        let (utxo, mut tx) = generate_tx_with_ins_and_outs_assets(&[(3, None, None)], &[(3, None)]);
+        tx.inputs[0].previous_out = None;
 
-    #[test]
-    /// Checks that correct multisig validation signatures are validated as such
-    fn test_pass_multisig_validation_valid_v0() {
-        test_pass_multisig_validation_valid_common(Some(NETWORK_VERSION_V0));
-    }
+        //
+        // Act
+        //
+        let result = tx_is_valid(&tx, |v| utxo.get(v));
 
-    #[test]
-    /// Checks that correct multisig validation signatures are validated as such
-    fn test_pass_multisig_validation_valid_temp() {
-        test_pass_multisig_validation_valid_common(Some(NETWORK_VERSION_TEMP));
+        //
+        // Assert
+        //
+        assert!(!result);
     }
 
-    fn test_pass_multisig_validation_valid_common(address_version: Option<u64>) {
-        let (first_pk, first_sk) = sign::gen_keypair();
-        let (second_pk, second_sk) = sign::gen_keypair();
-        let (third_pk, third_sk) = sign::gen_keypair();
-        let check_data = hex::encode(vec![0, 0, 0]);
-
-        let m = 2;
-        let first_sig = sign::sign_detached(check_data.as_bytes(), &first_sk);
-        let second_sig = sign::sign_detached(check_data.as_bytes(), &second_sk);
-
-        let tx_const = TxConstructor {
-            previous_out: OutPoint::new(check_data, 0),
-            signatures: vec![first_sig, second_sig],
-            pub_keys: vec![first_pk, second_pk, third_pk],
-            address_version,
-        };
+    #[test]
+    /// Checks that a transaction without inputs is only accepted in DDE shape
+    fn test_tx_is_valid_empty_inputs() {
+        //
+        // Arrange
+        //
+        let tx = Transaction::new();
+        let dde_tx = construct_dde_tx("VALUE".to_owned(), Vec::new(), Vec::new(), 1, Vec::new());
+        let utxo: BTreeMap<OutPoint, TxOut> = BTreeMap::new();
 
-        let tx_ins = create_multisig_tx_ins(vec![tx_const], m);
+        //
+        // Act
+        //
+        let result = tx_is_valid(&tx, |v| utxo.get(v));
+        let dde_result = tx_is_valid(&dde_tx, |v| utxo.get(v));
 
-        assert!(&tx_ins[0].script_signature.interpret());
+        //
+        // Assert
+        //
+        assert!(!result);
+        assert!(dde_result);
     }
 
     #[test]
-    /// Validate tx_is_valid for multiple TxIn configurations
-    fn test_tx_is_valid() {
-        test_tx_is_valid_common(None, OpCodes::OP_HASH256);
-    }
+    /// Checks that spending scripts carrying `OP_CREATE` are rejected
+    fn test_tx_is_valid_create_op_in_spend() {
+        //
+        // Arrange
+        //
+        let redeem = Script::from(vec![StackEntry::Num(1)]);
+        let create_redeem =
+            Script::from(vec![StackEntry::Op(OpCodes::OP_CREATE), StackEntry::Num(1)]);
+        let tx_outpoint = OutPoint::new("tx_hash".to_owned(), 0);
+        let (pk, _) = sign::gen_keypair();
+        let spend_tx = |redeem: &Script| {
+            let mut utxo_set = UtxoSet::new();
+            utxo_set.insert(
+                tx_outpoint.clone(),
+                TxOut::new_token_amount(construct_p2sh_address(redeem), TokenAmount(10)),
+            );
+            let mut tx = Transaction::new();
+            tx.inputs.push(TxIn::new_from_input(
+                tx_outpoint.clone(),
+                Script::p2sh_unlock(redeem, Vec::new()),
+            ));
+            tx.outputs.push(TxOut::new_token_amount(
+                construct_address(&pk),
+                TokenAmount(10),
+            ));
+            (utxo_set, tx)
+        };
+        let (p2sh_utxo, p2sh_tx) = spend_tx(&redeem);
+        let (create_utxo, create_tx) = spend_tx(&create_redeem);
 
-    #[test]
-    /// Validate tx_is_valid for multiple TxIn configurations
-    fn test_tx_is_valid_v0() {
-        test_tx_is_valid_common(Some(NETWORK_VERSION_V0), OpCodes::OP_HASH256_V0);
-    }
+        //
+        // Act
+        //
+        let p2sh_result = tx_is_valid(&p2sh_tx, p2sh_utxo.lookup_fn());
+        let create_result = tx_is_valid(&create_tx, create_utxo.lookup_fn());
 
-    #[test]
-    /// Validate tx_is_valid for multiple TxIn configurations
-    fn test_tx_is_valid_temp() {
-        test_tx_is_valid_common(Some(NETWORK_VERSION_TEMP), OpCodes::OP_HASH256_TEMP);
+        //
+        // Assert
+        //
+        assert!(p2sh_result);
+        assert!(!create_result);
     }
 
-    fn test_tx_is_valid_common(address_version: Option<u64>, op_hash256: OpCodes) {
+    #[test]
+    /// Checks that a P2SH output wrapping a multisig redeem script can be spent
+    fn test_tx_is_valid_p2sh_multisig() {
         //
         // Arrange
         //
-        let (pk, sk) = sign::gen_keypair();
-        let tx_hash = hex::encode(vec![0, 0, 0]);
-        let tx_outpoint = OutPoint::new(tx_hash, 0);
-        let script_public_key = construct_address_for(&pk, address_version);
-        let tx_in_previous_out = TxOut::new_token_amount(script_public_key.clone(), TokenAmount(5));
-        let ongoing_tx_outs = vec![tx_in_previous_out.clone()];
-
-        let valid_bytes = construct_tx_in_signable_hash(&tx_outpoint);
-        let valid_sig = sign::sign_detached(valid_bytes.as_bytes(), &sk);
+        let (pk1, sk1) = sign::gen_keypair();
+        let (pk2, sk2) = sign::gen_keypair();
+        let (pk3, _) = sign::gen_keypair();
+        let check_data = hex::encode(vec![0, 0, 0]);
+        let sig1 = sign::sign_detached(check_data.as_bytes(), &sk1);
+        let sig2 = sign::sign_detached(check_data.as_bytes(), &sk2);
+        let redeem = ScriptBuilder::new()
+            .push_num(2)
+            .push_pubkey(pk1)
+            .push_pubkey(pk2)
+            .push_pubkey(pk3)
+            .push_num(3)
+            .push_op(OpCodes::OP_CHECKMULTISIG)
+            .build()
+            .unwrap();
+        let p2sh_address = construct_p2sh_address(&redeem);
+
+        let tx_outpoint = OutPoint::new("tx_hash".to_owned(), 0);
+        let mut utxo_set = UtxoSet::new();
+        utxo_set.insert(
+            tx_outpoint.clone(),
+            TxOut::new_token_amount(p2sh_address.clone(), TokenAmount(10)),
+        );
 
-        // Test cases:
-        let inputs = vec![
-            // 0. Happy case: valid test
-            (
-                vec![
-                    StackEntry::Bytes(valid_bytes),
-                    StackEntry::Signature(valid_sig),
-                    StackEntry::PubKey(pk),
-                    StackEntry::Op(OpCodes::OP_DUP),
-                    StackEntry::Op(op_hash256),
-                    StackEntry::PubKeyHash(script_public_key),
-                    StackEntry::Op(OpCodes::OP_EQUALVERIFY),
-                    StackEntry::Op(OpCodes::OP_CHECKSIG),
-                ],
-                true,
-            ),
-            // 2. Empty script
-            (vec![StackEntry::Bytes("".to_string())], false),
-        ];
+        let (pk, _) = sign::gen_keypair();
+        let spend_tx = |sig_stack: Vec<StackEntry>| {
+            let mut tx = Transaction::new();
+            tx.inputs.push(TxIn::new_from_input(
+                tx_outpoint.clone(),
+                Script::p2sh_unlock(&redeem, sig_stack),
+            ));
+            tx.outputs.push(TxOut::new_token_amount(
+                construct_address(&pk),
+                TokenAmount(10),
+            ));
+            tx
+        };
+        let valid_tx = spend_tx(vec![
+            StackEntry::Bytes(check_data.clone()),
+            StackEntry::Signature(sig1),
+            StackEntry::Signature(sig2),
+        ]);
+        let invalid_tx = spend_tx(vec![
+            StackEntry::Bytes(check_data),
+            StackEntry::Signature(sig1),
+        ]);
 
         //
         // Act
         //
-        let mut actual_result = Vec::new();
-        for (script, _) in &inputs {
-            let tx_ins = vec![TxIn {
-                script_signature: Script {
-                    stack: script.clone(),
-                },
-                previous_out: Some(tx_outpoint.clone()),
-            }];
-
-            let tx = Transaction {
-                inputs: tx_ins,
-                outputs: ongoing_tx_outs.clone(),
-                ..Default::default()
-            };
-
-            let result = tx_is_valid(&tx, |v| {
-                Some(&tx_in_previous_out).filter(|_| v == &tx_outpoint)
-            });
-            actual_result.push(result);
-        }
+        let lock = Script::p2sh_lock(&redeem);
+        let valid_result = tx_is_valid(&valid_tx, utxo_set.lookup_fn());
+        let invalid_result = tx_is_valid(&invalid_tx, utxo_set.lookup_fn());
 
         //
         // Assert
         //
-        assert_eq!(
-            actual_result,
-            inputs.iter().map(|(_, e)| *e).collect::<Vec<bool>>(),
-        );
+        assert_eq!(lock.stack, vec![StackEntry::PubKeyHash(p2sh_address)]);
+        assert!(valid_result);
+        assert!(!invalid_result);
     }
 
     #[test]
-    /// ### Test Case 1
-    ///
-    ///  - *Tokens only*
-    /// -  *Success*
-    ///
-    /// 1. Inputs contain two `TxIn`s for `Token`s of amounts `3` and `2`
-    /// 2. Outputs contain `TxOut`s for `Token`s of amounts `3` and `2`
-    fn test_tx_drs_tokens_only_success() {
-        test_tx_drs_common(
-            &[(3, None, None), (2, None, None)],
-            &[(3, None), (2, None)],
-            true,
+    /// Checks that P2PKH signatures only verify on the network they were made for
+    fn test_tx_is_valid_network_id() {
+        //
+        // Arrange
+        //
+        let (pk, sk) = sign::gen_keypair();
+        let spk = construct_address(&pk);
+        let out_point = OutPoint::new("tx_hash".to_owned(), 0);
+        let mut utxo_set = UtxoSet::new();
+        utxo_set.insert(
+            out_point.clone(),
+            TxOut::new_token_amount(spk.clone(), TokenAmount(10)),
         );
-    }
 
-    #[test]
-    /// ### Test Case 2
-    ///
-    ///  - *Tokens only*
-    /// -  *Failure*
-    ///
-    /// 1. Inputs contain two `TxIn`s for `Token`s of amounts `3` and `2`
-    /// 2. Outputs contain `TxOut`s for `Token`s of amounts `3` and `3`
-    /// 3. `TxIn` `Token`s amount does not match `TxOut` `Token`s amount
-    fn test_tx_drs_tokens_only_failure_amount_mismatch() {
-        test_tx_drs_common(
-            &[(3, None, None), (2, None, None)],
-            &[(3, None), (3, None)],
-            false,
-        );
-    }
+        let signable_hash = construct_tx_in_signable_hash_with_network(&out_point, 1);
+        let signature = sign::sign_detached(signable_hash.as_bytes(), &sk);
+        let mut tx = Transaction::new();
+        tx.inputs.push(TxIn::new_from_input(
+            out_point.clone(),
+            Script::pay2pkh(signable_hash, signature, pk, None),
+        ));
+        tx.outputs
+            .push(TxOut::new_token_amount(spk.clone(), TokenAmount(10)));
+        let on_network = |network_id| ScriptContext {
+            network_id,
+            ..Default::default()
+        };
 
-    #[test]
-    /// ### Test Case 3
-    ///
-    ///  - *Receipts only*
-    /// -  *Failure*
-    ///
-    /// 1. Inputs contain two `TxIn`s for `Receipt`s of amount `3` and `2` with different `drs_tx_hash` values
-    /// 2. Outputs contain `TxOut`s for `Receipt`s of amount `3` and `3`
-    /// 3. `TxIn` DRS matches `TxOut` DRS for `Receipt`s; Amount of `Receipt`s spent does not match    
-    fn test_tx_drs_receipts_only_failure_amount_mismatch() {
-        test_tx_drs_common(
-            &[
-                (3, Some("drs_tx_hash_1"), None),
-                (2, Some("drs_tx_hash_2"), None),
-            ],
-            &[(3, Some("drs_tx_hash_1")), (3, Some("drs_tx_hash_2"))],
-            false,
+        //
+        // Act
+        //
+        let default_network_result = tx_is_valid(&tx, utxo_set.lookup_fn());
+        let same_network_result =
+            tx_is_valid_with_context(&tx, &on_network(1), utxo_set.lookup_fn());
+        let other_network_result =
+            tx_is_valid_with_context(&tx, &on_network(2), utxo_set.lookup_fn());
+        let default_signed_result = tx_is_valid_with_context(
+            &construct_spending_tx(&out_point, 10, &spk, &pk, &sk),
+            &on_network(1),
+            utxo_set.lookup_fn(),
         );
-    }
 
-    #[test]
-    /// ### Test Case 4
-    ///
-    ///  - *Receipts only*
-    /// -  *Failure*
-    ///
-    /// 1. Inputs contain two `TxIn`s for `Receipt`s of amount `3` and `2` with different `drs_tx_hash` values
-    /// 2. Outputs contain `TxOut`s for `Receipt`s of amount `3` and `2`
-    /// 3. `TxIn` DRS does not match `TxOut` DRS for `Receipt`s; Amount of `Receipt`s spent matches     
-    fn test_tx_drs_receipts_only_failure_drs_mismatch() {
-        test_tx_drs_common(
-            &[
-                (3, Some("drs_tx_hash_1"), None),
-                (2, Some("drs_tx_hash_2"), None),
-            ],
-            &[(3, Some("drs_tx_hash_1")), (2, Some("invalid_drs_tx_hash"))],
-            false,
-        );
+        //
+        // Assert
+        //
+        assert!(!default_network_result);
+        assert!(same_network_result);
+        assert!(!other_network_result);
+        assert!(!default_signed_result);
     }
 
     #[test]
-    /// ### Test Case 5
-    ///
-    ///  - *Receipts and Tokens*
-    /// -  *Success*
-    ///
-    /// 1. Inputs contain two `TxIn`s for `Receipt`s of amount `3` and `Token`s of amount `2`
-    /// 2. Outputs contain `TxOut`s for `Receipt`s of amount `3` and `Token`s of amount `2`
-    /// 3. `TxIn` DRS matches `TxOut` DRS for `Receipt`s; Amount of `Receipt`s and `Token`s spent matches      
-    fn test_tx_drs_receipts_and_tokens_success() {
-        test_tx_drs_common(
-            &[(3, Some("drs_tx_hash"), None), (2, None, None)],
-            &[(3, Some("drs_tx_hash")), (2, None)],
-            true,
+    /// Checks that a P2SH output committing to a template can only be spent by a
+    /// transaction matching it
+    fn test_tx_is_valid_checktemplateverify() {
+        //
+        // Arrange
+        //
+        let out_point = OutPoint::new("tx_hash".to_owned(), 0);
+        let mut template = Transaction::new();
+        template.inputs.push(TxIn::new());
+        template.outputs = vec![TxOut::new_token_amount("a".repeat(64), TokenAmount(10))];
+        let redeem = Script::from(vec![
+            StackEntry::Bytes(hex::encode(template.template_hash())),
+            StackEntry::Op(OpCodes::OP_CHECKTEMPLATEVERIFY),
+        ]);
+        let mut utxo_set = UtxoSet::new();
+        utxo_set.insert(
+            out_point.clone(),
+            TxOut::new_token_amount(construct_p2sh_address(&redeem), TokenAmount(10)),
         );
-    }
 
-    #[test]
-    /// ### Test Case 6
-    ///
-    ///  - *Receipts and Tokens*
-    /// -  *Failure*
-    ///
-    /// 1. Inputs contain two `TxIn`s for `Receipt`s of amount `3` and `Token`s of amount `2`
-    /// 2. Outputs contain `TxOut`s for `Receipt`s of amount `2` and `Token`s of amount `2`
-    /// 3. `TxIn` DRS matches `TxOut` DRS for `Receipt`s; Amount of `Receipt`s spent does not match      
-    fn test_tx_drs_receipts_and_tokens_failure_amount_mismatch() {
-        test_tx_drs_common(
-            &[(3, Some("drs_tx_hash"), None), (2, None, None)],
-            &[(2, Some("drs_tx_hash")), (2, None)],
-            false,
-        );
+        let mut tx = Transaction::new();
+        tx.inputs.push(TxIn::new_from_input(
+            out_point,
+            Script::p2sh_unlock(&redeem, vec![]),
+        ));
+        tx.outputs = template.outputs.clone();
+        let mut altered_tx = tx.clone();
+        altered_tx.outputs = vec![TxOut::new_token_amount("b".repeat(64), TokenAmount(10))];
+
+        //
+        // Act
+        //
+        let valid_result = tx_is_valid(&tx, utxo_set.lookup_fn());
+        let altered_result = tx_is_valid(&altered_tx, utxo_set.lookup_fn());
+        let mut two_inputs = template.clone();
+        two_inputs.inputs.push(TxIn::new());
+
+        //
+        // Assert
+        //
+        assert!(valid_result);
+        assert!(!altered_result);
+        assert_ne!(two_inputs.template_hash(), template.template_hash());
     }
 
     #[test]
-    /// ### Test Case 7
-    ///
-    ///  - *Receipts and Tokens*
-    /// -  *Failure*
-    ///
-    /// 1. Inputs contain two `TxIn`s for `Receipt`s of amount `3` and `Token`s of amount `2`
-    /// 2. Outputs contain `TxOut`s for `Receipt`s of amount `1` and Tokens of amount `1`
-    /// 3. `TxIn` DRS does not match `TxOut` DRS for `Receipt`s; Amount of `Receipt`s and `Token`s spent does not match;
-    /// Metadata does not match                
-    fn test_tx_drs_receipts_and_tokens_failure_amount_and_drs_mismatch() {
-        let test_metadata: Option<String> = Some(
-            "{\"name\":\"test\",\"description\":\"test\",\"image\":\"test\",\"url\":\"test\"}"
-                .to_string(),
-        );
-
-        test_tx_drs_common(
-            &[
-                (3, Some("drs_tx_hash"), test_metadata.clone()),
-                (2, None, test_metadata),
-            ],
-            &[(1, Some("invalid_drs_tx_hash")), (1, None)],
-            false,
+    /// Checks that single inputs are verified against the output they spend
+    fn test_txin_unlocks_txout() {
+        //
+        // Arrange
+        //
+        let (pk, sk) = sign::gen_keypair();
+        let (other_pk, _) = sign::gen_keypair();
+        let out_point = OutPoint::new("tx_hash".to_owned(), 0);
+        let signable_hash = construct_tx_in_signable_hash(&out_point);
+        let signature = sign::sign_detached(signable_hash.as_bytes(), &sk);
+        let p2pkh_in = TxIn::new_from_input(
+            out_point.clone(),
+            Script::pay2pkh(signable_hash, signature, pk, None),
         );
-    }
+        let p2pkh_out = TxOut::new_token_amount(construct_address(&pk), TokenAmount(10));
+        let other_out = TxOut::new_token_amount(construct_address(&other_pk), TokenAmount(10));
 
-    /// Test transaction validation with multiple different DRS
-    /// configurations for `TxIn` and `TxOut` values
-    fn test_tx_drs_common(
-        inputs: &[(u64, Option<&str>, Option<String>)],
-        outputs: &[(u64, Option<&str>)],
-        expected_result: bool,
-    ) {
-        ///
-        /// Arrange
-        ///
-        let (utxo, tx) = generate_tx_with_ins_and_outs_assets(inputs, outputs);
+        let redeem = Script::from(vec![StackEntry::Op(OpCodes::OP_1)]);
+        let p2sh_in = TxIn::new_from_input(out_point.clone(), Script::p2sh_unlock(&redeem, vec![]));
+        let p2sh_out = TxOut::new_token_amount(construct_p2sh_address(&redeem), TokenAmount(10));
 
-        ///
-        /// Act
-        ///
-        let actual_result = tx_is_valid(&tx, |v| utxo.get(v));
+        //
+        // Act
+        //
+        let p2pkh_result = txin_unlocks_txout(&p2pkh_in, &p2pkh_out, &out_point);
+        let p2sh_result = txin_unlocks_txout(&p2sh_in, &p2sh_out, &out_point);
+        let mismatched_result = txin_unlocks_txout(&p2pkh_in, &other_out, &out_point);
+        let wrong_out_point_result = txin_unlocks_txout(
+            &p2pkh_in,
+            &p2pkh_out,
+            &OutPoint::new("tx_hash".to_owned(), 1),
+        );
 
-        ///
-        /// Assert
-        ///
-        assert_eq!(actual_result, expected_result);
+        //
+        // Assert
+        //
+        assert!(p2pkh_result);
+        assert!(p2sh_result);
+        assert!(!mismatched_result);
+        assert!(!wrong_out_point_result);
+    }
+
+    /// Util function to create a transaction spending a single P2PKH `Token` output
+    fn construct_spending_tx(
+        out_point: &OutPoint,
+        amount: u64,
+        to_address: &str,
+        pk: &PublicKey,
+        sk: &sign::SecretKey,
+    ) -> Transaction {
+        let signable_hash = construct_tx_in_signable_hash(out_point);
+        let signature = sign::sign_detached(signable_hash.as_bytes(), sk);
+        let mut tx = Transaction::new();
+        tx.inputs.push(TxIn::new_from_input(
+            out_point.clone(),
+            Script::pay2pkh(signable_hash, signature, *pk, None),
+        ));
+        tx.outputs.push(TxOut::new_token_amount(
+            to_address.to_owned(),
+            TokenAmount(amount),
+        ));
+        tx
     }
 
     #[test]
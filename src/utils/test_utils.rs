@@ -68,3 +68,43 @@ pub fn generate_tx_with_ins_and_outs_assets(
 
     (utxo_set, tx)
 }
+
+/// Generate a transaction with valid Script values
+/// and accompanying UTXO set for testing a set of
+/// transaction inputs and outputs of any `Asset` variant.
+///
+/// ### Purpose:
+///
+/// Similar to `generate_tx_with_ins_and_outs_assets`, but takes fully specified
+/// `Asset` values so that `Data` assets can be mixed with `Token` and `Receipt` assets.
+pub fn generate_tx_with_ins_and_outs_mixed_assets(
+    input_assets: &[Asset],
+    output_assets: &[Asset],
+) -> (BTreeMap<OutPoint, TxOut>, Transaction) {
+    let (pk, sk) = sign::gen_keypair();
+    let spk = construct_address(&pk);
+    let mut tx = Transaction::new();
+    let mut utxo_set: BTreeMap<OutPoint, TxOut> = BTreeMap::new();
+
+    // Generate inputs
+    for input_asset in input_assets {
+        let tx_previous_out = OutPoint::new("tx_hash".to_owned(), tx.inputs.len() as i32);
+        let tx_in_previous_out = TxOut::new_asset(spk.clone(), input_asset.clone());
+        let signable_hash = construct_tx_in_signable_hash(&tx_previous_out);
+        let signature = sign::sign_detached(signable_hash.as_bytes(), &sk);
+        let tx_in = TxIn::new_from_input(
+            tx_previous_out.clone(),
+            Script::pay2pkh(signable_hash, signature, pk, None),
+        );
+        utxo_set.insert(tx_previous_out, tx_in_previous_out);
+        tx.inputs.push(tx_in);
+    }
+
+    // Generate outputs
+    for output_asset in output_assets {
+        tx.outputs
+            .push(TxOut::new_asset(spk.clone(), output_asset.clone()));
+    }
+
+    (utxo_set, tx)
+}
@@ -1,13 +1,18 @@
 use crate::constants::*;
 use crate::crypto::sha3_256;
-use crate::crypto::sign_ed25519::{self as sign, PublicKey, SecretKey};
-use crate::primitives::asset::{Asset, DataAsset, TokenAmount};
+use crate::crypto::sign_ed25519::{
+    self as sign, PublicKey, SecretKey, Signature, ED25519_PUBLIC_KEY_LEN, ED25519_SIGNATURE_LEN,
+};
+use crate::primitives::asset::{Asset, AssetValues, DataAsset, TokenAmount};
 use crate::primitives::druid::{DdeValues, DruidExpectation};
 use crate::primitives::transaction::*;
+use crate::primitives::utxo::UtxoSet;
 use crate::script::lang::Script;
 use crate::script::{OpCodes, StackEntry};
+use crate::utils::script_utils::address_is_well_formed;
 use bincode::serialize;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
+use std::{error, fmt};
 
 /// Builds a P2SH address
 ///
@@ -25,11 +30,33 @@ pub fn construct_p2sh_address(script: &Script) -> String {
     addr
 }
 
+/// Builds the P2SH address of an m-of-n multisig lock over the given public keys.
+/// The keys are put in canonical order (by their address under `address_version`)
+/// and locked with empty check data, so every party derives the same address
+/// regardless of the order they hold the keys in
+///
+/// ### Arguments
+///
+/// * `m`               - Number of signatures required to unlock
+/// * `pub_keys`        - The constituent public keys
+/// * `address_version` - Network version of the constituent keys' addresses
+pub fn construct_multisig_address(
+    m: usize,
+    pub_keys: &[PublicKey],
+    address_version: Option<u64>,
+) -> String {
+    let mut pub_keys = pub_keys.to_vec();
+    pub_keys.sort_by_cached_key(|pk| construct_address_for(pk, address_version));
+    let redeem = Script::multisig_lock(m, pub_keys.len(), String::new(), pub_keys);
+    construct_p2sh_address(&redeem)
+}
+
 /// Builds an address from a public key and a specified network version
 ///
 /// ### Arguments
 ///
-/// * `pub_key` - A public key to build an address from
+/// * `pub_key`         - A public key to build an address from
+/// * `address_version` - Network version of the address, None for the current version
 pub fn construct_address_for(pub_key: &PublicKey, address_version: Option<u64>) -> String {
     match address_version {
         Some(NETWORK_VERSION_V0) => construct_address_v0(pub_key),
@@ -38,7 +65,25 @@ pub fn construct_address_for(pub_key: &PublicKey, address_version: Option<u64>)
     }
 }
 
-/// Builds an address from a public key
+/// Infers the network version of an address from its format: 32 hex characters for
+/// network version 0, and 64 hex characters, optionally P2SH prefixed, for the current
+/// version. Temporary addresses share the current format, so are reported as current
+///
+/// ### Arguments
+///
+/// * `address` - Address to infer the version of
+pub fn parse_address_version(address: &str) -> Option<u64> {
+    if !address_is_well_formed(address) {
+        return None;
+    }
+    match address.len() {
+        STANDARD_ADDRESS_LENGTH => Some(NETWORK_VERSION as u64),
+        len if len == V0_ADDRESS_LENGTH * 2 => Some(NETWORK_VERSION_V0),
+        _ => None,
+    }
+}
+
+/// Builds an address from a public key: the hex encoded SHA3-256 of its raw bytes
 ///
 /// ### Arguments
 ///
@@ -47,7 +92,8 @@ pub fn construct_address(pub_key: &PublicKey) -> String {
     hex::encode(sha3_256::digest(pub_key.as_ref()))
 }
 
-/// Builds an old (network version 0) address from a public key
+/// Builds an old (network version 0) address from a public key: the SHA3-256 of its
+/// length-prefixed bytes, truncated to `V0_ADDRESS_LENGTH` bytes
 ///
 /// ### Arguments
 ///
@@ -66,7 +112,8 @@ pub fn construct_address_v0(pub_key: &PublicKey) -> String {
 }
 
 /// Builds an address from a public key using the
-/// temporary address scheme present on the wallet
+/// temporary address scheme present on the wallet: the SHA3-256 of its base64
+/// encoding lossily decoded as hex
 ///
 /// TODO: Deprecate after addresses retire
 ///
@@ -114,8 +161,45 @@ pub fn get_out_point_signable_string(out_point: &OutPoint) -> String {
 ///
 /// * `previous_out`   - Previous transaction used as input
 pub fn construct_tx_in_signable_hash(previous_out: &OutPoint) -> String {
-    hex::encode(sha3_256::digest(
-        get_out_point_signable_string(previous_out).as_bytes(),
+    construct_tx_in_signable_hash_with_network(previous_out, DEFAULT_NETWORK_ID)
+}
+
+/// Constructs signable hash for a TxIn on a given network, so that signatures made
+/// for one network cannot be replayed on another. The default network id produces
+/// the same hash as `construct_tx_in_signable_hash`
+///
+/// ### Arguments
+///
+/// * `previous_out`    - Previous transaction used as input
+/// * `network_id`      - Id of the network the signature is for
+pub fn construct_tx_in_signable_hash_with_network(
+    previous_out: &OutPoint,
+    network_id: u64,
+) -> String {
+    let signable = get_out_point_signable_string(previous_out);
+    if network_id == DEFAULT_NETWORK_ID {
+        return hex::encode(sha3_256::digest(signable.as_bytes()));
+    }
+    hex::encode(sha3_256::digest_all(
+        [&network_id.to_le_bytes()[..], signable.as_bytes()]
+            .iter()
+            .copied(),
+    ))
+}
+
+/// Constructs signable hash for a TxIn, feeding the components of the signable
+/// string to the hasher one at a time rather than formatting the string first.
+/// Produces the same hash as `construct_tx_in_signable_hash`
+///
+/// ### Arguments
+///
+/// * `previous_out`   - Previous transaction used as input
+pub fn construct_tx_in_signable_hash_streaming(previous_out: &OutPoint) -> String {
+    let n = previous_out.n.to_string();
+    hex::encode(sha3_256::digest_all(
+        [n.as_bytes(), b"-", previous_out.t_hash.as_bytes()]
+            .iter()
+            .copied(),
     ))
 }
 
@@ -133,6 +217,14 @@ pub fn get_asset_signable_string(asset: &Asset) -> String {
             data_asset.amount
         ),
         Asset::Receipt(receipt) => format!("Receipt:{}", receipt.amount),
+        Asset::Bundle(assets) => format!(
+            "Bundle:{}",
+            assets
+                .iter()
+                .map(|member| get_asset_signable_string(&member.to_asset()))
+                .collect::<Vec<_>>()
+                .join(",")
+        ),
     }
 }
 
@@ -162,6 +254,7 @@ pub fn get_stack_entry_signable_string(entry: &StackEntry) -> String {
         StackEntry::PubKeyHash(pub_key_hash) => format!("PubKeyHash:{pub_key_hash}"),
         StackEntry::Num(num) => format!("Num:{num}"),
         StackEntry::Bytes(bytes) => format!("Bytes:{bytes}"),
+        StackEntry::SignedNum(num) => format!("SignedNum:{num}"),
     }
 }
 
@@ -338,16 +431,7 @@ pub fn construct_create_tx(
     amount: u64,
 ) -> Transaction {
     let asset = Asset::Data(DataAsset { data: drs, amount });
-    let receiver_address = construct_address(&public_key);
-
-    let tx_ins = construct_create_tx_in(block_num, &asset, public_key, secret_key);
-    let tx_out = TxOut {
-        value: asset,
-        script_public_key: Some(receiver_address),
-        ..Default::default()
-    };
-
-    construct_tx_core(tx_ins, vec![tx_out])
+    construct_asset_create_tx(block_num, asset, public_key, secret_key)
 }
 
 /// Constructs a receipt data asset for use in accepting payments
@@ -369,6 +453,24 @@ pub fn construct_receipt_create_tx(
 ) -> Transaction {
     let drs_tx_hash = drs_tx_hash_spec.get_drs_tx_hash();
     let asset = Asset::receipt(amount, drs_tx_hash, metadata);
+    construct_asset_create_tx(block_num, asset, public_key, secret_key)
+}
+
+/// Constructs a transaction creating the given asset, paid to the creator's address.
+/// The input carries the create script expected by `tx_has_valid_create_script`
+///
+/// ### Arguments
+///
+/// * `block_num`           - Block number
+/// * `asset`               - Asset to create
+/// * `public_key`          - Public key for the output address
+/// * `secret_key`          - Corresponding secret key for signing the asset
+pub fn construct_asset_create_tx(
+    block_num: u64,
+    asset: Asset,
+    public_key: PublicKey,
+    secret_key: &SecretKey,
+) -> Transaction {
     let receiver_address = construct_address(&public_key);
 
     let tx_ins = construct_create_tx_in(block_num, &asset, public_key, secret_key);
@@ -438,6 +540,30 @@ pub fn construct_p2sh_tx(
     construct_tx_core(tx_ins, vec![tx_out])
 }
 
+/// Constructs a coinbase transaction paying the block reward. Its output is locked
+/// until the block height reaches `block_height` plus `COINBASE_MATURITY`
+///
+/// ### Arguments
+///
+/// * `block_height`        - Height of the block the coinbase is for
+/// * `amount`              - Amount of tokens rewarded
+/// * `receiver_address`    - Address to send to
+pub fn construct_coinbase_tx(
+    block_height: u64,
+    amount: TokenAmount,
+    receiver_address: String,
+) -> Transaction {
+    let tx_in = TxIn::new_from_script(Script::new_for_coinbase(block_height));
+    let tx_out = TxOut {
+        value: Asset::Token(amount),
+        locktime: block_height.saturating_add(COINBASE_MATURITY),
+        script_public_key: Some(receiver_address),
+        drs_block_hash: None,
+    };
+
+    construct_tx_core(vec![tx_in], vec![tx_out])
+}
+
 /// Constructs a P2SH transaction to burn tokens
 ///
 /// ### Arguments
@@ -634,6 +760,313 @@ pub fn construct_dde_tx(
     tx
 }
 
+/// Errors raised when building a transaction with `TransactionBuilder`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BuildError {
+    NoInputs,
+    MissingInput(OutPoint),
+    KeyMismatch(OutPoint),
+    InsufficientFunds,
+    Overflow,
+    NoChangeAddress,
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::NoInputs => write!(f, "Transaction has no inputs"),
+            Self::MissingInput(out_point) => {
+                write!(f, "Input {out_point:?} is not in the UTXO set")
+            }
+            Self::KeyMismatch(out_point) => {
+                write!(f, "Public key does not own input {out_point:?}")
+            }
+            Self::InsufficientFunds => write!(f, "Inputs do not cover the outputs"),
+            Self::Overflow => write!(f, "Asset amounts overflow"),
+            Self::NoChangeAddress => write!(f, "Change is due but no change address is set"),
+        }
+    }
+}
+
+impl error::Error for BuildError {}
+
+/// Builds a P2PKH transaction, signing each input and paying any assets
+/// left over from the outputs back to a change address
+#[derive(Clone, Debug, Default)]
+pub struct TransactionBuilder {
+    inputs: Vec<(OutPoint, SecretKey, PublicKey)>,
+    outputs: Vec<TxOut>,
+    change_address: Option<String>,
+}
+
+impl TransactionBuilder {
+    /// Creates a new builder with no inputs or outputs
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Adds an input spending the `TxOut` at `out_point`
+    ///
+    /// ### Arguments
+    ///
+    /// * `out_point`   - `OutPoint` of the `TxOut` to spend
+    /// * `secret_key`  - Secret key to sign the input with
+    /// * `public_key`  - Public key owning the `TxOut`
+    pub fn add_input(
+        mut self,
+        out_point: OutPoint,
+        secret_key: SecretKey,
+        public_key: PublicKey,
+    ) -> Self {
+        self.inputs.push((out_point, secret_key, public_key));
+        self
+    }
+
+    /// Adds an output paying `asset` to `address`
+    ///
+    /// ### Arguments
+    ///
+    /// * `address` - Address to pay
+    /// * `asset`   - Asset to pay
+    pub fn add_output(mut self, address: String, asset: Asset) -> Self {
+        self.outputs.push(TxOut::new_asset(address, asset));
+        self
+    }
+
+    /// Sets the address that assets left over from the outputs are paid to
+    ///
+    /// ### Arguments
+    ///
+    /// * `address` - Address to pay change to
+    pub fn set_change(mut self, address: String) -> Self {
+        self.change_address = Some(address);
+        self
+    }
+
+    /// Builds the transaction, appending a change output for each asset the
+    /// inputs hold beyond what the outputs pay
+    ///
+    /// ### Arguments
+    ///
+    /// * `utxo`    - UTXO set holding the `TxOut`s spent by the inputs
+    pub fn build(self, utxo: &UtxoSet) -> Result<Transaction, BuildError> {
+        if self.inputs.is_empty() {
+            return Err(BuildError::NoInputs);
+        }
+
+        let mut spent = AssetValues::default();
+        let mut tx_ins = Vec::new();
+        for (out_point, secret_key, public_key) in &self.inputs {
+            let tx_out = utxo
+                .get(out_point)
+                .ok_or_else(|| BuildError::MissingInput(out_point.clone()))?;
+            let address_version = [None, Some(NETWORK_VERSION_V0), Some(NETWORK_VERSION_TEMP)]
+                .iter()
+                .copied()
+                .find(|version| {
+                    tx_out.script_public_key.as_deref()
+                        == Some(construct_address_for(public_key, *version).as_str())
+                })
+                .ok_or_else(|| BuildError::KeyMismatch(out_point.clone()))?;
+
+            let asset = tx_out.value.clone().with_fixed_hash(out_point);
+            if !spent.checked_update_add(&asset) {
+                return Err(BuildError::Overflow);
+            }
+
+            let signable_hash = construct_tx_in_signable_hash(out_point);
+            let signature = sign::sign_detached(signable_hash.as_bytes(), secret_key);
+            tx_ins.push(TxIn {
+                previous_out: Some(out_point.clone()),
+                script_signature: Script::pay2pkh(
+                    signable_hash,
+                    signature,
+                    *public_key,
+                    address_version,
+                ),
+            });
+        }
+
+        let mut paid = AssetValues::default();
+        for tx_out in &self.outputs {
+            if !paid.checked_update_add(&tx_out.value) {
+                return Err(BuildError::Overflow);
+            }
+        }
+
+        let change = change_assets(&spent, &paid)?;
+        let mut tx_outs = self.outputs;
+        if !change.is_empty() {
+            let change_address = self.change_address.ok_or(BuildError::NoChangeAddress)?;
+            tx_outs.extend(
+                change
+                    .into_iter()
+                    .map(|asset| TxOut::new_asset(change_address.clone(), asset)),
+            );
+        }
+
+        Ok(construct_tx_core(tx_ins, tx_outs))
+    }
+}
+
+/// Computes the assets spent beyond those paid, failing if any paid amount is not covered
+///
+/// ### Arguments
+///
+/// * `spent`   - Assets held by the inputs
+/// * `paid`    - Assets paid by the outputs
+fn change_assets(spent: &AssetValues, paid: &AssetValues) -> Result<Vec<Asset>, BuildError> {
+    let tokens = spent
+        .tokens
+        .checked_sub(paid.tokens)
+        .ok_or(BuildError::InsufficientFunds)?;
+    let receipts = change_amounts(&spent.receipts, &paid.receipts)?;
+    let data = change_amounts(&spent.data, &paid.data)?;
+
+    let mut change = Vec::new();
+    if tokens > TokenAmount(0) {
+        change.push(Asset::Token(tokens));
+    }
+    change.extend(
+        receipts
+            .into_iter()
+            .map(|(drs_tx_hash, amount)| Asset::receipt(amount, Some(drs_tx_hash), None)),
+    );
+    change.extend(
+        data.into_iter()
+            .map(|(data, amount)| Asset::Data(DataAsset { data, amount })),
+    );
+    Ok(change)
+}
+
+/// Computes the non-zero amount left over for each key, failing if any paid
+/// amount is not covered
+///
+/// ### Arguments
+///
+/// * `spent`   - Amounts held by the inputs
+/// * `paid`    - Amounts paid by the outputs
+fn change_amounts<K: Ord + Clone>(
+    spent: &BTreeMap<K, u64>,
+    paid: &BTreeMap<K, u64>,
+) -> Result<Vec<(K, u64)>, BuildError> {
+    if paid.keys().any(|key| !spent.contains_key(key)) {
+        return Err(BuildError::InsufficientFunds);
+    }
+
+    let mut change = Vec::new();
+    for (key, amount) in spent {
+        let paid_amount = paid.get(key).copied().unwrap_or_default();
+        match amount.checked_sub(paid_amount) {
+            Some(0) => {}
+            Some(left) => change.push((key.clone(), left)),
+            None => return Err(BuildError::InsufficientFunds),
+        }
+    }
+    Ok(change)
+}
+
+/// Errors raised when selecting coins with `select_coins`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SelectionError {
+    InsufficientFunds,
+    Overflow,
+}
+
+impl fmt::Display for SelectionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::InsufficientFunds => write!(f, "Owned outputs do not cover the target and fee"),
+            Self::Overflow => write!(f, "Token amounts overflow"),
+        }
+    }
+}
+
+impl error::Error for SelectionError {}
+
+/// Selects owned, unlocked token outputs covering a target amount plus the fee for
+/// spending them. A single output paying the target and fee exactly, without change,
+/// is preferred. Otherwise outputs are taken largest first, ties broken by `OutPoint`,
+/// until they cover the target and the fee of a transaction without change. What is
+/// left over pays a change output only if it exceeds that output's fee, and is otherwise
+/// added to the fee, so no zero change output is needed. Dust outputs, worth no more
+/// than the fee to spend them, are never selected
+///
+/// ### Arguments
+///
+/// * `utxo`            - UTXO set to select from
+/// * `target`          - Amount to pay, excluding the fee
+/// * `fee_rate`        - Fee in tokens per byte of the estimated transaction size
+/// * `owned`           - Addresses of the public keys owned
+/// * `block_height`    - Current block height, above which outputs are still locked
+pub fn select_coins(
+    utxo: &UtxoSet,
+    target: TokenAmount,
+    fee_rate: u64,
+    owned: &BTreeSet<String>,
+    block_height: u64,
+) -> Result<Vec<OutPoint>, SelectionError> {
+    let fee = |inputs: usize| {
+        estimate_selection_fee(inputs, false, fee_rate).ok_or(SelectionError::Overflow)
+    };
+    let required = |inputs: usize| {
+        target
+            .checked_add(fee(inputs)?)
+            .ok_or(SelectionError::Overflow)
+    };
+
+    let input_fee = fee(1)?.checked_sub(fee(0)?).unwrap_or_default();
+    let mut candidates: Vec<(&OutPoint, TokenAmount)> = utxo
+        .iter()
+        .filter(|(_, tx_out)| tx_out.value.is_token() && tx_out.locktime <= block_height)
+        .filter(|(_, tx_out)| tx_out.is_spendable_by(owned))
+        .map(|(out_point, tx_out)| (out_point, tx_out.value.token_amount()))
+        .filter(|(_, amount)| *amount > input_fee)
+        .collect();
+    candidates.sort_by(|(a_out, a), (b_out, b)| b.cmp(a).then_with(|| a_out.cmp(b_out)));
+
+    let exact = required(1)?;
+    if let Some((out_point, _)) = candidates.iter().find(|(_, amount)| *amount == exact) {
+        return Ok(vec![(*out_point).clone()]);
+    }
+
+    let mut selected = Vec::new();
+    let mut total = TokenAmount(0);
+    for (out_point, amount) in candidates {
+        selected.push(out_point.clone());
+        total = total.checked_add(amount).ok_or(SelectionError::Overflow)?;
+        if total >= required(selected.len())? {
+            return Ok(selected);
+        }
+    }
+    Err(SelectionError::InsufficientFunds)
+}
+
+/// Estimates the fee for a P2PKH token payment from the given number of inputs,
+/// with a change output if wanted, returning None on overflow. Inputs are sized
+/// with placeholder signatures and public keys of their encoded length
+///
+/// ### Arguments
+///
+/// * `inputs`      - Number of inputs spent
+/// * `with_change` - Whether a change output is paid
+/// * `fee_rate`    - Fee in tokens per byte of the estimated transaction size
+fn estimate_selection_fee(inputs: usize, with_change: bool, fee_rate: u64) -> Option<TokenAmount> {
+    let signature = Signature::from_slice(&[0; ED25519_SIGNATURE_LEN])?;
+    let pub_key = PublicKey::from_slice(&[0; ED25519_PUBLIC_KEY_LEN])?;
+    let out_point = OutPoint::new("0".repeat(TX_HASH_LENGTH * 2), 0);
+    let check_data = construct_tx_in_signable_hash(&out_point);
+    let tx_in = TxIn::new_from_input(
+        out_point,
+        Script::pay2pkh(check_data, signature, pub_key, None),
+    );
+    let tx_out = TxOut::new_token_amount(construct_address(&pub_key), TokenAmount(0));
+    let tx_outs = vec![tx_out; if with_change { 2 } else { 1 }];
+
+    let size = construct_tx_core(vec![tx_in; inputs], tx_outs).estimated_size();
+    fee_rate.checked_mul(size as u64).map(TokenAmount)
+}
+
 /*---- TESTS ----*/
 
 #[cfg(test)]
@@ -642,7 +1075,10 @@ mod tests {
     use crate::crypto::sign_ed25519::{self as sign, Signature};
     use crate::primitives::asset::{AssetValues, ReceiptAsset};
     use crate::script::OpCodes;
-    use crate::utils::script_utils::{tx_has_valid_p2sh_script, tx_outs_are_valid};
+    use crate::utils::script_utils::{
+        tx_has_valid_create_script, tx_has_valid_p2sh_script, tx_is_valid, tx_outs_are_valid,
+    };
+    use std::collections::BTreeSet;
 
     #[test]
     // Creates a valid creation transaction
@@ -665,6 +1101,86 @@ mod tests {
         );
     }
 
+    #[test]
+    // Creates valid receipt creation transactions, with and without metadata
+    fn test_construct_a_valid_receipt_create_tx() {
+        let (pk, sk) = sign::gen_keypair();
+        let metadata = Some("{\"name\":\"Ticket\",\"description\":\"Entry\"}".to_owned());
+
+        for metadata in [None, metadata] {
+            let tx =
+                construct_receipt_create_tx(0, pk, &sk, 5, DrsTxHashSpec::Create, metadata.clone());
+            let asset = Asset::receipt(5, None, metadata);
+
+            assert!(tx.is_create_tx());
+            assert_eq!(tx.outputs[0].value, asset);
+            assert!(tx_has_valid_create_script(
+                &tx.inputs[0].script_signature,
+                &tx.outputs[0].value
+            ));
+            assert!(!tx_has_valid_create_script(
+                &tx.inputs[0].script_signature,
+                &Asset::receipt(6, None, None)
+            ));
+        }
+    }
+
+    #[test]
+    // Builds a token payment with change that is valid against the UTXO set
+    fn test_transaction_builder_payment_with_change() {
+        //
+        // Arrange
+        //
+        let (pk, sk) = sign::gen_keypair();
+        let (receiver_pk, _) = sign::gen_keypair();
+        let (change_pk, _) = sign::gen_keypair();
+        let out_point = OutPoint::new("tx_hash".to_owned(), 0);
+        let mut utxo = UtxoSet::new();
+        utxo.insert(
+            out_point.clone(),
+            TxOut::new_token_amount(construct_address(&pk), TokenAmount(10)),
+        );
+        let builder = TransactionBuilder::new()
+            .add_input(out_point.clone(), sk.clone(), pk)
+            .add_output(construct_address(&receiver_pk), Asset::token_u64(7));
+
+        //
+        // Act
+        //
+        let no_change = builder.clone().build(&utxo);
+        let tx = builder
+            .clone()
+            .set_change(construct_address(&change_pk))
+            .build(&utxo)
+            .unwrap();
+        let over_spend = TransactionBuilder::new()
+            .add_input(out_point.clone(), sk.clone(), pk)
+            .add_output(construct_address(&receiver_pk), Asset::token_u64(11))
+            .build(&utxo);
+        let wrong_key = TransactionBuilder::new()
+            .add_input(out_point, sk.clone(), receiver_pk)
+            .build(&utxo);
+        let missing = TransactionBuilder::new()
+            .add_input(OutPoint::new("missing".to_owned(), 0), sk, pk)
+            .build(&utxo);
+
+        //
+        // Assert
+        //
+        assert_eq!(
+            tx.outputs,
+            vec![
+                TxOut::new_token_amount(construct_address(&receiver_pk), TokenAmount(7)),
+                TxOut::new_token_amount(construct_address(&change_pk), TokenAmount(3)),
+            ]
+        );
+        assert!(tx_is_valid(&tx, utxo.lookup_fn()));
+        assert_eq!(no_change, Err(BuildError::NoChangeAddress));
+        assert_eq!(over_spend, Err(BuildError::InsufficientFunds));
+        assert!(matches!(wrong_key, Err(BuildError::KeyMismatch(_))));
+        assert!(matches!(missing, Err(BuildError::MissingInput(_))));
+    }
+
     #[test]
     // Creates a valid payment transaction
     fn test_construct_a_valid_payment_tx() {
@@ -747,6 +1263,92 @@ mod tests {
         // TODO: Add assertion for full tx validity
     }
 
+    #[test]
+    fn test_construct_tx_in_signable_hash_streaming() {
+        let out_points = vec![
+            OutPoint::new(String::new(), 0),
+            OutPoint::new("g48dda5bbe9171a6656206ec56c595c5".to_owned(), 1),
+            OutPoint::new(hex::encode(vec![0; 32]), -1),
+            OutPoint::new("a".repeat(10_000), i32::MAX),
+        ];
+
+        for out_point in &out_points {
+            assert_eq!(
+                construct_tx_in_signable_hash_streaming(out_point),
+                construct_tx_in_signable_hash(out_point)
+            );
+        }
+    }
+
+    #[test]
+    fn test_construct_tx_in_signable_hash_with_network() {
+        let (pk, sk) = sign::gen_keypair();
+        let out_point = OutPoint::new("000000".to_owned(), 0);
+
+        let default_hash =
+            construct_tx_in_signable_hash_with_network(&out_point, DEFAULT_NETWORK_ID);
+        let hash_a = construct_tx_in_signable_hash_with_network(&out_point, 1);
+        let hash_b = construct_tx_in_signable_hash_with_network(&out_point, 2);
+
+        let sig_a = sign::sign_detached(hash_a.as_bytes(), &sk);
+        let sig_b = sign::sign_detached(hash_b.as_bytes(), &sk);
+
+        assert_eq!(default_hash, construct_tx_in_signable_hash(&out_point));
+        assert_ne!(hash_a, default_hash);
+        assert_ne!(hash_a, hash_b);
+        assert!(sign::verify_detached(&sig_a, hash_a.as_bytes(), &pk));
+        assert!(sign::verify_detached(&sig_b, hash_b.as_bytes(), &pk));
+        assert!(!sign::verify_detached(&sig_a, hash_b.as_bytes(), &pk));
+        assert!(!sign::verify_detached(&sig_b, hash_a.as_bytes(), &pk));
+    }
+
+    #[test]
+    fn test_parse_address_version() {
+        let (pk, _) = sign::gen_keypair();
+        let redeem = Script::multisig_lock(1, 1, String::new(), vec![pk]);
+        let current = Some(NETWORK_VERSION as u64);
+
+        for version in [None, Some(NETWORK_VERSION_V0)] {
+            let address = construct_address_for(&pk, version);
+            let parsed = parse_address_version(&address);
+            let expected = version.or(current);
+
+            assert_eq!(parsed, expected);
+            assert_eq!(construct_address_for(&pk, parsed), address);
+        }
+
+        // Temporary addresses cannot be told apart from current ones
+        let address_temp = construct_address_temp(&pk);
+        assert_eq!(parse_address_version(&address_temp), current);
+        assert_ne!(construct_address_for(&pk, current), address_temp);
+
+        assert_eq!(
+            parse_address_version(&construct_p2sh_address(&redeem)),
+            current
+        );
+        assert_eq!(parse_address_version(""), None);
+        assert_eq!(parse_address_version(&"z".repeat(64)), None);
+        assert_eq!(parse_address_version(&"a".repeat(48)), None);
+    }
+
+    #[test]
+    fn test_construct_multisig_address() {
+        let mut pub_keys: Vec<PublicKey> = (0..3).map(|_| sign::gen_keypair().0).collect();
+        pub_keys.sort_by_cached_key(construct_address);
+
+        let address = construct_multisig_address(2, &pub_keys, None);
+        let redeem = Script::multisig_lock(2, 3, String::new(), pub_keys.clone());
+
+        assert_eq!(address, construct_p2sh_address(&redeem));
+        assert_eq!(address.as_bytes()[0], P2SH_PREPEND);
+        assert_eq!(address.len(), STANDARD_ADDRESS_LENGTH);
+
+        // Key order does not change the address
+        pub_keys.reverse();
+        assert_eq!(address, construct_multisig_address(2, &pub_keys, None));
+        assert_ne!(address, construct_multisig_address(3, &pub_keys, None));
+    }
+
     #[test]
     fn test_construct_a_valid_burn_tx() {
         let token_amount = TokenAmount(400000);
@@ -981,6 +1583,136 @@ mod tests {
         assert_eq!(dde.druid_info.unwrap().participants, participants);
     }
 
+    #[test]
+    // Round-trips a payment transaction and its script through JSON
+    fn test_payment_tx_json_round_trip() {
+        let (pk, sk) = sign::gen_keypair();
+        let t_hash = hex::encode(vec![0, 0, 0]);
+        let signature = sign::sign_detached(t_hash.as_bytes(), &sk);
+
+        let tx_const = TxConstructor {
+            previous_out: OutPoint::new(t_hash, 0),
+            signatures: vec![signature],
+            pub_keys: vec![pk],
+            address_version: None,
+        };
+        let tx_ins = construct_payment_tx_ins(vec![tx_const]);
+        let tx_out = TxOut::new_token_amount(construct_address(&pk), TokenAmount(10));
+        let tx = construct_tx_core(tx_ins, vec![tx_out]);
+
+        let script = &tx.inputs[0].script_signature;
+        let script_json = script.to_json().unwrap();
+        let tx_json = tx.to_json().unwrap();
+
+        assert!(script_json.contains("\"OP_DUP\""));
+        assert!(script_json.contains(&hex::encode(pk)));
+        assert!(script_json.contains(&hex::encode(signature)));
+        assert_eq!(&Script::from_json(&script_json).unwrap(), script);
+        assert_eq!(Transaction::from_json(&tx_json).unwrap(), tx);
+    }
+
+    #[test]
+    // Compares the estimated sizes of a P2PKH payment transaction and its script to their serialized sizes
+    fn test_payment_tx_estimated_size() {
+        let (pk, sk) = sign::gen_keypair();
+        let t_hash = hex::encode(vec![0, 0, 0]);
+        let signature = sign::sign_detached(t_hash.as_bytes(), &sk);
+
+        let tx_const = TxConstructor {
+            previous_out: OutPoint::new(t_hash, 0),
+            signatures: vec![signature],
+            pub_keys: vec![pk],
+            address_version: None,
+        };
+        let tx_ins = construct_payment_tx_ins(vec![tx_const]);
+        let tx = construct_payment_tx(
+            tx_ins,
+            construct_address(&pk),
+            Some("drs_block_hash".to_owned()),
+            Asset::Token(TokenAmount(10)),
+            0,
+        );
+
+        let script = &tx.inputs[0].script_signature;
+        let script_size = serialize(script).unwrap().len();
+        let tx_size = serialize(&tx).unwrap().len();
+        let tolerance = 8;
+
+        assert!(script.serialized_size().abs_diff(script_size) <= tolerance);
+        assert_eq!(tx.estimated_size(), tx_size);
+    }
+
+    #[test]
+    // Round-trips a DDE transaction through JSON
+    fn test_dde_tx_json_round_trip() {
+        let (pk, sk) = sign::gen_keypair();
+        let t_hash = hex::encode(vec![0, 0, 0]);
+        let signature = sign::sign_detached(t_hash.as_bytes(), &sk);
+
+        let tx_const = TxConstructor {
+            previous_out: OutPoint::new(t_hash, 0),
+            signatures: vec![signature],
+            pub_keys: vec![pk],
+            address_version: None,
+        };
+        let tx_ins = construct_payment_tx_ins(vec![tx_const]);
+        let from_addr = construct_tx_ins_address(&tx_ins);
+        let data = Asset::Data(DataAsset {
+            data: vec![0, 12, 3, 5, 6],
+            amount: 1,
+        });
+        let tx_outs = vec![TxOut::new_asset("2222".to_owned(), data.clone())];
+        let expects = vec![DruidExpectation {
+            from: from_addr,
+            to: "2222".to_owned(),
+            asset: data,
+        }];
+        let druid = hex::encode(vec![1, 2, 3, 4, 5]);
+        let tx = construct_dde_tx(druid, tx_ins, tx_outs, 2, expects);
+
+        let tx_json = tx.to_json().unwrap();
+
+        assert_eq!(Transaction::from_json(&tx_json).unwrap(), tx);
+        assert_eq!(
+            construct_tx_hash(&Transaction::from_json(&tx_json).unwrap()),
+            construct_tx_hash(&tx)
+        );
+    }
+
+    #[test]
+    // Checks the transaction hash is stable under reordered DRUID expectations and changes with the outputs
+    fn test_tx_hash() {
+        let tx_ins = construct_payment_tx_ins(vec![]);
+        let from_addr = construct_tx_ins_address(&tx_ins);
+        let expects: Vec<DruidExpectation> = ["1111", "2222", "3333"]
+            .iter()
+            .map(|to| DruidExpectation {
+                from: from_addr.clone(),
+                to: to.to_string(),
+                asset: Asset::token_u64(10),
+            })
+            .collect();
+        let tx_outs = vec![TxOut::new_token_amount("2222".to_owned(), TokenAmount(10))];
+        let tx = construct_dde_tx("VALUE".to_owned(), tx_ins, tx_outs, 3, expects);
+
+        let mut reordered_tx = tx.clone();
+        if let Some(druid_info) = reordered_tx.druid_info.as_mut() {
+            druid_info.expectations.reverse();
+        }
+
+        let mut changed_tx = tx.clone();
+        changed_tx.outputs[0].value = Asset::token_u64(11);
+
+        let mut extra_output_tx = tx.clone();
+        extra_output_tx.outputs.push(tx.outputs[0].clone());
+
+        assert_eq!(tx.tx_hash(), tx.clone().tx_hash());
+        assert_eq!(reordered_tx.tx_hash(), tx.tx_hash());
+        assert_ne!(construct_tx_hash(&reordered_tx), construct_tx_hash(&tx));
+        assert_ne!(changed_tx.tx_hash(), tx.tx_hash());
+        assert_ne!(extra_output_tx.tx_hash(), tx.tx_hash());
+    }
+
     #[test]
     // Creates a valid receipt based tx pair
     fn test_construct_a_valid_receipt_tx_pair() {
@@ -1005,7 +1737,8 @@ mod tests {
                 // constructors with enough money for amount and excess, caller responsibility.
                 construct_payment_tx_ins(vec![])
             };
-            let excess_tx_out = TxOut::new_token_amount(sender_address_excess, amount - payment);
+            let (_, change) = amount.split_payment(payment).unwrap();
+            let excess_tx_out = TxOut::new_token_amount(sender_address_excess, change);
 
             let expectation = DruidExpectation {
                 from: from_addr.clone(),
@@ -1258,4 +1991,247 @@ mod tests {
         //
         assert_eq!(actual, expected);
     }
+
+    #[test]
+    // Test that transactions paying the same outputs in different orders canonicalize to the same hash
+    fn test_canonicalize_reordered_outputs() {
+        //
+        // Arrange
+        //
+        let tx_ins = vec![TxIn::new_from_input(
+            OutPoint::new("tx_hash".to_owned(), 0),
+            Script::new(),
+        )];
+        let tx_outs = vec![
+            TxOut::new_token_amount("c".repeat(64), TokenAmount(5)),
+            TxOut::new_token_amount("b".repeat(64), TokenAmount(5)),
+            TxOut::new_receipt_amount("a".repeat(64), ReceiptAsset::new(1, None, None)),
+            TxOut::new_token_amount("a".repeat(64), TokenAmount(3)),
+        ];
+        let mut reordered = tx_outs.clone();
+        reordered.reverse();
+        reordered.swap(0, 1);
+
+        let mut tx = construct_tx_core(tx_ins.clone(), tx_outs);
+        let mut reordered_tx = construct_tx_core(tx_ins, reordered);
+
+        //
+        // Act
+        //
+        let hashes_before = (tx.tx_hash(), reordered_tx.tx_hash());
+        let canonical_before = (tx.is_canonical(), reordered_tx.is_canonical());
+        tx.canonicalize();
+        reordered_tx.canonicalize();
+
+        //
+        // Assert
+        //
+        assert_ne!(hashes_before.0, hashes_before.1);
+        assert_eq!(canonical_before, (false, false));
+        assert!(tx.is_canonical());
+        assert!(reordered_tx.is_canonical());
+        assert_eq!(tx, reordered_tx);
+        assert_eq!(tx.tx_hash(), reordered_tx.tx_hash());
+        assert_eq!(
+            tx.outputs[0].script_public_key.as_deref(),
+            Some("a".repeat(64).as_str())
+        );
+        assert!(tx.outputs[0].value.is_token());
+    }
+
+    #[test]
+    // Test that coin selection prefers an exact match, then covers the target and fee with
+    // unlocked outputs, leaving change too small for a change output to the fee
+    fn test_select_coins() {
+        //
+        // Arrange
+        //
+        let (pk, _) = sign::gen_keypair();
+        let address = construct_address(&pk);
+        let owned: BTreeSet<String> = [address.clone()].into();
+        let fee = |inputs, with_change| estimate_selection_fee(inputs, with_change, 1).unwrap();
+        let target = TokenAmount(1000);
+        let exact = target + fee(1, false);
+
+        let mut utxo = UtxoSet::new();
+        let mut insert = |n: i32, address: &str, amount: TokenAmount, locktime: u64| {
+            let out_point = OutPoint::new("tx_hash".to_owned(), n);
+            let mut tx_out = TxOut::new_token_amount(address.to_owned(), amount);
+            tx_out.locktime = locktime;
+            utxo.insert(out_point.clone(), tx_out);
+            out_point
+        };
+        let exact_out = insert(0, &address, exact, 0);
+        let large_out = insert(1, &address, exact + TokenAmount(1), 0);
+        let small_out = insert(2, &address, TokenAmount(600), 0);
+        insert(3, &address, fee(1, false) - fee(0, false), 0);
+        insert(4, &"b".repeat(64), TokenAmount(u64::MAX), 0);
+        let locked_out = insert(5, &address, TokenAmount(u64::MAX / 2), 10);
+
+        //
+        // Act
+        //
+        let exact_selection = select_coins(&utxo, target, 1, &owned, 0);
+        let change_selection = select_coins(&utxo, exact, 1, &owned, 0);
+        let no_change_selection = select_coins(&utxo, target - TokenAmount(1), 1, &owned, 0);
+        let insufficient = select_coins(&utxo, TokenAmount(3 * exact.0), 1, &owned, 0);
+        let unlocked = select_coins(&utxo, TokenAmount(3 * exact.0), 1, &owned, 10);
+        let overflow = select_coins(&utxo, target, u64::MAX, &owned, 0);
+
+        //
+        // Assert
+        //
+        assert_eq!(exact_selection, Ok(vec![exact_out.clone()]));
+        assert_eq!(change_selection, Ok(vec![large_out.clone(), exact_out]));
+        assert_eq!(no_change_selection, Ok(vec![large_out]));
+        assert_eq!(insufficient, Err(SelectionError::InsufficientFunds));
+        assert_eq!(unlocked, Ok(vec![locked_out]));
+        assert_eq!(overflow, Err(SelectionError::Overflow));
+        assert!(fee(2, true) > fee(2, false));
+        assert!(!change_selection.unwrap().contains(&small_out));
+    }
+
+    #[test]
+    // Test that outputs are spendable by the owners of their P2PKH or multisig keys
+    fn test_tx_out_is_spendable_by() {
+        //
+        // Arrange
+        //
+        let pub_keys: Vec<PublicKey> = (0..3).map(|_| sign::gen_keypair().0).collect();
+        let owned_one: BTreeSet<String> = [construct_address(&pub_keys[0])].into();
+        let mut owned_two = owned_one.clone();
+        owned_two.insert(construct_address_v0(&pub_keys[1]));
+
+        let owned_out = TxOut::new_token_amount(construct_address(&pub_keys[0]), TokenAmount(1));
+        let unowned_out = TxOut::new_token_amount(construct_address(&pub_keys[2]), TokenAmount(1));
+        let redeem = Script::multisig_lock(2, 3, String::new(), pub_keys.clone());
+        let other_redeem = Script::multisig_lock(1, 3, String::new(), pub_keys);
+        let multisig_out = TxOut::new_token_amount(construct_p2sh_address(&redeem), TokenAmount(1));
+
+        //
+        // Act
+        //
+        let p2pkh_spendable = (
+            owned_out.is_spendable_by(&owned_one),
+            unowned_out.is_spendable_by(&owned_one),
+            multisig_out.is_spendable_by(&owned_two),
+        );
+        let multisig_spendable = (
+            multisig_out.is_spendable_by_multisig(&redeem, &owned_one),
+            multisig_out.is_spendable_by_multisig(&redeem, &owned_two),
+            multisig_out.is_spendable_by_multisig(&other_redeem, &owned_two),
+            owned_out.is_spendable_by_multisig(&redeem, &owned_two),
+        );
+
+        //
+        // Assert
+        //
+        assert_eq!(p2pkh_spendable, (true, false, false));
+        assert_eq!(multisig_spendable, (false, true, false, false));
+    }
+
+    #[test]
+    // Test that transactions conflict only when they spend a shared outpoint
+    fn test_spent_outpoints_conflicts() {
+        //
+        // Arrange
+        //
+        let out_point = |n: i32| OutPoint::new("tx_hash".to_owned(), n);
+        let construct_spending = |ns: &[i32]| {
+            let tx_ins = ns
+                .iter()
+                .map(|n| TxIn::new_from_input(out_point(*n), Script::new()))
+                .collect();
+            construct_tx_core(
+                tx_ins,
+                vec![TxOut::new_token_amount("a".repeat(64), TokenAmount(1))],
+            )
+        };
+        let tx = construct_spending(&[0, 1]);
+        let conflicting_tx = construct_spending(&[2, 1]);
+        let independent_tx = construct_spending(&[2, 3]);
+        let mut coinbase_tx = construct_spending(&[]);
+        coinbase_tx.inputs.push(TxIn::new());
+
+        //
+        // Act
+        //
+        let spent: Vec<&OutPoint> = tx.spent_outpoints().collect();
+
+        //
+        // Assert
+        //
+        assert_eq!(spent, vec![&out_point(0), &out_point(1)]);
+        assert_eq!(coinbase_tx.spent_outpoints().count(), 0);
+        assert!(tx.conflicts_with(&conflicting_tx));
+        assert!(conflicting_tx.conflicts_with(&tx));
+        assert!(!tx.conflicts_with(&independent_tx));
+        assert!(!tx.conflicts_with(&coinbase_tx));
+    }
+
+    #[test]
+    // Test that only unlocking scripts in their canonical form are non-malleable
+    fn test_tx_is_non_malleable() {
+        //
+        // Arrange
+        //
+        let (pk, sk) = sign::gen_keypair();
+        let out_point = OutPoint::new("tx_hash".to_owned(), 0);
+        let signable_hash = construct_tx_in_signable_hash(&out_point);
+        let sig = sign::sign_detached(signable_hash.as_bytes(), &sk);
+        let redeem = Script::multisig_lock(1, 1, signable_hash.clone(), vec![pk]);
+        let sig_stack = Script::multisig_unlock(signable_hash.clone(), vec![sig]).stack;
+        let construct_spending = |script: Script| {
+            construct_tx_core(
+                vec![TxIn::new_from_input(out_point.clone(), script)],
+                vec![TxOut::new_token_amount("a".repeat(64), TokenAmount(1))],
+            )
+        };
+
+        let p2pkh = Script::pay2pkh(signable_hash.clone(), sig, pk, None);
+        let mut trailing_junk = p2pkh.clone();
+        trailing_junk.stack.push(StackEntry::Num(1));
+        let mut leading_junk = p2pkh.clone();
+        leading_junk
+            .stack
+            .insert(0, StackEntry::Bytes("00".to_owned()));
+        let p2sh = Script::p2sh_unlock(&redeem, sig_stack.clone());
+        let mut p2sh_upper_hex = p2sh.clone();
+        if let Some(StackEntry::Bytes(b)) = p2sh_upper_hex.stack.last_mut() {
+            *b = b.to_uppercase();
+        }
+        let mut p2sh_extra_op = sig_stack.clone();
+        p2sh_extra_op.push(StackEntry::Op(OpCodes::OP_NOP));
+        let p2sh_extra_op = Script::p2sh_unlock(&redeem, p2sh_extra_op);
+        let escrow = Script::escrow_lock(1, &[pk], 10, 1);
+        let escrow_stack = |branch| vec![sig_stack[0].clone(), sig_stack[1].clone(), branch];
+        let escrow_branches = [StackEntry::num(0), StackEntry::num(1)]
+            .map(|branch| Script::p2sh_unlock(&escrow, escrow_stack(branch)));
+        let escrow_non_minimal = Script::p2sh_unlock(&escrow, escrow_stack(StackEntry::Num(1)));
+
+        //
+        // Act
+        //
+        let [escrow_fallback, escrow_multisig] = escrow_branches;
+        let canonical = [
+            construct_spending(p2pkh).is_non_malleable(),
+            construct_spending(p2sh).is_non_malleable(),
+            construct_coinbase_tx(10, TokenAmount(1), "a".repeat(64)).is_non_malleable(),
+            construct_spending(escrow_fallback).is_non_malleable(),
+            construct_spending(escrow_multisig).is_non_malleable(),
+        ];
+        let malleable = [
+            construct_spending(trailing_junk).is_non_malleable(),
+            construct_spending(leading_junk).is_non_malleable(),
+            construct_spending(p2sh_upper_hex).is_non_malleable(),
+            construct_spending(p2sh_extra_op).is_non_malleable(),
+            construct_spending(escrow_non_minimal).is_non_malleable(),
+        ];
+
+        //
+        // Assert
+        //
+        assert_eq!(canonical, [true; 5]);
+        assert_eq!(malleable, [false; 5]);
+    }
 }
@@ -0,0 +1,137 @@
+use std::collections::BTreeMap;
+
+/// Least-recently-used cache of script validity results, keyed by `Transaction::tx_hash`.
+/// Only results that do not depend on the UTXO set's contents are stored
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ValidationCache {
+    capacity: usize,
+    tick: u64,
+    entries: BTreeMap<String, (bool, u64)>,
+    recency: BTreeMap<u64, String>,
+}
+
+impl ValidationCache {
+    /// Creates a new, empty cache holding at most `capacity` results
+    ///
+    /// ### Arguments
+    ///
+    /// * `capacity`    - Maximum number of results held
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            tick: 0,
+            entries: BTreeMap::new(),
+            recency: BTreeMap::new(),
+        }
+    }
+
+    /// Gets the cached result for a transaction, marking it as most recently used
+    ///
+    /// ### Arguments
+    ///
+    /// * `tx_hash`     - Hash of the transaction
+    pub fn get(&mut self, tx_hash: &str) -> Option<bool> {
+        let tick = self.next_tick();
+        let (valid, last_used) = self.entries.get_mut(tx_hash)?;
+        let key = self.recency.remove(last_used)?;
+        self.recency.insert(tick, key);
+        *last_used = tick;
+        Some(*valid)
+    }
+
+    /// Caches the result for a transaction, evicting the least recently used
+    /// result if the cache is full
+    ///
+    /// ### Arguments
+    ///
+    /// * `tx_hash`     - Hash of the transaction
+    /// * `valid`       - Whether the transaction's scripts are valid
+    pub fn insert(&mut self, tx_hash: String, valid: bool) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let tick = self.next_tick();
+        if let Some((_, last_used)) = self.entries.insert(tx_hash.clone(), (valid, tick)) {
+            self.recency.remove(&last_used);
+        } else if self.entries.len() > self.capacity {
+            if let Some((_, evicted)) = self.recency.pop_first() {
+                self.entries.remove(&evicted);
+            }
+        }
+        self.recency.insert(tick, tx_hash);
+    }
+
+    /// Number of results held
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the cache holds no results
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Maximum number of results held
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Advances the recency counter
+    fn next_tick(&mut self) -> u64 {
+        self.tick += 1;
+        self.tick
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// Checks that the cache never holds more results than its capacity
+    fn should_respect_size_bound() {
+        let mut cache = ValidationCache::new(2);
+
+        for i in 0..5 {
+            cache.insert(format!("tx_{i}"), true);
+            assert!(cache.len() <= cache.capacity());
+        }
+
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get("tx_0"), None);
+        assert_eq!(cache.get("tx_3"), Some(true));
+        assert_eq!(cache.get("tx_4"), Some(true));
+    }
+
+    #[test]
+    /// Checks that the least recently used result is evicted first
+    fn should_evict_least_recently_used() {
+        let mut cache = ValidationCache::new(2);
+        cache.insert("tx_0".to_owned(), true);
+        cache.insert("tx_1".to_owned(), false);
+
+        // Using tx_0 leaves tx_1 as least recently used
+        assert_eq!(cache.get("tx_0"), Some(true));
+        cache.insert("tx_2".to_owned(), true);
+
+        assert_eq!(cache.get("tx_1"), None);
+        assert_eq!(cache.get("tx_0"), Some(true));
+        assert_eq!(cache.get("tx_2"), Some(true));
+    }
+
+    #[test]
+    /// Checks that re-inserting a result updates it without growing the cache
+    fn should_update_existing_result() {
+        let mut cache = ValidationCache::new(2);
+        cache.insert("tx_0".to_owned(), true);
+        cache.insert("tx_0".to_owned(), false);
+
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.get("tx_0"), Some(false));
+
+        let mut cache = ValidationCache::new(0);
+        cache.insert("tx_0".to_owned(), true);
+        assert!(cache.is_empty());
+    }
+}